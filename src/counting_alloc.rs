@@ -0,0 +1,57 @@
+//! Global allocator used by the tests to count the allocations made by the current thread.
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::cell::Cell;
+
+struct CountingAlloc;
+
+thread_local!
+{
+  static ALLOCATIONS: Cell<usize>=const { Cell::new(0) };
+}
+
+fn count()
+{
+  let _=ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get()+1));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc
+{
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8
+  {
+    count();
+    System.alloc(layout)
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8
+  {
+    count();
+    System.alloc_zeroed(layout)
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8
+  {
+    count();
+    System.realloc(ptr,layout,new_size)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+  {
+    System.dealloc(ptr,layout)
+  }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc=CountingAlloc;
+
+/// Runs `f` and returns its result together with the number of allocations it made.
+pub(crate) fn count_allocations<F,R>(f: F) -> (R,usize)
+where F: FnOnce() -> R
+{
+  let before: usize=ALLOCATIONS.with(|allocations| allocations.get());
+  let result: R=f();
+  let after: usize=ALLOCATIONS.with(|allocations| allocations.get());
+  (result,after-before)
+}
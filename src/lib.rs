@@ -1,6 +1,20 @@
-
 #![feature(const_generics)]
 #![allow(incomplete_features)]
 #![feature(custom_test_frameworks)]
+#![feature(new_uninit)]
+#![feature(try_reserve)]
+#![feature(int_roundings)]
+#![feature(is_some_and)]
+#![cfg_attr(all(test,not(debug_assertions)), feature(test))]
+#![cfg_attr(test, feature(thread_local_const_init))]
+#![cfg_attr(not(any(feature="std",test)),no_std)]
+
+extern crate alloc;
 
 mod tensor;
+
+#[doc(hidden)]
+pub use tensor::ui;
+
+#[cfg(test)]
+mod counting_alloc;
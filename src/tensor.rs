@@ -1,27 +1,127 @@
 
 #![allow(dead_code,unused_imports)]
 
-use std::boxed::Box;
-
-use std::clone::Clone;
-use std::default::Default;
-use std::marker::Copy;
-
-use std::ops::Add;
-use std::ops::AddAssign;
-use std::ops::Index;
-use std::ops::IndexMut;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::clone::Clone;
+use core::default::Default;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::marker::Copy;
+
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ops::Mul;
+use core::ops::MulAssign;
+use core::ops::Rem;
+use core::ops::RemAssign;
+
+#[cfg(feature="arbitrary")]
+mod arbitrary;
+mod axis;
+mod bitwise;
+mod builder;
+mod cast;
+mod close;
+mod const_axis;
+#[cfg(feature="std")]
+mod container;
+mod digest;
+#[cfg(feature="std")]
+mod distance;
+mod display;
+#[cfg(test)]
+mod empty_tests;
+mod encoding;
+mod error;
+#[cfg(feature="std")]
+mod export;
+mod float;
+#[cfg(feature="half")]
+mod float16;
+#[cfg(feature="std")]
+mod geometry;
+mod gradient;
+mod group;
+mod image;
+#[cfg(feature="std")]
+mod iterative;
+mod json;
+mod kernel;
+mod layout;
+mod linalg;
+mod lu;
+mod mask;
+mod matfun;
+mod metrics;
+#[cfg(feature="mmap")]
+mod mmap;
+mod modular;
+mod names;
+#[cfg(feature="std")]
+mod npy;
+mod ode;
+mod optim;
+mod order;
+#[cfg(feature="std")]
+mod parallel;
+mod patch;
+#[cfg(feature="std")]
+mod pnm;
+mod poly;
+mod pool;
+#[cfg(feature="std")]
+mod qr;
+mod quantize;
+#[cfg(feature="rand")]
+mod random;
+mod region;
+mod running;
+#[cfg(feature="std")]
+mod scale;
+mod scatter;
+mod shape;
+mod signal;
+mod smatrix;
+#[cfg(feature="std")]
+mod stats;
+mod stencil;
+mod storage;
+mod structure;
+#[cfg(test)]
+mod testing;
+mod tridiagonal;
+#[doc(hidden)]
+pub mod ui;
+mod view;
+
+use cast::CastFrom;
+use error::TensorError;
+use float::Float;
+use kernel::Kernel;
+use layout::Layout;
+use linalg::MatrixKernel;
+use order::TotalOrder;
+use storage::Storage;
+use storage::UninitStorage;
+use view::TensorView;
 
 type Idx=usize;
 type Dim<const N: Idx>=[Idx;N];
 
 trait Operand: Clone {}
-trait Scalar: Operand + Default + AddAssign {}
+trait Scalar: Operand + Default + PartialEq + Add<Output=Self> + AddAssign + Mul<Output=Self> + MulAssign + Kernel + MatrixKernel {}
 
 trait Dimension: Sized
 {
   type D: Sized;
   fn index(self, ind: Self::D) -> Idx;
+  fn checked_index(self, ind: Self::D) -> Idx;
+  fn unravel(self, ind: Idx) -> Self::D;
   fn size(self) -> Idx;
 }
 
@@ -40,6 +140,30 @@ impl<const N: Idx> Dimension for Dim<N>
       })
   }
 
+  /// Row-major position of the element at `ind`, panicking if `ind` is out of bounds along any axis
+  /// rather than reading another element.
+  fn checked_index(self, ind: Dim<N>) -> Idx
+  {
+    for axis in 0..N
+    {
+      if self[axis]==0 { panic!("The tensor is empty along axis {} and cannot be indexed.",axis) }
+      if ind[axis]>=self[axis] { panic!("The index {} is out of bounds for axis {} of length {}.",ind[axis],axis,self[axis]) }
+    }
+    self.index(ind)
+  }
+
+  /// Multi-index of the element at the row-major position `ind`, the inverse of `index`.
+  fn unravel(self, mut ind: Idx) -> Dim<N>
+  {
+    let mut res: Dim<N>=[0;N];
+    for axis in (0..N).rev()
+    {
+      res[axis]=ind%self[axis];
+      ind/=self[axis];
+    }
+    res
+  }
+
   fn size(self) -> Idx
   {
     self.iter()
@@ -51,253 +175,1169 @@ impl Operand for f32 {}
 impl Operand for f64 {}
 impl Operand for &f32 {}
 impl Operand for &f64 {}
+impl Operand for i8 {}
+impl Operand for i16 {}
+impl Operand for i32 {}
+impl Operand for i64 {}
+impl Operand for u8 {}
+impl Operand for u16 {}
+impl Operand for u32 {}
+impl Operand for u64 {}
+impl Operand for &i8 {}
+impl Operand for &i16 {}
+impl Operand for &i32 {}
+impl Operand for &i64 {}
+impl Operand for &u8 {}
+impl Operand for &u16 {}
+impl Operand for &u32 {}
+impl Operand for &u64 {}
 
 impl Scalar for f32 {}
 impl Scalar for f64 {}
-
-struct Tensor<T: Scalar, const N: Idx>
+impl Scalar for i8 {}
+impl Scalar for i16 {}
+impl Scalar for i32 {}
+impl Scalar for i64 {}
+impl Scalar for u8 {}
+impl Scalar for u16 {}
+impl Scalar for u32 {}
+impl Scalar for u64 {}
+
+#[cfg(feature="half")]
+impl Operand for half::f16 {}
+#[cfg(feature="half")]
+impl Operand for half::bf16 {}
+#[cfg(feature="half")]
+impl Operand for &half::f16 {}
+#[cfg(feature="half")]
+impl Operand for &half::bf16 {}
+#[cfg(feature="half")]
+impl Scalar for half::f16 {}
+#[cfg(feature="half")]
+impl Scalar for half::bf16 {}
+
+struct Tensor<T, const N: Idx>
 {
-  data: Box<[T]>,
+  data: Storage<T>,
   dim: Dim<N>,
+  layout: Layout,
+  names: Option<[&'static str;N]>,
 }
 
 impl<T,const N: Idx> Tensor<T,N>
-where T: Scalar
 {
-  fn new(dim: Dim<N>) -> Tensor<T,N>
+  /// Allocates a tensor whose elements are left uninitialised until `UninitTensor::init` writes them.
+  ///
+  /// Constructors that overwrite every element anyway use this to avoid writing them twice.
+  fn new_uninit(dim: Dim<N>) -> UninitTensor<T,N>
   {
-    let size: usize=dim.size();
-    let data: Box<[T]>=vec![T::default();size].into_boxed_slice();
-    Tensor{data:data,dim:dim}
+    UninitTensor{data:UninitStorage::new(dim.size()),dim:dim}
   }
-}
 
-impl<T,const N: Idx> Index<Dim<N>> for Tensor<T,N>
-where T: Scalar
-{
-  type Output=T;
-  fn index(&self, ind: Dim<N>) -> &Self::Output
+  /// Creates a tensor whose element at every index is `f` applied to that index.
+  fn from_fn<F>(dim: Dim<N>, f: F) -> Tensor<T,N>
+  where F: FnMut(Dim<N>) -> T
   {
-    &self.data[self.dim.index(ind)]
+    Tensor::<T,N>::new_uninit(dim).init(Indices::new(dim).map(f))
   }
-}
 
-impl<T> Index<Idx> for Tensor<T,1>
-where T: Scalar
-{
-  type Output=T;
-  fn index(&self, ind: Idx) -> &Self::Output
+  /// Creates a tensor of the same dimensions with `f` applied to every element.
+  fn map<U,F>(&self, f: F) -> Tensor<U,N>
+  where F: FnMut(&T) -> U
+  {
+    let mut t: Tensor<U,N>=Tensor::<U,N>::new_uninit(self.dim).init(self.data.iter().map(f));
+    t.layout=self.layout;
+    t.names=self.names;
+    t
+  }
+
+  /// Creates a tensor of the same dimensions with `f` applied to every element, as `map` does, but
+  /// stops at and returns the first error `f` returns, without applying it to the remaining elements.
+  fn try_map<U,E,F>(&self, f: F) -> Result<Tensor<U,N>,E>
+  where F: FnMut(&T) -> Result<U,E>
   {
-    &self.data[ind]
+    let data: Vec<U>=self.data.iter().map(f).collect::<Result<Vec<U>,E>>()?;
+    Ok(Tensor{data:data.into(),dim:self.dim,layout:self.layout,names:self.names})
   }
-}
 
-impl<T,const N: Idx> IndexMut<Dim<N>> for Tensor<T,N>
-where T: Scalar
-{
-  fn index_mut(&mut self, ind: Dim<N>) -> &mut Self::Output
+  /// Multi-index of the first element in row-major order, whatever the layout, for which `pred` holds,
+  /// without testing the elements after it.
+  fn position<P>(&self, mut pred: P) -> Option<Dim<N>>
+  where P: FnMut(&T) -> bool
   {
-    &mut self.data[self.dim.index(ind)]
+    self.indexed_iter().find(|(_,elem)| pred(elem)).map(|(ind,_)| ind)
   }
-}
 
-impl<T> IndexMut<Idx> for Tensor<T,1>
-where T: Scalar
-{
-  fn index_mut(&mut self, ind: Idx) -> &mut Self::Output
+  /// Number of elements, which is zero if any axis has length zero.
+  fn len(&self) -> Idx
+  {
+    self.data.len()
+  }
+
+  /// Whether the tensor has no elements.
+  fn is_empty(&self) -> bool
+  {
+    self.data.is_empty()
+  }
+
+  /// Iterator over the elements in the order they are stored, which is row-major unless the tensor
+  /// has a column-major layout.
+  fn iter(&self) -> core::slice::Iter<'_,T>
+  {
+    self.data.iter()
+  }
+
+  /// Iterator over the multi-indices and elements in row-major order, whatever the layout.
+  fn indexed_iter(&self) -> IndexedIter<'_,T,N>
+  {
+    IndexedIter{tensor:self,indices:Indices::new(self.dim)}
+  }
+
+  /// Iterator over the elements in the reverse of row-major order, whatever the layout.
+  fn iter_rev(&self) -> impl Iterator<Item=&T>
+  {
+    self.indexed_iter().rev().map(|(_,elem)| elem)
+  }
+
+  /// Creates a tensor with dimensions `dim` that takes ownership of `data`, which must hold exactly as
+  /// many elements as the dimensions describe, in row-major order.
+  fn from_parts(dim: Dim<N>, data: Box<[T]>) -> Result<Tensor<T,N>,TensorError>
+  {
+    if dim.size()!=data.len() { return Err(TensorError::SizeMismatch{size:dim.size(),len:data.len()}); }
+
+    Ok(Tensor{data:data.into(),dim:dim,layout:Layout::RowMajor,names:None})
+  }
+
+  /// Creates a tensor with dimensions `dim` that takes ownership of the row-major elements `data`, as
+  /// `from_parts` does, reusing its allocation whatever its spare capacity unless it holds at most
+  /// `INLINE_LEN` elements, which are moved into the tensor itself.
+  fn from_vec(dim: Dim<N>, data: Vec<T>) -> Result<Tensor<T,N>,TensorError>
   {
-    &mut self.data[ind]
+    if dim.size()!=data.len() { return Err(TensorError::SizeMismatch{size:dim.size(),len:data.len()}); }
+
+    Ok(Tensor{data:data.into(),dim:dim,layout:Layout::RowMajor,names:None})
+  }
+
+  /// Pointer to the first element, for passing the elements to foreign code.
+  ///
+  /// The `element_count` elements are contiguous, in the order given by `layout`. More than
+  /// `INLINE_LEN` elements are stored in a single allocation, and the pointer is valid until the
+  /// tensor is mutated or dropped. Up to `INLINE_LEN` elements are held in the tensor itself, so the
+  /// pointer is also invalidated when the tensor is moved, and should only be taken once it is in
+  /// place.
+  fn as_ptr(&self) -> *const T
+  {
+    self.data.as_ptr()
+  }
+
+  /// Number of elements behind `as_ptr`, which is `len`.
+  fn element_count(&self) -> Idx
+  {
+    self.data.len()
+  }
+
+  /// Exchanges the elements of `self` and `other` by swapping their buffers, failing unless both are
+  /// of the same dimensions. No element is moved, unless up to `INLINE_LEN` of them are held in the
+  /// tensors themselves.
+  fn swap_with(&mut self, other: &mut Tensor<T,N>) -> Result<(),TensorError>
+  {
+    if self.dim!=other.dim { return Err(TensorError::DimMismatch{lhs:self.dim.to_vec(),rhs:other.dim.to_vec()}); }
+
+    core::mem::swap(self,other);
+    Ok(())
+  }
+
+  /// Whether `self` and `other` currently share their element buffer.
+  #[cfg(test)]
+  fn shares_data(&self, other: &Tensor<T,N>) -> bool
+  {
+    self.data.ptr_eq(&other.data)
   }
 }
 
-impl<T,const N: Idx> Clone for Tensor<T,N>
-where T: Scalar
+impl<T,const N: Idx> Tensor<T,N>
+where T: Clone
 {
-  fn clone(&self) -> Tensor<T,N>
+  /// Mutable pointer to the first element, with the guarantees of `as_ptr`.
+  ///
+  /// The elements are copied first if they are shared with another tensor, so writes through the
+  /// pointer are only seen by `self`.
+  fn as_mut_ptr(&mut self) -> *mut T
   {
-    let mut t: Tensor<T,N>=Tensor::<T,N>::new(self.dim);
-    t.data=self.data.clone();
-    t
+    self.data.as_mut_ptr()
+  }
+
+  /// Dimensions and elements of the tensor, in the order they are stored.
+  ///
+  /// The elements are returned without copying unless they are shared with another tensor, through a
+  /// clone or `split_first_axis`, or are few enough to be held in the tensor itself.
+  fn into_parts(self) -> (Dim<N>,Box<[T]>)
+  {
+    (self.dim,self.data.into_boxed_slice())
+  }
+
+  /// One dimensional tensor of all elements in row-major order, reusing the element buffer unless the
+  /// tensor has a column-major layout.
+  fn flatten(self) -> Tensor<T,1>
+  {
+    let t: Tensor<T,N>=self.into_layout(Layout::RowMajor);
+    Tensor{dim:[t.dim.size()],data:t.data,layout:Layout::RowMajor,names:None}
+  }
+
+  /// Splits the tensor along its first axis into the first `at` entries and the rest.
+  ///
+  /// Both halves are contiguous ranges of the original buffer and keep sharing it rather than being
+  /// copied. Mutating a half while the other is still alive copies the mutated half only, the first
+  /// time it is mutated. A tensor with a column-major layout is copied to row-major order first.
+  fn split_first_axis(self, at: Idx) -> (Tensor<T,N>,Tensor<T,N>)
+  {
+    if N==0 || at>self.dim[0] { panic!("The split index must not exceed the length of the first axis to split a tensor.") }
+
+    let t: Tensor<T,N>=self.into_layout(Layout::RowMajor);
+
+    let stride: Idx=t.dim[1..].iter().product();
+    let (head,tail): (Storage<T>,Storage<T>)=t.data.split_at(at*stride);
+    let mut head_dim: Dim<N>=t.dim;
+    let mut tail_dim: Dim<N>=t.dim;
+    head_dim[0]=at;
+    tail_dim[0]-=at;
+    (Tensor{data:head,dim:head_dim,layout:Layout::RowMajor,names:t.names},Tensor{data:tail,dim:tail_dim,layout:Layout::RowMajor,names:t.names})
+  }
+
+  /// Iterator that consumes the tensor and yields the subtensors at every index along the first axis,
+  /// of rank `M`, which must be one less than `N`.
+  ///
+  /// As with `split_first_axis` every subtensor is a contiguous range of the original buffer and
+  /// shares it rather than being copied, and a tensor with a column-major layout is copied to
+  /// row-major order first. The names of the other axes are kept.
+  fn into_outer_iter<const M: Idx>(self) -> OuterIter<T,M>
+  {
+    if N==0 || M+1!=N { panic!("The rank of the subtensors must be one less than that of the tensor to iterate over its first axis.") }
+
+    let t: Tensor<T,N>=self.into_layout(Layout::RowMajor);
+    let mut dim: Dim<M>=[0;M];
+    dim.copy_from_slice(&t.dim[1..]);
+    let names: Option<[&'static str;M]>=t.names.map(|names| { let mut res: [&'static str;M]=["";M]; res.copy_from_slice(&names[1..]); res });
+    OuterIter{data:Some(t.data),dim:dim,names:names,len:t.dim[0]}
   }
 }
 
-impl<T,const N: Idx> AddAssign for Tensor<T,N>
+impl<T,const N: Idx> Tensor<T,N>
 where T: Scalar
 {
-  fn add_assign(&mut self, rhs: Self)
+  fn new(dim: Dim<N>) -> Tensor<T,N>
   {
-    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    let size: usize=dim.size();
+    // Few enough elements to be held inline are written in place rather than into a buffer to move
+    // them out of.
+    if size<=storage::INLINE_LEN { return Tensor::<T,N>::new_uninit(dim).init((0..size).map(|_| T::default())); }
+    let data: Box<[T]>=vec![T::default();size].into_boxed_slice();
+    Tensor{data:data.into(),dim:dim,layout:Layout::RowMajor,names:None}
+  }
+
+  /// Elements of the tensor, leaving a tensor of default elements of the same dimensions in their
+  /// place, without moving any element unless they are few enough to be held in the tensor itself.
+  fn take(&mut self) -> Tensor<T,N>
+  {
+    core::mem::replace(self,Tensor::<T,N>::new(self.dim))
+  }
+
+  /// Creates a tensor of default elements as `new` does, failing if any axis has length zero unless
+  /// `allow_empty` is set.
+  fn try_new(dim: Dim<N>, allow_empty: bool) -> Result<Tensor<T,N>,TensorError>
+  {
+    match dim.iter().position(|&len| len==0)
     {
-      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to add them.")}
+      Some(axis) if !allow_empty => Err(TensorError::EmptyAxis{axis:axis}),
+      _ => Ok(Tensor::<T,N>::new(dim)),
     }
+  }
+
+  /// Sum of all elements, which is zero for an empty tensor.
+  ///
+  /// Half precision elements are accumulated in `f32`.
+  fn sum(&self) -> T
+  {
+    T::sum_slice(&self.data)
+  }
 
-    for (this,other) in self.data.iter_mut().zip(rhs.data.iter())
+  /// Sum of all elements, each converted to and added up in the wider type `A`.
+  fn sum_acc<A>(&self) -> A
+  where A: Scalar + CastFrom<T>
+  {
+    self.data.iter().fold(A::default(),|sum,elem| sum+A::cast_from(elem.clone()))
+  }
+
+  /// Element-wise `self*a+b`.
+  ///
+  /// Float elements are rounded only once, after the addition, when `std` is available.
+  fn mul_add(&self, a: &Tensor<T,N>, b: &Tensor<T,N>) -> Tensor<T,N>
+  {
+    for ((dim1,dim2),dim3) in self.dim.iter().zip(a.dim.iter()).zip(b.dim.iter())
     {
-      *this+=other.clone();
+      if dim1!=dim2 || dim1!=dim3 { panic!("All dimensions of three tensors must be of the same size to multiply and add them.")}
     }
+
+    let (a,b): (Tensor<T,N>,Tensor<T,N>)=(a.to_layout(self.layout),b.to_layout(self.layout));
+    let mut res: Tensor<T,N>=self.clone();
+    T::mul_add_slice(&mut res.data,&a.data,&b.data);
+    res
   }
-}
 
-impl<T,const N: Idx> AddAssign<&Tensor<T,N>> for Tensor<T,N>
-where T: Scalar
-{
-  fn add_assign(&mut self, rhs: &Self)
+  /// Adds `alpha*x` to `self` in place, without allocating a temporary for the product.
+  fn axpy(&mut self, alpha: T, x: &Tensor<T,N>)
   {
-    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter() )
+    for (dim1,dim2) in self.dim.iter().zip(x.dim.iter())
     {
       if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to add them.")}
     }
 
-    for (this,other) in self.data.iter_mut().zip(rhs.data.iter())
+    let x: Tensor<T,N>=x.to_layout(self.layout);
+    T::axpy_slice(&mut self.data,&alpha,&x.data);
+  }
+
+  /// Whether every element is finite, that is neither infinite nor NaN.
+  fn is_finite(&self) -> bool
+  {
+    T::first_non_finite(&self.data).is_none()
+  }
+
+  /// Whether any element is NaN.
+  fn has_nan(&self) -> bool
+  {
+    T::first_nan(&self.data).is_some()
+  }
+
+  /// Checks that every element is finite, reporting the multi-index and value of the first element
+  /// that is not otherwise.
+  ///
+  /// Integer elements are always finite, for which this does not read the elements at all.
+  fn assert_finite(&self) -> Result<(),TensorError>
+  {
+    match T::first_non_finite(&self.data)
     {
-      *this+=other.clone();
+      Some((pos,value)) => Err(TensorError::NonFinite{index:self.unravel_offset(pos).to_vec(),value:value}),
+      None => Ok(()),
     }
   }
 }
 
-impl<T,U,const N: Idx> AddAssign<U> for Tensor<T,N>
-where T: Scalar + AddAssign<U>, U: Operand
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
 {
-  fn add_assign(&mut self, rhs: U)
+  /// Mean of all elements, which is NaN for an empty tensor.
+  ///
+  /// Half precision elements are accumulated and divided in `f32`.
+  fn mean(&self) -> T
   {
-    self.data.iter_mut().for_each(|this| *this+=rhs.clone());
+    T::mean_slice(&self.data)
   }
 }
 
-impl<T,const N: Idx> Add<T> for Tensor<T,N>
-where T: Scalar
+impl<const N: Idx> Tensor<f32,N>
 {
-  type Output=Self;
-  fn add(mut self, rhs: T) -> Self::Output
+  /// Sum of all elements accumulated in `f64`.
+  fn sum_f64(&self) -> f64
   {
-    self+=rhs;
-    self
+    self.sum_acc()
   }
 }
 
-impl<T,const N: Idx> Add for Tensor<T,N>
-where T: Scalar
+/// Tensor whose elements have been allocated but not yet written.
+struct UninitTensor<T, const N: Idx>
 {
-  type Output=Self;
-  fn add(mut self, rhs: Self) -> Self::Output
-  {
-    self+=rhs;
-    self
-  }
+  data: UninitStorage<T>,
+  dim: Dim<N>,
 }
 
-impl<T,const N: Idx> Add for &Tensor<T,N>
-where T: Scalar
+impl<T,const N: Idx> UninitTensor<T,N>
 {
-  type Output=Tensor<T,N>;
-  fn add(self, rhs: Self) -> Self::Output
+  /// Writes the elements yielded by `elems` in row-major order, which must be exactly as many as
+  /// the tensor holds.
+  fn init<I>(self, elems: I) -> Tensor<T,N>
+  where I: IntoIterator<Item=T>
   {
-    let mut t: Tensor<T,N>=self.clone();
-    t+=rhs;
-    t
+    Tensor{data:self.data.init(elems),dim:self.dim,layout:Layout::RowMajor,names:None}
   }
 }
 
-impl<T,const N: Idx> Add<Tensor<T,N>> for &Tensor<T,N>
-where T: Scalar
+/// Iterator over all indices of a tensor with dimensions `dim` in row-major order, from either end.
+struct Indices<const N: Idx>
 {
-  type Output=Tensor<T,N>;
-  fn add(self, rhs: Tensor<T,N>) -> Self::Output
-  {
-    let mut t: Tensor<T,N>=self.clone();
-    t+=rhs;
-    t
-  }
+  dim: Dim<N>,
+  front: Dim<N>,
+  back: Dim<N>,
+  /// Number of indices not yet returned from either end.
+  remaining: Idx,
 }
 
-impl<T,const N: Idx> Add<&Tensor<T,N>> for Tensor<T,N>
-where T: Scalar
+impl<const N: Idx> Indices<N>
 {
-  type Output=Tensor<T,N>;
-  fn add(self, rhs: &Self) -> Self::Output
+  fn new(dim: Dim<N>) -> Indices<N>
   {
-    let mut t: Tensor<T,N>=self.clone();
-    t+=rhs;
-    t
+    let mut back: Dim<N>=[0;N];
+    back.iter_mut().zip(dim.iter()).for_each(|(back,&len)| *back=len.saturating_sub(1));
+    Indices{dim:dim,front:[0;N],back:back,remaining:dim.size()}
   }
 }
 
+impl<const N: Idx> Iterator for Indices<N>
+{
+  type Item=Dim<N>;
+  fn next(&mut self) -> Option<Self::Item>
+  {
+    if self.remaining==0 { return None; }
+    self.remaining-=1;
+    let current: Dim<N>=self.front;
+    for axis in (0..N).rev()
+    {
+      self.front[axis]+=1;
+      if self.front[axis]<self.dim[axis] { break; }
+      self.front[axis]=0;
+    }
+    Some(current)
+  }
 
-//
-// Tests
-//
+  fn size_hint(&self) -> (Idx,Option<Idx>)
+  {
+    (self.remaining,Some(self.remaining))
+  }
+}
 
-#[cfg(test)]
-mod tensor_tests
+impl<const N: Idx> DoubleEndedIterator for Indices<N>
 {
-  use super::*;
-  use rstest::rstest;
-
-  macro_rules! tensor_test_new {
-    ($size:literal,$type:ty,$init:expr,$dim_tst:ident,$dim_attr:meta,$size_tst:ident,$size_attr:meta,$init_tst:ident,$init_attr:meta) => {
-      #[$dim_attr]
-      fn $dim_tst(dim: Dim<$size>)
-      {
-        let t: Tensor<$type,$size>=Tensor::<$type,$size>::new(dim);
-        assert!(t.dim==dim);
-      }
-      #[$size_attr]
-      fn $size_tst(dim: Dim<$size>, expected_data_len: usize)
-      {
-        let t: Tensor<$type,$size>=Tensor::<$type,$size>::new(dim);
-        assert!(t.data.len()==expected_data_len);
-      }
-      #[$init_attr]
-      fn $init_tst(dim: Dim<$size>)
+  fn next_back(&mut self) -> Option<Self::Item>
+  {
+    if self.remaining==0 { return None; }
+    self.remaining-=1;
+    let current: Dim<N>=self.back;
+    for axis in (0..N).rev()
+    {
+      if self.back[axis]>0
       {
-        let t: Tensor<$type,$size>=Tensor::<$type,$size>::new(dim);
-        for &elem in t.data.iter()
-        {
-          assert!(elem==$init);
-        }
+        self.back[axis]-=1;
+        break;
       }
-    };
+      self.back[axis]=self.dim[axis].saturating_sub(1);
+    }
+    Some(current)
   }
+}
 
-  tensor_test_new!(1,f64,0f64
-    ,tensor_test_new_dim_1d,rstest(dim,case([2]),case([3]),case([4]))
-    ,tensor_test_new_size_1d,rstest(dim,expected_data_len,case([2],2),case([3],3),case([4],4))
-    ,tensor_test_new_init_1d,rstest(dim,case([4]),case([5]))
-  );
+impl<const N: Idx> ExactSizeIterator for Indices<N>
+{}
 
-  tensor_test_new!(2,f64,0f64
-    ,tensor_test_new_dim_2d,rstest(dim,case([2,2]),case([3,3]),case([4,4]))
-    ,tensor_test_new_size_2d,rstest(dim,expected_data_len,case([2,3],6),case([3,4],12),case([4,5],20))
-    ,tensor_test_new_init_2d,rstest(dim,case([7,3]),case([4,9]))
-  );
+/// Iterator over the elements of a tensor together with their multi-indices, in row-major order
+/// whatever the layout, returned by `Tensor::indexed_iter`.
+struct IndexedIter<'a,T,const N: Idx>
+{
+  tensor: &'a Tensor<T,N>,
+  indices: Indices<N>,
+}
 
-  tensor_test_new!(3,f64,0f64
-    ,tensor_test_new_dim_3d,rstest(dim,case([2,4,6]),case([3,5,7]),case([1,1,1]))
-    ,tensor_test_new_size_3d,rstest(dim,expected_data_len,case([2,3,4],24),case([3,4,5],60),case([4,5,6],120))
-    ,tensor_test_new_init_3d,rstest(dim,case([7,3,5]),case([4,9,2]))
-  );
+impl<'a,T,const N: Idx> Iterator for IndexedIter<'a,T,N>
+{
+  type Item=(Dim<N>,&'a T);
+  fn next(&mut self) -> Option<Self::Item>
+  {
+    let ind: Dim<N>=self.indices.next()?;
+    Some((ind,&self.tensor[ind]))
+  }
 
-  #[test]
-  fn tensor_test_index()
+  fn size_hint(&self) -> (Idx,Option<Idx>)
   {
-    let t: Tensor<f64,3>=Tensor::<f64,3>::new([2,4,3]);
-    for itr in 0..2
-    {
-      for jtr in 0..4
-      {
-        for ktr in 0..3
-        {
-          assert!(t[[itr,jtr,ktr]]==0f64);
-        }
-      }
-    }
+    self.indices.size_hint()
   }
+}
 
-  #[test]
-  fn tensor_test_index_mut()
+impl<'a,T,const N: Idx> DoubleEndedIterator for IndexedIter<'a,T,N>
+{
+  fn next_back(&mut self) -> Option<Self::Item>
   {
-    let mut t: Tensor<f64,1>=Tensor::<f64,1>::new([5]);
-    t[[1]]=3.14;
+    let ind: Dim<N>=self.indices.next_back()?;
+    Some((ind,&self.tensor[ind]))
+  }
+}
+
+impl<'a,T,const N: Idx> ExactSizeIterator for IndexedIter<'a,T,N>
+{}
+
+/// Iterator over the subtensors along the first axis of a tensor it has consumed, returned by
+/// `Tensor::into_outer_iter`.
+struct OuterIter<T,const M: Idx>
+{
+  /// Range of the buffer holding the subtensors not yet yielded from either end.
+  data: Option<Storage<T>>,
+  dim: Dim<M>,
+  names: Option<[&'static str;M]>,
+  len: Idx,
+}
+
+impl<T,const M: Idx> OuterIter<T,M>
+{
+  fn subtensor(&self, data: Storage<T>) -> Tensor<T,M>
+  {
+    Tensor{data:data,dim:self.dim,layout:Layout::RowMajor,names:self.names}
+  }
+}
+
+impl<T,const M: Idx> Iterator for OuterIter<T,M>
+{
+  type Item=Tensor<T,M>;
+  fn next(&mut self) -> Option<Self::Item>
+  {
+    if self.len==0 { return None; }
+    self.len-=1;
+    let (head,tail): (Storage<T>,Storage<T>)=self.data.take()?.split_at(self.dim.size());
+    self.data=Some(tail);
+    Some(self.subtensor(head))
+  }
+
+  fn size_hint(&self) -> (Idx,Option<Idx>)
+  {
+    (self.len,Some(self.len))
+  }
+}
+
+impl<T,const M: Idx> DoubleEndedIterator for OuterIter<T,M>
+{
+  fn next_back(&mut self) -> Option<Self::Item>
+  {
+    if self.len==0 { return None; }
+    self.len-=1;
+    let (head,tail): (Storage<T>,Storage<T>)=self.data.take()?.split_at(self.len*self.dim.size());
+    self.data=Some(head);
+    Some(self.subtensor(tail))
+  }
+}
+
+impl<T,const M: Idx> ExactSizeIterator for OuterIter<T,M>
+{}
+
+impl<T,const N: Idx> Index<Dim<N>> for Tensor<T,N>
+{
+  type Output=T;
+  fn index(&self, ind: Dim<N>) -> &Self::Output
+  {
+    &self.data[self.offset(ind)]
+  }
+}
+
+impl<T> Index<Idx> for Tensor<T,1>
+{
+  type Output=T;
+  fn index(&self, ind: Idx) -> &Self::Output
+  {
+    &self.data[self.dim.checked_index([ind])]
+  }
+}
+
+impl<T,const N: Idx> IndexMut<Dim<N>> for Tensor<T,N>
+where T: Clone
+{
+  fn index_mut(&mut self, ind: Dim<N>) -> &mut Self::Output
+  {
+    let offset: Idx=self.offset(ind);
+    &mut self.data[offset]
+  }
+}
+
+impl<T> IndexMut<Idx> for Tensor<T,1>
+where T: Clone
+{
+  fn index_mut(&mut self, ind: Idx) -> &mut Self::Output
+  {
+    &mut self.data[self.dim.checked_index([ind])]
+  }
+}
+
+impl<T,const N: Idx> Clone for Tensor<T,N>
+where T: Clone
+{
+  /// Returns a tensor sharing the element buffer of `self`, which is only copied once either of the
+  /// two is mutated, or with a copy of the elements if they are few enough to be held inline.
+  fn clone(&self) -> Tensor<T,N>
+  {
+    Tensor{data:self.data.clone(),dim:self.dim,layout:self.layout,names:self.names}
+  }
+}
+
+impl<T,const N: Idx> PartialEq for Tensor<T,N>
+where T: Clone + PartialEq
+{
+  /// Whether both tensors are of the same dimensions and hold equal elements at every index, whatever
+  /// their layouts.
+  fn eq(&self, other: &Tensor<T,N>) -> bool
+  {
+    if self.dim!=other.dim { return false; }
+
+    let other: Tensor<T,N>=other.to_layout(self.layout);
+    self.data[..]==other.data[..]
+  }
+}
+
+impl<T,const N: Idx> Eq for Tensor<T,N>
+where T: Clone + Eq
+{}
+
+impl<T,const N: Idx> Hash for Tensor<T,N>
+where T: Clone + Hash
+{
+  /// Hashes the dimensions and then the elements in row-major order, so tensors that are equal hash
+  /// equally whatever their layouts.
+  fn hash<H: Hasher>(&self, state: &mut H)
+  {
+    self.dim.hash(state);
+    self.to_layout(Layout::RowMajor).iter().for_each(|elem| elem.hash(state));
+  }
+}
+
+impl<T,const N: Idx> AddAssign for Tensor<T,N>
+where T: Scalar
+{
+  fn add_assign(&mut self, rhs: Self)
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    {
+      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to add them.")}
+    }
+
+    self.adopt_names(&rhs);
+    let rhs: Tensor<T,N>=rhs.into_layout(self.layout);
+    T::add_assign_slice(&mut self.data,&rhs.data);
+  }
+}
+
+impl<T,const N: Idx> AddAssign<&Tensor<T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  fn add_assign(&mut self, rhs: &Self)
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter() )
+    {
+      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to add them.")}
+    }
+
+    self.adopt_names(rhs);
+    let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+    T::add_assign_slice(&mut self.data,&rhs.data);
+  }
+}
+
+impl<T,U,const N: Idx> AddAssign<U> for Tensor<T,N>
+where T: Scalar + AddAssign<U>, U: Operand
+{
+  fn add_assign(&mut self, rhs: U)
+  {
+    self.data.iter_mut().for_each(|this| *this+=rhs.clone());
+  }
+}
+
+/// Implements `$op` between a tensor and a scalar for owned and borrowed tensors and scalars, through
+/// the compound assignment `$assign`. A borrowed tensor is cloned once to hold the result.
+macro_rules! impl_scalar_op {
+  ($op:ident,$fn:ident,$assign:tt$(,$bound:ident)?) => {
+    impl<T,const N: Idx> $op<T> for Tensor<T,N>
+    where T: Scalar $(+ $bound)?
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(mut self, rhs: T) -> Self::Output
+      {
+        self $assign rhs;
+        self
+      }
+    }
+
+    impl<T,const N: Idx> $op<&T> for Tensor<T,N>
+    where T: Scalar $(+ $bound)?
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(mut self, rhs: &T) -> Self::Output
+      {
+        self $assign rhs.clone();
+        self
+      }
+    }
+
+    impl<T,const N: Idx> $op<T> for &Tensor<T,N>
+    where T: Scalar $(+ $bound)?
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(self, rhs: T) -> Self::Output
+      {
+        let mut t: Tensor<T,N>=self.clone();
+        t $assign rhs;
+        t
+      }
+    }
+
+    impl<T,const N: Idx> $op<&T> for &Tensor<T,N>
+    where T: Scalar $(+ $bound)?
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(self, rhs: &T) -> Self::Output
+      {
+        let mut t: Tensor<T,N>=self.clone();
+        t $assign rhs.clone();
+        t
+      }
+    }
+  };
+}
+
+impl_scalar_op!(Add,add,+=);
+
+impl<T,const N: Idx> Add for Tensor<T,N>
+where T: Scalar
+{
+  type Output=Self;
+  fn add(mut self, rhs: Self) -> Self::Output
+  {
+    self+=rhs;
+    self
+  }
+}
+
+impl<T,const N: Idx> Add for &Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn add(self, rhs: Self) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t+=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> Add<Tensor<T,N>> for &Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn add(self, rhs: Tensor<T,N>) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t+=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> Add<&Tensor<T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn add(self, rhs: &Self) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t+=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> MulAssign for Tensor<T,N>
+where T: Scalar
+{
+  fn mul_assign(&mut self, rhs: Self)
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    {
+      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to multiply them.")}
+    }
+
+    self.adopt_names(&rhs);
+    let rhs: Tensor<T,N>=rhs.into_layout(self.layout);
+    T::mul_assign_slice(&mut self.data,&rhs.data);
+  }
+}
+
+impl<T,const N: Idx> MulAssign<&Tensor<T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  fn mul_assign(&mut self, rhs: &Self)
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    {
+      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to multiply them.")}
+    }
+
+    self.adopt_names(rhs);
+    let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+    T::mul_assign_slice(&mut self.data,&rhs.data);
+  }
+}
+
+impl<T,U,const N: Idx> MulAssign<U> for Tensor<T,N>
+where T: Scalar + MulAssign<U>, U: Operand
+{
+  fn mul_assign(&mut self, rhs: U)
+  {
+    self.data.iter_mut().for_each(|this| *this*=rhs.clone());
+  }
+}
+
+impl_scalar_op!(Mul,mul,*=);
+
+impl<T,const N: Idx> Mul for Tensor<T,N>
+where T: Scalar
+{
+  type Output=Self;
+  fn mul(mut self, rhs: Self) -> Self::Output
+  {
+    self*=rhs;
+    self
+  }
+}
+
+impl<T,const N: Idx> Mul for &Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn mul(self, rhs: Self) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t*=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> Mul<Tensor<T,N>> for &Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn mul(self, rhs: Tensor<T,N>) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t*=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> Mul<&Tensor<T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn mul(mut self, rhs: &Self) -> Self::Output
+  {
+    self*=rhs;
+    self
+  }
+}
+
+impl<T,const N: Idx> RemAssign for Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  fn rem_assign(&mut self, rhs: Self)
+  {
+    *self%=&rhs;
+  }
+}
+
+impl<T,const N: Idx> RemAssign<&Tensor<T,N>> for Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  fn rem_assign(&mut self, rhs: &Self)
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    {
+      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to take the remainder of them.")}
+    }
+
+    self.adopt_names(rhs);
+    let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+    self.data.iter_mut().zip(rhs.data.iter()).for_each(|(this,other)| *this%=other.clone());
+  }
+}
+
+impl<T,U,const N: Idx> RemAssign<U> for Tensor<T,N>
+where T: Scalar + RemAssign<U>, U: Operand
+{
+  fn rem_assign(&mut self, rhs: U)
+  {
+    self.data.iter_mut().for_each(|this| *this%=rhs.clone());
+  }
+}
+
+impl_scalar_op!(Rem,rem,%=,RemAssign);
+
+impl<T,const N: Idx> Rem for Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  type Output=Self;
+  fn rem(mut self, rhs: Self) -> Self::Output
+  {
+    self%=rhs;
+    self
+  }
+}
+
+impl<T,const N: Idx> Rem for &Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  type Output=Tensor<T,N>;
+  fn rem(self, rhs: Self) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t%=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> Rem<Tensor<T,N>> for &Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  type Output=Tensor<T,N>;
+  fn rem(self, rhs: Tensor<T,N>) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t%=rhs;
+    t
+  }
+}
+
+impl<T,const N: Idx> Rem<&Tensor<T,N>> for Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  type Output=Tensor<T,N>;
+  fn rem(mut self, rhs: &Self) -> Self::Output
+  {
+    self%=rhs;
+    self
+  }
+}
+
+
+//
+// no_std
+//
+
+/// Exercises the core of the crate, so that building without the `std` feature checks that it
+/// compiles and testing without it checks that it works.
+#[cfg(not(feature="std"))]
+fn no_std_core() -> (Tensor<f64,2>,alloc::string::String)
+{
+  use core::fmt::Write;
+
+  let t1: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (itr+jtr) as f64);
+  let t2: Tensor<f64,2>=&(&t1+&t1)*&t1;
+  let t3: Tensor<f64,2>=t2.matmul(&t1.transpose());
+
+  let mut message: alloc::string::String=alloc::string::String::new();
+  write!(message,"{}",TensorError::SizeMismatch{size:4,len:3}).unwrap();
+  (t3,message)
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod tensor_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use testing::assert_tensor_eq;
+
+  macro_rules! tensor_test_new {
+    ($size:literal,$type:ty,$init:expr,$dim_tst:ident,$dim_attr:meta,$size_tst:ident,$size_attr:meta,$init_tst:ident,$init_attr:meta) => {
+      #[$dim_attr]
+      fn $dim_tst(dim: Dim<$size>)
+      {
+        let t: Tensor<$type,$size>=Tensor::<$type,$size>::new(dim);
+        assert!(t.dim==dim);
+      }
+      #[$size_attr]
+      fn $size_tst(dim: Dim<$size>, expected_data_len: usize)
+      {
+        let t: Tensor<$type,$size>=Tensor::<$type,$size>::new(dim);
+        assert!(t.data.len()==expected_data_len);
+      }
+      #[$init_attr]
+      fn $init_tst(dim: Dim<$size>)
+      {
+        let t: Tensor<$type,$size>=Tensor::<$type,$size>::new(dim);
+        for &elem in t.data.iter()
+        {
+          assert!(elem==$init);
+        }
+      }
+    };
+  }
+
+  tensor_test_new!(1,f64,0f64
+    ,tensor_test_new_dim_1d,rstest(dim,case([2]),case([3]),case([4]))
+    ,tensor_test_new_size_1d,rstest(dim,expected_data_len,case([2],2),case([3],3),case([4],4))
+    ,tensor_test_new_init_1d,rstest(dim,case([4]),case([5]))
+  );
+
+  tensor_test_new!(2,f64,0f64
+    ,tensor_test_new_dim_2d,rstest(dim,case([2,2]),case([3,3]),case([4,4]))
+    ,tensor_test_new_size_2d,rstest(dim,expected_data_len,case([2,3],6),case([3,4],12),case([4,5],20))
+    ,tensor_test_new_init_2d,rstest(dim,case([7,3]),case([4,9]))
+  );
+
+  tensor_test_new!(3,f64,0f64
+    ,tensor_test_new_dim_3d,rstest(dim,case([2,4,6]),case([3,5,7]),case([1,1,1]))
+    ,tensor_test_new_size_3d,rstest(dim,expected_data_len,case([2,3,4],24),case([3,4,5],60),case([4,5,6],120))
+    ,tensor_test_new_init_3d,rstest(dim,case([7,3,5]),case([4,9,2]))
+  );
+
+  #[test]
+  fn tensor_test_indices()
+  {
+    let ind: Vec<Dim<3>>=Indices::new([2,1,3]).collect();
+    assert!(ind==vec![[0,0,0],[0,0,1],[0,0,2],[1,0,0],[1,0,1],[1,0,2]]);
+
+    assert!(Indices::new([2,0,3]).next().is_none());
+    assert!(Indices::new([]).collect::<Vec<Dim<0>>>()==vec![[]]);
+  }
+
+  #[test]
+  fn tensor_test_indices_rev()
+  {
+    let ind: Vec<Dim<3>>=Indices::new([2,1,3]).rev().collect();
+    assert!(ind==vec![[1,0,2],[1,0,1],[1,0,0],[0,0,2],[0,0,1],[0,0,0]]);
+
+    // The two ends meet in the middle without repeating an index.
+    let mut indices: Indices<2>=Indices::new([2,2]);
+    assert!(indices.len()==4 && indices.next()==Some([0,0]) && indices.next_back()==Some([1,1]));
+    assert!(indices.len()==2 && indices.next_back()==Some([1,0]) && indices.next()==Some([0,1]));
+    assert!(indices.len()==0 && indices.next().is_none() && indices.next_back().is_none());
+
+    assert!(Indices::new([2,0,3]).next_back().is_none() && Indices::new([3,0]).len()==0);
+    assert!(Indices::new([]).rev().collect::<Vec<Dim<0>>>()==vec![[]]);
+  }
+
+  #[test]
+  fn tensor_test_iter_rev()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    for t in [t.clone(),t.to_layout(Layout::ColMajor)].iter()
+    {
+      assert!(t.iter_rev().copied().eq([5.0,4.0,3.0,2.0,1.0,0.0].iter().copied()));
+
+      let forward: Vec<(Dim<2>,f64)>=t.indexed_iter().map(|(ind,&elem)| (ind,elem)).collect();
+      assert!(forward==vec![([0,0],0.0),([0,1],1.0),([0,2],2.0),([1,0],3.0),([1,1],4.0),([1,2],5.0)]);
+      assert!(t.indexed_iter().rev().map(|(ind,&elem)| (ind,elem)).eq(forward.iter().rev().copied()));
+      assert!(t.indexed_iter().len()==6);
+
+      // The last element that is even lies at [1,1].
+      assert!(t.indexed_iter().rev().find(|(_,&elem)| elem%2.0==0.0).map(|(ind,_)| ind)==Some([1,1]));
+      assert!(t.indexed_iter().rposition(|(_,&elem)| elem<3.0)==Some(2));
+      assert!(t.indexed_iter().nth_back(1).map(|(ind,_)| ind)==Some([1,1]));
+      assert!(t.view().iter().rev().copied().eq(t.iter_rev().copied()) && t.view().iter().len()==6);
+    }
+    assert!(Tensor::<f64,2>::new([0,3]).iter_rev().next().is_none());
+  }
+
+  #[test]
+  fn tensor_test_from_fn()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (10*itr+jtr) as f64);
+    assert_tensor_eq!(t,Tensor::<f64,2>::from_rows(&[&[0.0,1.0,2.0],&[10.0,11.0,12.0]]).unwrap());
+
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([0,3],|_| 1f64);
+    assert!(t.data.is_empty());
+  }
+
+  #[test]
+  fn tensor_test_map()
+  {
+    let t1: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| (itr+jtr) as f64);
+    let t2: Tensor<f32,2>=t1.map(|elem| (elem*2.0) as f32);
+    assert_tensor_eq!(t2,Tensor::<f32,2>::from_rows(&[&[0.0,2.0],&[2.0,4.0],&[4.0,6.0]]).unwrap());
+  }
+
+  #[test]
+  fn tensor_test_try_map()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| if itr==1 && jtr==0 { f64::NAN } else { (2*itr+jtr) as f64 });
+    let mut calls: Idx=0;
+    let res: Result<Tensor<i32,2>,TensorError>=t.try_map(|&elem| {
+      calls+=1;
+      if elem.is_nan() { Err(TensorError::NonFinite{index:t.dim.unravel(calls-1).to_vec(),value:elem}) } else { Ok(elem as i32) }
+    });
+    // The elements after the NaN at [1,0] are never visited.
+    match res
+    {
+      Err(TensorError::NonFinite{index,value}) => assert!(index==[1,0] && value.is_nan() && calls==3),
+      _ => panic!("The NaN element was not reported."),
+    }
+
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| (2*itr+jtr) as f64).with_axis_names(["row","col"]);
+    let res: Tensor<i32,2>=t.to_layout(Layout::ColMajor).try_map(|&elem| Ok::<i32,TensorError>(elem as i32)).unwrap();
+    assert!(res.layout==Layout::ColMajor && res.axis_names()==t.axis_names());
+    assert_tensor_eq!(res,Tensor::<i32,2>::from_rows(&[&[0,1],&[2,3],&[4,5]]).unwrap());
+  }
+
+  #[test]
+  fn tensor_test_position()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    for t in [t.clone(),t.to_layout(Layout::ColMajor)].iter()
+    {
+      let mut calls: Idx=0;
+      assert!(t.position(|&elem| { calls+=1; elem>5.0 })==Some([1,2]) && calls==7);
+      let mut calls: Idx=0;
+      assert!(t.position(|&elem| { calls+=1; elem<0.0 }).is_none() && calls==12);
+    }
+    assert!(Tensor::<f64,2>::new([0,3]).position(|_| true).is_none());
+  }
+
+  #[test]
+  fn tensor_test_iter_sum()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+
+    assert!(t.iter().cloned().collect::<Vec<f64>>()==vec![0.0,1.0,2.0,3.0,4.0,5.0]);
+    assert!(t.sum()==15.0);
+    assert!(Tensor::<f64,2>::new([0,3]).sum()==0.0);
+  }
+
+  #[test]
+  fn tensor_test_sum_acc()
+  {
+    let t: Tensor<f32,2>=Tensor::<f32,2>::from_fn([1000,1000],|_| 0.1);
+    let expected: f64=1e6*0.1f32 as f64;
+
+    assert!((t.sum() as f64-expected).abs()>100.0);
+    assert!((t.sum_f64()-expected).abs()<1e-6);
+
+    let t: Tensor<u8,1>=Tensor::<u8,1>::from_fn([3],|_| 200);
+    assert!(t.sum_acc::<u32>()==600);
+  }
+
+  #[test]
+  #[cfg(not(feature="std"))]
+  fn tensor_test_no_std()
+  {
+    let (t,message)=no_std_core();
+
+    assert!(t.iter().cloned().collect::<Vec<f64>>()==vec![2.0,4.0,8.0,18.0]);
+    assert!(message=="A tensor of size 4 cannot be created from 3 elements.");
+  }
+
+  #[test]
+  fn tensor_test_index()
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::new([2,4,3]);
+    assert_tensor_eq!(t,Tensor::<f64,3>::from_fn([2,4,3],|_| 0f64));
+  }
+
+  #[test]
+  #[should_panic(expected="The index 5 is out of bounds for axis 1 of length 3.")]
+  fn tensor_test_index_bounds()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    let _=t[[0,5]];
+  }
+
+  #[test]
+  fn tensor_test_index_mut()
+  {
+    let mut t: Tensor<f64,1>=Tensor::<f64,1>::new([5]);
+    t[[1]]=3.14;
     assert!(t[[1]]==3.14);
     t[[4]]=1.618;
     assert!(t[[4]]==1.618);
@@ -338,35 +1378,17 @@ mod tensor_tests
     t2[[1,2]]=9.7;
 
     t1+=t2.clone();
-
-    assert!(t1[[0,0]]==1.3);
-    assert!(t1[[0,1]]==7.9);
-    assert!(t1[[0,2]]==2.2);
-    assert!(t1[[1,0]]==8.8);
-    assert!(t1[[1,1]]==3.1);
-    assert!(t1[[1,2]]==9.7);
+    assert_tensor_eq!(t1,Tensor::<f64,2>::from_rows(&[&[1.3,7.9,2.2],&[8.8,3.1,9.7]]).unwrap());
 
     t1[[0,1]]=1.1;
     t1[[1,0]]=1.1;
     t1[[1,2]]=1.1;
 
     t1+=&t2;
-
-    assert!(t1[[0,0]]==1.3);
-    assert!(t1[[0,1]]==7.9+1.1);
-    assert!(t1[[0,2]]==2.2);
-    assert!(t1[[1,0]]==8.8+1.1);
-    assert!(t1[[1,1]]==3.1);
-    assert!(t1[[1,2]]==9.7+1.1);
+    assert_tensor_eq!(t1,Tensor::<f64,2>::from_rows(&[&[1.3,7.9+1.1,2.2],&[8.8+1.1,3.1,9.7+1.1]]).unwrap());
 
     t1+=&t2;
-
-    assert!(t1[[0,0]]==1.3);
-    assert!(t1[[0,1]]==1.1+7.9+7.9);
-    assert!(t1[[0,2]]==2.2);
-    assert!(t1[[1,0]]==1.1+8.8+8.8);
-    assert!(t1[[1,1]]==3.1);
-    assert!(t1[[1,2]]==1.1+9.7+9.7);
+    assert_tensor_eq!(t1,Tensor::<f64,2>::from_rows(&[&[1.3,1.1+7.9+7.9,2.2],&[1.1+8.8+8.8,3.1,1.1+9.7+9.7]]).unwrap());
 
     t1+=t2;
   }
@@ -374,24 +1396,15 @@ mod tensor_tests
   #[test]
   fn tensor_test_add_assign_scalar()
   {
-    let mut t: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
-    t[0]=3.14;
-    t[1]=1.618;
-    t[2]=2.71;
-    t[3]=1.414;
+    let init: [f64;4]=[3.14,1.618,2.71,1.414];
+    let mut t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| init[itr]);
 
     let s: f64=1.202;
 
     t+=s;
-    assert!(t[0]==3.14+s);
-    assert!(t[1]==1.618+s);
-    assert!(t[2]==2.71+s);
-    assert!(t[3]==1.414+s);
+    assert_tensor_eq!(t,Tensor::<f64,1>::from_fn([4],|[itr]| init[itr]+s));
     t+=&s;
-    assert!(t[0]==3.14+s+s);
-    assert!(t[1]==1.618+s+s);
-    assert!(t[2]==2.71+s+s);
-    assert!(t[3]==1.414+s+s);
+    assert_tensor_eq!(t,Tensor::<f64,1>::from_fn([4],|[itr]| init[itr]+s+s));
   }
 
   #[test]
@@ -409,10 +1422,7 @@ mod tensor_tests
     t2[2]=9.7;
 
     let t3: Tensor<f64,1>=t1+t2;
-
-    assert!(t3[0]==1.3+7.9);
-    assert!(t3[1]==2.2+8.8);
-    assert!(t3[2]==3.1+9.7);
+    assert_tensor_eq!(t3,Tensor::<f64,1>::from_vec([3],vec![1.3+7.9,2.2+8.8,3.1+9.7]).unwrap());
   }
 
   #[test]
@@ -424,10 +1434,80 @@ mod tensor_tests
     t1[2]=3.1;
 
     let t2: Tensor<f64,1>=t1+3.14;
+    assert_tensor_eq!(t2,Tensor::<f64,1>::from_vec([3],vec![1.3+3.14,2.2+3.14,3.1+3.14]).unwrap());
+  }
+
+  /// Checks every combination of owned and borrowed tensor and scalar for every scalar operator.
+  fn tensor_test_scalar_ops<T>(s: T)
+  where T: Scalar + RemAssign + From<u8>
+  {
+    let t: Tensor<T,2>=Tensor::<T,2>::from_fn([2,3],|[itr,jtr]| T::from((3*itr+jtr) as u8+1)).to_layout(Layout::ColMajor);
+    let apply=|f: &dyn Fn(&mut T)| t.map(|elem| { let mut elem: T=elem.clone(); f(&mut elem); elem });
+
+    let sum: Tensor<T,2>=apply(&|elem| *elem+=s.clone());
+    assert!(t.clone()+s.clone()==sum && t.clone()+&s==sum && &t+s.clone()==sum && &t+&s==sum);
+    let prod: Tensor<T,2>=apply(&|elem| *elem*=s.clone());
+    assert!(t.clone()*s.clone()==prod && t.clone()*&s==prod && &t*s.clone()==prod && &t*&s==prod);
+    let rem: Tensor<T,2>=apply(&|elem| *elem%=s.clone());
+    assert!(t.clone()%s.clone()==rem && t.clone()%&s==rem && &t%s.clone()==rem && &t%&s==rem);
+    assert!(t==Tensor::<T,2>::from_fn([2,3],|[itr,jtr]| T::from((3*itr+jtr) as u8+1)));
+  }
+
+  #[test]
+  fn tensor_test_scalar_ref_ops()
+  {
+    tensor_test_scalar_ops::<f32>(2.5);
+    tensor_test_scalar_ops::<f64>(2.5);
+    tensor_test_scalar_ops::<i32>(4);
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to multiply them.")]
+  fn tensor_test_mul_assign_tensor()
+  {
+    let mut t1: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    let t2: Tensor<f64,2>=Tensor::<f64,2>::new([3,2]);
+
+    t1*=t2;
+  }
+
+  #[test]
+  fn tensor_test_mul_tensor()
+  {
+    let mut t1: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+    let mut t2: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+
+    t1[0]=1.3;
+    t1[1]=2.2;
+    t1[2]=3.1;
+
+    t2[0]=7.9;
+    t2[1]=8.8;
+    t2[2]=9.7;
+
+    let t3: Tensor<f64,1>=&t1*&t2;
+    assert_tensor_eq!(t3,Tensor::<f64,1>::from_vec([3],vec![1.3*7.9,2.2*8.8,3.1*9.7]).unwrap());
+
+    let t4: Tensor<f64,1>=t3*2.0;
+    assert_tensor_eq!(t4,Tensor::<f64,1>::from_vec([3],vec![1.3*7.9*2.0,2.2*8.8*2.0,3.1*9.7*2.0]).unwrap());
+  }
+
+  #[rstest(len,case(5),case(8),case(19))]
+  fn tensor_test_add_mul_tail(len: usize)
+  {
+    let mut t1: Tensor<f32,1>=Tensor::<f32,1>::new([len]);
+    let mut t2: Tensor<f32,1>=Tensor::<f32,1>::new([len]);
+    for itr in 0..len
+    {
+      t1[itr]=itr as f32;
+      t2[itr]=3f32;
+    }
 
-    assert!(t2[0]==1.3+3.14);
-    assert!(t2[1]==2.2+3.14);
-    assert!(t2[2]==3.1+3.14);
+    let t3: Tensor<f32,1>=&(&t1+&t2)*&t2;
+    for itr in 0..len
+    {
+      assert!(t3[itr]==(itr as f32+3f32)*3f32);
+    }
   }
 
   #[test]
@@ -443,4 +1523,533 @@ mod tensor_tests
     assert!(t2[1]==2.2);
     assert!(t2[2]==3.1);
   }
+
+  #[test]
+  fn tensor_test_clone_shared()
+  {
+    // More elements than are held inline, which are copied on cloning rather than shared.
+    let mut t1: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    t1[[0,0]]=1.3;
+    t1[[1,1]]=2.2;
+
+    let mut t2: Tensor<f64,2>=t1.clone();
+    let t3: Tensor<f64,2>=t2.clone();
+    assert!(t1.shares_data(&t2));
+    assert!(t2.shares_data(&t3));
+
+    t2[[0,1]]=3.1;
+    assert!(!t1.shares_data(&t2));
+    assert!(t1.shares_data(&t3));
+    assert!(t1[[0,1]]==0.0);
+    assert!(t2[[0,1]]==3.1);
+    assert!(t3[[0,1]]==0.0);
+
+    t1+=&t3;
+    assert!(!t1.shares_data(&t3));
+    assert!(t1[[0,0]]==2.6);
+    assert!(t1[[1,1]]==4.4);
+    assert!(t3[[0,0]]==1.3);
+    assert!(t3[[1,1]]==2.2);
+  }
+
+  #[test]
+  fn tensor_test_clone_unique()
+  {
+    let mut t1: Tensor<f64,1>=Tensor::<f64,1>::new([5]);
+    let ptr: *const f64=t1.data.as_ptr();
+
+    t1[1]=1.3;
+    t1*=2.0;
+    assert!(t1.data.as_ptr()==ptr);
+
+    let t2: Tensor<f64,1>=t1.clone();
+    drop(t1);
+    let mut t2: Tensor<f64,1>=t2;
+    t2[2]=2.2;
+    assert!(t2.data.as_ptr()==ptr);
+    assert!(t2[1]==2.6);
+  }
+
+  #[test]
+  fn tensor_test_parts()
+  {
+    let data: Box<[f64]>=(0..6).map(|itr| itr as f64).collect();
+    let ptr: *const f64=data.as_ptr();
+
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_parts([2,3],data).unwrap();
+    assert!(t.data.as_ptr()==ptr);
+    assert!(t[[1,0]]==3.0);
+
+    let (dim,data): (Dim<2>,Box<[f64]>)=t.into_parts();
+    assert!(dim==[2,3]);
+    assert!(data.as_ptr()==ptr);
+
+    let t: Result<Tensor<f64,2>,TensorError>=Tensor::<f64,2>::from_parts([4,2],data);
+    assert!(t.err()==Some(TensorError::SizeMismatch{size:8,len:6}));
+
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64);
+    let t2: Tensor<f64,1>=t.clone();
+    let (_,data): (Dim<1>,Box<[f64]>)=t.into_parts();
+    assert!(data.as_ptr()!=t2.data.as_ptr());
+    assert!(*data==*t2.data);
+  }
+
+  #[test]
+  fn tensor_test_swap_with()
+  {
+    let mut current: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    let mut next: Tensor<f64,2>=current.map(|elem| 0.0-elem).to_layout(Layout::ColMajor);
+    let (current_ptr,next_ptr): (*const f64,*const f64)=(current.as_ptr(),next.as_ptr());
+
+    current.swap_with(&mut next).unwrap();
+    assert!(current.as_ptr()==next_ptr && next.as_ptr()==current_ptr);
+    assert!(current[[1,2]]==-5.0 && next[[1,2]]==5.0 && current.layout()==Layout::ColMajor);
+
+    let mut other: Tensor<f64,2>=Tensor::<f64,2>::new([3,2]);
+    assert!(current.swap_with(&mut other).err()==Some(TensorError::DimMismatch{lhs:vec![2,3],rhs:vec![3,2]}));
+    assert!(current.as_ptr()==next_ptr && other.as_ptr()!=next_ptr);
+
+    let mut small: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64);
+    let mut other: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+    small.swap_with(&mut other).unwrap();
+    assert!(small.iter().all(|&elem| elem==0.0) && other.iter().eq([0.0,1.0,2.0].iter()));
+  }
+
+  #[test]
+  fn tensor_test_take()
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    let ptr: *const f64=t.as_ptr();
+
+    let taken: Tensor<f64,2>=t.take();
+    assert!(taken.as_ptr()==ptr && taken[[1,2]]==5.0);
+    assert!(t.dim==[2,3] && t.as_ptr()!=ptr && t.iter().all(|&elem| elem==0.0));
+
+    // Elements held inline are moved out with the tensor.
+    let mut t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64+1.0);
+    let taken: Tensor<f64,1>=t.take();
+    assert!(taken.iter().eq([1.0,2.0,3.0].iter()) && t.iter().all(|&elem| elem==0.0));
+  }
+
+  #[test]
+  fn tensor_test_flatten()
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as f64);
+    let ptr: *const f64=t.data.as_ptr();
+
+    let t: Tensor<f64,1>=t.flatten();
+    assert!(t.data.as_ptr()==ptr);
+    assert_tensor_eq!(t,Tensor::<f64,1>::from_fn([24],|[itr]| itr as f64));
+  }
+
+  #[test]
+  fn tensor_test_split_first_axis()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    let ptr: *const f64=t.data.as_ptr();
+
+    let (mut t1,t2): (Tensor<f64,2>,Tensor<f64,2>)=t.split_first_axis(1);
+    assert!(t1.dim==[1,3]);
+    assert!(t2.dim==[3,3]);
+    assert!(t1.data.as_ptr()==ptr);
+    assert!(t2.data.as_ptr()==ptr.wrapping_add(3));
+    assert!(t2[[0,0]]==3.0);
+    assert!(t2[[2,2]]==11.0);
+
+    t1[[0,2]]=100.0;
+    assert!(!t1.shares_data(&t2));
+    assert!(t2.data.as_ptr()==ptr.wrapping_add(3));
+
+    let (t3,t4): (Tensor<f64,2>,Tensor<f64,2>)=t2.split_first_axis(3);
+    assert!(t3.dim==[3,3]);
+    assert!(t4.dim==[0,3]);
+    drop(t4);
+    let (_,data): (Dim<2>,Box<[f64]>)=t3.into_parts();
+    assert!(*data==[3.0,4.0,5.0,6.0,7.0,8.0,9.0,10.0,11.0]);
+  }
+
+  #[test]
+  #[should_panic(expected="The split index must not exceed the length of the first axis to split a tensor.")]
+  fn tensor_test_split_first_axis_index()
+  {
+    Tensor::<f64,2>::new([2,2]).split_first_axis(3);
+  }
+
+  #[test]
+  fn tensor_test_into_outer_iter()
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([4,2,3],|[itr,jtr,ktr]| (6*itr+3*jtr+ktr) as f64).with_axis_names(["batch","row","col"]);
+    let ptr: *const f64=t.data.as_ptr();
+
+    // Splitting the buffer allocates nothing, whatever the number of subtensors.
+    let (pieces,allocations): (Vec<Tensor<f64,2>>,Idx)=crate::counting_alloc::count_allocations(|| {
+      let mut pieces: Vec<Tensor<f64,2>>=Vec::with_capacity(4);
+      pieces.extend(t.clone().into_outer_iter::<2>());
+      pieces
+    });
+    assert!(allocations==1 && pieces.len()==4);
+    for (itr,piece) in pieces.iter().enumerate()
+    {
+      assert!(piece.dim==[2,3] && piece.data.as_ptr()==ptr.wrapping_add(6*itr) && piece.axis_names()==Some(["row","col"]));
+    }
+    assert!(pieces.iter().flat_map(|piece| piece.iter()).eq(t.iter()));
+
+    // Either end, and a column-major tensor, which is copied once.
+    let mut iter: OuterIter<f64,2>=t.to_layout(Layout::ColMajor).into_outer_iter();
+    assert!(iter.len()==4);
+    assert_tensor_eq!(iter.next_back().unwrap(),pieces[3]);
+    assert_tensor_eq!(iter.next().unwrap(),pieces[0]);
+    assert!(iter.len()==2 && iter.rev().zip(pieces[1..3].iter().rev()).all(|(piece,expected)| piece==*expected));
+
+    let t: Tensor<f64,2>=Tensor::<f64,2>::new([3,0]);
+    assert!(t.clone().into_outer_iter::<1>().all(|piece| piece.dim==[0]) && t.into_outer_iter::<1>().count()==3);
+    assert!(Tensor::<f64,2>::new([0,3]).into_outer_iter::<1>().next().is_none());
+  }
+
+  #[test]
+  #[should_panic(expected="The rank of the subtensors must be one less than that of the tensor to iterate over its first axis.")]
+  fn tensor_test_into_outer_iter_rank()
+  {
+    Tensor::<f64,2>::new([2,2]).into_outer_iter::<2>();
+  }
+
+  #[test]
+  fn tensor_test_unravel()
+  {
+    let dim: Dim<3>=[2,3,4];
+    for (itr,ind) in Indices::new(dim).enumerate()
+    {
+      assert!(dim.unravel(itr)==ind);
+      assert!(dim.index(ind)==itr);
+    }
+  }
+
+  #[test]
+  fn tensor_test_finite()
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (itr+jtr) as f64);
+    assert!(t.is_finite());
+    assert!(!t.has_nan());
+    assert!(t.assert_finite()==Ok(()));
+
+    t[[1,0]]=f64::INFINITY;
+    t[[1,2]]=f64::NAN;
+    assert!(!t.is_finite());
+    assert!(t.has_nan());
+    assert!(t.assert_finite()==Err(TensorError::NonFinite{index:vec![1,0],value:f64::INFINITY}));
+
+    t[[1,0]]=0.0;
+    match t.assert_finite()
+    {
+      Err(TensorError::NonFinite{index,value}) => assert!(index==[1,2] && value.is_nan()),
+      _ => panic!(),
+    }
+
+    let t: Tensor<f32,1>=Tensor::<f32,1>::from_fn([3],|[itr]| [1.0,f32::NEG_INFINITY,0.0][itr]);
+    assert!(!t.is_finite());
+    assert!(!t.has_nan());
+    assert!(t.assert_finite()==Err(TensorError::NonFinite{index:vec![1],value:f64::NEG_INFINITY}));
+
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,2],|[itr,jtr]| i32::MAX-(itr+jtr) as i32);
+    assert!(t.is_finite());
+    assert!(!t.has_nan());
+    assert!(t.assert_finite()==Ok(()));
+  }
+
+  #[test]
+  fn tensor_test_rem()
+  {
+    let t1: Tensor<i32,1>=Tensor::<i32,1>::from_fn([4],|[itr]| [7,-7,9,-1][itr]);
+    let t2: Tensor<i32,1>=Tensor::<i32,1>::from_fn([4],|[itr]| [3,3,-4,5][itr]);
+
+    // The remainder takes the sign of the dividend, as for the elements themselves.
+    assert!((&t1%&t2).iter().copied().eq([1,-1,1,-1].iter().copied()));
+    assert!((t1.clone()%3).iter().copied().eq([1,-1,0,-1].iter().copied()));
+    assert!((&t1%t2.to_layout(Layout::ColMajor))==t1.clone()%&t2);
+
+    let mut t3: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr) as f64+0.5);
+    t3%=2.0;
+    assert!(t3.iter().copied().eq([0.5,1.5,0.5,1.5].iter().copied()));
+    t3%=&1.0;
+    assert!(t3.iter().all(|&elem| elem==0.5));
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to take the remainder of them.")]
+  fn tensor_test_rem_assign_tensor()
+  {
+    let mut t1: Tensor<i32,2>=Tensor::<i32,2>::new([2,3]);
+    t1%=Tensor::<i32,2>::new([3,2]);
+  }
+
+  #[test]
+  fn tensor_test_mul_add()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (itr+jtr) as f64/2.0);
+    let b: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,_]| itr as f64-1.0);
+
+    let res: Tensor<f64,2>=t.mul_add(&a,&b);
+    assert!(res.dim==[2,3]);
+    assert!(res.iter().copied().eq([-1.0,-0.5,1.0,1.5,4.0,7.5].iter().copied()));
+    assert!(t[[1,2]]==5.0);
+
+    let t: Tensor<i32,1>=Tensor::<i32,1>::from_fn([3],|[itr]| itr as i32);
+    assert!(t.mul_add(&t,&t).iter().copied().eq([0,2,6].iter().copied()));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn tensor_test_mul_add_fused()
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([1],|_| 0.1);
+    let a: Tensor<f64,1>=Tensor::<f64,1>::from_fn([1],|_| 10.0);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([1],|_| -1.0);
+
+    let mut unfused: Tensor<f64,1>=&t*&a;
+    unfused+=&b;
+    assert!(unfused[0]==0.0);
+    assert!(t.mul_add(&a,&b)[0]==5.551115123125783e-17);
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of three tensors must be of the same size to multiply and add them.")]
+  fn tensor_test_mul_add_dims()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    t.mul_add(&t,&Tensor::<f64,2>::new([3,2]));
+  }
+
+  #[test]
+  fn tensor_test_axpy()
+  {
+    let mut y: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    let x: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,_]| itr as f64);
+    let ptr: *const f64=y.data.as_ptr();
+
+    let ((),allocations)=crate::counting_alloc::count_allocations(|| {
+      for _ in 0..4
+      {
+        y.axpy(0.5,&x);
+      }
+    });
+    assert!(allocations==0);
+    assert!(y.data.as_ptr()==ptr);
+    for itr in 0..3
+    {
+      for jtr in 0..4
+      {
+        assert!(y[[itr,jtr]]==(4*itr+jtr) as f64+2.0*itr as f64);
+      }
+    }
+  }
+
+  #[test]
+  fn tensor_test_small_no_alloc()
+  {
+    // Positions and velocities of particles, which are created, cloned and added without allocating.
+    let (sum,allocations): (Tensor<f64,1>,Idx)=crate::counting_alloc::count_allocations(|| {
+      let mut pos: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64);
+      let vel: Tensor<f64,1>=pos.map(|elem| elem*0.5+1.0);
+      let copy: Tensor<f64,1>=vel.clone();
+      pos+=&vel;
+      let sum: Tensor<f64,1>=&pos+&copy;
+      sum+copy*2.0
+    });
+    assert!(allocations==0 && sum.iter().eq([4.0,7.0,10.0].iter()));
+
+    let (m,allocations): (Tensor<f64,2>,Idx)=crate::counting_alloc::count_allocations(|| {
+      let m: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr) as f64);
+      &m+&m.to_layout(Layout::ColMajor)
+    });
+    assert!(allocations==0 && m.iter().eq([0.0,2.0,4.0,6.0].iter()));
+
+    // Nor do `new` and `from_vec`, which moves the elements out of the vector.
+    let data: Vec<f64>=vec![1.0,2.0,3.0];
+    let ((zeros,t),allocations): ((Tensor<f64,1>,Tensor<f64,2>),Idx)=crate::counting_alloc::count_allocations(|| {
+      (Tensor::<f64,1>::new([4]),Tensor::<f64,2>::from_vec([1,3],data).unwrap())
+    });
+    assert!(allocations==0 && zeros.iter().all(|&elem| elem==0.0) && t.iter().eq([1.0,2.0,3.0].iter()));
+    let mut t1: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]);
+    let t2: Tensor<f64,2>=t1.clone();
+    t1[[0,0]]=1.0;
+    assert!(!t1.shares_data(&t2) && t2[[0,0]]==0.0);
+
+    // Five elements no longer fit inline.
+    let (_,allocations): (Tensor<f64,1>,Idx)=crate::counting_alloc::count_allocations(|| Tensor::<f64,1>::from_fn([5],|_| 0.0));
+    assert!(allocations>0);
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to add them.")]
+  fn tensor_test_axpy_dims()
+  {
+    let mut y: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+    y.axpy(1.0,&Tensor::<f64,1>::new([4]));
+  }
+
+  #[test]
+  fn tensor_test_send_sync()
+  {
+    fn assert_send<S: Send>() {}
+    fn assert_sync<S: Sync>() {}
+
+    assert_send::<Tensor<f64,2>>();
+    assert_sync::<Tensor<f64,2>>();
+    assert_send::<Tensor<i32,3>>();
+    assert_sync::<Tensor<i32,3>>();
+    assert_send::<TensorView<f64,2>>();
+    assert_sync::<TensorView<f64,2>>();
+    assert_send::<axis::LaneMut<f64>>();
+    assert_sync::<axis::LaneMut<f64>>();
+  }
+
+  /// Stands in for a C routine that scales `len` doubles in place.
+  extern "C" fn tensor_test_scale_ffi(data: *mut f64, len: usize, factor: f64)
+  {
+    let data: &mut [f64]=unsafe { core::slice::from_raw_parts_mut(data,len) };
+    data.iter_mut().for_each(|elem| *elem*=factor);
+  }
+
+  #[test]
+  fn tensor_test_ffi()
+  {
+    let data: Vec<f64>=(0..6).map(|itr| itr as f64).collect();
+    let ptr: *const f64=data.as_ptr();
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_vec([2,3],data).unwrap();
+    assert!(t.as_ptr()==ptr && t.element_count()==6);
+
+    let copy: Tensor<f64,2>=t.clone();
+    tensor_test_scale_ffi(t.as_mut_ptr(),t.element_count(),2.0);
+    assert!(t.as_ptr()!=ptr);
+    assert!(t.iter().copied().eq((0..6).map(|itr| 2.0*itr as f64)));
+    assert!(copy.iter().copied().eq((0..6).map(|itr| itr as f64)));
+
+    let (_,data): (Dim<2>,Box<[f64]>)=t.into_parts();
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_vec([1,2,3],data.into_vec()).unwrap();
+    assert!(t[[0,1,2]]==10.0);
+    assert!(Tensor::<f64,2>::from_vec([2,2],vec![0.0;3]).err()==Some(TensorError::SizeMismatch{size:4,len:3}));
+  }
+
+  #[test]
+  fn tensor_test_eq()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+
+    assert!(t==t.to_layout(Layout::ColMajor));
+    assert!(t!=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| (2*itr+jtr) as f64));
+    assert!(t!=t.map(|elem| elem+1.0));
+    assert!(Tensor::<f64,1>::from_fn([1],|_| f64::NAN)!=Tensor::<f64,1>::from_fn([1],|_| f64::NAN));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn tensor_test_hash()
+  {
+    use std::collections::HashSet;
+
+    let coords: [[i64;2];4]=[[1,2],[3,-4],[1,2],[0,0]];
+    let mut set: HashSet<Tensor<i64,1>>=HashSet::new();
+    for coord in coords.iter()
+    {
+      set.insert(Tensor::<i64,1>::from_fn([2],|[itr]| coord[itr]));
+    }
+    assert!(set.len()==3);
+
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as i32);
+    let mut set: HashSet<Tensor<i32,2>>=HashSet::new();
+    set.insert(t.clone());
+    set.insert(t.to_layout(Layout::ColMajor));
+    set.insert(Tensor::<i32,2>::from_fn([3,2],|[itr,jtr]| (2*itr+jtr) as i32));
+    assert!(set.len()==2);
+  }
+
+  #[test]
+  fn tensor_test_structural()
+  {
+    use alloc::string::String;
+    use alloc::string::ToString;
+
+    // A label is neither a number nor `Default`, so only the structural operations apply to it.
+    #[derive(Debug,Clone,PartialEq,Eq)]
+    struct Label(String);
+
+    let labels: Vec<Label>=["a","b","c","d","e","f"].iter().map(|name| Label(name.to_string())).collect();
+    let mut t: Tensor<Label,2>=Tensor::<Label,2>::from_vec([2,3],labels).unwrap();
+    assert!(t.len()==6 && t[[1,0]]==Label("d".to_string()));
+
+    let u: Tensor<Label,2>=t.clone();
+    t[[0,2]]=Label("z".to_string());
+    assert!(u[[0,2]]==Label("c".to_string()) && t[[0,2]]==Label("z".to_string()));
+    assert!(t!=u && t==t.to_layout(Layout::ColMajor));
+    assert!(t.iter().map(|label| label.0.as_str()).eq(["a","b","z","d","e","f"].iter().copied()));
+
+    let lens: Tensor<u64,2>=u.map(|label| label.0.len() as u64);
+    assert!(lens.sum()==6);
+    assert!(format!("{:?}",u.flatten()).contains("data: [Label(\"a\"), Label(\"b\")"));
+  }
+}
+
+#[cfg(all(test,not(debug_assertions)))]
+mod tensor_benches
+{
+  extern crate test;
+
+  use super::*;
+  use test::Bencher;
+
+  const BENCH_LEN: usize=100_000_000;
+  const SMALL_BENCH_LEN: usize=1_000_000;
+
+  #[bench]
+  fn tensor_bench_from_fn(b: &mut Bencher)
+  {
+    b.iter(|| Tensor::<f64,1>::from_fn([BENCH_LEN],|[itr]| itr as f64));
+  }
+
+  #[bench]
+  fn tensor_bench_from_fn_overwrite(b: &mut Bencher)
+  {
+    b.iter(|| {
+      let mut t: Tensor<f64,1>=Tensor::<f64,1>::new([BENCH_LEN]);
+      for (ind,elem) in Indices::new(t.dim).zip(t.data.iter_mut())
+      {
+        *elem=ind[0] as f64;
+      }
+      t
+    });
+  }
+
+  #[bench]
+  fn tensor_bench_map(b: &mut Bencher)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::new([BENCH_LEN]);
+    b.iter(|| t.map(|elem| elem+1f64));
+  }
+
+  #[bench]
+  fn tensor_bench_map_overwrite(b: &mut Bencher)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::new([BENCH_LEN]);
+    b.iter(|| {
+      let mut out: Tensor<f64,1>=Tensor::<f64,1>::new(t.dim);
+      out.data.iter_mut().zip(t.data.iter()).for_each(|(out,elem)| *out=elem+1f64);
+      out
+    });
+  }
+
+  #[bench]
+  fn tensor_bench_small_add(b: &mut Bencher)
+  {
+    let vel: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64);
+    b.iter(|| (0..SMALL_BENCH_LEN).fold(Tensor::<f64,1>::from_fn([3],|_| 0.0),|pos,_| &pos+&vel));
+  }
+
+  #[bench]
+  fn tensor_bench_small_add_shared(b: &mut Bencher)
+  {
+    // Tensors built from a vector keep it as a shared buffer, so every sum copies it on writing.
+    let vel: Tensor<f64,1>=Tensor::<f64,1>::from_vec([3],vec![0.0,1.0,2.0]).unwrap();
+    b.iter(|| (0..SMALL_BENCH_LEN).fold(Tensor::<f64,1>::from_vec([3],vec![0.0;3]).unwrap(),|pos,_| &pos+&vel));
+  }
 }
@@ -0,0 +1,153 @@
+use core::fmt::Debug;
+use core::ops::RangeInclusive;
+
+use proptest::arbitrary::Arbitrary;
+use proptest::arbitrary::any_with;
+use proptest::collection::vec;
+use proptest::strategy::BoxedStrategy;
+use proptest::strategy::Strategy;
+
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Clone + Debug + 'static
+{
+  /// Strategy for property tests with `proptest` that generates tensors whose length along every axis
+  /// lies in `dims`, with elements drawn from `elem`, stored in either layout.
+  ///
+  /// `Tensor::<f64,2>::arbitrary_with(1..=8,-1e3..1e3)` generates matrices of one to eight rows and
+  /// columns of finite elements, whereas `any::<f64>()` for `elem` includes infinities and NaN.
+  pub(super) fn arbitrary_with<S>(dims: RangeInclusive<Idx>, elem: S) -> BoxedStrategy<Tensor<T,N>>
+  where S: Strategy<Value=T> + Clone + 'static
+  {
+    vec(dims,N).prop_flat_map(move |lens| {
+      let mut dim: Dim<N>=[0;N];
+      dim.copy_from_slice(&lens);
+      Tensor::<T,N>::arbitrary_dim(dim,elem.clone())
+    }).boxed()
+  }
+
+  /// Strategy that generates tensors of dimensions `dim`, with elements drawn from `elem`, stored in
+  /// either layout, such as further operands of the dimensions of a tensor from `arbitrary_with`.
+  pub(super) fn arbitrary_dim<S>(dim: Dim<N>, elem: S) -> BoxedStrategy<Tensor<T,N>>
+  where S: Strategy<Value=T> + 'static
+  {
+    (vec(elem,dim.size()),proptest::bool::ANY).prop_map(move |(data,col_major)| {
+      let t: Tensor<T,N>=Tensor::<T,N>::from_vec(dim,data).unwrap();
+      if col_major { t.to_layout(Layout::ColMajor) } else { t }
+    }).boxed()
+  }
+}
+
+impl<T,const N: Idx> Arbitrary for Tensor<T,N>
+where T: Arbitrary + Clone + 'static, <T as Arbitrary>::Strategy: Clone + 'static
+{
+  type Parameters=<T as Arbitrary>::Parameters;
+  type Strategy=BoxedStrategy<Tensor<T,N>>;
+
+  /// Tensors of lengths up to four along every axis, with elements from the strategy of `T` for `args`.
+  fn arbitrary_with(args: <T as Arbitrary>::Parameters) -> BoxedStrategy<Tensor<T,N>>
+  {
+    Tensor::<T,N>::arbitrary_with(0..=4,any_with::<T>(args))
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod arbitrary_tests
+{
+  use super::*;
+  use super::super::container::Encoding;
+  use super::super::testing::tensor_allclose_failure;
+
+  use alloc::format;
+  use alloc::string::String;
+  use alloc::vec::Vec;
+
+  use proptest::arbitrary::any;
+  use proptest::prop_assert;
+  use proptest::prop_assert_eq;
+  use proptest::proptest;
+  use proptest::strategy::Just;
+
+  /// Three tensors of the same dimensions with finite elements.
+  fn arbitrary_test_operands() -> impl Strategy<Value=(Tensor<f64,3>,Tensor<f64,3>,Tensor<f64,3>)>
+  {
+    Tensor::<f64,3>::arbitrary_with(0..=5,-1e3..1e3).prop_flat_map(|a| {
+      let dim: Dim<3>=a.dim;
+      (Just(a),Tensor::<f64,3>::arbitrary_dim(dim,-1e3..1e3),Tensor::<f64,3>::arbitrary_dim(dim,-1e3..1e3))
+    })
+  }
+
+  proptest! {
+    #[test]
+    fn arbitrary_test_dims(t in Tensor::<i32,3>::arbitrary_with(1..=8,any::<i32>()))
+    {
+      prop_assert!(t.dim.iter().all(|len| (1..=8).contains(len)) && t.len()==t.dim.size());
+    }
+
+    #[test]
+    fn arbitrary_test_add_associative((a,b,c) in arbitrary_test_operands())
+    {
+      prop_assert_eq!(tensor_allclose_failure(&(&(&a+&b)+&c),&(&a+&(&b+&c)),1e-9),None);
+    }
+
+    #[test]
+    fn arbitrary_test_add_zeros(a in any::<Tensor<i64,3>>())
+    {
+      prop_assert_eq!(&a+&Tensor::<i64,3>::new(a.dim),a);
+    }
+
+    #[test]
+    fn arbitrary_test_transpose(m in Tensor::<i32,2>::arbitrary_with(0..=8,any::<i32>()))
+    {
+      prop_assert_eq!(m.transpose().transpose(),m);
+    }
+
+    #[test]
+    fn arbitrary_test_reshape(t in any::<Tensor<u8,3>>())
+    {
+      let flat: Tensor<u8,1>=t.reshape([t.len()]).unwrap();
+      prop_assert!(flat.iter().eq(t.to_layout(Layout::RowMajor).iter()));
+      prop_assert_eq!(flat.reshape(t.dim).unwrap(),t);
+    }
+
+    // Floats are compared by their bits, so that NaN elements must round-trip too.
+    #[test]
+    fn arbitrary_test_container(t in Tensor::<f64,2>::arbitrary_with(0..=8,any::<f64>()))
+    {
+      let mut bytes: Vec<u8>=Vec::new();
+      t.save(&mut bytes).unwrap();
+      prop_assert_eq!(Tensor::<f64,2>::load_as(&bytes[..]).unwrap().map(|elem| elem.to_bits()),t.map(|elem| elem.to_bits()));
+    }
+
+    #[test]
+    fn arbitrary_test_encoded((t,encoding) in (any::<Tensor<i64,2>>(),0..4usize))
+    {
+      let encoding: Encoding=[Encoding::Raw,Encoding::Rle,Encoding::Delta,Encoding::DeltaRle][encoding];
+      let mut bytes: Vec<u8>=Vec::new();
+      t.save_encoded(&mut bytes,encoding).unwrap();
+      prop_assert_eq!(Tensor::<i64,2>::load_as(&bytes[..]).unwrap(),t);
+    }
+
+    // Integers are written exactly whatever the precision, so the text round-trips.
+    #[test]
+    fn arbitrary_test_columns(t in Tensor::<i32,2>::arbitrary_with(1..=8,any::<i32>()))
+    {
+      let headers: Vec<String>=(0..t.dim[1]).map(|jtr| format!("c{}",jtr)).collect();
+      let mut bytes: Vec<u8>=Vec::new();
+      t.write_columns(&mut bytes,&headers.iter().map(|header| header.as_str()).collect::<Vec<&str>>(),',',0).unwrap();
+      let (res_headers,res): (Vec<String>,Tensor<i32,2>)=Tensor::<i32,2>::read_columns(&bytes[..],',').unwrap();
+      prop_assert_eq!(res_headers,headers);
+      prop_assert_eq!(res,t);
+    }
+  }
+}
@@ -0,0 +1,794 @@
+use core::marker::PhantomData;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::slice;
+
+use super::Dim;
+use super::Dimension;
+use super::Float;
+use super::Idx;
+use super::Indices;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+use super::layout::layout_indices;
+use super::layout::strides;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Applies `f` to every element of the slice of `self` at `index` along `axis` and the element at the
+  /// same position in `rhs`, whose dimensions must be those of `self` without `axis`.
+  ///
+  /// The slice is visited as runs of contiguous elements, one per index along the axes before `axis`,
+  /// so the closer `axis` is to the first, the longer the runs. In a column-major layout the runs are
+  /// instead one per index along the axes after `axis`.
+  pub(super) fn axis_slice_op<F,const M: Idx>(&mut self, axis: Idx, index: Idx, rhs: &Tensor<T,M>, mut f: F)
+  where F: FnMut(&mut T,&T)
+  {
+    let (outer,inner): (Idx,Idx)=self.slice_layout(axis,index,&rhs.dim);
+    let rhs: Tensor<T,M>=rhs.to_layout(self.layout);
+    let len: Idx=self.dim[axis];
+    let data: &mut [T]=&mut self.data;
+    for itr in 0..outer
+    {
+      let start: Idx=(itr*len+index)*inner;
+      for (this,other) in data[start..start+inner].iter_mut().zip(rhs.data[itr*inner..(itr+1)*inner].iter())
+      {
+        f(this,other);
+      }
+    }
+  }
+
+  /// Checks that `index` along `axis` is a slice of `self` of dimensions `dim`, and returns the number
+  /// of contiguous runs the slice consists of and their length in the layout of `self`.
+  fn slice_layout<const M: Idx>(&self, axis: Idx, index: Idx, dim: &[Idx;M]) -> (Idx,Idx)
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+    if index>=self.dim[axis] { panic!("The index must be less than the length of the axis to slice a tensor.") }
+    if M+1!=N || self.dim[..axis]!=dim[..axis] || self.dim[axis+1..]!=dim[axis..]
+    {
+      panic!("All dimensions of a tensor but the sliced axis must be of the same size as those of the lower rank tensor to update the slice.")
+    }
+
+    let (before,after): (Idx,Idx)=(self.dim[..axis].iter().product(),self.dim[axis+1..].iter().product());
+    match self.layout
+    {
+      Layout::RowMajor => (before,after),
+      Layout::ColMajor => (after,before),
+    }
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// Adds `rhs` to row `row` in place.
+  pub(super) fn row_add_assign(&mut self, row: Idx, rhs: &Tensor<T,1>)
+  {
+    match self.layout
+    {
+      Layout::RowMajor =>
+      {
+        let (_,cols): (Idx,Idx)=self.slice_layout(0,row,&rhs.dim);
+        T::add_assign_slice(&mut self.data[row*cols..(row+1)*cols],&rhs.data);
+      },
+      Layout::ColMajor => self.axis_slice_op(0,row,rhs,|this,other| *this+=other.clone()),
+    }
+  }
+}
+
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Iterator over the lanes along `axis`, each holding the elements whose multi-indices differ only
+  /// along `axis`, in row-major order of the other axes.
+  ///
+  /// The lanes along the axis that varies fastest in memory, the last one unless the tensor has a
+  /// column-major layout, are contiguous and also available as slices through `as_mut_slice`.
+  pub(super) fn lanes_mut(&mut self, axis: Idx) -> LanesMut<'_,T,N>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    let mut outer: Dim<N>=self.dim;
+    outer[axis]=1;
+    let strides: [isize;N]=strides(self.dim,self.layout);
+    LanesMut{ptr:self.data.as_mut_ptr(),strides:strides,axis:axis,len:self.dim[axis],starts:Indices::new(outer),marker:PhantomData}
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Reduces every lane along `axis` to a single element by applying `f` to an accumulator, starting
+  /// at `init`, and every element of the lane in turn. The result has the dimensions of `self`
+  /// without `axis`, so `M` must be one less than `N`.
+  pub(super) fn reduce_axis<F,const M: Idx>(&self, axis: Idx, init: T, f: F) -> Tensor<T,M>
+  where F: FnMut(T,&T) -> T
+  {
+    self.reduce_axis_keepdims(axis,init,f).drop_axis(axis)
+  }
+
+  /// Variant of `reduce_axis` that keeps `axis` with length one, so the result can be broadcast
+  /// against `self`.
+  pub(super) fn reduce_axis_keepdims<F>(&self, axis: Idx, init: T, mut f: F) -> Tensor<T,N>
+  where F: FnMut(T,&T) -> T
+  {
+    self.fold_axis(axis,init,|acc,elem,_| f(acc,elem))
+  }
+
+  /// Sum of the elements of every lane along `axis`.
+  pub(super) fn sum_axis<const M: Idx>(&self, axis: Idx) -> Tensor<T,M>
+  {
+    self.sum_axis_keepdims(axis).drop_axis(axis)
+  }
+
+  /// Variant of `sum_axis` that keeps `axis` with length one.
+  pub(super) fn sum_axis_keepdims(&self, axis: Idx) -> Tensor<T,N>
+  {
+    self.reduce_axis_keepdims(axis,T::default(),|sum,elem| sum+elem.clone())
+  }
+
+  /// Reduces the elements sharing their coordinates along every axis but `axes` to a single element
+  /// by applying `f` to an accumulator, starting at `init`, and every such element in turn. The result
+  /// has the dimensions of `self` without `axes`, so `M` must be `N` less the number of axes, which
+  /// must be distinct.
+  ///
+  /// Every element is visited once, whatever the number of axes, without the intermediate tensors of
+  /// reducing one axis after another.
+  pub(super) fn reduce_axes<F,const M: Idx>(&self, axes: &[Idx], init: T, f: F) -> Tensor<T,M>
+  where F: FnMut(T,&T) -> T
+  {
+    self.fold_axes(axes,init,f)
+  }
+
+  /// Sum of the elements sharing their coordinates along every axis but `axes`.
+  pub(super) fn sum_axes<const M: Idx>(&self, axes: &[Idx]) -> Tensor<T,M>
+  {
+    self.reduce_axes(axes,T::default(),|sum,elem| sum+elem.clone())
+  }
+
+  /// Folds every lane along `axis` into an accumulator of type `U` as `reduce_axis_keepdims` does,
+  /// also passing `f` the position of the lane's result in the row-major result.
+  ///
+  /// The elements are visited in the order they are stored, which visits every lane in order along
+  /// `axis`.
+  fn fold_axis<U,F>(&self, axis: Idx, init: U, mut f: F) -> Tensor<U,N>
+  where U: Scalar, F: FnMut(U,&T,Idx) -> U
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    let mut dim: Dim<N>=self.dim;
+    dim[axis]=1;
+    let mut res: Tensor<U,N>=Tensor::<U,N>::from_fn(dim,|_| init.clone());
+    res.names=self.names;
+    let inner: Idx=self.dim[axis+1..].iter().product();
+    for (ind,elem) in layout_indices(self.dim,self.layout).zip(self.data.iter())
+    {
+      let flat: Idx=self.dim.index(ind);
+      let pos: Idx=flat/(self.dim[axis]*inner)*inner+flat%inner;
+      let acc: U=core::mem::take(&mut res.data[pos]);
+      res.data[pos]=f(acc,elem,pos);
+    }
+    res
+  }
+
+  /// Tensor of rank `M`, one less than `N`, with the elements of `self`, whose `axis` has length one,
+  /// sharing its element buffer.
+  fn drop_axis<const M: Idx>(self, axis: Idx) -> Tensor<T,M>
+  {
+    if M+1!=N { panic!("The rank of the result must be one less than that of the tensor to reduce an axis.") }
+
+    let mut dim: Dim<M>=[0;M];
+    let mut names: [&'static str;M]=["";M];
+    for itr in 0..M
+    {
+      let from: Idx=if itr<axis { itr } else { itr+1 };
+      dim[itr]=self.dim[from];
+      names[itr]=self.names.map_or("",|names| names[from]);
+    }
+    Tensor{data:self.data,dim:dim,layout:self.layout,names:self.names.map(|_| names)}
+  }
+
+  /// Folds the elements sharing their coordinates along every axis but `axes` into an accumulator of
+  /// type `U`, in a single pass over the elements in the order they are stored, which visits them in
+  /// order along `axes`. The result is of row-major layout.
+  fn fold_axes<U,F,const M: Idx>(&self, axes: &[Idx], init: U, mut f: F) -> Tensor<U,M>
+  where U: Scalar, F: FnMut(U,&T) -> U
+  {
+    let mut reduced: [bool;N]=[false;N];
+    for &axis in axes
+    {
+      if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+      if reduced[axis] { panic!("The axes must be distinct to reduce them.") }
+      reduced[axis]=true;
+    }
+    if M+axes.len()!=N { panic!("The rank of the result must be that of the tensor less the number of axes to reduce.") }
+
+    let mut kept: [Idx;M]=[0;M];
+    let mut dim: Dim<M>=[0;M];
+    let mut names: [&'static str;M]=["";M];
+    for ((kept,(len,name)),axis) in kept.iter_mut().zip(dim.iter_mut().zip(names.iter_mut())).zip((0..N).filter(|&axis| !reduced[axis]))
+    {
+      *kept=axis;
+      *len=self.dim[axis];
+      *name=self.names.map_or("",|names| names[axis]);
+    }
+    let mut res: Tensor<U,M>=Tensor::<U,M>::from_fn(dim,|_| init.clone());
+    res.names=self.names.map(|_| names);
+    let res_strides: [isize;M]=strides(dim,Layout::RowMajor);
+    for (ind,elem) in layout_indices(self.dim,self.layout).zip(self.data.iter())
+    {
+      let pos: Idx=kept.iter().zip(res_strides.iter()).map(|(&axis,&stride)| ind[axis]*stride as Idx).sum();
+      let acc: U=core::mem::take(&mut res.data[pos]);
+      res.data[pos]=f(acc,elem);
+    }
+    res
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Clone
+{
+  /// Tensor whose axis `itr` is axis `axes[itr]` of `self`, which must be a permutation of the axes.
+  /// The names of the axes, if any, are permuted alike.
+  pub(super) fn permute(&self, axes: [Idx;N]) -> Tensor<T,N>
+  {
+    let mut seen: [bool;N]=[false;N];
+    for &axis in axes.iter()
+    {
+      if axis>=N || seen[axis] { panic!("The axes must be a permutation of the axes of the tensor to permute it.") }
+      seen[axis]=true;
+    }
+
+    let mut dim: Dim<N>=[0;N];
+    axes.iter().enumerate().for_each(|(itr,&axis)| dim[itr]=self.dim[axis]);
+    let mut t: Tensor<T,N>=Tensor::<T,N>::from_fn(dim,|ind| {
+      let mut from: Dim<N>=[0;N];
+      axes.iter().zip(ind.iter()).for_each(|(&axis,&itr)| from[axis]=itr);
+      self[from].clone()
+    });
+    t.names=self.names.map(|names| axes.map(|axis| names[axis]));
+    t
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Maximum of the elements of every lane along `axis`, which is NaN for a lane holding NaN and
+  /// negative infinity for an empty lane.
+  pub(super) fn max_axis<const M: Idx>(&self, axis: Idx) -> Tensor<T,M>
+  {
+    self.max_axis_keepdims(axis).drop_axis(axis)
+  }
+
+  /// Variant of `max_axis` that keeps `axis` with length one.
+  pub(super) fn max_axis_keepdims(&self, axis: Idx) -> Tensor<T,N>
+  {
+    self.reduce_axis_keepdims(axis,T::default()-T::infinity(),|max,&elem| if !max.is_nan() && (elem.is_nan() || elem>max) { elem } else { max })
+  }
+
+  /// Maximum of the elements sharing their coordinates along every axis but `axes`, as `max_axis`
+  /// finds along one.
+  pub(super) fn max_axes<const M: Idx>(&self, axes: &[Idx]) -> Tensor<T,M>
+  {
+    self.reduce_axes(axes,T::default()-T::infinity(),|max,&elem| if !max.is_nan() && (elem.is_nan() || elem>max) { elem } else { max })
+  }
+
+  /// Minimum of the elements of every lane along `axis`, keeping `axis` with length one. The minimum
+  /// is NaN for a lane holding NaN and infinity for an empty lane.
+  pub(super) fn min_axis_keepdims(&self, axis: Idx) -> Tensor<T,N>
+  {
+    self.reduce_axis_keepdims(axis,T::infinity(),|min,&elem| if !min.is_nan() && (elem.is_nan() || elem<min) { elem } else { min })
+  }
+
+  /// Mean of the elements of every lane along `axis`, keeping `axis` with length one. The mean of an
+  /// empty lane is NaN.
+  pub(super) fn mean_axis_keepdims(&self, axis: Idx) -> Tensor<T,N>
+  {
+    let len: T=T::from_f64(self.dim[axis] as f64);
+    self.sum_axis_keepdims(axis).map(|&sum| sum/len)
+  }
+
+  /// Mean of the elements sharing their coordinates along every axis but `axes`.
+  pub(super) fn mean_axes<const M: Idx>(&self, axes: &[Idx]) -> Tensor<T,M>
+  {
+    let sum: Tensor<T,M>=self.sum_axes(axes);
+    let len: T=T::from_f64(axes.iter().map(|&axis| self.dim[axis]).product::<Idx>() as f64);
+    sum.map(|&sum| sum/len)
+  }
+
+  /// Population standard deviation of the elements of every lane along `axis`, as `standardize`
+  /// divides by, keeping `axis` with length one.
+  #[cfg(feature="std")]
+  pub(super) fn std_axis_keepdims(&self, axis: Idx) -> Tensor<T,N>
+  {
+    let mean: Tensor<T,N>=self.mean_axis_keepdims(axis);
+    let len: T=T::from_f64(self.dim[axis] as f64);
+    self.fold_axis(axis,T::default(),|ss,&elem,pos| ss+(elem-mean.data[pos])*(elem-mean.data[pos]))
+      .map(|&ss| (ss/len).sqrt())
+  }
+
+  /// Logarithm of the sum of the exponentials of the elements of every lane along `axis`.
+  ///
+  /// The maximum of the lane is subtracted before exponentiating and added back afterwards, so large
+  /// elements do not overflow and small ones do not all underflow to zero.
+  #[cfg(feature="std")]
+  pub(super) fn logsumexp_axis<const M: Idx>(&self, axis: Idx) -> Tensor<T,M>
+  {
+    let max: Tensor<T,N>=self.max_axis_keepdims(axis);
+    let sums: Tensor<T,N>=self.fold_axis(axis,T::default(),|sum,&elem,pos| sum+(elem-max.data[pos]).exp());
+    let mut res: Tensor<T,N>=max.clone();
+    for (elem,&sum) in res.data.iter_mut().zip(sums.data.iter())
+    {
+      // An infinite maximum would turn every difference into NaN, but is the result itself.
+      if *elem!=T::infinity() && *elem!=T::default()-T::infinity() { *elem+=sum.ln(); }
+    }
+    res.drop_axis(axis)
+  }
+}
+
+/// Iterator over the lanes of a tensor along an axis, returned by `Tensor::lanes_mut`.
+pub(super) struct LanesMut<'a,T,const N: Idx>
+{
+  ptr: *mut T,
+  strides: [isize;N],
+  axis: Idx,
+  len: Idx,
+  starts: Indices<N>,
+  marker: PhantomData<&'a mut T>,
+}
+
+impl<'a,T,const N: Idx> Iterator for LanesMut<'a,T,N>
+{
+  type Item=LaneMut<'a,T>;
+  fn next(&mut self) -> Option<LaneMut<'a,T>>
+  {
+    let start: Dim<N>=self.starts.next()?;
+    let offset: isize=start.iter().zip(self.strides.iter()).map(|(&itr,&stride)| itr as isize*stride).sum();
+    Some(LaneMut{ptr:self.ptr.wrapping_offset(offset),len:self.len,stride:self.strides[self.axis] as Idx,marker:PhantomData})
+  }
+}
+
+/// Mutable lane of a tensor along an axis, whose elements are `stride` apart in memory.
+pub(super) struct LaneMut<'a,T>
+{
+  ptr: *mut T,
+  len: Idx,
+  stride: Idx,
+  marker: PhantomData<&'a mut T>,
+}
+
+impl<'a,T> LaneMut<'a,T>
+{
+  pub(super) fn len(&self) -> Idx
+  {
+    self.len
+  }
+
+  pub(super) fn is_empty(&self) -> bool
+  {
+    self.len==0
+  }
+
+  /// The elements of the lane as a slice, if they are contiguous in memory.
+  pub(super) fn as_mut_slice(&mut self) -> Option<&mut [T]>
+  {
+    if self.len==0 { return Some(&mut []); }
+    if self.stride!=1 && self.len>1 { return None; }
+
+    // The lane holds `len` adjacent elements of the tensor, which no other lane holds and the tensor
+    // cannot be accessed through while the lanes borrow it.
+    Some(unsafe { slice::from_raw_parts_mut(self.ptr,self.len) })
+  }
+
+  /// Iterator over mutable references to the elements of the lane.
+  pub(super) fn iter_mut(&mut self) -> impl Iterator<Item=&mut T> + '_
+  {
+    let (ptr,stride): (*mut T,Idx)=(self.ptr,self.stride);
+    // Every element of the lane is distinct and yielded once.
+    (0..self.len).map(move |itr| unsafe { &mut *ptr.add(itr*stride) })
+  }
+
+  /// Swaps the elements at `a` and `b`.
+  pub(super) fn swap(&mut self, a: Idx, b: Idx)
+  {
+    for &ind in [a,b].iter()
+    {
+      if ind>=self.len { panic!("The index {} is out of bounds for a lane of length {}.",ind,self.len) }
+    }
+    // Both elements are in the lane, and `ptr::swap` allows them to be the same element.
+    unsafe { core::ptr::swap(self.ptr.add(a*self.stride),self.ptr.add(b*self.stride)) }
+  }
+}
+
+// A lane stands in for a mutable borrow of its elements, which no other lane holds, so it can be
+// sent and shared between threads as `&mut [T]` can.
+unsafe impl<'a,T> Send for LaneMut<'a,T>
+where T: Send
+{}
+
+unsafe impl<'a,T> Sync for LaneMut<'a,T>
+where T: Sync
+{}
+
+impl<'a,T> Index<Idx> for LaneMut<'a,T>
+{
+  type Output=T;
+  fn index(&self, ind: Idx) -> &T
+  {
+    if ind>=self.len { panic!("The index {} is out of bounds for a lane of length {}.",ind,self.len) }
+    unsafe { &*self.ptr.add(ind*self.stride) }
+  }
+}
+
+impl<'a,T> IndexMut<Idx> for LaneMut<'a,T>
+{
+  fn index_mut(&mut self, ind: Idx) -> &mut T
+  {
+    if ind>=self.len { panic!("The index {} is out of bounds for a lane of length {}.",ind,self.len) }
+    unsafe { &mut *self.ptr.add(ind*self.stride) }
+  }
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod axis_tests
+{
+  use super::*;
+  use super::super::testing::assert_tensor_allclose;
+  use super::super::testing::assert_tensor_eq;
+  use rstest::rstest;
+
+  #[rstest(row,case(0),case(1),case(2))]
+  fn axis_test_row_add_assign(row: usize)
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    let rhs: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| -(itr as f64));
+    let ptr: *const f64=t.data.as_ptr();
+
+    t.row_add_assign(row,&rhs);
+    assert!(t.data.as_ptr()==ptr);
+    assert!(Indices::new([3,4]).all(|[itr,jtr]| t[[itr,jtr]]==if itr==row { (4*itr) as f64 } else { (4*itr+jtr) as f64 }));
+  }
+
+  #[rstest(axis,index,
+    case(0,1),
+    case(1,2),
+    case(2,0),
+  )]
+  fn axis_test_axis_slice_op(axis: usize, index: usize)
+  {
+    let dim: [usize;3]=[2,3,4];
+    let mut t: Tensor<i64,3>=Tensor::<i64,3>::from_fn(dim,|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as i64);
+    let mut rhs_dim: [usize;2]=[0;2];
+    rhs_dim.copy_from_slice(&[&dim[..axis],&dim[axis+1..]].concat());
+    let rhs: Tensor<i64,2>=Tensor::<i64,2>::from_fn(rhs_dim,|[itr,jtr]| 100*(itr*rhs_dim[1]+jtr+1) as i64);
+
+    t.axis_slice_op(axis,index,&rhs,|this,other| *this=*this*2+other);
+    for ind in Indices::new(dim)
+    {
+      let elem: i64=(12*ind[0]+4*ind[1]+ind[2]) as i64;
+      if ind[axis]==index
+      {
+        let rest: Vec<usize>=[&ind[..axis],&ind[axis+1..]].concat();
+        assert!(t[ind]==2*elem+rhs[[rest[0],rest[1]]]);
+      }
+      else
+      {
+        assert!(t[ind]==elem);
+      }
+    }
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of a tensor but the sliced axis must be of the same size as those of the lower rank tensor to update the slice.")]
+  fn axis_test_axis_slice_op_dims()
+  {
+    let mut t: Tensor<f64,3>=Tensor::<f64,3>::new([2,3,4]);
+    t.axis_slice_op(1,0,&Tensor::<f64,2>::new([3,4]),|this,other| *this+=*other);
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of a tensor but the sliced axis must be of the same size as those of the lower rank tensor to update the slice.")]
+  fn axis_test_axis_slice_op_rank()
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]);
+    t.axis_slice_op(0,0,&Tensor::<f64,2>::new([1,2]),|this,other| *this+=*other);
+  }
+
+  #[test]
+  #[should_panic(expected="The index must be less than the length of the axis to slice a tensor.")]
+  fn axis_test_row_add_assign_index()
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]);
+    t.row_add_assign(2,&Tensor::<f64,1>::new([2]));
+  }
+
+  #[rstest(index,case(0),case(1),case(2))]
+  fn axis_test_lanes_mut_rows(index: usize)
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr+1) as f64);
+    let before: Tensor<f64,2>=t.clone();
+
+    for (itr,mut row) in t.lanes_mut(1).enumerate()
+    {
+      if itr==index
+      {
+        let row: &mut [f64]=row.as_mut_slice().unwrap();
+        let sum: f64=row.iter().sum();
+        row.iter_mut().for_each(|elem| *elem/=sum);
+      }
+    }
+    assert!(((0..4).map(|jtr| t[[index,jtr]]).sum::<f64>()-1.0).abs()<1e-15);
+    for ind in Indices::new([3,4])
+    {
+      if ind[0]!=index { assert!(t[ind]==before[ind]); }
+    }
+  }
+
+  #[test]
+  fn axis_test_lanes_mut_strided()
+  {
+    let mut t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+
+    let mut count: usize=0;
+    for mut lane in t.lanes_mut(1)
+    {
+      assert!(lane.len()==3 && lane.as_mut_slice().is_none());
+      lane[2]=0-lane[0];
+      lane.iter_mut().for_each(|elem| *elem*=2);
+      count+=1;
+    }
+    assert!(count==8);
+    assert!(t[[1,2,3]]==-206 && t[[1,1,3]]==226 && t[[0,0,2]]==4);
+
+    let mut col: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,2],|[itr,jtr]| (2*itr+jtr) as i32).to_layout(Layout::ColMajor);
+    let sums: Vec<i32>=col.lanes_mut(0).map(|mut lane| lane.as_mut_slice().unwrap().iter().sum()).collect();
+    assert!(sums==[6,9]);
+    assert!(Tensor::<f64,2>::new([0,3]).lanes_mut(0).all(|lane| lane.is_empty()));
+
+    let mut t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,2],|[itr,jtr]| (2*itr+jtr) as i32);
+    t.lanes_mut(0).for_each(|mut lane| { lane.swap(0,2); lane.swap(1,1); });
+    assert!(t.iter().copied().eq([4,5,2,3,0,1].iter().copied()));
+  }
+
+  #[test]
+  #[should_panic(expected="The index 3 is out of bounds for a lane of length 3.")]
+  fn axis_test_lanes_mut_index()
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::new([3,3]);
+    let mut lane: LaneMut<f64>=t.lanes_mut(0).next().unwrap();
+    lane[3]=1.0;
+  }
+
+  #[rstest(axis,dim,expected,
+    case(0,[3,4],&[12,14,16,18,20,22,24,26,28,30,32,34]),
+    case(1,[2,4],&[12,15,18,21,48,51,54,57]),
+    case(2,[2,3],&[6,22,38,54,70,86]),
+  )]
+  fn axis_test_sum_axis(axis: usize, dim: Dim<2>, expected: &[i32])
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as i32);
+
+    let sum: Tensor<i32,2>=t.sum_axis(axis);
+    assert!(sum.dim==dim);
+    assert!(sum.iter().copied().eq(expected.iter().copied()));
+    assert!(t.to_layout(Layout::ColMajor).sum_axis::<2>(axis).iter().eq(sum.iter()));
+  }
+
+  #[test]
+  fn axis_test_reduce_axis()
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as i32);
+
+    let count: Tensor<i32,2>=t.reduce_axis(2,0,|count,&elem| if elem%3==0 { count+1 } else { count });
+    assert!(count.dim==[2,3]);
+    assert!(count.iter().copied().eq([2,1,1,2,1,1].iter().copied()));
+
+    let order: Tensor<i32,1>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as i32).to_layout(Layout::ColMajor)
+      .reduce_axis(1,0,|acc,&elem| 10*acc+elem);
+    assert!(order.iter().copied().eq([12,345].iter().copied()));
+  }
+
+  #[rstest(axis,layout,expected,
+    case(0,Layout::RowMajor,&[f64::NAN,2.0,3.0]),
+    case(0,Layout::ColMajor,&[f64::NAN,2.0,3.0]),
+    case(1,Layout::RowMajor,&[3.0,f64::NAN]),
+    case(1,Layout::ColMajor,&[3.0,f64::NAN]),
+  )]
+  fn axis_test_max_axis(axis: usize, layout: Layout, expected: &[f64])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| [[1.0,-4.0,3.0],[f64::NAN,2.0,0.5]][itr][jtr]).to_layout(layout);
+
+    let max: Tensor<f64,1>=t.max_axis(axis);
+    assert!(max.dim==[expected.len()]);
+    assert!(max.iter().zip(expected.iter()).all(|(&elem,&expected)| elem==expected || elem.is_nan() && expected.is_nan()));
+  }
+
+  #[rstest(dim,axis,case([2,0],1),case([0,2],0))]
+  fn axis_test_max_axis_empty(dim: Dim<2>, axis: usize)
+  {
+    assert!(Tensor::<f64,2>::new(dim).max_axis::<1>(axis).iter().all(|&elem| elem==f64::NEG_INFINITY));
+  }
+
+  /// Reducing the higher axis first leaves the lower one where it was.
+  #[rstest(first,second,layout,
+    case(0,1,Layout::RowMajor),
+    case(0,2,Layout::RowMajor),
+    case(1,2,Layout::RowMajor),
+    case(0,1,Layout::ColMajor),
+    case(0,2,Layout::ColMajor),
+    case(1,2,Layout::ColMajor),
+  )]
+  fn axis_test_reduce_axes_pair(first: usize, second: usize, layout: Layout)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| ((12*itr+4*jtr+ktr) as f64*0.7).sin()*10.0).to_layout(layout);
+
+    let expected: Tensor<f64,1>=t.sum_axis::<2>(second).sum_axis(first);
+    assert_tensor_allclose!(t.sum_axes::<1>(&[first,second]),expected,1e-12);
+    assert_tensor_allclose!(t.sum_axes::<1>(&[second,first]),expected,1e-12);
+    assert_tensor_eq!(t.max_axes::<1>(&[first,second]),t.max_axis::<2>(second).max_axis(first));
+    let len: f64=(t.dim[first]*t.dim[second]) as f64;
+    assert_tensor_allclose!(t.mean_axes::<1>(&[first,second]),expected.map(|&sum| sum/len),1e-12);
+  }
+
+  #[rstest(axis,layout,
+    case(0,Layout::RowMajor),
+    case(1,Layout::RowMajor),
+    case(2,Layout::RowMajor),
+    case(0,Layout::ColMajor),
+    case(1,Layout::ColMajor),
+    case(2,Layout::ColMajor),
+  )]
+  fn axis_test_reduce_axes_single(axis: usize, layout: Layout)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| ((12*itr+4*jtr+ktr) as f64*0.7).sin()*10.0).to_layout(layout);
+
+    assert_tensor_eq!(t.max_axes::<2>(&[axis]),t.max_axis::<2>(axis));
+    assert_tensor_allclose!(t.sum_axes::<2>(&[axis]),t.sum_axis::<2>(axis),1e-12);
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn axis_test_reduce_axes_all(layout: Layout)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| ((12*itr+4*jtr+ktr) as f64*0.7).sin()*10.0).to_layout(layout);
+
+    let all: Tensor<f64,0>=t.sum_axes(&[2,0,1]);
+    assert!((all[[]]-t.iter().sum::<f64>()).abs()<1e-12);
+    assert_tensor_eq!(t.sum_axes::<3>(&[]),t.to_layout(Layout::RowMajor));
+  }
+
+  #[test]
+  fn axis_test_reduce_axes()
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| ((12*itr+4*jtr+ktr) as f64*0.7).sin()*10.0)
+      .with_axis_names(["batch","h","w"]);
+
+    assert!(t.mean_axes::<1>(&[0,2]).axis_names()==Some(["h"]));
+    assert!(t.sum_axes::<1>(&[0,2]).dim==[3] && t.sum_axes::<2>(&[1]).axis_names()==Some(["batch","w"]));
+    let count: Tensor<i32,1>=Tensor::<i32,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as i32)
+      .reduce_axes(&[0,2],0,|count,&elem| if elem%3==0 { count+1 } else { count });
+    assert!(count.iter().copied().eq([4,2,2].iter().copied()));
+    assert!(Tensor::<f64,3>::new([2,0,3]).max_axes::<1>(&[0,1]).iter().all(|&elem| elem==f64::NEG_INFINITY));
+  }
+
+  #[test]
+  #[should_panic(expected="The axes must be distinct to reduce them.")]
+  fn axis_test_reduce_axes_distinct()
+  {
+    Tensor::<f64,3>::new([2,3,4]).sum_axes::<1>(&[2,2]);
+  }
+
+  #[test]
+  #[should_panic(expected="The axis must be less than the number of dimensions of the tensor.")]
+  fn axis_test_reduce_axes_range()
+  {
+    Tensor::<f64,3>::new([2,3,4]).sum_axes::<1>(&[0,3]);
+  }
+
+  #[test]
+  #[should_panic(expected="The rank of the result must be that of the tensor less the number of axes to reduce.")]
+  fn axis_test_reduce_axes_rank()
+  {
+    Tensor::<f64,3>::new([2,3,4]).mean_axes::<2>(&[0,1]);
+  }
+
+  #[rstest(row,expected,tol,
+    case([1000.0,1000.0],1000.0+2f64.ln(),1e-12),
+    case([-1000.0,-1001.0],-1000.0+(1.0+(-1f64).exp()).ln(),1e-12),
+    case([0.5,-2.0],(0.5f64.exp()+(-2f64).exp()).ln(),1e-15),
+  )]
+  #[cfg(feature="std")]
+  fn axis_test_logsumexp_axis(row: [f64;2], expected: f64, tol: f64)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[_,jtr]| row[jtr]);
+
+    let res: Tensor<f64,1>=t.logsumexp_axis(1);
+    assert!(res.dim==[3] && res.iter().all(|&elem| (elem-expected).abs()<tol));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn axis_test_logsumexp_axis_infinite()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([1,2],|[_,jtr]| [f64::NEG_INFINITY,f64::INFINITY][jtr]);
+    assert!(t.logsumexp_axis::<1>(0).iter().copied().eq([f64::NEG_INFINITY,f64::INFINITY].iter().copied()));
+  }
+
+  #[test]
+  #[should_panic(expected="The rank of the result must be one less than that of the tensor to reduce an axis.")]
+  fn axis_test_reduce_axis_rank()
+  {
+    Tensor::<f64,2>::new([2,2]).sum_axis::<2>(0);
+  }
+
+  #[rstest(reduce,layout,dim,expected,
+    case(|t: &Tensor<f64,2>| t.sum_axis_keepdims(1),Layout::RowMajor,[2,1],&[0.0,12.0]),
+    case(|t: &Tensor<f64,2>| t.sum_axis_keepdims(0),Layout::RowMajor,[1,3],&[3.0,-2.0,11.0]),
+    case(|t: &Tensor<f64,2>| t.mean_axis_keepdims(0),Layout::ColMajor,[1,3],&[1.5,-1.0,5.5]),
+    case(|t: &Tensor<f64,2>| t.mean_axis_keepdims(1),Layout::ColMajor,[2,1],&[0.0,4.0]),
+    case(|t: &Tensor<f64,2>| t.min_axis_keepdims(0),Layout::RowMajor,[1,3],&[1.0,-4.0,3.0]),
+    case(|t: &Tensor<f64,2>| t.max_axis_keepdims(1),Layout::RowMajor,[2,1],&[3.0,8.0]),
+    case(|t: &Tensor<f64,2>| t.max_axis_keepdims(1),Layout::ColMajor,[2,1],&[3.0,8.0]),
+  )]
+  fn axis_test_keepdims(reduce: fn(&Tensor<f64,2>) -> Tensor<f64,2>, layout: Layout, dim: Dim<2>, expected: &[f64])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| [[1.0,-4.0,3.0],[2.0,2.0,8.0]][itr][jtr]).to_layout(layout);
+
+    let res: Tensor<f64,2>=reduce(&t);
+    assert!(res.dim==dim && res.iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[rstest(axis,case(0),case(1))]
+  #[cfg(feature="std")]
+  fn axis_test_keepdims_center_and_scale(axis: usize)
+  {
+    let x: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,3],|[itr,jtr]| ((itr+1)*(jtr+2)) as f64+(itr*itr) as f64);
+
+    let mean: Tensor<f64,2>=x.mean_axis_keepdims(axis);
+    let std: Tensor<f64,2>=x.std_axis_keepdims(axis);
+    assert!(mean.dim[axis]==1 && std.dim[axis]==1 && mean.dim[1-axis]==x.dim[1-axis]);
+    let z: Tensor<f64,2>=(&x+&(mean.broadcast_to(x.dim)*-1.0))*std.map(|&s| 1.0/s).broadcast_to(x.dim);
+
+    let (mean,std): (Tensor<f64,2>,Tensor<f64,2>)=(z.mean_axis_keepdims(axis),z.std_axis_keepdims(axis));
+    assert!(mean.iter().all(|&elem| elem.abs()<1e-14));
+    assert!(std.iter().all(|&elem| (elem-1.0).abs()<1e-14));
+    assert!(z.allclose(&x.standardize(Some(axis)).0,1e-14,1e-14));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn axis_test_lanes_mut_threads()
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([8,5],|[itr,jtr]| (itr*jtr+1) as f64);
+    let expected: Tensor<f64,2>=Tensor::<f64,2>::from_fn([8,5],|[itr,jtr]| {
+      let sum: f64=(0..5).map(|ktr| (itr*ktr+1) as f64).sum();
+      (itr*jtr+1) as f64/sum
+    });
+
+    std::thread::scope(|scope| {
+      for mut lane in t.lanes_mut(1)
+      {
+        scope.spawn(move || {
+          let row: &mut [f64]=lane.as_mut_slice().unwrap();
+          let sum: f64=row.iter().sum();
+          row.iter_mut().for_each(|elem| *elem/=sum);
+        });
+      }
+    });
+    assert!(t==expected);
+
+    let shared: &Tensor<f64,2>=&t;
+    let sums: Vec<f64>=std::thread::scope(|scope| {
+      let handles: Vec<std::thread::ScopedJoinHandle<f64>>=(0..2).map(|half| scope.spawn(move || (4*half..4*half+4).map(|itr| (0..5).map(|jtr| shared[[itr,jtr]]).sum::<f64>()).sum::<f64>())).collect();
+      handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    assert!(sums.iter().all(|&sum| (sum-4.0).abs()<1e-12));
+  }
+}
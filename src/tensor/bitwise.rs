@@ -0,0 +1,280 @@
+use core::ops::BitAnd;
+use core::ops::BitAndAssign;
+use core::ops::BitOr;
+use core::ops::BitOrAssign;
+use core::ops::BitXor;
+use core::ops::BitXorAssign;
+use core::ops::Not;
+use core::ops::Shl;
+use core::ops::ShlAssign;
+use core::ops::Shr;
+use core::ops::ShrAssign;
+
+use super::Idx;
+use super::Operand;
+use super::Scalar;
+use super::Tensor;
+
+// Element-wise bitwise and shift operators for integer tensors, in the same forms and with the same
+// check of the dimensions as `Add`.
+//
+// Shifts follow the policy of the integer types themselves: shifting by the bit width or more, or by a
+// negative amount, panics when overflow checks are enabled, as in debug builds, and shifts by the
+// amount masked to the bit width otherwise.
+
+macro_rules! impl_bit_op {
+  ($op:ident,$fn:ident,$op_assign:ident,$fn_assign:ident,$msg:literal) => {
+    impl<T,const N: Idx> $op_assign for Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      fn $fn_assign(&mut self, rhs: Self)
+      {
+        $op_assign::$fn_assign(self,&rhs);
+      }
+    }
+
+    impl<T,const N: Idx> $op_assign<&Tensor<T,N>> for Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      fn $fn_assign(&mut self, rhs: &Self)
+      {
+        for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+        {
+          if dim1!=dim2 { panic!($msg)}
+        }
+
+        self.adopt_names(rhs);
+        let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+        self.data.iter_mut().zip(rhs.data.iter()).for_each(|(this,other)| $op_assign::$fn_assign(this,other.clone()));
+      }
+    }
+
+    impl<T,U,const N: Idx> $op_assign<U> for Tensor<T,N>
+    where T: Scalar + $op_assign<U>, U: Operand
+    {
+      fn $fn_assign(&mut self, rhs: U)
+      {
+        self.data.iter_mut().for_each(|this| $op_assign::$fn_assign(this,rhs.clone()));
+      }
+    }
+
+    impl<T,const N: Idx> $op<T> for Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      type Output=Self;
+      fn $fn(mut self, rhs: T) -> Self::Output
+      {
+        $op_assign::$fn_assign(&mut self,rhs);
+        self
+      }
+    }
+
+    impl<T,const N: Idx> $op for Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      type Output=Self;
+      fn $fn(mut self, rhs: Self) -> Self::Output
+      {
+        $op_assign::$fn_assign(&mut self,rhs);
+        self
+      }
+    }
+
+    impl<T,const N: Idx> $op for &Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(self, rhs: Self) -> Self::Output
+      {
+        let mut t: Tensor<T,N>=self.clone();
+        $op_assign::$fn_assign(&mut t,rhs);
+        t
+      }
+    }
+
+    impl<T,const N: Idx> $op<Tensor<T,N>> for &Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(self, rhs: Tensor<T,N>) -> Self::Output
+      {
+        let mut t: Tensor<T,N>=self.clone();
+        $op_assign::$fn_assign(&mut t,rhs);
+        t
+      }
+    }
+
+    impl<T,const N: Idx> $op<&Tensor<T,N>> for Tensor<T,N>
+    where T: Scalar + $op_assign
+    {
+      type Output=Tensor<T,N>;
+      fn $fn(mut self, rhs: &Self) -> Self::Output
+      {
+        $op_assign::$fn_assign(&mut self,rhs);
+        self
+      }
+    }
+  };
+}
+
+impl_bit_op!(BitAnd,bitand,BitAndAssign,bitand_assign,"All dimensions of two tensors must be of the same size to take their bitwise and.");
+impl_bit_op!(BitOr,bitor,BitOrAssign,bitor_assign,"All dimensions of two tensors must be of the same size to take their bitwise or.");
+impl_bit_op!(BitXor,bitxor,BitXorAssign,bitxor_assign,"All dimensions of two tensors must be of the same size to take their bitwise exclusive or.");
+impl_bit_op!(Shl,shl,ShlAssign,shl_assign,"All dimensions of two tensors must be of the same size to shift one by the other.");
+impl_bit_op!(Shr,shr,ShrAssign,shr_assign,"All dimensions of two tensors must be of the same size to shift one by the other.");
+
+impl<T,const N: Idx> Not for Tensor<T,N>
+where T: Scalar + Not<Output=T>
+{
+  type Output=Self;
+  fn not(mut self) -> Self::Output
+  {
+    self.data.iter_mut().for_each(|this| *this=Not::not(this.clone()));
+    self
+  }
+}
+
+impl<T,const N: Idx> Not for &Tensor<T,N>
+where T: Scalar + Not<Output=T>
+{
+  type Output=Tensor<T,N>;
+  fn not(self) -> Self::Output
+  {
+    !self.clone()
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod bitwise_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  fn bitwise_test_u8() -> Tensor<u8,2>
+  {
+    Tensor::<u8,2>::from_fn([2,3],|[itr,jtr]| [[0b0000_0000,0b1010_1010,0b1111_0000],[0b0000_1111,0b1000_0001,0b1111_1111]][itr][jtr])
+  }
+
+  fn bitwise_test_mask() -> Tensor<u8,2>
+  {
+    Tensor::<u8,2>::from_fn([2,3],|[itr,jtr]| if (itr+jtr)%2==0 { 0b0011_1100 } else { 0b1100_0011 })
+  }
+
+  #[rstest(op,lhs_layout,rhs_layout,expected,
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs&rhs,Layout::RowMajor,Layout::RowMajor,[0b0000_0000,0b1000_0010,0b0011_0000,0b0000_0011,0b0000_0000,0b1100_0011]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs&rhs,Layout::ColMajor,Layout::RowMajor,[0b0000_0000,0b1000_0010,0b0011_0000,0b0000_0011,0b0000_0000,0b1100_0011]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs|rhs,Layout::RowMajor,Layout::ColMajor,[0b0011_1100,0b1110_1011,0b1111_1100,0b1100_1111,0b1011_1101,0b1111_1111]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs|rhs,Layout::ColMajor,Layout::ColMajor,[0b0011_1100,0b1110_1011,0b1111_1100,0b1100_1111,0b1011_1101,0b1111_1111]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs^rhs,Layout::RowMajor,Layout::ColMajor,[0b0011_1100,0b0110_1001,0b1100_1100,0b1100_1100,0b1011_1101,0b0011_1100]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs.clone()^rhs.clone(),Layout::ColMajor,Layout::RowMajor,[0b0011_1100,0b0110_1001,0b1100_1100,0b1100_1100,0b1011_1101,0b0011_1100]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs.clone()&rhs,Layout::RowMajor,Layout::ColMajor,[0b0000_0000,0b1000_0010,0b0011_0000,0b0000_0011,0b0000_0000,0b1100_0011]),
+    case(|lhs: &Tensor<u8,2>, rhs: &Tensor<u8,2>| lhs|rhs.clone(),Layout::ColMajor,Layout::RowMajor,[0b0011_1100,0b1110_1011,0b1111_1100,0b1100_1111,0b1011_1101,0b1111_1111]),
+  )]
+  fn bitwise_test_logic_u8(op: fn(&Tensor<u8,2>,&Tensor<u8,2>) -> Tensor<u8,2>, lhs_layout: Layout, rhs_layout: Layout, expected: [u8;6])
+  {
+    let res: Tensor<u8,2>=op(&bitwise_test_u8().to_layout(lhs_layout),&bitwise_test_mask().to_layout(rhs_layout));
+    assert!(res.to_layout(Layout::RowMajor).iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn bitwise_test_not_u8(layout: Layout)
+  {
+    let t: Tensor<u8,2>=bitwise_test_u8().to_layout(layout);
+    assert!(!&t==t.map(|&elem| 255-elem) && !!t.clone()==t);
+  }
+
+  #[rstest(op,elem_op,rhs,
+    case(|lhs: Tensor<u8,2>, rhs: u8| lhs&rhs,|lhs: u8, rhs: u8| lhs&rhs,0b1000_0001),
+    case(|lhs: Tensor<u8,2>, rhs: u8| lhs|rhs,|lhs: u8, rhs: u8| lhs|rhs,0b0001_1000),
+    case(|lhs: Tensor<u8,2>, rhs: u8| lhs^rhs,|lhs: u8, rhs: u8| lhs^rhs,0b0000_1000),
+    case(|mut lhs: Tensor<u8,2>, rhs: u8| { lhs&=rhs; lhs },|lhs: u8, rhs: u8| lhs&rhs,0b0110_0110),
+    case(|mut lhs: Tensor<u8,2>, rhs: u8| { lhs|=&rhs; lhs },|lhs: u8, rhs: u8| lhs|rhs,0b0001_1000),
+    case(|mut lhs: Tensor<u8,2>, rhs: u8| { lhs^=&rhs; lhs },|lhs: u8, rhs: u8| lhs^rhs,0b1111_1111),
+  )]
+  fn bitwise_test_scalar_u8(op: fn(Tensor<u8,2>,u8) -> Tensor<u8,2>, elem_op: fn(u8,u8) -> u8, rhs: u8)
+  {
+    let t: Tensor<u8,2>=bitwise_test_u8();
+    assert!(op(t.clone(),rhs)==t.map(|&elem| elem_op(elem,rhs)));
+  }
+
+  #[rstest(amount,left,right,
+    case(0,[1,0xff,1<<63,u64::MAX],[1,0xff,1<<63,u64::MAX]),
+    case(1,[2,0x1fe,0,u64::MAX-1],[0,0x7f,1<<62,u64::MAX>>1]),
+    case(4,[16,0xff0,0,u64::MAX-15],[0,0xf,1<<59,u64::MAX>>4]),
+    case(63,[1<<63,1<<63,0,1<<63],[0,0,1,1]),
+  )]
+  fn bitwise_test_shift_u64(amount: u64, left: [u64;4], right: [u64;4])
+  {
+    let t: Tensor<u64,1>=Tensor::<u64,1>::from_fn([4],|[itr]| [1,0xff,1<<63,u64::MAX][itr]);
+    assert!((t.clone()<<amount).iter().copied().eq(left.iter().copied()));
+    assert!((t.clone()>>amount).iter().copied().eq(right.iter().copied()));
+
+    // Shifting left and back clears the bits shifted out.
+    let mut shifted: Tensor<u64,1>=t.clone();
+    shifted<<=amount as u32;
+    shifted>>=&(amount as u8);
+    assert!(shifted==t.map(|&elem| elem&(u64::MAX>>amount)));
+  }
+
+  /// Every element is shifted by the amount at the same position, up to one less than the bit width.
+  #[rstest(amounts,left,right,
+    case([63,4,63,0],[1<<63,0xff0,0,u64::MAX],[0,0xf,1,u64::MAX]),
+    case([0,0,0,0],[1,0xff,1<<63,u64::MAX],[1,0xff,1<<63,u64::MAX]),
+    case([1,8,1,32],[2,0xff00,0,u64::MAX<<32],[0,0,1<<62,u64::MAX>>32]),
+  )]
+  fn bitwise_test_shift_tensor_u64(amounts: [u64;4], left: [u64;4], right: [u64;4])
+  {
+    let t: Tensor<u64,1>=Tensor::<u64,1>::from_fn([4],|[itr]| [1,0xff,1<<63,u64::MAX][itr]);
+    let amounts: Tensor<u64,1>=Tensor::<u64,1>::from_fn([4],|[itr]| amounts[itr]);
+    assert!((&t<<&amounts).iter().copied().eq(left.iter().copied()));
+    assert!((&t>>amounts).iter().copied().eq(right.iter().copied()));
+  }
+
+  fn bitwise_test_shift_width() -> Tensor<u8,1>
+  {
+    let t: Tensor<u8,1>=Tensor::<u8,1>::from_fn([2],|[itr]| [0b1000_0001,3][itr]);
+    assert!((t.clone()<<7).iter().copied().eq([0b1000_0000,0b1000_0000].iter().copied()));
+    t<<Tensor::<u8,1>::from_fn([2],|[itr]| [8,9][itr])
+  }
+
+  // Shifting by the bit width or more panics with overflow checks and shifts by the amount modulo the
+  // width without, as for the integers themselves.
+
+  #[test]
+  #[cfg(debug_assertions)]
+  #[should_panic(expected="attempt to shift left with overflow")]
+  fn bitwise_test_shift_width_checked()
+  {
+    bitwise_test_shift_width();
+  }
+
+  #[test]
+  #[cfg(not(debug_assertions))]
+  fn bitwise_test_shift_width_masked()
+  {
+    assert!(bitwise_test_shift_width().iter().copied().eq([0b1000_0001,6].iter().copied()));
+  }
+
+  #[rstest(lhs,rhs,case([2,3],[3,2]),case([2,3],[2,2]),case([1,3],[3,1]))]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to take their bitwise and.")]
+  fn bitwise_test_and_dim(lhs: Dim<2>, rhs: Dim<2>)
+  {
+    let _=Tensor::<u32,2>::new(lhs)&Tensor::<u32,2>::new(rhs);
+  }
+
+  #[rstest(lhs,rhs,case([2,3],[2,2]),case([2,3],[3,2]),case([0,3],[3,0]))]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to shift one by the other.")]
+  fn bitwise_test_shl_dim(lhs: Dim<2>, rhs: Dim<2>)
+  {
+    let mut t: Tensor<u32,2>=Tensor::<u32,2>::new(lhs);
+    t<<=&Tensor::<u32,2>::new(rhs);
+  }
+}
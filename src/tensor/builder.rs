@@ -0,0 +1,205 @@
+use alloc::vec::Vec;
+
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+
+/// Collects the rows of a matrix one at a time, for when the number of rows is not known up front.
+///
+/// The first row pushed fixes the width of the matrix. The elements are accumulated in a single
+/// row-major buffer, which `build` moves into the matrix without copying unless it holds few enough
+/// elements to be held in the matrix itself.
+pub(super) struct TensorBuilder<T>
+{
+  data: Vec<T>,
+  width: Option<Idx>,
+  rows: Idx,
+}
+
+impl<T> TensorBuilder<T>
+{
+  pub(super) fn new() -> TensorBuilder<T>
+  {
+    TensorBuilder{data:Vec::new(),width:None,rows:0}
+  }
+
+  /// Appends a copy of `row`, failing without appending it if it is not as long as the first row.
+  pub(super) fn push_row(&mut self, row: &[T]) -> Result<(),TensorError>
+  where T: Clone
+  {
+    match self.width
+    {
+      Some(width) if width!=row.len() => return Err(TensorError::RowLength{row:self.rows,len:row.len(),width:width}),
+      Some(_) => {},
+      None => self.width=Some(row.len()),
+    }
+    self.data.extend_from_slice(row);
+    self.rows+=1;
+    Ok(())
+  }
+
+  /// Number of rows pushed so far.
+  pub(super) fn len(&self) -> Idx
+  {
+    self.rows
+  }
+
+  /// Whether no row has been pushed yet.
+  pub(super) fn is_empty(&self) -> bool
+  {
+    self.rows==0
+  }
+
+  /// Matrix of the rows pushed, which is of dimensions `[0,0]` if none were.
+  pub(super) fn build(self) -> Tensor<T,2>
+  {
+    let dim: [Idx;2]=[self.rows,self.width.unwrap_or(0)];
+    Tensor{data:self.data.into(),dim:dim,layout:Layout::RowMajor,names:None}
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Clone
+{
+  /// Matrix with the rows `rows`, failing if they are not all of the same length. An empty slice gives
+  /// a matrix of dimensions `[0,0]`.
+  pub(super) fn from_rows(rows: &[&[T]]) -> Result<Tensor<T,2>,TensorError>
+  {
+    let mut builder: TensorBuilder<T>=TensorBuilder::new();
+    rows.iter().try_for_each(|row| builder.push_row(row))?;
+    Ok(builder.build())
+  }
+}
+
+/// Collects the elements of a vector one at a time, the one dimensional counterpart of
+/// `TensorBuilder`.
+pub(super) struct VectorBuilder<T>
+{
+  data: Vec<T>,
+}
+
+impl<T> VectorBuilder<T>
+{
+  pub(super) fn new() -> VectorBuilder<T>
+  {
+    VectorBuilder{data:Vec::new()}
+  }
+
+  /// Appends `value`.
+  pub(super) fn push(&mut self, value: T)
+  {
+    self.data.push(value);
+  }
+
+  /// Number of elements pushed so far.
+  pub(super) fn len(&self) -> Idx
+  {
+    self.data.len()
+  }
+
+  /// Whether no element has been pushed yet.
+  pub(super) fn is_empty(&self) -> bool
+  {
+    self.data.is_empty()
+  }
+
+  /// Vector of the elements pushed, moved into the tensor without copying unless there are few enough
+  /// to be held in the tensor itself.
+  pub(super) fn build(self) -> Tensor<T,1>
+  {
+    let dim: [Idx;1]=[self.data.len()];
+    Tensor{data:self.data.into(),dim:dim,layout:Layout::RowMajor,names:None}
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod builder_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::testing::assert_tensor_eq;
+
+  #[rstest(rows,width,case(5,3),case(1,5),case(2,7))]
+  fn builder_test_push_row(rows: usize, width: usize)
+  {
+    let mut builder: TensorBuilder<f64>=TensorBuilder::<f64>::new();
+    for itr in 0..rows
+    {
+      let row: Vec<f64>=(0..width).map(|jtr| (width*itr+jtr) as f64).collect();
+      builder.push_row(&row).unwrap();
+    }
+    assert!(builder.len()==rows && !builder.is_empty());
+
+    let ptr: *const f64=builder.data.as_ptr();
+    let t: Tensor<f64,2>=builder.build();
+    assert!(t.dim==[rows,width] && t.as_ptr()==ptr);
+    assert_tensor_eq!(t,Tensor::<f64,2>::from_fn([rows,width],|[itr,jtr]| (width*itr+jtr) as f64));
+  }
+
+  #[rstest(row,len,case(&[5,6,7],3),case(&[],0),case(&[5],1))]
+  fn builder_test_width_mismatch(row: &[i32], len: usize)
+  {
+    let mut builder: TensorBuilder<i32>=TensorBuilder::<i32>::new();
+    builder.push_row(&[1,2]).unwrap();
+    builder.push_row(&[3,4]).unwrap();
+
+    assert!(builder.push_row(row)==Err(TensorError::RowLength{row:2,len:len,width:2}));
+    assert!(builder.len()==2);
+    assert!(builder.build().iter().copied().eq([1,2,3,4].iter().copied()));
+  }
+
+  #[rstest(rows,dim,case(0,[0,0]),case(1,[1,0]),case(2,[2,0]))]
+  fn builder_test_empty(rows: usize, dim: Dim<2>)
+  {
+    let mut builder: TensorBuilder<f64>=TensorBuilder::<f64>::new();
+    assert!(builder.is_empty());
+    (0..rows).for_each(|_| builder.push_row(&[]).unwrap());
+    assert!(builder.len()==rows);
+
+    let t: Tensor<f64,2>=builder.build();
+    assert!(t.dim==dim && t.is_empty());
+  }
+
+  #[test]
+  fn builder_test_empty_vector()
+  {
+    let t: Tensor<f64,1>=VectorBuilder::<f64>::new().build();
+    assert!(t.dim==[0] && t.is_empty());
+  }
+
+  #[rstest(rows,expected,
+    case(vec![vec![1,2,3],vec![4,5,6]],Ok(Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as i32+1))),
+    case(vec![],Ok(Tensor::<i32,2>::new([0,0]))),
+    case(vec![vec![],vec![]],Ok(Tensor::<i32,2>::new([2,0]))),
+    case(vec![vec![1,2],vec![3]],Err(TensorError::RowLength{row:1,len:1,width:2})),
+    case(vec![vec![1],vec![2],vec![3,4]],Err(TensorError::RowLength{row:2,len:2,width:1})),
+  )]
+  fn builder_test_from_rows(rows: Vec<Vec<i32>>, expected: Result<Tensor<i32,2>,TensorError>)
+  {
+    let rows: Vec<&[i32]>=rows.iter().map(|row| row.as_slice()).collect();
+    match (Tensor::<i32,2>::from_rows(&rows),expected)
+    {
+      (Ok(t),Ok(expected)) => assert_tensor_eq!(t,expected),
+      (res,expected) => assert!(res.err()==expected.err()),
+    }
+  }
+
+  #[rstest(len,case(10),case(1),case(100))]
+  fn builder_test_push(len: usize)
+  {
+    let mut builder: VectorBuilder<u8>=VectorBuilder::<u8>::new();
+    (0..len).for_each(|elem| builder.push(elem as u8));
+    assert!(builder.len()==len);
+
+    let t: Tensor<u8,1>=builder.build();
+    assert!(t.dim==[len] && t.iter().enumerate().all(|(itr,&elem)| elem==itr as u8));
+  }
+}
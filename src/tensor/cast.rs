@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+
+use super::Idx;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+/// Conversion of an element of type `T` into `Self`.
+///
+/// `cast_from` follows the semantics of `as`: floats are truncated towards zero and saturate when
+/// converted to integers, NaN becomes zero, and integers wrap when narrowed. `try_cast_from`
+/// returns `None` instead whenever the value, after truncation towards zero, does not fit the
+/// target type. Precision lost in rounding to a float is not an error.
+pub(super) trait CastFrom<T>: Sized
+{
+  fn cast_from(val: T) -> Self;
+  fn try_cast_from(val: T) -> Option<Self>;
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Converts every element to `U` as `as` would.
+  pub(super) fn cast<U>(&self) -> Tensor<U,N>
+  where U: Scalar + CastFrom<T>
+  {
+    self.map(|elem| U::cast_from(elem.clone()))
+  }
+
+  /// Converts every element to `U`, failing on the first element whose value cannot be represented
+  /// in `U`, such as NaN or a value out of range of an integer type. The elements are checked, and
+  /// the result is stored, in row-major order.
+  pub(super) fn try_cast<U>(&self) -> Result<Tensor<U,N>,TensorError>
+  where U: Scalar + CastFrom<T>
+  {
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    let data: Vec<U>=t.data.iter()
+      .enumerate()
+      .map(|(itr,elem)| U::try_cast_from(elem.clone()).ok_or(TensorError::NotRepresentable{index:itr}))
+      .collect::<Result<Vec<U>,TensorError>>()?;
+    Ok(Tensor{data:data.into(),dim:self.dim,layout:Layout::RowMajor,names:self.names})
+  }
+}
+
+macro_rules! impl_cast_int_int {
+  ($src:ty => $($dst:ty),*) => {
+    $(
+      impl CastFrom<$src> for $dst
+      {
+        fn cast_from(val: $src) -> $dst { val as $dst }
+        fn try_cast_from(val: $src) -> Option<$dst> { <$dst>::try_from(val).ok() }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_cast_int_float {
+  ($src:ty => $($dst:ty),*) => {
+    $(
+      impl CastFrom<$src> for $dst
+      {
+        fn cast_from(val: $src) -> $dst { val as $dst }
+        fn try_cast_from(val: $src) -> Option<$dst> { Some(val as $dst) }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_cast_float_int {
+  ($src:ty => $($dst:ty),*) => {
+    $(
+      impl CastFrom<$src> for $dst
+      {
+        fn cast_from(val: $src) -> $dst { val as $dst }
+        fn try_cast_from(val: $src) -> Option<$dst>
+        {
+          // Both bounds are powers of two and so exact in the float type. Where the float type
+          // cannot tell `lower-1` from `lower`, it holds no value strictly between them either.
+          let lower: $src=<$dst>::MIN as $src;
+          let upper: $src=(<$dst>::MAX/2+1) as $src*2.0;
+          if (val>=lower || val>lower-1.0) && val<upper { Some(val as $dst) } else { None }
+        }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_cast_float_float {
+  ($src:ty => $($dst:ty),*) => {
+    $(
+      impl CastFrom<$src> for $dst
+      {
+        fn cast_from(val: $src) -> $dst { val as $dst }
+        fn try_cast_from(val: $src) -> Option<$dst>
+        {
+          let cast: $dst=val as $dst;
+          if val.is_finite() && !cast.is_finite() { None } else { Some(cast) }
+        }
+      }
+    )*
+  };
+}
+
+impl_cast_int_int!(i8 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(i16 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(i32 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(i64 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(u8 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(u16 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(u32 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_int_int!(u64 => i8,i16,i32,i64,u8,u16,u32,u64);
+
+impl_cast_int_float!(i8 => f32,f64);
+impl_cast_int_float!(i16 => f32,f64);
+impl_cast_int_float!(i32 => f32,f64);
+impl_cast_int_float!(i64 => f32,f64);
+impl_cast_int_float!(u8 => f32,f64);
+impl_cast_int_float!(u16 => f32,f64);
+impl_cast_int_float!(u32 => f32,f64);
+impl_cast_int_float!(u64 => f32,f64);
+
+impl_cast_float_int!(f32 => i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_float_int!(f64 => i8,i16,i32,i64,u8,u16,u32,u64);
+
+impl_cast_float_float!(f32 => f32,f64);
+impl_cast_float_float!(f64 => f32,f64);
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod cast_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+
+  /// Casts of floats to integers truncate, saturate and take NaN to zero.
+  #[rstest(elem,as_i32,as_u8,
+    case(2.9,2,2),
+    case(-2.9,-2,0),
+    case(0.5,0,0),
+    case(300.0,300,255),
+    case(-1.0,-1,0),
+    case(f64::NAN,0,0),
+    case(f64::INFINITY,i32::MAX,255),
+  )]
+  fn cast_test_float_int(elem: f64, as_i32: i32, as_u8: u8)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,1],|_| elem);
+    assert!(t.cast::<i32>().dim==[2,1] && t.cast::<i32>().iter().all(|&cast| cast==as_i32));
+    assert!(t.cast::<u8>().iter().all(|&cast| cast==as_u8));
+  }
+
+  #[rstest(elem,as_i8,as_u32,as_f32,
+    case(-1,-1,u32::MAX,-1.0),
+    case(0,0,0,0.0),
+    case(2,2,2,2.0),
+    case(127,127,127,127.0),
+  )]
+  fn cast_test_int(elem: i64, as_i8: i8, as_u32: u32, as_f32: f32)
+  {
+    let t: Tensor<i64,2>=Tensor::<i64,2>::from_fn([2,2],|_| elem);
+    assert!(t.cast::<i8>().dim==[2,2] && t.cast::<i8>().iter().all(|&cast| cast==as_i8));
+    assert!(t.cast::<u32>().iter().all(|&cast| cast==as_u32));
+    assert!(t.cast::<f32>().iter().all(|&cast| cast==as_f32));
+  }
+
+  /// The index is that of the first element that the target type cannot represent.
+  #[rstest(elems,try_cast,index,
+    case(&[-0.5,127.9,128.0],|t: &Tensor<f64,1>| t.try_cast::<i8>().err(),Some(2)),
+    case(&[-0.5,127.9,128.0],|t: &Tensor<f64,1>| t.try_cast::<u8>().err(),None),
+    case(&[-0.5,-1.0],|t: &Tensor<f64,1>| t.try_cast::<u8>().err(),Some(1)),
+    case(&[1.0,f64::NAN],|t: &Tensor<f64,1>| t.try_cast::<i64>().err(),Some(1)),
+    case(&[1.0,f64::NAN],|t: &Tensor<f64,1>| t.try_cast::<f32>().err(),None),
+    case(&[1e300,f64::INFINITY,-1e300],|t: &Tensor<f64,1>| t.try_cast::<f32>().err(),Some(0)),
+    case(&[f64::INFINITY,f64::NEG_INFINITY],|t: &Tensor<f64,1>| t.try_cast::<f32>().err(),None),
+    case(&[i64::MIN as f64,-(i64::MIN as f64)],|t: &Tensor<f64,1>| t.try_cast::<i64>().err(),Some(1)),
+    case(&[i64::MIN as f64,-(i64::MIN as f64)],|t: &Tensor<f64,1>| t.try_cast::<u64>().err(),Some(0)),
+    case(&[i64::MIN as f64],|t: &Tensor<f64,1>| t.try_cast::<i64>().err(),None),
+  )]
+  fn cast_test_try_cast(elems: &[f64], try_cast: fn(&Tensor<f64,1>) -> Option<TensorError>, index: Option<Idx>)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr]);
+    assert!(try_cast(&t)==index.map(|index| TensorError::NotRepresentable{index:index}));
+  }
+
+  #[rstest(elems,expected,
+    case(&[-0.5,127.9,128.0],&[0,127,128]),
+    case(&[255.5,0.0],&[255,0]),
+  )]
+  fn cast_test_try_cast_values(elems: &[f64], expected: &[u8])
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr]);
+    assert!(t.try_cast::<u8>().unwrap().iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[test]
+  fn cast_test_try_cast_exact()
+  {
+    assert!(Tensor::<f32,1>::from_fn([1],|_| f32::NAN).try_cast::<f64>().unwrap()[0].is_nan());
+    assert!(Tensor::<f64,1>::from_fn([1],|_| i64::MIN as f64).try_cast::<i64>().unwrap()[0]==i64::MIN);
+  }
+
+  #[rstest(elems,index,case(&[-1,0,1,2],Some(0)),case(&[0,1,-2],Some(2)),case(&[0,1,2],None))]
+  fn cast_test_try_cast_int(elems: &[i64], index: Option<Idx>)
+  {
+    let t: Tensor<i64,1>=Tensor::<i64,1>::from_fn([elems.len()],|[itr]| elems[itr]);
+    assert!(t.try_cast::<u32>().err()==index.map(|index| TensorError::NotRepresentable{index:index}));
+  }
+
+  #[rstest(dim,case([2,3,4]),case([1,1,1]),case([0,3,2]))]
+  fn cast_test_round_trip(dim: Dim<3>)
+  {
+    let t: Tensor<f32,3>=Tensor::<f32,3>::from_fn(dim,|[itr,jtr,ktr]| (itr as f32-jtr as f32)/(ktr as f32+3.0));
+    let wide: Tensor<f64,3>=t.try_cast().unwrap();
+    let narrow: Tensor<f32,3>=wide.try_cast().unwrap();
+
+    assert!(narrow.dim==t.dim);
+    assert!(narrow.iter().eq(t.iter()));
+  }
+}
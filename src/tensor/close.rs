@@ -0,0 +1,120 @@
+use core::fmt;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Whether `self` and `rhs` are of the same dimensions and every pair of elements `a` and `b`
+  /// satisfies `|a-b| <= atol+rtol*|b|`, with NaN only close to NaN.
+  pub(super) fn allclose(&self, rhs: &Tensor<T,N>, rtol: T, atol: T) -> bool
+  {
+    if self.dim!=rhs.dim { return false; }
+
+    let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+    self.data.iter().zip(rhs.data.iter()).all(|(&a,&b)| close(a,b,rtol,atol))
+  }
+
+  /// Panics unless `allclose` holds, reporting the dimensions if they differ, and otherwise the number
+  /// of elements that are not close and the index, values, and absolute and relative error of the
+  /// element with the largest absolute error among them. An error that is NaN, from only one of the
+  /// elements being NaN, is larger than any other, and the first of several such errors is reported.
+  #[track_caller]
+  pub(super) fn assert_allclose(&self, rhs: &Tensor<T,N>, rtol: T, atol: T)
+  where T: fmt::Display
+  {
+    if self.dim!=rhs.dim { panic!("Tensors of dimensions {:?} and {:?} are not close as they differ in dimensions.",self.dim,rhs.dim) }
+
+    let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+    let mut count: Idx=0;
+    let mut worst: Option<(Idx,T)>=None;
+    for (itr,(&a,&b)) in self.data.iter().zip(rhs.data.iter()).enumerate()
+    {
+      if close(a,b,rtol,atol) { continue; }
+      count+=1;
+      let err: T=(a-b).abs();
+      match worst
+      {
+        Some((_,worst_err)) if !worst_err.is_nan() && (err.is_nan() || err>worst_err) => worst=Some((itr,err)),
+        Some(_) => (),
+        None => worst=Some((itr,err)),
+      }
+    }
+
+    if let Some((itr,err))=worst
+    {
+      let (a,b): (T,T)=(self.data[itr],rhs.data[itr]);
+      panic!("Tensors are not close with rtol {} and atol {}: {} of {} elements differ, the worst at index {:?} \
+        where the left is {} and the right is {}, an absolute error of {} and a relative error of {}.",
+        rtol,atol,count,self.data.len(),self.unravel_offset(itr),a,b,err,err/b.abs())
+    }
+  }
+}
+
+fn close<T>(a: T, b: T, rtol: T, atol: T) -> bool
+where T: Float
+{
+  (a.is_nan() && b.is_nan()) || (a-b).abs()<=atol+rtol*b.abs() || a==b
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod close_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::string::String;
+  use std::panic::catch_unwind;
+
+  #[rstest(lhs,rhs,rtol,atol,expected,
+    case(&[0.0,1.0,2.0,3.0],&[0.0,1.000000001,2.000000002,3.000000003],1e-8,0.0,true),
+    case(&[0.0,1.0,2.0,3.0],&[0.0,1.000000001,2.000000002,3.000000003],1e-10,0.0,false),
+    case(&[0.0,1.0,2.0,3.0],&[1e-7,1.000000001,2.000000002,3.000000003],1e-8,0.0,false),
+    case(&[0.0,1.0,2.0,3.0],&[1e-7,1.000000001,2.000000002,3.000000003],1e-8,1e-6,true),
+    case(&[0.0,1.0,2.0,3.0],&[0.0,1.0,f64::NAN,3.0],1e-8,1e-6,false),
+    case(&[0.0,1.0,f64::NAN,3.0],&[0.0,1.0,f64::NAN,3.0],1e-8,1e-6,true),
+    case(&[f64::INFINITY],&[f64::INFINITY],0.0,0.0,true),
+    case(&[f64::INFINITY],&[f64::NEG_INFINITY],0.0,1.0,false),
+    case(&[0.0,1.0],&[0.0,1.0,2.0],1.0,1.0,false),
+  )]
+  fn close_test_allclose(lhs: &[f64], rhs: &[f64], rtol: f64, atol: f64, expected: bool)
+  {
+    let t1: Tensor<f64,1>=Tensor::<f64,1>::from_fn([lhs.len()],|[itr]| lhs[itr]);
+    let t2: Tensor<f64,1>=Tensor::<f64,1>::from_fn([rhs.len()],|[itr]| rhs[itr]);
+
+    assert!(t1.allclose(&t2,rtol,atol)==expected);
+  }
+
+  #[rstest(rhs,expected,
+    case([[0.0,1.0,2.5],[3.0,4.5,5.01]],"3 of 6 elements differ, the worst at index [0, 2] where the left is 2 and the right is 2.5, \
+      an absolute error of 0.5 and a relative error of 0.2."),
+    case([[0.0,1.0,2.0],[3.0,4.0,5.01]],"1 of 6 elements differ, the worst at index [1, 2] where the left is 5 and the right is 5.01, \
+      an absolute error of 0.009999999999999787 and a relative error of 0.0019960079840318935."),
+    case([[0.0,1.0,2.5],[f64::NAN,4.5,f64::NAN]],"4 of 6 elements differ, the worst at index [1, 0] where the left is 3 and the right is NaN, \
+      an absolute error of NaN"),
+  )]
+  fn close_test_assert_allclose(rhs: [[f64;3];2], expected: &str)
+  {
+    let t1: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    let t2: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| rhs[itr][jtr]);
+    t1.assert_allclose(&t1.clone(),0.0,0.0);
+
+    let err=catch_unwind(|| t1.assert_allclose(&t2,1e-3,0.0)).unwrap_err();
+    let msg: &String=err.downcast_ref::<String>().unwrap();
+    assert!(msg.strip_prefix("Tensors are not close with rtol 0.001 and atol 0: ").unwrap().starts_with(expected));
+  }
+
+  #[test]
+  #[should_panic(expected="Tensors of dimensions [2, 3] and [3, 2] are not close as they differ in dimensions.")]
+  fn close_test_assert_allclose_dims()
+  {
+    Tensor::<f64,2>::new([2,3]).assert_allclose(&Tensor::<f64,2>::new([3,2]),1.0,1.0);
+  }
+}
@@ -0,0 +1,113 @@
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+
+/// Checks of an axis `AXIS` of a tensor of rank `N` reduced to one of rank `M`, which fail to compile
+/// when a method referring to them is instantiated with arguments that break them.
+struct AxisCheck<const AXIS: Idx, const N: Idx, const M: Idx>;
+
+impl<const AXIS: Idx, const N: Idx, const M: Idx> AxisCheck<AXIS,N,M>
+{
+  /// `AXIS` is an axis of the tensor.
+  const AXIS: ()=assert!(AXIS<N,"The axis must be less than the number of dimensions of the tensor.");
+  /// The result is of one dimension fewer than the tensor.
+  const REDUCED: ()=assert!(M+1==N,"The rank of the result must be one less than that of the tensor to reduce an axis.");
+}
+
+// Variants of the axis reductions with the axis given as a const argument, so that an axis beyond the
+// rank, or a result of the wrong rank, fails to compile rather than panics. The runtime-axis methods
+// remain for axes only known at run time.
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Variant of `reduce_axis` along the axis `AXIS`.
+  pub(super) fn reduce_axis_const<F,const AXIS: Idx,const M: Idx>(&self, init: T, f: F) -> Tensor<T,M>
+  where F: FnMut(T,&T) -> T
+  {
+    let _: ()=AxisCheck::<AXIS,N,M>::AXIS;
+    let _: ()=AxisCheck::<AXIS,N,M>::REDUCED;
+    self.reduce_axis(AXIS,init,f)
+  }
+
+  /// Variant of `sum_axis` along the axis `AXIS`.
+  pub(super) fn sum_axis_const<const AXIS: Idx,const M: Idx>(&self) -> Tensor<T,M>
+  {
+    let _: ()=AxisCheck::<AXIS,N,M>::AXIS;
+    let _: ()=AxisCheck::<AXIS,N,M>::REDUCED;
+    self.sum_axis(AXIS)
+  }
+
+  /// Variant of `sum_axis_keepdims` along the axis `AXIS`.
+  pub(super) fn sum_axis_keepdims_const<const AXIS: Idx>(&self) -> Tensor<T,N>
+  {
+    let _: ()=AxisCheck::<AXIS,N,0>::AXIS;
+    self.sum_axis_keepdims(AXIS)
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Variant of `max_axis` along the axis `AXIS`.
+  pub(super) fn max_axis_const<const AXIS: Idx,const M: Idx>(&self) -> Tensor<T,M>
+  {
+    let _: ()=AxisCheck::<AXIS,N,M>::AXIS;
+    let _: ()=AxisCheck::<AXIS,N,M>::REDUCED;
+    self.max_axis(AXIS)
+  }
+
+  /// Variant of `mean_axis_keepdims` along the axis `AXIS`.
+  pub(super) fn mean_axis_keepdims_const<const AXIS: Idx>(&self) -> Tensor<T,N>
+  {
+    let _: ()=AxisCheck::<AXIS,N,0>::AXIS;
+    self.mean_axis_keepdims(AXIS)
+  }
+}
+
+
+//
+// Tests
+//
+
+// Out of range arguments are compile errors, which are tested under `tests/ui` through the entry point
+// in `ui.rs`: a call of `sum_axis_const::<5,2>` on a tensor of rank three fails to build with "The axis
+// must be less than the number of dimensions of the tensor.", and one of `sum_axis_const::<1,1>` with
+// the message of the rank of the result. As the checks are evaluated when the call is compiled to
+// code, `cargo check` does not report them, but `cargo build` does.
+
+#[cfg(test)]
+mod const_axis_tests
+{
+  use super::*;
+  use super::super::Layout;
+  use super::super::testing::assert_tensor_eq;
+  use rstest::rstest;
+
+  #[rstest(reduce,expected,
+    case(|t: &Tensor<f64,3>| t.sum_axis_const::<0,2>(),|t: &Tensor<f64,3>| t.sum_axis::<2>(0)),
+    case(|t: &Tensor<f64,3>| t.sum_axis_const::<2,_>(),|t: &Tensor<f64,3>| t.sum_axis::<2>(2)),
+    case(|t: &Tensor<f64,3>| t.max_axis_const::<1,2>(),|t: &Tensor<f64,3>| t.max_axis::<2>(1)),
+    case(|t: &Tensor<f64,3>| t.reduce_axis_const::<_,0,2>(1.0,|prod,&elem| prod*elem),|t: &Tensor<f64,3>| t.reduce_axis::<_,2>(0,1.0,|prod,&elem| prod*elem)),
+  )]
+  fn const_axis_test_matches_runtime(reduce: fn(&Tensor<f64,3>) -> Tensor<f64,2>, expected: fn(&Tensor<f64,3>) -> Tensor<f64,2>)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as f64-5.0);
+
+    assert_tensor_eq!(reduce(&t),expected(&t));
+    assert_tensor_eq!(reduce(&t.to_layout(Layout::ColMajor)),expected(&t));
+  }
+
+  #[rstest(reduce,expected,
+    case(|t: &Tensor<f64,3>| t.sum_axis_keepdims_const::<1>(),|t: &Tensor<f64,3>| t.sum_axis_keepdims(1)),
+    case(|t: &Tensor<f64,3>| t.mean_axis_keepdims_const::<2>(),|t: &Tensor<f64,3>| t.mean_axis_keepdims(2)),
+  )]
+  fn const_axis_test_matches_runtime_keepdims(reduce: fn(&Tensor<f64,3>) -> Tensor<f64,3>, expected: fn(&Tensor<f64,3>) -> Tensor<f64,3>)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as f64-5.0);
+
+    assert_tensor_eq!(reduce(&t),expected(&t));
+    assert_tensor_eq!(reduce(&t.to_layout(Layout::ColMajor)),expected(&t));
+  }
+}
@@ -0,0 +1,694 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+use super::Dim;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+use super::encoding::Integer;
+use super::encoding::RleEncoded;
+use super::encoding::decode_delta_slice;
+use super::npy::NpyElement;
+use super::npy::io_error;
+
+/// Magic bytes that every container starts with.
+const MAGIC: &[u8;8]=b"\x89LEMMA\r\n";
+/// Version of the container format written by `save`.
+const VERSION: u16=1;
+/// Version of the container format written by `save_encoded`, whose header also holds the encoding
+/// of the elements.
+const VERSION_ENCODED: u16=2;
+
+/// Element type of a tensor in a container, stored as its discriminant.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub(super) enum Dtype
+{
+  F32=1,
+  F64=2,
+  I8=3,
+  I16=4,
+  I32=5,
+  I64=6,
+  U8=7,
+  U16=8,
+  U32=9,
+  U64=10,
+}
+
+impl Dtype
+{
+  const ALL: [Dtype;10]=[Dtype::F32,Dtype::F64,Dtype::I8,Dtype::I16,Dtype::I32,Dtype::I64,Dtype::U8,Dtype::U16,Dtype::U32,Dtype::U64];
+
+  /// Name of the element type in Rust.
+  pub(super) fn name(self) -> &'static str
+  {
+    match self
+    {
+      Dtype::F32 => "f32",
+      Dtype::F64 => "f64",
+      Dtype::I8 => "i8",
+      Dtype::I16 => "i16",
+      Dtype::I32 => "i32",
+      Dtype::I64 => "i64",
+      Dtype::U8 => "u8",
+      Dtype::U16 => "u16",
+      Dtype::U32 => "u32",
+      Dtype::U64 => "u64",
+    }
+  }
+}
+
+/// Encoding of the elements of a tensor in a container, stored as its discriminant.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub(super) enum Encoding
+{
+  /// The elements in row-major order.
+  Raw=0,
+  /// The runs of `Tensor::encode_rle`: their number, and then every element followed by the length of
+  /// its run, the number and lengths as LEB128 integers.
+  Rle=1,
+  /// The elements of `Tensor::encode_delta` in row-major order.
+  Delta=2,
+  /// The runs of the elements of `Tensor::encode_delta`, stored as for `Rle`.
+  DeltaRle=3,
+}
+
+impl Encoding
+{
+  const ALL: [Encoding;4]=[Encoding::Raw,Encoding::Rle,Encoding::Delta,Encoding::DeltaRle];
+
+  fn is_delta(self) -> bool
+  {
+    self==Encoding::Delta || self==Encoding::DeltaRle
+  }
+
+  fn is_rle(self) -> bool
+  {
+    self==Encoding::Rle || self==Encoding::DeltaRle
+  }
+}
+
+/// Elements of a `DynTensor`, of the element type that was stored.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) enum DynData
+{
+  F32(Vec<f32>),
+  F64(Vec<f64>),
+  I8(Vec<i8>),
+  I16(Vec<i16>),
+  I32(Vec<i32>),
+  I64(Vec<i64>),
+  U8(Vec<u8>),
+  U16(Vec<u16>),
+  U32(Vec<u32>),
+  U64(Vec<u64>),
+}
+
+/// Element types that can be stored in a container.
+pub(super) trait ContainerElement: NpyElement + Clone
+{
+  const DTYPE: Dtype;
+  fn into_dyn(data: Vec<Self>) -> DynData;
+  /// The elements of `data` if they are of this type.
+  fn from_dyn(data: DynData) -> Option<Vec<Self>>;
+  /// Reverses `Tensor::encode_delta` on the row-major elements `data`, returning whether elements of
+  /// this type can be delta encoded at all.
+  fn decode_delta(data: &mut [Self]) -> bool;
+}
+
+macro_rules! impl_container_element {
+  ($($type:ty => $variant:ident),*; $($int:ty => $int_variant:ident),*) => {
+    $(impl_container_element!(@impl $type,$variant,|_: &mut [$type]| false);)*
+    $(impl_container_element!(@impl $int,$int_variant,|data: &mut [$int]| { decode_delta_slice(data); true });)*
+  };
+  (@impl $type:ty,$variant:ident,$decode_delta:expr) => {
+    impl ContainerElement for $type
+    {
+      const DTYPE: Dtype=Dtype::$variant;
+      fn into_dyn(data: Vec<$type>) -> DynData { DynData::$variant(data) }
+      fn from_dyn(data: DynData) -> Option<Vec<$type>>
+      {
+        match data
+        {
+          DynData::$variant(data) => Some(data),
+          _ => None,
+        }
+      }
+      fn decode_delta(data: &mut [$type]) -> bool { ($decode_delta)(data) }
+    }
+  };
+}
+
+impl_container_element!(f32 => F32,f64 => F64; i8 => I8,i16 => I16,i32 => I32,i64 => I64,u8 => U8,u16 => U16,u32 => U32,u64 => U64);
+
+/// Tensor whose element type and number of dimensions are only known at run time, as loaded from a
+/// container whose contents are not known in advance.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) struct DynTensor
+{
+  dim: Vec<Idx>,
+  /// Elements in row-major order.
+  data: DynData,
+}
+
+impl DynTensor
+{
+  /// Reads a tensor of any element type and number of dimensions from the container in `reader`,
+  /// decoding its elements if they are encoded.
+  ///
+  /// Fails with the header field at fault if the container is malformed or ends within its header, and
+  /// with the elements at fault if it ends before all of them are read or their runs do not add up to
+  /// the dimensions.
+  pub(super) fn load<R>(mut reader: R) -> Result<DynTensor,TensorError>
+  where R: Read
+  {
+    let (dtype,dim,encoding): (Dtype,Vec<Idx>,Encoding)=read_header(&mut reader)?;
+    let size: Idx=dim.iter().product();
+    let data: DynData=match dtype
+    {
+      Dtype::F32 => f32::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::F64 => f64::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::I8 => i8::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::I16 => i16::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::I32 => i32::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::I64 => i64::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::U8 => u8::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::U16 => u16::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::U32 => u32::into_dyn(read_encoded(&mut reader,size,encoding)?),
+      Dtype::U64 => u64::into_dyn(read_encoded(&mut reader,size,encoding)?),
+    };
+    Ok(DynTensor{dim:dim,data:data})
+  }
+
+  /// Dimensions of the tensor, as many as it has.
+  pub(super) fn dim(&self) -> &[Idx]
+  {
+    &self.dim
+  }
+
+  /// Element type of the tensor.
+  pub(super) fn dtype(&self) -> Dtype
+  {
+    match self.data
+    {
+      DynData::F32(_) => Dtype::F32,
+      DynData::F64(_) => Dtype::F64,
+      DynData::I8(_) => Dtype::I8,
+      DynData::I16(_) => Dtype::I16,
+      DynData::I32(_) => Dtype::I32,
+      DynData::I64(_) => Dtype::I64,
+      DynData::U8(_) => Dtype::U8,
+      DynData::U16(_) => Dtype::U16,
+      DynData::U32(_) => Dtype::U32,
+      DynData::U64(_) => Dtype::U64,
+    }
+  }
+
+  /// Elements of the tensor in row-major order.
+  pub(super) fn data(&self) -> &DynData
+  {
+    &self.data
+  }
+
+  /// The tensor as a `Tensor` of elements of type `T` and `N` dimensions, failing unless those are its
+  /// element type and number of dimensions.
+  pub(super) fn into_tensor<T,const N: Idx>(self) -> Result<Tensor<T,N>,TensorError>
+  where T: ContainerElement
+  {
+    let (dtype,rank): (Dtype,Idx)=(self.dtype(),self.dim.len());
+    let mismatch=|| TensorError::ContainerType{dtype:dtype.name(),rank:rank};
+    if rank!=N { return Err(mismatch()); }
+    let data: Vec<T>=T::from_dyn(self.data).ok_or_else(mismatch)?;
+    let mut dim: Dim<N>=[0;N];
+    dim.copy_from_slice(&self.dim);
+    Tensor::<T,N>::from_vec(dim,data)
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: ContainerElement
+{
+  /// Writes the tensor to `w` as a container that describes itself: the magic bytes, the version of
+  /// the format as a 16-bit integer, the element type and the number of dimensions as a byte each, the
+  /// dimensions as 64-bit integers, and then the elements in row-major order, all little-endian.
+  pub(super) fn save<W>(&self, mut w: W) -> Result<(),TensorError>
+  where W: Write
+  {
+    let mut bytes: Vec<u8>=Vec::with_capacity(MAGIC.len()+4+8*N+self.len()*T::SIZE);
+    self.extend_header(&mut bytes,None);
+    self.to_layout(Layout::RowMajor).data.iter().for_each(|elem| elem.extend_le(&mut bytes));
+    w.write_all(&bytes).map_err(io_error)
+  }
+
+  /// Reads a tensor from the container in `reader`, failing as `DynTensor::load` does, or before the
+  /// elements are read if they are not of type `T` or the tensor is not of `N` dimensions.
+  pub(super) fn load_as<R>(mut reader: R) -> Result<Tensor<T,N>,TensorError>
+  where R: Read
+  {
+    let (dtype,dim,encoding): (Dtype,Vec<Idx>,Encoding)=read_header(&mut reader)?;
+    if dtype!=T::DTYPE || dim.len()!=N { return Err(TensorError::ContainerType{dtype:dtype.name(),rank:dim.len()}); }
+    let data: Vec<T>=read_encoded(&mut reader,dim.iter().product(),encoding)?;
+    DynTensor{dim:dim,data:T::into_dyn(data)}.into_tensor()
+  }
+
+  /// Writes the magic bytes, the version and the element type, the encoding if one is given, and the
+  /// number of dimensions and the dimensions.
+  fn extend_header(&self, bytes: &mut Vec<u8>, encoding: Option<Encoding>)
+  {
+    bytes.extend_from_slice(MAGIC);
+    match encoding
+    {
+      None => bytes.extend_from_slice(&VERSION.to_le_bytes()),
+      Some(_) => bytes.extend_from_slice(&VERSION_ENCODED.to_le_bytes()),
+    }
+    bytes.extend_from_slice(&[T::DTYPE as u8,N as u8]);
+    if let Some(encoding)=encoding { bytes.push(encoding as u8); }
+    self.dim.iter().for_each(|&dim| bytes.extend_from_slice(&(dim as u64).to_le_bytes()));
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: ContainerElement + Integer
+{
+  /// Writes the tensor to `w` as a container as `save` does, but with its elements in `encoding`,
+  /// which is recorded in the header after the number of dimensions, so `load_as` and
+  /// `DynTensor::load` decode them without being told.
+  ///
+  /// A container that holds runs can decode to far more elements than it holds bytes, so loading one
+  /// fails with the elements, rather than aborts, if there is no memory for them.
+  pub(super) fn save_encoded<W>(&self, mut w: W, encoding: Encoding) -> Result<(),TensorError>
+  where W: Write
+  {
+    let t: Tensor<T,N>=if encoding.is_delta() { self.encode_delta() } else { self.to_layout(Layout::RowMajor) };
+    let mut bytes: Vec<u8>=Vec::new();
+    self.extend_header(&mut bytes,Some(encoding));
+    if encoding.is_rle()
+    {
+      let rle: RleEncoded<T,N>=t.encode_rle();
+      extend_leb128(&mut bytes,rle.runs.len() as u64);
+      for (elem,len) in rle.runs.iter()
+      {
+        elem.extend_le(&mut bytes);
+        extend_leb128(&mut bytes,*len as u64);
+      }
+    }
+    else
+    {
+      t.data.iter().for_each(|elem| elem.extend_le(&mut bytes));
+    }
+    w.write_all(&bytes).map_err(io_error)
+  }
+}
+
+/// Reads the header of a container up to the elements, returning the element type, the dimensions and
+/// the encoding of the elements, which is `Raw` for containers written by `save`.
+fn read_header<R>(reader: &mut R) -> Result<(Dtype,Vec<Idx>,Encoding),TensorError>
+where R: Read
+{
+  let mut magic: [u8;8]=[0;8];
+  read_field(reader,&mut magic,"magic bytes")?;
+  if magic!=*MAGIC { return Err(TensorError::ContainerField{field:"magic bytes"}); }
+
+  let mut version: [u8;2]=[0;2];
+  read_field(reader,&mut version,"version")?;
+  let version: u16=u16::from_le_bytes(version);
+  if version!=VERSION && version!=VERSION_ENCODED { return Err(TensorError::ContainerField{field:"version"}); }
+
+  let mut tag: [u8;2]=[0;2];
+  read_field(reader,&mut tag,"element type")?;
+  let dtype: Dtype=*Dtype::ALL.iter().find(|&&dtype| dtype as u8==tag[0]).ok_or(TensorError::ContainerField{field:"element type"})?;
+
+  let mut byte: [u8;1]=[Encoding::Raw as u8];
+  if version==VERSION_ENCODED { read_field(reader,&mut byte,"encoding")?; }
+  let encoding: Encoding=*Encoding::ALL.iter().find(|&&encoding| encoding as u8==byte[0]).ok_or(TensorError::ContainerField{field:"encoding"})?;
+
+  let mut dim: Vec<Idx>=vec![0;tag[1] as Idx];
+  let mut size: Option<Idx>=Some(1);
+  for len in dim.iter_mut()
+  {
+    let mut bytes: [u8;8]=[0;8];
+    read_field(reader,&mut bytes,"dimensions")?;
+    *len=u64::from_le_bytes(bytes) as Idx;
+    size=size.and_then(|size| size.checked_mul(*len));
+  }
+  // The size in bytes must be addressable for the elements to be read at all.
+  if size.and_then(|size| size.checked_mul(dtype_size(dtype))).is_none() { return Err(TensorError::ContainerField{field:"dimensions"}); }
+  Ok((dtype,dim,encoding))
+}
+
+/// Fills `buf` from `reader`, failing with `field` if the container ends first.
+fn read_field<R>(reader: &mut R, buf: &mut [u8], field: &'static str) -> Result<(),TensorError>
+where R: Read
+{
+  reader.read_exact(buf).map_err(|err| match err.kind()
+  {
+    io::ErrorKind::UnexpectedEof => TensorError::ContainerField{field:field},
+    _ => io_error(err),
+  })
+}
+
+/// Reads `size` elements of type `T`, allocating no more than the container actually holds, so that
+/// the dimensions of a corrupt header cannot exhaust memory.
+fn read_elements<T,R>(reader: &mut R, size: Idx) -> Result<Vec<T>,TensorError>
+where T: ContainerElement, R: Read
+{
+  let len: Idx=size*T::SIZE;
+  let mut bytes: Vec<u8>=Vec::new();
+  reader.take(len as u64).read_to_end(&mut bytes).map_err(io_error)?;
+  if bytes.len()<len { return Err(TensorError::ContainerField{field:"elements"}); }
+  Ok(bytes.chunks_exact(T::SIZE).map(T::from_le_slice).collect())
+}
+
+/// Reads `size` elements of type `T` stored in `encoding`, failing with the encoding if elements of
+/// type `T` cannot be stored in it.
+fn read_encoded<T,R>(reader: &mut R, size: Idx, encoding: Encoding) -> Result<Vec<T>,TensorError>
+where T: ContainerElement, R: Read
+{
+  let mut data: Vec<T>=if encoding.is_rle() { read_runs(reader,size)? } else { read_elements(reader,size)? };
+  if encoding.is_delta() && !T::decode_delta(&mut data) { return Err(TensorError::ContainerField{field:"encoding"}); }
+  Ok(data)
+}
+
+/// Reads the runs of `size` elements of type `T`, failing with the elements if they are cut short,
+/// if any run is empty, if the runs do not add up to `size` elements, or if a run cannot be allocated.
+fn read_runs<T,R>(reader: &mut R, size: Idx) -> Result<Vec<T>,TensorError>
+where T: ContainerElement, R: Read
+{
+  let count: u64=read_leb128(reader)?;
+  let mut data: Vec<T>=Vec::new();
+  let mut bytes: Vec<u8>=vec![0;T::SIZE];
+  for _ in 0..count
+  {
+    read_field(reader,&mut bytes,"elements")?;
+    let len: u64=read_leb128(reader)?;
+    if len==0 || len>(size-data.len()) as u64 { return Err(TensorError::ContainerField{field:"elements"}); }
+    data.try_reserve(len as Idx).map_err(|_| TensorError::ContainerField{field:"elements"})?;
+    data.resize(data.len()+len as Idx,T::from_le_slice(&bytes));
+  }
+  if data.len()!=size { return Err(TensorError::ContainerField{field:"elements"}); }
+  Ok(data)
+}
+
+/// Appends `val` as an unsigned LEB128 integer, seven bits to a byte from the least significant, with
+/// the high bit set on every byte but the last.
+fn extend_leb128(bytes: &mut Vec<u8>, mut val: u64)
+{
+  while val>=0x80
+  {
+    bytes.push((val&0x7f) as u8|0x80);
+    val>>=7;
+  }
+  bytes.push(val as u8);
+}
+
+/// Reads an unsigned LEB128 integer written by `extend_leb128`, failing with the elements if it is cut
+/// short or does not fit in 64 bits.
+fn read_leb128<R>(reader: &mut R) -> Result<u64,TensorError>
+where R: Read
+{
+  let mut val: u64=0;
+  for shift in (0..64).step_by(7)
+  {
+    let mut byte: [u8;1]=[0];
+    read_field(reader,&mut byte,"elements")?;
+    let bits: u64=(byte[0]&0x7f) as u64;
+    if (bits<<shift)>>shift!=bits { break; }
+    val|=bits<<shift;
+    if byte[0]&0x80==0 { return Ok(val); }
+  }
+  Err(TensorError::ContainerField{field:"elements"})
+}
+
+fn dtype_size(dtype: Dtype) -> Idx
+{
+  match dtype
+  {
+    Dtype::I8 | Dtype::U8 => 1,
+    Dtype::I16 | Dtype::U16 => 2,
+    Dtype::F32 | Dtype::I32 | Dtype::U32 => 4,
+    Dtype::F64 | Dtype::I64 | Dtype::U64 => 8,
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod container_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Scalar;
+
+  fn container_test_roundtrip<T,const N: Idx>(t: &Tensor<T,N>)
+  where T: ContainerElement + Scalar + core::fmt::Debug
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    t.save(&mut bytes).unwrap();
+    assert!(bytes.len()==12+8*N+t.len()*T::SIZE);
+    assert!(Tensor::<T,N>::load_as(&bytes[..]).unwrap()==*t);
+
+    let loaded: DynTensor=DynTensor::load(&bytes[..]).unwrap();
+    assert!(loaded.dim()==t.dim && loaded.dtype()==T::DTYPE);
+    assert!(loaded.into_tensor::<T,N>().unwrap()==*t);
+  }
+
+  #[rstest(roundtrip,
+    case(|| container_test_roundtrip(&Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64*0.1-0.5).to_layout(Layout::ColMajor))),
+    case(|| container_test_roundtrip(&Tensor::<f32,3>::from_fn([2,1,3],|[itr,_,ktr]| (3*itr+ktr) as f32-2.5))),
+    case(|| container_test_roundtrip(&Tensor::<i8,1>::from_fn([5],|[itr]| itr as i8-2))),
+    case(|| container_test_roundtrip(&Tensor::<i64,2>::from_fn([2,2],|[itr,jtr]| i64::MIN+(2*itr+jtr) as i64))),
+    case(|| container_test_roundtrip(&Tensor::<u16,1>::from_fn([3],|[itr]| u16::MAX-itr as u16))),
+    case(|| container_test_roundtrip(&Tensor::<u64,2>::new([0,3]))),
+    case(|| container_test_roundtrip(&Tensor::<u32,0>::from_fn([],|_| 7))),
+  )]
+  fn container_test_save(roundtrip: fn())
+  {
+    roundtrip();
+  }
+
+  #[test]
+  fn container_test_save_bytes()
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<i32,1>::from_fn([2],|[itr]| itr as i32-1).save(&mut bytes).unwrap();
+    assert!(bytes==b"\x89LEMMA\r\n\x01\x00\x05\x01\x02\x00\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff\x00\x00\x00\x00");
+  }
+
+  #[test]
+  fn container_test_load()
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<u8,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as u8).save(&mut bytes).unwrap();
+    let loaded: DynTensor=DynTensor::load(&bytes[..]).unwrap();
+    assert!(loaded.dim()==[2,3] && loaded.dtype()==Dtype::U8 && *loaded.data()==DynData::U8(vec![0,1,2,3,4,5]));
+  }
+
+  #[rstest(load,
+    case(|bytes: &[u8]| DynTensor::load(bytes).unwrap().into_tensor::<i8,2>().err()),
+    case(|bytes: &[u8]| DynTensor::load(bytes).unwrap().into_tensor::<u8,3>().err()),
+    case(|bytes: &[u8]| Tensor::<f64,2>::load_as(bytes).err()),
+    case(|bytes: &[u8]| Tensor::<u8,1>::load_as(bytes).err()),
+  )]
+  fn container_test_load_mismatch(load: fn(&[u8]) -> Option<TensorError>)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<u8,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as u8).save(&mut bytes).unwrap();
+
+    assert!(load(&bytes[..])==Some(TensorError::ContainerType{dtype:"u8",rank:2}));
+  }
+
+  /// Truncating the container anywhere fails on the field that is cut short.
+  #[rstest(start,end,field,
+    case(0,8,"magic bytes"),
+    case(8,10,"version"),
+    case(10,12,"element type"),
+    case(12,28,"dimensions"),
+    case(28,76,"elements"),
+  )]
+  fn container_test_load_truncated(start: Idx, end: Idx, field: &'static str)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (itr+jtr) as f64).save(&mut bytes).unwrap();
+    assert!(bytes.len()==76);
+
+    for len in start..end
+    {
+      assert!(DynTensor::load(&bytes[..len]).err()==Some(TensorError::ContainerField{field:field}));
+      assert!(Tensor::<f64,2>::load_as(&bytes[..len]).err()==Some(TensorError::ContainerField{field:field}));
+    }
+  }
+
+  #[rstest(at,byte,field,
+    case(1,b'X',"magic bytes"),
+    case(8,3,"version"),
+    case(10,0,"element type"),
+    case(10,11,"element type"),
+    case(12,3,"elements"),
+    case(19,0x80,"dimensions"),
+  )]
+  fn container_test_load_corrupt(at: Idx, byte: u8, field: &'static str)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (itr+jtr) as f64).save(&mut bytes).unwrap();
+
+    bytes[at]=byte;
+    assert!(DynTensor::load(&bytes[..]).err()==Some(TensorError::ContainerField{field:field}));
+  }
+
+  #[rstest(t,
+    case(Tensor::<i64,2>::from_fn([25,40],|_| -3)),
+    case(Tensor::<i64,2>::from_fn([25,40],|[itr,jtr]| i64::MAX-7000+(40*itr+jtr) as i64*7)),
+    case(Tensor::<i64,2>::from_fn([25,40],|[itr,jtr]| if (itr+jtr)%2==0 { i64::MIN } else { i64::MAX }).to_layout(Layout::ColMajor)),
+    case(Tensor::<i64,2>::new([0,40])),
+  )]
+  fn container_test_encoded(t: Tensor<i64,2>)
+  {
+    for &encoding in Encoding::ALL.iter()
+    {
+      let mut bytes: Vec<u8>=Vec::new();
+      t.save_encoded(&mut bytes,encoding).unwrap();
+      assert!(bytes[8..10]==[2,0] && bytes[12]==encoding as u8);
+      assert!(Tensor::<i64,2>::load_as(&bytes[..]).unwrap()==t);
+      assert!(DynTensor::load(&bytes[..]).unwrap().into_tensor::<i64,2>().unwrap()==t);
+    }
+  }
+
+  /// Runs shrink a constant tensor to its header and a single run, and differences do the same for
+  /// evenly spaced elements.
+  #[rstest(t,encoding,len,
+    case(Tensor::<i64,2>::from_fn([25,40],|_| -3),Encoding::Raw,13+16+8000),
+    case(Tensor::<i64,2>::from_fn([25,40],|_| -3),Encoding::Rle,13+16+1+8+2),
+    case(Tensor::<i64,2>::from_fn([25,40],|[itr,jtr]| i64::MAX-7000+(40*itr+jtr) as i64*7),Encoding::Rle,13+16+2+1000*9),
+    case(Tensor::<i64,2>::from_fn([25,40],|[itr,jtr]| i64::MAX-7000+(40*itr+jtr) as i64*7),Encoding::DeltaRle,13+16+1+8+1+8+2),
+  )]
+  fn container_test_encoded_size(t: Tensor<i64,2>, encoding: Encoding, len: Idx)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    t.save_encoded(&mut bytes,encoding).unwrap();
+    assert!(bytes.len()==len);
+  }
+
+  #[test]
+  fn container_test_encoded_runs()
+  {
+    let labels: Tensor<u8,1>=Tensor::<u8,1>::from_fn([300],|[itr]| (itr/100) as u8);
+    let mut bytes: Vec<u8>=Vec::new();
+    labels.save_encoded(&mut bytes,Encoding::Rle).unwrap();
+    assert!(bytes[21..]==[3,0,100,1,100,2,100]);
+    assert!(*DynTensor::load(&bytes[..]).unwrap().data()==DynData::U8(labels.iter().copied().collect()));
+  }
+
+  #[rstest(val,len,
+    case(0,1),
+    case(1,1),
+    case(0x7f,1),
+    case(0x80,2),
+    case(300,2),
+    case(u32::MAX as u64,5),
+    case(u64::MAX,10),
+  )]
+  fn container_test_leb128(val: u64, len: Idx)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    extend_leb128(&mut bytes,val);
+    assert!(bytes.len()==len && read_leb128(&mut &bytes[..])==Ok(val));
+  }
+
+  #[test]
+  fn container_test_leb128_bytes()
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    extend_leb128(&mut bytes,300);
+    assert!(bytes==[0xac,0x02]);
+  }
+
+  #[rstest(bytes,
+    case(vec![0x80,0x80]),
+    case(vec![]),
+    case(vec![0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0x02]),
+  )]
+  fn container_test_leb128_invalid(bytes: Vec<u8>)
+  {
+    assert!(read_leb128(&mut &bytes[..])==Err(TensorError::ContainerField{field:"elements"}));
+  }
+
+  #[rstest(start,end,field,
+    case(0,8,"magic bytes"),
+    case(8,10,"version"),
+    case(10,12,"element type"),
+    case(12,13,"encoding"),
+    case(13,21,"dimensions"),
+    case(21,37,"elements"),
+  )]
+  fn container_test_encoded_truncated(start: Idx, end: Idx, field: &'static str)
+  {
+    let t: Tensor<u16,1>=Tensor::<u16,1>::from_fn([6],|[itr]| (itr/2) as u16);
+    let mut bytes: Vec<u8>=Vec::new();
+    t.save_encoded(&mut bytes,Encoding::DeltaRle).unwrap();
+    assert!(bytes[21..]==[5,0,0,2,1,0,1,0,0,1,1,0,1,0,0,1]);
+
+    for len in start..end
+    {
+      assert!(DynTensor::load(&bytes[..len]).err()==Some(TensorError::ContainerField{field:field}));
+      assert!(Tensor::<u16,1>::load_as(&bytes[..len]).err()==Some(TensorError::ContainerField{field:field}));
+    }
+  }
+
+  /// The last three are runs that are empty, overrun the dimensions or fall short of them.
+  #[rstest(at,byte,field,
+    case(8,3,"version"),
+    case(12,4,"encoding"),
+    case(24,0,"elements"),
+    case(24,7,"elements"),
+    case(21,2,"elements"),
+  )]
+  fn container_test_encoded_corrupt(at: Idx, byte: u8, field: &'static str)
+  {
+    let t: Tensor<u16,1>=Tensor::<u16,1>::from_fn([6],|[itr]| (itr/2) as u16);
+    let mut bytes: Vec<u8>=Vec::new();
+    t.save_encoded(&mut bytes,Encoding::DeltaRle).unwrap();
+
+    bytes[at]=byte;
+    assert!(DynTensor::load(&bytes[..]).err()==Some(TensorError::ContainerField{field:field}));
+  }
+
+  #[test]
+  fn container_test_encoded_allocation()
+  {
+    // A single run of more elements than can be allocated, in a container of a few bytes.
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<u8,1>::from_fn([3],|_| 7).save_encoded(&mut bytes,Encoding::Rle).unwrap();
+    bytes[13..21].copy_from_slice(&(1u64<<62).to_le_bytes());
+    bytes.truncate(23);
+    extend_leb128(&mut bytes,1<<62);
+    assert!(bytes.len()==32 && DynTensor::load(&bytes[..]).err()==Some(TensorError::ContainerField{field:"elements"}));
+  }
+
+  #[test]
+  fn container_test_encoded_float()
+  {
+    let field=|field: &'static str| Some(TensorError::ContainerField{field:field});
+
+    // Floats can be stored in runs, but not as differences.
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<f64,1>::from_fn([2],|[itr]| itr as f64).save(&mut bytes).unwrap();
+    bytes[8]=2;
+    bytes.insert(12,Encoding::Delta as u8);
+    assert!(DynTensor::load(&bytes[..]).err()==field("encoding"));
+    bytes[12]=Encoding::Rle as u8;
+    bytes.truncate(21);
+    bytes.extend_from_slice(&[1]);
+    bytes.extend_from_slice(&2.5f64.to_le_bytes());
+    bytes.extend_from_slice(&[2]);
+    assert!(Tensor::<f64,1>::load_as(&bytes[..]).unwrap()==Tensor::<f64,1>::from_fn([2],|_| 2.5));
+  }
+}
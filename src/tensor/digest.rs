@@ -0,0 +1,182 @@
+use alloc::format;
+use alloc::string::String;
+
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+
+const FNV_OFFSET: u64=0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64=0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hash, which depends only on the bytes fed to it.
+pub(super) struct Fnv1a(u64);
+
+impl Fnv1a
+{
+  fn write(&mut self, bytes: &[u8])
+  {
+    self.0=bytes.iter().fold(self.0,|hash,&byte| (hash^byte as u64).wrapping_mul(FNV_PRIME));
+  }
+}
+
+/// Element types whose bits can be fed to a content hash, in an order that is the same on every
+/// platform.
+pub(super) trait ContentBytes
+{
+  fn write_bytes(&self, hasher: &mut Fnv1a);
+}
+
+macro_rules! impl_content_bytes {
+  ($($type:ty),*) => {
+    $(
+      impl ContentBytes for $type
+      {
+        fn write_bytes(&self, hasher: &mut Fnv1a) { hasher.write(&self.to_le_bytes()); }
+      }
+    )*
+  };
+}
+
+impl_content_bytes!(f32,f64,i8,i16,i32,i64,i128,u8,u16,u32,u64,u128);
+
+impl ContentBytes for bool
+{
+  fn write_bytes(&self, hasher: &mut Fnv1a)
+  {
+    hasher.write(&[*self as u8]);
+  }
+}
+
+#[cfg(feature="half")]
+impl ContentBytes for half::f16
+{
+  fn write_bytes(&self, hasher: &mut Fnv1a)
+  {
+    hasher.write(&self.to_bits().to_le_bytes());
+  }
+}
+
+#[cfg(feature="half")]
+impl ContentBytes for half::bf16
+{
+  fn write_bytes(&self, hasher: &mut Fnv1a)
+  {
+    hasher.write(&self.to_bits().to_le_bytes());
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Clone + ContentBytes
+{
+  /// FNV-1a hash of the number of dimensions and the dimensions, each as 64-bit little-endian integers,
+  /// followed by the little-endian bytes of the elements in row-major order. The hash is the same on
+  /// every platform and in every run and does not depend on the layout, unlike that of `Hash`, so it
+  /// can key a cache that outlives the process.
+  ///
+  /// The hash is of the exact bits of the elements, so `-0.0` and `0.0` hash differently though they
+  /// compare equal, and so do NaNs with different payloads.
+  pub(super) fn content_hash(&self) -> u64
+  {
+    let mut hasher: Fnv1a=Fnv1a(FNV_OFFSET);
+    hasher.write(&(N as u64).to_le_bytes());
+    self.dim.iter().for_each(|&dim| hasher.write(&(dim as u64).to_le_bytes()));
+    self.to_layout(Layout::RowMajor).data.iter().for_each(|elem| elem.write_bytes(&mut hasher));
+    hasher.0
+  }
+
+  /// `content_hash` as sixteen lowercase hexadecimal digits, for use in file names.
+  pub(super) fn content_digest_hex(&self) -> String
+  {
+    format!("{:016x}",self.content_hash())
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod digest_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  /// Test vectors of the reference implementation of FNV-1a.
+  #[rstest(bytes,expected,
+    case(b"",0xcbf29ce484222325),
+    case(b"a",0xaf63dc4c8601ec8c),
+    case(b"foobar",0x85944171f73967e8),
+  )]
+  fn digest_test_fnv1a(bytes: &[u8], expected: u64)
+  {
+    let mut hasher: Fnv1a=Fnv1a(FNV_OFFSET);
+    hasher.write(bytes);
+    assert!(hasher.0==expected);
+  }
+
+  #[rstest(hash,expected,
+    case(|| Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-1.5).content_hash(),0x95d9dbae025c006e),
+    case(|| Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-1.5).to_layout(Layout::ColMajor).content_hash(),0x95d9dbae025c006e),
+    case(|| Tensor::<i32,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr) as i32-1).content_hash(),0xfba25560556634a0),
+    case(|| Tensor::<u8,1>::new([0]).content_hash(),0x392209f14dea4c24),
+    case(|| Tensor::<bool,1>::from_fn([3],|[itr]| itr==1).content_hash(),0x361113012b047704),
+  )]
+  fn digest_test_content_hash(hash: fn() -> u64, expected: u64)
+  {
+    assert!(hash()==expected);
+  }
+
+  /// The same elements in a different shape or order hash differently.
+  #[rstest(hash,
+    case(|| Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-1.5).transpose().content_hash()),
+    case(|| Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-1.5).flatten().content_hash()),
+    case(|| Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| (2*itr+jtr) as f64-1.5).content_hash()),
+  )]
+  fn digest_test_content_hash_shape(hash: fn() -> u64)
+  {
+    assert!(hash()!=0x95d9dbae025c006e);
+  }
+
+  #[rstest(hex,expected,
+    case(|| Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-1.5).content_digest_hex(),"95d9dbae025c006e"),
+    case(|| Tensor::<i32,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr) as i32-1).content_digest_hex(),"fba25560556634a0"),
+  )]
+  fn digest_test_content_digest_hex(hex: fn() -> String, expected: &str)
+  {
+    assert!(hex()==expected);
+  }
+
+  /// The last case has the same bits as elements of different types, which hash differently only
+  /// through the dimensions.
+  #[rstest(lhs,rhs,
+    case(|| Tensor::<f64,1>::from_fn([1],|_| 0.0).content_hash(),|| Tensor::<f64,1>::from_fn([1],|_| -0.0).content_hash()),
+    case(|| Tensor::<f64,1>::from_fn([1],|_| f64::NAN).content_hash(),|| Tensor::<f64,1>::from_fn([1],|_| f64::from_bits(f64::NAN.to_bits()|1)).content_hash()),
+    case(|| Tensor::<u8,1>::from_fn([8],|[itr]| 1.5f64.to_le_bytes()[itr]).content_hash(),|| Tensor::<f64,1>::from_fn([1],|_| 1.5).content_hash()),
+  )]
+  fn digest_test_content_hash_bits(lhs: fn() -> u64, rhs: fn() -> u64)
+  {
+    assert!(lhs()!=rhs());
+  }
+
+  #[test]
+  fn digest_test_content_hash_nan()
+  {
+    let nan: Tensor<f64,1>=Tensor::<f64,1>::from_fn([1],|_| f64::NAN);
+    assert!(nan.content_hash()==nan.clone().content_hash());
+    assert!(Tensor::<f64,1>::from_fn([1],|_| 0.0)==Tensor::<f64,1>::from_fn([1],|_| -0.0));
+  }
+
+  #[cfg(feature="half")]
+  #[test]
+  fn digest_test_content_hash_half()
+  {
+    // Half-precision elements hash as their bits, like `u16` elements of the same bits do.
+    let elems: [f64;3]=[-1.5,0.0,3.25];
+    let f16: Tensor<half::f16,1>=Tensor::<half::f16,1>::from_fn([3],|[itr]| half::f16::from_f64(elems[itr]));
+    let bf16: Tensor<half::bf16,1>=Tensor::<half::bf16,1>::from_fn([3],|[itr]| half::bf16::from_f64(elems[itr]));
+    assert!(f16.content_hash()==Tensor::<u16,1>::from_fn([3],|[itr]| f16[itr].to_bits()).content_hash());
+    assert!(bf16.content_hash()==Tensor::<u16,1>::from_fn([3],|[itr]| bf16[itr].to_bits()).content_hash());
+    assert!(f16.content_hash()!=bf16.content_hash());
+  }
+}
@@ -0,0 +1,182 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+
+impl<T,const N: Idx> fmt::Display for Tensor<T,N>
+where T: Clone + fmt::Display
+{
+  /// Writes the elements in nested brackets, one row of the last axis per line.
+  ///
+  /// A requested precision is applied to every element, and the elements in every column are aligned
+  /// on their decimal point.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    fmt_display(f,&t.data,&t.dim)
+  }
+}
+
+impl<T,const N: Idx> fmt::LowerExp for Tensor<T,N>
+where T: Clone + fmt::LowerExp
+{
+  /// Writes the elements in scientific notation in nested brackets, one row of the last axis per
+  /// line, right-aligning the elements in every column.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    fmt_lower_exp(f,&t.data,&t.dim)
+  }
+}
+
+impl<T,const N: Idx> fmt::Debug for Tensor<T,N>
+where T: fmt::Debug
+{
+  /// Writes the dimensions, the layout and the elements in the order they are stored.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    f.debug_struct("Tensor").field("dim",&self.dim).field("layout",&self.layout).field("data",&&self.data[..]).finish()
+  }
+}
+
+/// Formats the row-major elements `data` of a tensor with dimensions `dim` for `Display`.
+pub(super) fn fmt_display<T>(f: &mut fmt::Formatter, data: &[T], dim: &[Idx]) -> fmt::Result
+where T: fmt::Display
+{
+  let elems: Vec<String>=match f.precision()
+  {
+    Some(precision) => data.iter().map(|elem| format!("{:.*}",precision,elem)).collect(),
+    None => data.iter().map(|elem| format!("{}",elem)).collect(),
+  };
+  write_nested(f,&elems,dim,true)
+}
+
+/// Formats the row-major elements `data` of a tensor with dimensions `dim` for `LowerExp`.
+pub(super) fn fmt_lower_exp<T>(f: &mut fmt::Formatter, data: &[T], dim: &[Idx]) -> fmt::Result
+where T: fmt::LowerExp
+{
+  let elems: Vec<String>=match f.precision()
+  {
+    Some(precision) => data.iter().map(|elem| format!("{:.*e}",precision,elem)).collect(),
+    None => data.iter().map(|elem| format!("{:e}",elem)).collect(),
+  };
+  write_nested(f,&elems,dim,false)
+}
+
+/// Writes the formatted elements `elems` of a tensor with dimensions `dim` in nested brackets.
+///
+/// The elements are padded per column, that is per index along the last axis, either to align
+/// their decimal points or, if `decimal` is unset, to right-align them.
+fn write_nested(f: &mut fmt::Formatter, elems: &[String], dim: &[Idx], decimal: bool) -> fmt::Result
+{
+  if dim.is_empty() { return write!(f,"{}",elems[0]); }
+
+  let cols: Idx=dim[dim.len()-1];
+  let mut int_width: Vec<usize>=vec![0;cols];
+  let mut frac_width: Vec<usize>=vec![0;cols];
+  for (itr,elem) in elems.iter().enumerate()
+  {
+    let (int,frac): (&str,&str)=split_decimal(elem,decimal);
+    int_width[itr%cols.max(1)]=int_width[itr%cols.max(1)].max(int.len());
+    frac_width[itr%cols.max(1)]=frac_width[itr%cols.max(1)].max(frac.len());
+  }
+
+  let padded: Vec<String>=elems.iter()
+    .enumerate()
+    .map(|(itr,elem)| {
+      let (int,frac): (&str,&str)=split_decimal(elem,decimal);
+      let col: Idx=itr%cols.max(1);
+      format!("{:>int_width$}{:<frac_width$}",int,frac,int_width=int_width[col],frac_width=frac_width[col])
+    })
+    .collect();
+  write_axis(f,&padded,dim,0)
+}
+
+/// Splits a formatted element at its decimal point, or not at all if `decimal` is unset.
+fn split_decimal(elem: &str, decimal: bool) -> (&str,&str)
+{
+  match elem.find('.')
+  {
+    Some(pos) if decimal => elem.split_at(pos),
+    _ => (elem,""),
+  }
+}
+
+fn write_axis(f: &mut fmt::Formatter, elems: &[String], dim: &[Idx], depth: usize) -> fmt::Result
+{
+  write!(f,"[")?;
+  if dim.len()==1
+  {
+    write!(f,"{}",elems.join(", "))?;
+  }
+  else if let Some(stride)=elems.len().checked_div(dim[0])
+  {
+    for itr in 0..dim[0]
+    {
+      if itr>0
+      {
+        write!(f,",{}{:depth$}","\n".repeat(dim.len()-1),"",depth=depth+1)?;
+      }
+      write_axis(f,&elems[itr*stride..(itr+1)*stride],&dim[1..],depth+1)?;
+    }
+  }
+  write!(f,"]")
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod display_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use super::super::Dim;
+
+  #[rstest(fmt,expected,
+    case(|t: &Tensor<f64,2>| format!("{}",t),"[[-1.5 ,  0    , 100000],\n [ 2.25, -0.125,      3]]"),
+    case(|t: &Tensor<f64,2>| format!("{:.2}",t),"[[-1.50,  0.00, 100000.00],\n [ 2.25, -0.12,      3.00]]"),
+    case(|t: &Tensor<f64,2>| format!("{:e}",t),"[[-1.5e0,      0e0, 1e5],\n [2.25e0, -1.25e-1, 3e0]]"),
+    case(|t: &Tensor<f64,2>| format!("{:.1e}",t),"[[-1.5e0,   0.0e0, 1.0e5],\n [ 2.2e0, -1.2e-1, 3.0e0]]"),
+  )]
+  fn display_test_matrix(fmt: fn(&Tensor<f64,2>) -> String, expected: &str)
+  {
+    let elems: [[f64;3];2]=[[-1.5,0.0,100000.0],[2.25,-0.125,3.0]];
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| elems[itr][jtr]);
+
+    assert!(fmt(&t)==expected);
+  }
+
+  #[rstest(fmt,expected,
+    case(|t: &Tensor<f64,1>| format!("{}",t),"[-0.5, 0.5, 1.5]"),
+    case(|t: &Tensor<f64,1>| format!("{:.3}",t),"[-0.500, 0.500, 1.500]"),
+    case(|t: &Tensor<f64,1>| format!("{:.0}",t),"[-0, 0, 2]"),
+    case(|t: &Tensor<f64,1>| format!("{:e}",t),"[-5e-1, 5e-1, 1.5e0]"),
+  )]
+  fn display_test_vector(fmt: fn(&Tensor<f64,1>) -> String, expected: &str)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64-0.5);
+
+    assert!(fmt(&t)==expected);
+  }
+
+  #[rstest(dim,expected,
+    case([2,2,2],"[[[0, 1],\n  [2, 3]],\n\n [[4, 5],\n  [6, 7]]]"),
+    case([1,2,3],"[[[0, 1, 2],\n  [3, 4, 5]]]"),
+    case([2,1,1],"[[[0]],\n\n [[1]]]"),
+  )]
+  fn display_test_rank_3(dim: Dim<3>, expected: &str)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn(dim,|[itr,jtr,ktr]| ((itr*dim[1]+jtr)*dim[2]+ktr) as f64);
+
+    assert!(format!("{}",t)==expected);
+  }
+}
@@ -0,0 +1,296 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+/// Distance between two points for `Tensor::pairwise_dist`.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) enum Metric
+{
+  Euclidean,
+  SqEuclidean,
+  Manhattan,
+  /// One minus the cosine similarity, as defined by `Tensor::cosine_similarity`.
+  Cosine,
+}
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Matrix of the distances under `metric` between every row of `self` and every row of `other`,
+  /// with as many rows as `self` and as many columns as `other` has rows.
+  ///
+  /// The Euclidean distances are computed from `|a|^2+|b|^2-2a.b` with a matrix product, which is
+  /// much faster than subtracting every pair of rows but can round to slightly negative squared
+  /// distances for nearly equal rows. Those are clamped to zero.
+  pub(super) fn pairwise_dist(&self, other: &Tensor<T,2>, metric: Metric) -> Tensor<T,2>
+  {
+    if self.dim[1]!=other.dim[1] { panic!("The number of columns of two matrices must be equal to take the distances between their rows.") }
+
+    let (m,n,k): (Idx,Idx,Idx)=(self.dim[0],other.dim[0],self.dim[1]);
+    match metric
+    {
+      Metric::Euclidean | Metric::SqEuclidean | Metric::Cosine =>
+      {
+        let mut dist: Tensor<T,2>=self.matmul_t_b(other);
+        let lhs_norms: Tensor<T,1>=self.row_sq_norms();
+        let rhs_norms: Tensor<T,1>=other.row_sq_norms();
+        let two: T=T::from_f64(2.0);
+        for itr in 0..m
+        {
+          for jtr in 0..n
+          {
+            let dot: T=dist[[itr,jtr]];
+            dist[[itr,jtr]]=match metric
+            {
+              Metric::Cosine => T::from_f64(1.0)-cosine(dot,lhs_norms[itr],rhs_norms[jtr]),
+              _ =>
+              {
+                let sq: T=lhs_norms[itr]+rhs_norms[jtr]-two*dot;
+                let sq: T=if sq<T::default() { T::default() } else { sq };
+                if metric==Metric::Euclidean { sq.sqrt() } else { sq }
+              },
+            };
+          }
+        }
+        dist
+      },
+      Metric::Manhattan => Tensor::<T,2>::from_fn([m,n],|[itr,jtr]| {
+        (0..k).fold(T::default(),|sum,ktr| sum+(self[[itr,ktr]]-other[[jtr,ktr]]).abs())
+      }),
+    }
+  }
+
+  /// Distances under `metric` to, and indices of, the `k` rows of `self` nearest to every row of
+  /// `queries`, ordered from nearest to furthest, with ties going to the lower index.
+  ///
+  /// Rather than sorting all distances of a query, the `k` nearest rows seen so far are kept in a
+  /// max-heap, which costs time logarithmic in `k` only for rows nearer than the furthest kept.
+  pub(super) fn knn(&self, queries: &Tensor<T,2>, k: Idx, metric: Metric) -> Result<(Tensor<T,2>,Vec<Vec<Idx>>),TensorError>
+  {
+    if k>self.dim[0] { return Err(TensorError::NeighbourCount{k:k,len:self.dim[0]}); }
+
+    let dist: Tensor<T,2>=queries.pairwise_dist(self,metric);
+    let mut nearest: Tensor<T,2>=Tensor::<T,2>::new([queries.dim[0],k]);
+    let mut indices: Vec<Vec<Idx>>=Vec::with_capacity(queries.dim[0]);
+    let mut heap: BinaryHeap<Neighbour<T>>=BinaryHeap::with_capacity(k+1);
+    for itr in 0..queries.dim[0]
+    {
+      for jtr in 0..self.dim[0]
+      {
+        let candidate: Neighbour<T>=Neighbour{dist:dist[[itr,jtr]],index:jtr};
+        if heap.len()<k { heap.push(candidate); }
+        else if heap.peek().is_some_and(|furthest| candidate<*furthest)
+        {
+          heap.pop();
+          heap.push(candidate);
+        }
+      }
+
+      let sorted: Vec<Neighbour<T>>=core::mem::take(&mut heap).into_sorted_vec();
+      for (ktr,neighbour) in sorted.iter().enumerate()
+      {
+        nearest[[itr,ktr]]=neighbour.dist;
+      }
+      indices.push(sorted.iter().map(|neighbour| neighbour.index).collect());
+    }
+    Ok((nearest,indices))
+  }
+
+  /// Squared Euclidean norm of every row.
+  fn row_sq_norms(&self) -> Tensor<T,1>
+  {
+    Tensor::<T,1>::from_fn([self.dim[0]],|[itr]| {
+      (0..self.dim[1]).fold(T::default(),|sum,jtr| sum+self[[itr,jtr]]*self[[itr,jtr]])
+    })
+  }
+}
+
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Cosine of the angle between `self` and `rhs`.
+  ///
+  /// The angle to a zero vector is undefined, for which the similarity is taken to be zero, that is
+  /// as for orthogonal vectors.
+  pub(super) fn cosine_similarity(&self, rhs: &Tensor<T,1>) -> T
+  {
+    cosine(self.dot(rhs),self.dot(self),rhs.dot(rhs))
+  }
+}
+
+/// Candidate neighbour of a query in `Tensor::knn`, ordered by distance and then index.
+struct Neighbour<T>
+{
+  dist: T,
+  index: Idx,
+}
+
+impl<T> Ord for Neighbour<T>
+where T: Float
+{
+  fn cmp(&self, other: &Neighbour<T>) -> Ordering
+  {
+    self.dist.partial_cmp(&other.dist).unwrap_or(Ordering::Equal).then(self.index.cmp(&other.index))
+  }
+}
+
+impl<T> PartialOrd for Neighbour<T>
+where T: Float
+{
+  fn partial_cmp(&self, other: &Neighbour<T>) -> Option<Ordering>
+  {
+    Some(self.cmp(other))
+  }
+}
+
+impl<T> PartialEq for Neighbour<T>
+where T: Float
+{
+  fn eq(&self, other: &Neighbour<T>) -> bool
+  {
+    self.cmp(other)==Ordering::Equal
+  }
+}
+
+impl<T> Eq for Neighbour<T> where T: Float {}
+
+/// Cosine similarity from the dot product and the squared norms of two vectors.
+fn cosine<T>(dot: T, lhs_sq_norm: T, rhs_sq_norm: T) -> T
+where T: Float
+{
+  if lhs_sq_norm==T::default() || rhs_sq_norm==T::default() { return T::default(); }
+
+  dot/(lhs_sq_norm.sqrt()*rhs_sq_norm.sqrt())
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod distance_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest(metric,expected,
+    case(Metric::Euclidean,[5.0,1.0,0.0,32f64.sqrt(),13f64.sqrt(),5f64.sqrt()]),
+    case(Metric::SqEuclidean,[25.0,1.0,0.0,32.0,13.0,5.0]),
+    case(Metric::Manhattan,[7.0,1.0,0.0,8.0,5.0,3.0]),
+    case(Metric::Cosine,[1.0,1.0,0.0,1.6,1.0-7.0/(5.0*2f64.sqrt()),1.0+1.0/2f64.sqrt()]),
+  )]
+  fn distance_test_pairwise_dist(metric: Metric, expected: [f64;6])
+  {
+    let lhs: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| [[0.0,0.0],[3.0,4.0],[1.0,1.0]][itr][jtr]);
+    let rhs: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| [[3.0,4.0],[-1.0,0.0]][itr][jtr]);
+    let dist: Tensor<f64,2>=lhs.pairwise_dist(&rhs,metric);
+
+    assert!(dist.dim==[3,2]);
+    assert!(dist.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-12));
+  }
+
+  /// Rows that are equal are at a distance of exactly zero, however large their elements.
+  #[rstest(metric,offset,scale,
+    case(Metric::Euclidean,0.1,1e3),
+    case(Metric::SqEuclidean,0.1,1e3),
+    case(Metric::Euclidean,-7.3,1e5),
+    case(Metric::SqEuclidean,1e4,1.0),
+  )]
+  fn distance_test_pairwise_dist_clamp(metric: Metric, offset: f32, scale: f32)
+  {
+    let t: Tensor<f32,2>=Tensor::<f32,2>::from_fn([2,3],|[_,jtr]| offset+scale*jtr as f32);
+    let dist: Tensor<f32,2>=t.pairwise_dist(&t,metric);
+
+    assert!(dist.iter().all(|&elem| elem==0.0));
+  }
+
+  #[rstest(lhs,rhs,metric,
+    case([2,3],[2,2],Metric::Manhattan),
+    case([1,2],[4,3],Metric::Euclidean),
+    case([3,0],[3,1],Metric::Cosine),
+  )]
+  #[should_panic(expected="The number of columns of two matrices must be equal to take the distances between their rows.")]
+  fn distance_test_pairwise_dist_dims(lhs: [usize;2], rhs: [usize;2], metric: Metric)
+  {
+    Tensor::<f64,2>::new(lhs).pairwise_dist(&Tensor::<f64,2>::new(rhs),metric);
+  }
+
+  /// Vectors that are parallel, opposite or orthogonal, and those that are zero, whose similarity is
+  /// zero.
+  #[rstest(lhs,rhs,expected,
+    case([1.0,2.0,2.0],[2.0,4.0,4.0],1.0),
+    case([1.0,2.0,2.0],[-2.0,-4.0,-4.0],-1.0),
+    case([1.0,2.0,2.0],[0.0,1.0,-1.0],0.0),
+    case([1.0,2.0,2.0],[0.0,0.0,0.0],0.0),
+    case([0.0,0.0,0.0],[0.0,0.0,0.0],0.0),
+    case([1.0,0.0,0.0],[1.0,1.0,0.0],0.5f64.sqrt()),
+  )]
+  fn distance_test_cosine_similarity(lhs: [f64;3], rhs: [f64;3], expected: f64)
+  {
+    let t1: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| lhs[itr]);
+    let t2: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| rhs[itr]);
+
+    assert!((t1.cosine_similarity(&t2)-expected).abs()<1e-15);
+    assert!((t2.cosine_similarity(&t1)-expected).abs()<1e-15);
+  }
+
+  #[rstest(metric,k,
+    case(Metric::Euclidean,1),
+    case(Metric::Euclidean,7),
+    case(Metric::Manhattan,3),
+    case(Metric::Cosine,20),
+  )]
+  fn distance_test_knn(metric: Metric, k: usize)
+  {
+    let mut seed: u64=7;
+    let mut next=|| { seed=seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed>>40) as f64/16777216.0 };
+    let points: Tensor<f64,2>=Tensor::<f64,2>::from_fn([20,3],|_| next());
+    let queries: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,3],|_| next());
+
+    let (dist,indices): (Tensor<f64,2>,Vec<Vec<usize>>)=points.knn(&queries,k,metric).unwrap();
+    let all: Tensor<f64,2>=queries.pairwise_dist(&points,metric);
+    assert!(dist.dim==[4,k]);
+    for itr in 0..4
+    {
+      let mut expected: Vec<usize>=(0..20).collect();
+      expected.sort_by(|&lhs,&rhs| all[[itr,lhs]].partial_cmp(&all[[itr,rhs]]).unwrap().then(lhs.cmp(&rhs)));
+      assert!(indices[itr]==expected[..k]);
+      for ktr in 0..k
+      {
+        assert!(dist[[itr,ktr]]==all[[itr,expected[ktr]]]);
+      }
+    }
+  }
+
+  /// Ties are broken by index, whatever the order of the points.
+  #[rstest(k,indices,dist,
+    case(4,&[1,2,4,0],&[1.0,1.0,1.0,2.0]),
+    case(5,&[1,2,4,0,3],&[1.0,1.0,1.0,2.0,2.0]),
+    case(2,&[1,2],&[1.0,1.0]),
+    case(0,&[],&[]),
+  )]
+  fn distance_test_knn_ties(k: usize, indices: &[usize], dist: &[f64])
+  {
+    let points: Tensor<f64,2>=Tensor::<f64,2>::from_fn([5,1],|[itr,_]| [2.0,-1.0,1.0,-2.0,1.0][itr]);
+    let queries: Tensor<f64,2>=Tensor::<f64,2>::new([1,1]);
+
+    let (found_dist,found_indices): (Tensor<f64,2>,Vec<Vec<usize>>)=points.knn(&queries,k,Metric::Manhattan).unwrap();
+    assert!(found_dist.dim==[1,k] && found_indices==[indices]);
+    assert!(found_dist.iter().copied().eq(dist.iter().copied()));
+  }
+
+  #[rstest(len,k,case(3,4),case(0,1),case(2,3))]
+  fn distance_test_knn_count(len: usize, k: usize)
+  {
+    let points: Tensor<f64,2>=Tensor::<f64,2>::new([len,2]);
+    let res=points.knn(&Tensor::<f64,2>::new([1,2]),k,Metric::Euclidean);
+    assert!(res.err()==Some(TensorError::NeighbourCount{k:k,len:len}));
+  }
+}
@@ -0,0 +1,150 @@
+use super::*;
+use rstest::rstest;
+
+use alloc::format;
+
+#[rstest(dim,case([0,5,1]),case([2,0,3]),case([1,1,0]),case([0,0,0]))]
+fn empty_test_new(dim: Dim<3>)
+{
+  let t: Tensor<f64,3>=Tensor::<f64,3>::new(dim);
+  assert!(t.dim==dim);
+  assert!(t.is_empty());
+  assert!(t.iter().next().is_none());
+
+  let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn(dim,|_| panic!());
+  assert!(t.is_empty());
+}
+
+#[test]
+fn empty_test_new_scalar()
+{
+  let t: Tensor<f64,0>=Tensor::<f64,0>::new([]);
+  assert!(t.len()==1);
+  assert!(!t.is_empty());
+}
+
+#[rstest(dim,allow_empty,axis,
+  case([3,0,1],false,Some(1)),
+  case([0,2,0],false,Some(0)),
+  case([3,2,0],false,Some(2)),
+  case([3,0,1],true,None),
+  case([3,2,1],false,None),
+)]
+fn empty_test_try_new(dim: Dim<3>, allow_empty: bool, axis: Option<Idx>)
+{
+  match axis
+  {
+    Some(axis) => assert!(Tensor::<f64,3>::try_new(dim,allow_empty).err()==Some(TensorError::EmptyAxis{axis:axis})),
+    None => assert!(Tensor::<f64,3>::try_new(dim,allow_empty).unwrap().len()==dim.iter().product()),
+  }
+}
+
+#[rstest(dim,case([0,4]),case([4,0]),case([0,0]))]
+fn empty_test_reductions(dim: Dim<2>)
+{
+  let t: Tensor<f32,2>=Tensor::<f32,2>::new(dim);
+  assert!(t.sum()==0.0);
+  assert!(t.sum_f64()==0.0);
+  assert!(t.unique().is_empty());
+  assert!(t.value_counts().is_empty());
+  assert!(t.is_finite());
+  assert!(!t.has_nan());
+  assert!(t.assert_finite()==Ok(()));
+
+  let v: Tensor<f32,1>=Tensor::<f32,1>::new([0]);
+  assert!(v.dot(&v)==0.0);
+  assert!(v.dot_f64(&v)==0.0);
+}
+
+#[rstest(dim,case([3,0]),case([0,3]))]
+#[cfg(feature="std")]
+fn empty_test_statistics(dim: Dim<2>)
+{
+  let t: Tensor<f64,2>=Tensor::<f64,2>::new(dim);
+  let summary=t.describe();
+  assert!(summary.count==0 && summary.nan_count==0);
+  assert!(summary.mean.is_nan() && summary.min.is_nan());
+  assert!(t.quantile(0.5).is_nan());
+}
+
+#[rstest(display,expected,
+  case(|| format!("{}",Tensor::<f64,1>::new([0])),"[]"),
+  case(|| format!("{}",Tensor::<f64,2>::new([0,3])),"[]"),
+  case(|| format!("{}",Tensor::<f64,2>::new([2,0])),"[[],\n []]"),
+  case(|| format!("{:.2e}",Tensor::<f64,3>::new([1,0,2])),"[[]]"),
+)]
+fn empty_test_display(display: fn() -> String, expected: &str)
+{
+  assert!(display()==expected);
+}
+
+#[rstest(dim,case([0,3]),case([3,0]),case([0,0]))]
+fn empty_test_element_wise(dim: Dim<2>)
+{
+  let t1: Tensor<f64,2>=Tensor::<f64,2>::new(dim);
+  let t2: Tensor<f64,2>=Tensor::<f64,2>::new(dim);
+
+  let res: Tensor<f64,2>=&t1+&t2;
+  assert!(res.dim==dim && res.is_empty());
+  let res: Tensor<f64,2>=&t1*&t2;
+  assert!(res.dim==dim && res.is_empty());
+  let mut res: Tensor<f64,2>=t1.clone()+2.0;
+  res*=3.0;
+  res.axpy(2.0,&t2);
+  assert!(res.is_empty());
+  assert!(t1.mul_add(&t1,&t2).is_empty());
+  assert!(t1.map(|x| x+1.0).dim==dim);
+  assert!(t1.cast::<i32>().dim==dim);
+  assert!(t1.allclose(&t2,0.0,0.0));
+}
+
+/// Multiplying along an empty inner axis gives a result of zeros.
+#[rstest(lhs,rhs,dim,
+  case([0,3],[3,2],[0,2]),
+  case([3,2],[2,0],[3,0]),
+  case([2,0],[0,3],[2,3]),
+  case([0,0],[0,0],[0,0]),
+)]
+fn empty_test_linalg(lhs: Dim<2>, rhs: Dim<2>, dim: Dim<2>)
+{
+  let a: Tensor<f64,2>=Tensor::<f64,2>::new(lhs);
+  let b: Tensor<f64,2>=Tensor::<f64,2>::new(rhs);
+
+  let res: Tensor<f64,2>=a.matmul(&b);
+  assert!(res.dim==dim);
+  assert!(res.iter().all(|&elem| elem==0.0));
+  assert!(a.transpose().dim==[lhs[1],lhs[0]]);
+}
+
+#[test]
+fn empty_test_shape()
+{
+  let t: Tensor<f64,2>=Tensor::<f64,2>::new([0,3]);
+  assert!(t.clone().flatten().dim==[0]);
+
+  let (t1,t2): (Tensor<f64,2>,Tensor<f64,2>)=Tensor::<f64,2>::new([2,3]).split_first_axis(0);
+  assert!(t1.dim==[0,3] && t1.is_empty());
+  assert!(t2.dim==[2,3]);
+
+  let (dim,data): (Dim<2>,Box<[f64]>)=t.into_parts();
+  assert!(dim==[0,3] && data.is_empty());
+  assert!(Tensor::<f64,2>::from_parts([4,0],data).unwrap().is_empty());
+  assert!(Tensor::<f64,2>::new([0,3]).crop([0,1],[0,2]).unwrap().dim==[0,2]);
+  assert!(Tensor::<f64,2>::new([0,3]).embed([2,3],[1,0],1.0).unwrap().sum()==6.0);
+}
+
+#[test]
+#[should_panic(expected="The tensor is empty along axis 1 and cannot be indexed.")]
+fn empty_test_index()
+{
+  let t: Tensor<f64,3>=Tensor::<f64,3>::new([2,0,3]);
+  let _=t[[0,0,0]];
+}
+
+#[test]
+#[should_panic(expected="The tensor is empty along axis 0 and cannot be indexed.")]
+fn empty_test_index_mut()
+{
+  let mut t: Tensor<f64,1>=Tensor::<f64,1>::new([0]);
+  t[0]=1.0;
+}
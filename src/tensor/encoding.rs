@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+
+/// Integer element types, whose consecutive differences can be taken and summed back exactly with
+/// wrapping arithmetic.
+pub(super) trait Integer: Copy + PartialEq
+{
+  fn wrapping_add(self, rhs: Self) -> Self;
+  fn wrapping_sub(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+  ($($type:ty),*) => {
+    $(
+      impl Integer for $type
+      {
+        fn wrapping_add(self, rhs: $type) -> $type { <$type>::wrapping_add(self,rhs) }
+        fn wrapping_sub(self, rhs: $type) -> $type { <$type>::wrapping_sub(self,rhs) }
+      }
+    )*
+  };
+}
+
+impl_integer!(i8,i16,i32,i64,u8,u16,u32,u64);
+
+/// Elements of a tensor of dimensions `dim` as runs of equal elements in row-major order, each an
+/// element and the number of times it repeats.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) struct RleEncoded<T,const N: Idx>
+{
+  pub(super) dim: Dim<N>,
+  pub(super) runs: Vec<(T,Idx)>,
+}
+
+impl<T,const N: Idx> RleEncoded<T,N>
+where T: Integer
+{
+  /// The tensor the runs encode.
+  pub(super) fn decode(&self) -> Tensor<T,N>
+  {
+    let mut data: Vec<T>=Vec::with_capacity(self.dim.size());
+    self.runs.iter().for_each(|&(elem,len)| data.resize(data.len()+len,elem));
+    Tensor::<T,N>::from_vec(self.dim,data).unwrap()
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Integer
+{
+  /// Runs of equal elements in row-major order, whatever the layout, which take far less space than
+  /// the elements for tensors that hold long stretches of the same element.
+  pub(super) fn encode_rle(&self) -> RleEncoded<T,N>
+  {
+    let mut runs: Vec<(T,Idx)>=Vec::new();
+    for &elem in self.to_layout(Layout::RowMajor).iter()
+    {
+      match runs.last_mut()
+      {
+        Some((last,len)) if *last==elem => *len+=1,
+        _ => runs.push((elem,1)),
+      }
+    }
+    RleEncoded{dim:self.dim,runs:runs}
+  }
+
+  /// Tensor of the same dimensions holding, in row-major order, the first element followed by the
+  /// difference of every element from the one before it, wrapping on overflow.
+  ///
+  /// A tensor of evenly spaced elements, such as indices, becomes constant after its first element,
+  /// which suits `encode_rle`. `decode_delta` reverses it exactly.
+  pub(super) fn encode_delta(&self) -> Tensor<T,N>
+  {
+    let mut t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    encode_delta_slice(&mut t.data);
+    t
+  }
+
+  /// Tensor whose elements in row-major order are the running sums of those of `self`, wrapping on
+  /// overflow, which reverses `encode_delta`.
+  pub(super) fn decode_delta(&self) -> Tensor<T,N>
+  {
+    let mut t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    decode_delta_slice(&mut t.data);
+    t
+  }
+}
+
+/// Replaces every element but the first by its difference from the one before it.
+pub(super) fn encode_delta_slice<T>(data: &mut [T])
+where T: Integer
+{
+  for itr in (1..data.len()).rev()
+  {
+    data[itr]=data[itr].wrapping_sub(data[itr-1]);
+  }
+}
+
+/// Replaces every element by the sum of it and the ones before it, reversing `encode_delta_slice`.
+pub(super) fn decode_delta_slice<T>(data: &mut [T])
+where T: Integer
+{
+  for itr in 1..data.len()
+  {
+    data[itr]=data[itr].wrapping_add(data[itr-1]);
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod encoding_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use super::super::testing::assert_tensor_eq;
+
+  #[rstest(t,layout,runs,
+    case(Tensor::<i32,2>::from_fn([8,5],|_| 7),Layout::RowMajor,1),
+    case(Tensor::<i32,2>::from_fn([8,5],|_| 7),Layout::ColMajor,1),
+    case(Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| (5*itr+jtr) as i32-3),Layout::RowMajor,40),
+    case(Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| (5*itr+jtr) as i32-3),Layout::ColMajor,40),
+    case(Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| if (itr+jtr)%2==0 { i32::MIN } else { i32::MAX }),Layout::ColMajor,40),
+    case(Tensor::<i32,2>::from_fn([8,5],|[itr,_]| (itr/3) as i32),Layout::ColMajor,3),
+    case(Tensor::<i32,2>::new([0,5]),Layout::RowMajor,0),
+  )]
+  fn encoding_test_rle(t: Tensor<i32,2>, layout: Layout, runs: Idx)
+  {
+    let encoded: RleEncoded<i32,2>=t.to_layout(layout).encode_rle();
+    assert!(encoded.dim==t.dim && encoded.runs.len()==runs);
+    assert!(encoded.runs.iter().map(|&(_,len)| len).sum::<Idx>()==t.len());
+    assert_tensor_eq!(encoded.decode(),t);
+  }
+
+  #[rstest(elems,runs,
+    case(&[1,1,0,0,0,1,2],vec![(1,2),(0,3),(1,1),(2,1)]),
+    case(&[5],vec![(5,1)]),
+    case(&[0,0,0],vec![(0,3)]),
+    case(&[],vec![]),
+  )]
+  fn encoding_test_rle_runs(elems: &[u8], runs: Vec<(u8,Idx)>)
+  {
+    let t: Tensor<u8,1>=Tensor::<u8,1>::from_fn([elems.len()],|[itr]| elems[itr]);
+    assert!(t.encode_rle()==RleEncoded{dim:[elems.len()],runs:runs});
+  }
+
+  /// Differences of strictly increasing indices are constant, and overflowing ones wrap.
+  #[rstest(t,expected,
+    case(Tensor::<i32,2>::from_fn([8,5],|_| 7),Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| if itr+jtr==0 { 7 } else { 0 })),
+    case(Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| (5*itr+jtr) as i32-3),Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| if itr+jtr==0 { -3 } else { 1 })),
+    case(
+      Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| if (itr+jtr)%2==0 { i32::MIN } else { i32::MAX }),
+      Tensor::<i32,2>::from_fn([8,5],|[itr,jtr]| if itr+jtr==0 { i32::MIN } else if (itr+jtr)%2==1 { -1 } else { 1 }),
+    ),
+    case(Tensor::<i32,2>::new([0,5]),Tensor::<i32,2>::new([0,5])),
+  )]
+  fn encoding_test_delta(t: Tensor<i32,2>, expected: Tensor<i32,2>)
+  {
+    for layout in [Layout::RowMajor,Layout::ColMajor].iter()
+    {
+      let encoded: Tensor<i32,2>=t.to_layout(*layout).encode_delta();
+      assert_tensor_eq!(encoded,expected);
+      assert_tensor_eq!(encoded.to_layout(*layout).decode_delta(),t);
+    }
+  }
+
+  #[rstest(elems,expected,
+    case(&[250,4,4,0],&[250,10,0,252]),
+    case(&[0,255],&[0,255]),
+    case(&[255,0],&[255,1]),
+    case(&[9],&[9]),
+    case(&[],&[]),
+  )]
+  fn encoding_test_delta_wrapping(elems: &[u8], expected: &[u8])
+  {
+    let t: Tensor<u8,1>=Tensor::<u8,1>::from_fn([elems.len()],|[itr]| elems[itr]);
+    assert_tensor_eq!(t.encode_delta(),Tensor::<u8,1>::from_fn([expected.len()],|[itr]| expected[itr]));
+    assert_tensor_eq!(t.encode_delta().decode_delta(),t);
+  }
+}
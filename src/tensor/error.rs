@@ -0,0 +1,310 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+#[cfg(feature="std")]
+use std::sync::Arc;
+
+use super::Idx;
+
+/// Kinds of `TensorError`, which group the errors by their cause so that they can be told apart by
+/// matching, without depending on the fields of the variants.
+///
+/// More kinds may be added, so matches on them need a catch-all arm.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+#[non_exhaustive]
+pub(super) enum TensorErrorKind
+{
+  /// A file to be read does not exist.
+  NotFound,
+  /// Reading or writing a file is not permitted.
+  PermissionDenied,
+  /// The input ends before all that it states it holds.
+  UnexpectedEof,
+  /// Reading or writing failed for another reason.
+  Io,
+  /// The input is malformed, such as a corrupt header or a field that cannot be parsed.
+  Format,
+  /// The input holds elements of a type, or stored in a way, other than what is requested or supported.
+  Dtype,
+  /// Dimensions or lengths do not agree with each other or with what an operation requires.
+  Shape,
+  /// An index, axis, channel, label or group refers to something that does not exist.
+  Index,
+  /// An argument lies outside the values an operation accepts.
+  InvalidArgument,
+  /// The input has no well-defined numeric result, such as a singular matrix or a non-finite element.
+  Numeric,
+}
+
+/// Error underlying a `TensorError`, such as the I/O error it was caused by, shared so that the
+/// `TensorError` stays cheap to clone. Two are equal if they display the same.
+#[cfg(feature="std")]
+#[derive(Debug,Clone)]
+pub(super) struct ErrorSource(Arc<dyn std::error::Error + Send + Sync>);
+
+#[cfg(feature="std")]
+impl ErrorSource
+{
+  pub(super) fn new<E>(err: E) -> ErrorSource
+  where E: std::error::Error + Send + Sync + 'static
+  {
+    ErrorSource(Arc::new(err))
+  }
+}
+
+#[cfg(feature="std")]
+impl PartialEq for ErrorSource
+{
+  fn eq(&self, other: &ErrorSource) -> bool
+  {
+    self.0.to_string()==other.0.to_string()
+  }
+}
+
+#[cfg(feature="std")]
+impl fmt::Display for ErrorSource
+{
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    self.0.fmt(f)
+  }
+}
+
+/// Errors reported by fallible tensor operations.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) enum TensorError
+{
+  /// The number of elements supplied does not equal the size of the requested dimensions.
+  SizeMismatch{size: Idx, len: Idx},
+  /// The element at the row-major position `index` cannot be represented in the requested type.
+  NotRepresentable{index: Idx},
+  /// The element at the multi-index `index` has the infinite or NaN value `value`.
+  NonFinite{index: Vec<Idx>, value: f64},
+  /// More nearest neighbours `k` were requested than there are points `len` to choose from.
+  NeighbourCount{k: Idx, len: Idx},
+  /// Two tensors that must be of the same length are of lengths `lhs` and `rhs`.
+  LengthMismatch{lhs: Idx, rhs: Idx},
+  /// A system of `equations` equations cannot determine `unknowns` unknowns.
+  Underdetermined{unknowns: Idx, equations: Idx},
+  /// A matrix is singular or, for least squares, does not have full column rank.
+  Singular,
+  /// A lag of `max_lag` samples does not fit in a signal of `len` samples.
+  LagTooLarge{max_lag: Idx, len: Idx},
+  /// A block does not fit in a tensor along `axis`.
+  OutOfBounds{axis: Idx},
+  /// The step or factor along `axis` is zero.
+  ZeroFactor{axis: Idx},
+  /// A tensor would be empty along `axis`.
+  EmptyAxis{axis: Idx},
+  /// Row `row` is of length `len` rather than the length `width` of the rows before it.
+  RowLength{row: Idx, len: Idx, width: Idx},
+  /// A matrix of `rows` rows and `cols` columns is not square.
+  NotSquare{rows: Idx, cols: Idx},
+  /// The diagonal element of row `row` of a matrix is zero.
+  ZeroDiagonal{row: Idx},
+  /// `k` samples cannot be drawn from `len` slices, as there are too few to draw from.
+  SampleSize{k: Idx, len: Idx},
+  /// The fraction `fraction` does not lie strictly between zero and one.
+  InvalidFraction{fraction: f64},
+  /// The label at position `index` is not the index of a class.
+  InvalidLabel{index: Idx},
+  /// Two tensors that must be of the same dimensions are of dimensions `lhs` and `rhs`.
+  DimMismatch{lhs: Vec<Idx>, rhs: Vec<Idx>},
+  /// The smoothing factor `alpha` does not lie in the interval from zero, exclusive, to one, inclusive.
+  InvalidSmoothing{alpha: f64},
+  /// A stationary iteration diverged after `iterations` sweeps, for a matrix that is diagonally dominant
+  /// if `dominant` is set and symmetric if `symmetric` is set.
+  Diverged{iterations: Idx, dominant: bool, symmetric: bool},
+  /// The multi-index at position `index` of a list of multi-indices lies outside the tensor.
+  IndexOutOfBounds{index: Idx},
+  /// A remainder was to be taken with respect to a modulus of zero.
+  ZeroModulus,
+  /// The range an element is to be wrapped into is empty, as its lower bound is not below its upper bound.
+  EmptyRange,
+  /// The `k` largest elements cannot be selected from `len` elements, as there are too few.
+  SelectionSize{k: Idx, len: Idx},
+  /// The group at position `index` is not the index of a group.
+  InvalidGroup{index: Idx},
+  /// The axis of a rotation is the zero vector, which has no direction.
+  ZeroAxis,
+  /// A matrix of `rows` rows and `cols` columns is not a homogeneous transform of points in two or three
+  /// dimensions.
+  InvalidTransform{rows: Idx, cols: Idx},
+  /// The point in row `row` is mapped to infinity by a homogeneous transform.
+  PointAtInfinity{row: Idx},
+  /// Two point sets have too few points, or points too close to a line, to determine a rotation.
+  DegeneratePoints,
+  /// There is no channel `channel` in an image of `channels` channels.
+  ChannelOutOfRange{channel: Idx, channels: Idx},
+  /// An image has `channels` channels where one of `expected` channels is required.
+  ChannelCount{channels: Idx, expected: Idx},
+  /// Reading or writing a tensor failed with the I/O error `source` of kind `kind`, at byte `offset` of
+  /// the input if it is known.
+  #[cfg(feature="std")]
+  Io{kind: std::io::ErrorKind, offset: Option<Idx>, source: ErrorSource},
+  /// The field in column `column` of line `line` of delimited text, both counted from one, cannot be
+  /// parsed, failing with `source`.
+  #[cfg(feature="std")]
+  Parse{line: Idx, column: Idx, source: ErrorSource},
+  /// The header of an .npy file is malformed.
+  NpyFormat,
+  /// The elements of an .npy file are of the type described by `descr` rather than the requested type.
+  NpyDtype{descr: String},
+  /// The elements of an .npy file of shape `shape` cannot be read as requested, as they are not of the
+  /// required rank or not in row-major order.
+  NpyShape{shape: Vec<Idx>},
+  /// The elements of a file are stored in a byte order other than that of the target.
+  ByteOrder,
+  /// The elements of a mapped file are not aligned for their type.
+  Misaligned,
+  /// The header field `field` of a tensor container, or its elements, are invalid or cut short.
+  ContainerField{field: &'static str},
+  /// A tensor container holds a tensor of `rank` dimensions with elements of type `dtype`, which is not
+  /// the requested type of tensor.
+  ContainerType{dtype: &'static str, rank: Idx},
+  /// No axis of a tensor is named `name`.
+  UnknownAxis{name: String},
+  /// Two tensors that must have the same names of their axes have names `lhs` and `rhs`.
+  AxisNames{lhs: Vec<&'static str>, rhs: Vec<&'static str>},
+  /// `count` lengths of a shape are left to be inferred, where at most one can be.
+  InferredAxes{count: Idx},
+  /// `size` elements cannot fill a shape whose lengths other than the inferred one hold `known`
+  /// elements.
+  Indivisible{size: Idx, known: Idx},
+  /// The length `extent` of axis `axis` of a shape is negative, but not -1 for a length to be
+  /// inferred.
+  InvalidExtent{axis: Idx, extent: isize},
+  /// JSON text holds something other than `expected` at line `line` and column `column`, both counted
+  /// from one.
+  JsonSyntax{expected: &'static str, line: Idx, column: Idx},
+  /// The element of JSON text at the indices `path`, at line `line` and column `column`, cannot be
+  /// represented in the requested type.
+  JsonElement{path: Vec<Idx>, line: Idx, column: Idx},
+  /// The value of JSON text at the indices `path`, at line `line` and column `column`, is not nested as
+  /// deeply as the rank `rank` of the requested tensor.
+  JsonDepth{path: Vec<Idx>, rank: Idx, line: Idx, column: Idx},
+  /// The array of JSON text at the indices `path`, at line `line` and column `column`, is of length
+  /// `len` rather than the length `expected` of the first array at its depth.
+  JsonRagged{path: Vec<Idx>, len: Idx, expected: Idx, line: Idx, column: Idx},
+}
+
+impl fmt::Display for TensorError
+{
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    match self
+    {
+      TensorError::SizeMismatch{size,len} => write!(f,"A tensor of size {} cannot be created from {} elements.",size,len),
+      TensorError::NotRepresentable{index} => write!(f,"The element at index {} cannot be represented in the target type.",index),
+      TensorError::NonFinite{index,value} => write!(f,"The element at index {:?} is {}, which is not finite.",index,value),
+      TensorError::NeighbourCount{k,len} => write!(f,"{} nearest neighbours cannot be found among {} points.",k,len),
+      TensorError::LengthMismatch{lhs,rhs} => write!(f,"Tensors of lengths {} and {} must be of the same length.",lhs,rhs),
+      TensorError::Underdetermined{unknowns,equations} => write!(f,"{} unknowns cannot be determined from {} equations.",unknowns,equations),
+      TensorError::Singular => write!(f,"The matrix is singular."),
+      TensorError::LagTooLarge{max_lag,len} => write!(f,"A lag of {} is too large for a signal of length {}.",max_lag,len),
+      TensorError::OutOfBounds{axis} => write!(f,"The block does not fit in the tensor along axis {}.",axis),
+      TensorError::ZeroFactor{axis} => write!(f,"The step or factor along axis {} must not be zero.",axis),
+      TensorError::EmptyAxis{axis} => write!(f,"The tensor is empty along axis {}.",axis),
+      TensorError::RowLength{row,len,width} => write!(f,"Row {} is of length {}, but the rows before it are of length {}.",row,len,width),
+      TensorError::NotSquare{rows,cols} => write!(f,"A matrix of {} rows and {} columns is not square.",rows,cols),
+      TensorError::ZeroDiagonal{row} => write!(f,"The diagonal element of row {} is zero.",row),
+      TensorError::SampleSize{k,len} => write!(f,"{} samples cannot be drawn from {} slices.",k,len),
+      TensorError::InvalidFraction{fraction} => write!(f,"The fraction {} must lie strictly between zero and one.",fraction),
+      TensorError::InvalidLabel{index} => write!(f,"The label at index {} is not the index of a class.",index),
+      TensorError::DimMismatch{lhs,rhs} => write!(f,"Tensors of dimensions {:?} and {:?} must be of the same dimensions.",lhs,rhs),
+      TensorError::InvalidSmoothing{alpha} => write!(f,"The smoothing factor {} must be greater than zero and at most one.",alpha),
+      TensorError::Diverged{iterations,dominant,symmetric} => write!(f,"The iteration diverged after {} sweeps, for a matrix that is {}diagonally dominant and {}symmetric.",
+        iterations,if *dominant { "" } else { "not " },if *symmetric { "" } else { "not " }),
+      TensorError::IndexOutOfBounds{index} => write!(f,"The multi-index at position {} lies outside the tensor.",index),
+      TensorError::ZeroModulus => write!(f,"The modulus must not be zero."),
+      TensorError::EmptyRange => write!(f,"The lower bound of the range must be less than its upper bound."),
+      TensorError::SelectionSize{k,len} => write!(f,"The {} largest elements cannot be selected from {} elements.",k,len),
+      TensorError::InvalidGroup{index} => write!(f,"The group at index {} is not the index of a group.",index),
+      TensorError::ZeroAxis => write!(f,"The axis of rotation must not be zero."),
+      TensorError::InvalidTransform{rows,cols} => write!(f,"A matrix of {} rows and {} columns is not a homogeneous transform in two or three dimensions.",rows,cols),
+      TensorError::PointAtInfinity{row} => write!(f,"The point in row {} is mapped to infinity.",row),
+      TensorError::DegeneratePoints => write!(f,"The points are too few or too close to a line to determine a rotation."),
+      TensorError::ChannelOutOfRange{channel,channels} => write!(f,"There is no channel {} in an image of {} channels.",channel,channels),
+      TensorError::ChannelCount{channels,expected} => write!(f,"An image of {} channels was given where one of {} channels is required.",channels,expected),
+      #[cfg(feature="std")]
+      TensorError::Io{offset:None,source,..} => write!(f,"Reading or writing the tensor failed: {}.",source),
+      #[cfg(feature="std")]
+      TensorError::Io{offset:Some(offset),source,..} => write!(f,"Reading or writing the tensor failed at byte {}: {}.",offset,source),
+      #[cfg(feature="std")]
+      TensorError::Parse{line,column,source} => write!(f,"The field in column {} of line {} cannot be parsed: {}.",column,line,source),
+      TensorError::NpyFormat => write!(f,"The header of the .npy file is malformed."),
+      TensorError::NpyDtype{descr} => write!(f,"The .npy file holds elements of type {}, which is not the requested type.",descr),
+      TensorError::NpyShape{shape} => write!(f,"The .npy file of shape {:?} is not of the required rank or not in row-major order.",shape),
+      TensorError::ByteOrder => write!(f,"The elements of the file are not stored in the byte order of the target."),
+      TensorError::Misaligned => write!(f,"The elements of the mapped file are not aligned for their type."),
+      TensorError::ContainerField{field} => write!(f,"The {} of the tensor container are invalid or cut short.",field),
+      TensorError::ContainerType{dtype,rank} => write!(f,"The container holds a tensor of {} dimensions of {} elements, which is not the requested type.",rank,dtype),
+      TensorError::UnknownAxis{name} => write!(f,"No axis of the tensor is named {}.",name),
+      TensorError::AxisNames{lhs,rhs} => write!(f,"Tensors with axes named {:?} and {:?} cannot be combined.",lhs,rhs),
+      TensorError::InferredAxes{count} => write!(f,"At most one length of a shape can be inferred, but {} are to be.",count),
+      TensorError::Indivisible{size,known} => write!(f,"A tensor of size {} cannot be shaped with lengths holding {} elements and one inferred.",size,known),
+      TensorError::InvalidExtent{axis,extent} => write!(f,"The length {} of axis {} of the shape is negative.",extent,axis),
+      TensorError::JsonSyntax{expected,line,column} => write!(f,"Expected {} at line {}, column {} of the JSON text.",expected,line,column),
+      TensorError::JsonElement{path,line,column} => write!(f,"The element at {:?} (line {}, column {}) cannot be represented in the element type.",path,line,column),
+      TensorError::JsonDepth{path,rank,line,column} => write!(f,"The value at {:?} (line {}, column {}) is not nested {} arrays deep, the rank of the tensor.",path,line,column,rank),
+      TensorError::JsonRagged{path,len,expected,line,column} => write!(f,"The array at {:?} (line {}, column {}) holds {} elements rather than the {} of the first array at its depth.",path,line,column,len,expected),
+    }
+  }
+}
+
+impl TensorError
+{
+  /// I/O error caused by `err`, at byte `offset` of the input if it is known.
+  #[cfg(feature="std")]
+  pub(super) fn io(err: std::io::Error, offset: Option<Idx>) -> TensorError
+  {
+    TensorError::Io{kind:err.kind(),offset:offset,source:ErrorSource::new(err)}
+  }
+
+  /// Kind of the error, which stays the same as variants gain or lose fields.
+  pub(super) fn kind(&self) -> TensorErrorKind
+  {
+    match self
+    {
+      #[cfg(feature="std")]
+      TensorError::Io{kind,..} => match kind
+      {
+        std::io::ErrorKind::NotFound => TensorErrorKind::NotFound,
+        std::io::ErrorKind::PermissionDenied => TensorErrorKind::PermissionDenied,
+        std::io::ErrorKind::UnexpectedEof => TensorErrorKind::UnexpectedEof,
+        _ => TensorErrorKind::Io,
+      },
+      #[cfg(feature="std")]
+      TensorError::Parse{..} => TensorErrorKind::Format,
+      TensorError::NpyFormat | TensorError::ContainerField{..} | TensorError::JsonSyntax{..} => TensorErrorKind::Format,
+      TensorError::NotRepresentable{..} | TensorError::NpyDtype{..} | TensorError::ContainerType{..} | TensorError::ByteOrder
+        | TensorError::Misaligned | TensorError::JsonElement{..} => TensorErrorKind::Dtype,
+      TensorError::SizeMismatch{..} | TensorError::LengthMismatch{..} | TensorError::Underdetermined{..} | TensorError::OutOfBounds{..}
+        | TensorError::EmptyAxis{..} | TensorError::RowLength{..} | TensorError::NotSquare{..} | TensorError::DimMismatch{..}
+        | TensorError::InvalidTransform{..} | TensorError::ChannelCount{..} | TensorError::NpyShape{..} | TensorError::InferredAxes{..}
+        | TensorError::Indivisible{..} | TensorError::InvalidExtent{..} | TensorError::JsonDepth{..} | TensorError::JsonRagged{..} => TensorErrorKind::Shape,
+      TensorError::InvalidLabel{..} | TensorError::IndexOutOfBounds{..} | TensorError::InvalidGroup{..} | TensorError::ChannelOutOfRange{..}
+        | TensorError::UnknownAxis{..} | TensorError::AxisNames{..} => TensorErrorKind::Index,
+      TensorError::NeighbourCount{..} | TensorError::LagTooLarge{..} | TensorError::ZeroFactor{..} | TensorError::SampleSize{..}
+        | TensorError::InvalidFraction{..} | TensorError::InvalidSmoothing{..} | TensorError::ZeroModulus | TensorError::EmptyRange
+        | TensorError::SelectionSize{..} | TensorError::ZeroAxis => TensorErrorKind::InvalidArgument,
+      TensorError::NonFinite{..} | TensorError::Singular | TensorError::ZeroDiagonal{..} | TensorError::PointAtInfinity{..}
+        | TensorError::DegeneratePoints | TensorError::Diverged{..} => TensorErrorKind::Numeric,
+    }
+  }
+}
+
+#[cfg(feature="std")]
+impl std::error::Error for TensorError
+{
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+  {
+    match self
+    {
+      TensorError::Io{source,..} | TensorError::Parse{source,..} => Some(&*source.0),
+      _ => None,
+    }
+  }
+}
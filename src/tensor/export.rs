@@ -0,0 +1,253 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt::Display;
+use core::str::FromStr;
+
+use std::io::BufRead;
+use std::io::Write;
+
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+use super::error::ErrorSource;
+use super::npy::io_error;
+use super::npy::io_error_at;
+
+impl<T> Tensor<T,2>
+where T: Clone + Display
+{
+  /// Writes the matrix as delimited text, a line of `headers` followed by one line per row, with the
+  /// elements separated by `delimiter` and written with `precision` digits after the decimal point,
+  /// which integers ignore. Fails unless there is a header for every column, or if writing fails.
+  pub(super) fn write_columns<W>(&self, mut w: W, headers: &[&str], delimiter: char, precision: Idx) -> Result<(),TensorError>
+  where W: Write
+  {
+    if headers.len()!=self.dim[1] { return Err(TensorError::LengthMismatch{lhs:self.dim[1],rhs:headers.len()}); }
+
+    let mut sep: [u8;4]=[0;4];
+    let sep: &str=delimiter.encode_utf8(&mut sep);
+    writeln!(w,"{}",headers.join(sep)).map_err(io_error)?;
+    for itr in 0..self.dim[0]
+    {
+      for jtr in 0..self.dim[1]
+      {
+        if jtr>0 { write!(w,"{}",sep).map_err(io_error)?; }
+        write!(w,"{:.*}",precision,self[[itr,jtr]]).map_err(io_error)?;
+      }
+      writeln!(w).map_err(io_error)?;
+    }
+    Ok(())
+  }
+
+  /// Writes `x` and every one of `ys` as a column of delimited text as for `write_columns`, with a
+  /// header for `x` followed by one for every one of `ys`. Fails unless all columns are of the same
+  /// length, besides the failures of `write_columns`.
+  pub(super) fn write_xy<W>(x: &Tensor<T,1>, ys: &[&Tensor<T,1>], w: W, headers: &[&str], delimiter: char, precision: Idx) -> Result<(),TensorError>
+  where W: Write
+  {
+    if let Some(y)=ys.iter().find(|y| y.dim[0]!=x.dim[0]) { return Err(TensorError::LengthMismatch{lhs:x.dim[0],rhs:y.dim[0]}); }
+    let columns: Tensor<T,2>=Tensor::<T,2>::from_fn([x.dim[0],ys.len()+1],|[itr,jtr]| if jtr==0 { x[itr].clone() } else { ys[jtr-1][itr].clone() });
+    columns.write_columns(w,headers,delimiter,precision)
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: FromStr, <T as FromStr>::Err: std::error::Error + Send + Sync + 'static
+{
+  /// Reads delimited text as written by `write_columns`, a line of headers followed by one line per
+  /// row with the fields separated by `delimiter`, returning the headers and the matrix of the rows.
+  /// Empty lines and whitespace around the fields are ignored.
+  ///
+  /// Fails if a row has a different number of fields than there are headers, if a field cannot be
+  /// parsed, with its line and column counted from one, or if reading fails, including on text that is
+  /// not UTF-8, with the offset of the line being read.
+  pub(super) fn read_columns<R>(mut r: R, delimiter: char) -> Result<(Vec<String>,Tensor<T,2>),TensorError>
+  where R: BufRead
+  {
+    let mut headers: Option<Vec<String>>=None;
+    let mut data: Vec<T>=Vec::new();
+    let mut rows: Idx=0;
+    let mut offset: Idx=0;
+    let mut line: String=String::new();
+    for number in 1..
+    {
+      line.clear();
+      let len: Idx=r.read_line(&mut line).map_err(io_error_at(offset))?;
+      if len==0 { break; }
+      offset+=len;
+      if line.trim().is_empty() { continue; }
+
+      let fields=line.trim_end_matches(&['\n','\r'][..]).split(delimiter).map(|field| field.trim());
+      match &headers
+      {
+        None => headers=Some(fields.map(String::from).collect()),
+        Some(headers) =>
+        {
+          let start: Idx=data.len();
+          for (column,field) in fields.enumerate()
+          {
+            data.push(field.parse().map_err(|err| TensorError::Parse{line:number,column:column+1,source:ErrorSource::new(err)})?);
+          }
+          if data.len()-start!=headers.len() { return Err(TensorError::RowLength{row:rows,len:data.len()-start,width:headers.len()}); }
+          rows+=1;
+        },
+      }
+    }
+    let headers: Vec<String>=headers.unwrap_or_default();
+    let t: Tensor<T,2>=Tensor::<T,2>::from_vec([rows,headers.len()],data)?;
+    Ok((headers,t))
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod export_tests
+{
+  use super::*;
+  use super::super::error::TensorErrorKind;
+  use rstest::rstest;
+
+  use std::error::Error;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  fn export_test_string<F>(f: F) -> String
+  where F: FnOnce(&mut Vec<u8>) -> Result<(),TensorError>
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    f(&mut bytes).unwrap();
+    String::from_utf8(bytes).unwrap()
+  }
+
+  #[rstest(write,expected,
+    case(|w: &mut Vec<u8>| Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| [[0.0,1.5],[0.25,-2.125],[0.5,1e3/3.0]][itr][jtr]).write_columns(w,&["t","v"],',',3),
+      "t,v\n0.000,1.500\n0.250,-2.125\n0.500,333.333\n"),
+    case(|w: &mut Vec<u8>| Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| [[0.0,1.5],[0.25,-2.125],[0.5,1e3/3.0]][itr][jtr]).to_layout(Layout::ColMajor).write_columns(w,&["time (s)","value"],'\t',1),
+      "time (s)\tvalue\n0.0\t1.5\n0.2\t-2.1\n0.5\t333.3\n"),
+    case(|w: &mut Vec<u8>| Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as i32-2).write_columns(w,&["a","b","c"],' ',4),"a b c\n-2 -1 0\n1 2 3\n"),
+    case(|w: &mut Vec<u8>| Tensor::<f64,2>::new([0,2]).write_columns(w,&["x","y"],'→',2),"x→y\n"),
+  )]
+  fn export_test_write_columns(write: fn(&mut Vec<u8>) -> Result<(),TensorError>, expected: &str)
+  {
+    assert!(export_test_string(write)==expected);
+  }
+
+  #[rstest(write,
+    case(|w: &mut Vec<u8>| Tensor::<f64,2>::new([3,2]).write_columns(w,&["t"],',',3)),
+    case(|w: &mut Vec<u8>| Tensor::<f64,2>::new([3,1]).write_columns(w,&["t","v"],',',3)),
+  )]
+  fn export_test_write_columns_headers(write: fn(&mut Vec<u8>) -> Result<(),TensorError>)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    assert!(matches!(write(&mut bytes),Err(TensorError::LengthMismatch{..})) && bytes.is_empty());
+  }
+
+  #[rstest(columns,headers,expected,
+    case(2,&["x","sin","sq"],"x,sin,sq\n0.00,0.00,0.00\n0.50,0.48,0.25\n1.00,0.84,1.00\n"),
+    case(1,&["x","sin"],"x,sin\n0.00,0.00\n0.50,0.48\n1.00,0.84\n"),
+    case(0,&["x"],"x\n0.00\n0.50\n1.00\n"),
+  )]
+  fn export_test_write_xy(columns: usize, headers: &[&str], expected: &str)
+  {
+    let x: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64*0.5);
+    let sin: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| [0.0,0.479426,0.841471][itr]);
+    let sq: Tensor<f64,1>=x.map(|elem| elem*elem);
+
+    let out: String=export_test_string(|w| Tensor::<f64,2>::write_xy(&x,&[&sin,&sq][..columns],w,headers,',',2));
+    assert!(out==expected);
+  }
+
+  #[test]
+  fn export_test_write_xy_precision()
+  {
+    let x: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64*0.5);
+    assert!(export_test_string(|w| Tensor::<f64,2>::write_xy(&x,&[],w,&["x"],',',0))=="x\n0\n0\n1\n");
+  }
+
+  #[rstest(len,headers,error,
+    case(2,&["x","sin","short"],TensorError::LengthMismatch{lhs:3,rhs:2}),
+    case(4,&["x","sin","long"],TensorError::LengthMismatch{lhs:3,rhs:4}),
+    case(3,&["x","sin"],TensorError::LengthMismatch{lhs:3,rhs:2}),
+  )]
+  fn export_test_write_xy_errors(len: Idx, headers: &[&str], error: TensorError)
+  {
+    let x: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64*0.5);
+    let sin: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+    let other: Tensor<f64,1>=Tensor::<f64,1>::new([len]);
+
+    let mut bytes: Vec<u8>=Vec::new();
+    assert!(Tensor::<f64,2>::write_xy(&x,&[&sin,&other],&mut bytes,headers,',',2).err()==Some(error));
+    assert!(bytes.is_empty());
+  }
+
+  #[test]
+  fn export_test_write_error()
+  {
+    let full: &mut [u8]=&mut [0;4];
+    let t: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]);
+    assert!(matches!(t.write_columns(full,&["a","b"],',',1),Err(TensorError::Io{kind:std::io::ErrorKind::WriteZero,offset:None,..})));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn export_test_read_columns_roundtrip(layout: Layout)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| [[0.0,1.5],[0.25,-2.125],[0.5,-1e3]][itr][jtr]);
+    let csv: String=export_test_string(|w| t.to_layout(layout).write_columns(w,&["time (s)","v"],',',3));
+    let (headers,res): (Vec<String>,Tensor<f64,2>)=Tensor::<f64,2>::read_columns(csv.as_bytes(),',').unwrap();
+    assert!(headers==["time (s)","v"] && res==t);
+  }
+
+  /// Fields are trimmed, and blank lines and carriage returns are skipped.
+  #[rstest(bytes,delim,expected_headers,dim,elems,
+    case(&b"a\tb\r\n 1\t-2 \r\n\n3\t4\n\n"[..],'\t',&["a","b"],[2,2],&[1,-2,3,4]),
+    case(&b"a;b;c\n7;8;9"[..],';',&["a","b","c"],[1,3],&[7,8,9]),
+    case(&b"x,y\n"[..],',',&["x","y"],[0,2],&[]),
+    case(&b""[..],',',&[],[0,0],&[]),
+  )]
+  fn export_test_read_columns(bytes: &[u8], delim: char, expected_headers: &[&str], dim: Dim<2>, elems: &[i32])
+  {
+    let (headers,res): (Vec<String>,Tensor<i32,2>)=Tensor::<i32,2>::read_columns(bytes,delim).unwrap();
+    assert!(headers==expected_headers && res.dim==dim && res.iter().copied().eq(elems.iter().copied()));
+  }
+
+  #[test]
+  fn export_test_read_columns_errors()
+  {
+    let err: TensorError=Tensor::<f64,2>::read_columns(&b"t,v\n0.0,1.5\n0.5,1.O\n"[..],',').unwrap_err();
+    assert!(err.kind()==TensorErrorKind::Format && err.source().is_some());
+    assert!(format!("{}",err)=="The field in column 2 of line 3 cannot be parsed: invalid float literal.");
+
+    let ragged: Result<(Vec<String>,Tensor<f64,2>),TensorError>=Tensor::<f64,2>::read_columns(&b"t,v\n0,1\n2\n"[..],',');
+    assert!(ragged.err()==Some(TensorError::RowLength{row:1,len:1,width:2}));
+
+    let err: TensorError=Tensor::<f64,2>::read_columns(&b"t,v\n0,1\n\xff,2\n"[..],',').unwrap_err();
+    assert!(err.kind()==TensorErrorKind::Io && err.source().is_some());
+    assert!(matches!(err,TensorError::Io{kind:std::io::ErrorKind::InvalidData,offset:Some(8),..}));
+  }
+
+  #[rstest(read,expected_line,expected_column,
+    case(|bytes: &[u8]| Tensor::<f64,2>::read_columns(bytes,',').err(),3,2),
+    case(|bytes: &[u8]| Tensor::<u8,2>::read_columns(bytes,',').err(),2,2),
+    case(|bytes: &[u8]| Tensor::<i32,2>::read_columns(bytes,',').err(),2,2),
+  )]
+  fn export_test_read_columns_parse(read: fn(&[u8]) -> Option<TensorError>, expected_line: usize, expected_column: usize)
+  {
+    match read(&b"t,v\n0,-1.5\n0.5,1.O\n"[..])
+    {
+      Some(TensorError::Parse{line,column,..}) => assert!(line==expected_line && column==expected_column),
+      err => panic!("{:?}",err),
+    }
+  }
+
+  #[test]
+  fn export_test_read_columns_unsigned()
+  {
+    assert!(matches!(Tensor::<u8,2>::read_columns(&b"a\n\n-1\n"[..],','),Err(TensorError::Parse{line:3,column:1,..})));
+  }
+}
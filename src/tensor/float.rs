@@ -0,0 +1,57 @@
+use core::ops::Div;
+use core::ops::Sub;
+
+use super::Scalar;
+
+/// Floating point element types, for operations that need division, ordering or square roots.
+pub(super) trait Float: Scalar + Copy + PartialOrd + Sub<Output=Self> + Div<Output=Self>
+{
+  fn nan() -> Self;
+  fn infinity() -> Self;
+  fn epsilon() -> Self;
+  fn from_f64(val: f64) -> Self;
+  fn to_f64(self) -> f64;
+  fn is_nan(self) -> bool;
+  fn abs(self) -> Self;
+  /// Only available with `std`, as `core` does not provide the square root.
+  #[cfg(feature="std")]
+  fn sqrt(self) -> Self;
+  #[cfg(feature="std")]
+  fn exp(self) -> Self;
+  #[cfg(feature="std")]
+  fn ln(self) -> Self;
+  #[cfg(feature="std")]
+  fn sin_cos(self) -> (Self,Self);
+
+  /// Mean of the elements of `elems`, which is NaN if there are none.
+  fn mean_slice(elems: &[Self]) -> Self
+  {
+    Self::sum_slice(elems)/Self::from_f64(elems.len() as f64)
+  }
+}
+
+macro_rules! impl_float {
+  ($type:ty) => {
+    impl Float for $type
+    {
+      fn nan() -> $type { <$type>::NAN }
+      fn infinity() -> $type { <$type>::INFINITY }
+      fn epsilon() -> $type { <$type>::EPSILON }
+      fn from_f64(val: f64) -> $type { val as $type }
+      fn to_f64(self) -> f64 { self as f64 }
+      fn is_nan(self) -> bool { <$type>::is_nan(self) }
+      fn abs(self) -> $type { if self<0.0 { -self } else { self } }
+      #[cfg(feature="std")]
+      fn sqrt(self) -> $type { <$type>::sqrt(self) }
+      #[cfg(feature="std")]
+      fn exp(self) -> $type { <$type>::exp(self) }
+      #[cfg(feature="std")]
+      fn ln(self) -> $type { <$type>::ln(self) }
+      #[cfg(feature="std")]
+      fn sin_cos(self) -> ($type,$type) { <$type>::sin_cos(self) }
+    }
+  };
+}
+
+impl_float!(f32);
+impl_float!(f64);
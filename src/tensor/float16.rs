@@ -0,0 +1,217 @@
+use half::bf16;
+use half::f16;
+
+use super::CastFrom;
+use super::Float;
+use super::Kernel;
+use super::MatrixKernel;
+
+macro_rules! impl_half {
+  ($type:ty) => {
+    impl Kernel for $type
+    {
+      fn first_non_finite(elems: &[$type]) -> Option<(usize,f64)>
+      {
+        elems.iter().position(|elem| !elem.is_finite()).map(|pos| (pos,elems[pos].to_f64()))
+      }
+
+      fn first_nan(elems: &[$type]) -> Option<usize>
+      {
+        elems.iter().position(|elem| elem.is_nan())
+      }
+
+      /// Accumulates in `f32`, as a sum in half precision stops growing once it is a couple of
+      /// thousand times larger than the elements added to it.
+      fn sum_slice(elems: &[$type]) -> $type
+      {
+        <$type>::from_f32(elems.iter().fold(0.0,|sum,elem| sum+elem.to_f32()))
+      }
+    }
+
+    impl MatrixKernel for $type {}
+
+    impl Float for $type
+    {
+      fn nan() -> $type { <$type>::NAN }
+      fn infinity() -> $type { <$type>::INFINITY }
+      fn epsilon() -> $type { <$type>::EPSILON }
+      fn from_f64(val: f64) -> $type { <$type>::from_f64(val) }
+      fn to_f64(self) -> f64 { <$type>::to_f64(self) }
+      fn is_nan(self) -> bool { <$type>::is_nan(self) }
+      fn abs(self) -> $type { if self.to_f32()<0.0 { -self } else { self } }
+      #[cfg(feature="std")]
+      fn sqrt(self) -> $type { <$type>::from_f32(self.to_f32().sqrt()) }
+      #[cfg(feature="std")]
+      fn exp(self) -> $type { <$type>::from_f32(self.to_f32().exp()) }
+      #[cfg(feature="std")]
+      fn ln(self) -> $type { <$type>::from_f32(self.to_f32().ln()) }
+      #[cfg(feature="std")]
+      fn sin_cos(self) -> ($type,$type)
+      {
+        let (sin,cos): (f32,f32)=self.to_f32().sin_cos();
+        (<$type>::from_f32(sin),<$type>::from_f32(cos))
+      }
+
+      /// Accumulates and divides in `f32`, so the mean is accurate even where the sum itself is not
+      /// representable in half precision.
+      fn mean_slice(elems: &[$type]) -> $type
+      {
+        <$type>::from_f32(elems.iter().fold(0.0,|sum,elem| sum+elem.to_f32())/elems.len() as f32)
+      }
+    }
+  };
+}
+
+impl_half!(f16);
+impl_half!(bf16);
+
+macro_rules! impl_cast_half_float {
+  ($src:ty => $($dst:ty),*) => {
+    $(
+      impl CastFrom<$src> for $dst
+      {
+        fn cast_from(val: $src) -> $dst { <$dst>::from_f32(val.to_f32()) }
+        fn try_cast_from(val: $src) -> Option<$dst>
+        {
+          let cast: $dst=<$dst>::cast_from(val);
+          if val.is_finite() && !cast.is_finite() { None } else { Some(cast) }
+        }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_cast_half {
+  ($half:ty => $($float:ty),* ; $($int:ty),*) => {
+    $(
+      impl CastFrom<$float> for $half
+      {
+        fn cast_from(val: $float) -> $half { <$half>::from_f64(val as f64) }
+        fn try_cast_from(val: $float) -> Option<$half>
+        {
+          let cast: $half=<$half>::cast_from(val);
+          if val.is_finite() && !cast.is_finite() { None } else { Some(cast) }
+        }
+      }
+
+      impl CastFrom<$half> for $float
+      {
+        fn cast_from(val: $half) -> $float { val.to_f64() as $float }
+        fn try_cast_from(val: $half) -> Option<$float> { Some(<$float>::cast_from(val)) }
+      }
+    )*
+    $(
+      impl CastFrom<$int> for $half
+      {
+        fn cast_from(val: $int) -> $half { <$half>::from_f64(val as f64) }
+        fn try_cast_from(val: $int) -> Option<$half>
+        {
+          let cast: $half=<$half>::cast_from(val);
+          if cast.is_finite() { Some(cast) } else { None }
+        }
+      }
+
+      impl CastFrom<$half> for $int
+      {
+        fn cast_from(val: $half) -> $int { <$int>::cast_from(val.to_f32()) }
+        fn try_cast_from(val: $half) -> Option<$int> { <$int>::try_cast_from(val.to_f32()) }
+      }
+    )*
+  };
+}
+
+impl_cast_half_float!(f16 => f16,bf16);
+impl_cast_half_float!(bf16 => f16,bf16);
+
+impl_cast_half!(f16 => f32,f64 ; i8,i16,i32,i64,u8,u16,u32,u64);
+impl_cast_half!(bf16 => f32,f64 ; i8,i16,i32,i64,u8,u16,u32,u64);
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod float16_tests
+{
+  use super::*;
+  use super::super::Dim;
+  use super::super::Tensor;
+  use super::super::TensorError;
+  use rstest::rstest;
+
+  #[rstest(dim,case([2,3]),case([1,1]),case([4,5]))]
+  fn float16_test_cast_round_trip(dim: Dim<2>)
+  {
+    let t: Tensor<f16,2>=Tensor::<f16,2>::from_fn(dim,|[itr,jtr]| f16::from_f32((dim[1]*itr+jtr) as f32*0.25-0.5));
+    let wide: Tensor<f32,2>=t.cast();
+    let narrow: Tensor<f16,2>=wide.try_cast().unwrap();
+
+    assert!(wide.iter().enumerate().all(|(itr,&elem)| elem==itr as f32*0.25-0.5));
+    assert!(narrow==t);
+    assert!(t.cast::<bf16>().cast::<f16>()==t);
+    assert!(t.cast::<i32>().iter().zip(wide.iter()).all(|(&elem,&wide)| elem==wide as i32));
+  }
+
+  #[rstest(try_cast,index,
+    case(|| Tensor::<f32,1>::from_fn([3],|[itr]| [1.5,-70000.0,f32::NAN][itr]).try_cast::<f16>().err(),Some(1)),
+    case(|| Tensor::<f32,1>::from_fn([3],|[itr]| [1.5,-70000.0,f32::NAN][itr]).try_cast::<bf16>().err(),None),
+    case(|| Tensor::<f32,1>::from_fn([3],|[itr]| [1.5,-70000.0,f32::NAN][itr]).cast::<f16>().try_cast::<i64>().err(),Some(1)),
+    case(|| Tensor::<u32,1>::from_fn([1],|_| 70000).try_cast::<f16>().err(),Some(0)),
+    case(|| Tensor::<u32,1>::from_fn([1],|_| 65504).try_cast::<f16>().err(),None),
+    case(|| Tensor::<f64,1>::from_fn([2],|[itr]| [0.0,1e40][itr]).try_cast::<bf16>().err(),Some(1)),
+  )]
+  fn float16_test_try_cast(try_cast: fn() -> Option<TensorError>, index: Option<usize>)
+  {
+    assert!(try_cast()==index.map(|index| TensorError::NotRepresentable{index:index}));
+  }
+
+  #[test]
+  fn float16_test_try_cast_values()
+  {
+    let t: Tensor<f32,1>=Tensor::<f32,1>::from_fn([3],|[itr]| [1.5,-70000.0,f32::NAN][itr]);
+    assert!(t.try_cast::<bf16>().unwrap()[1]==bf16::from_f32(-70000.0));
+  }
+
+  /// Both accumulate in `f32`, so the mean is accurate even where the sum overflows.
+  #[rstest(len,elem,sum,mean,
+    case(10_000,|_| 1.0,10_000.0,1.0),
+    case(100_000,|itr| (itr%2) as f32+0.5,f32::INFINITY,1.0),
+    case(3,|itr| itr as f32,3.0,1.0),
+  )]
+  fn float16_test_mean(len: usize, elem: fn(usize) -> f32, sum: f32, mean: f32)
+  {
+    let t: Tensor<f16,1>=Tensor::<f16,1>::from_fn([len],|[itr]| f16::from_f32(elem(itr)));
+
+    assert!(t.sum()==f16::from_f32(sum));
+    assert!(t.mean()==f16::from_f32(mean));
+  }
+
+  #[test]
+  fn float16_test_mean_naive()
+  {
+    let t: Tensor<f16,1>=Tensor::<f16,1>::from_fn([10_000],|_| f16::from_f32(1.0));
+
+    let naive: f16=t.iter().fold(f16::from_f32(0.0),|sum,&elem| sum+elem);
+    assert!(naive==f16::from_f32(2048.0));
+    assert!(Tensor::<bf16,1>::from_fn([10_000],|_| bf16::from_f32(0.5)).mean()==bf16::from_f32(0.5));
+  }
+
+  #[test]
+  fn float16_test_ops()
+  {
+    let mut t: Tensor<f16,2>=Tensor::<f16,2>::from_fn([2,2],|[itr,jtr]| f16::from_f32((2*itr+jtr) as f32));
+    t+=&t.clone();
+    t[[0,1]]=f16::NAN;
+
+    assert!(t[[1,1]]==f16::from_f32(6.0));
+    assert!(t.has_nan());
+    match t.assert_finite()
+    {
+      Err(TensorError::NonFinite{index,value}) => assert!(index==[0,1] && value.is_nan()),
+      _ => panic!(),
+    }
+    let prod: Tensor<f16,2>=t.matmul(&t);
+    assert!(prod[[1,0]]==f16::from_f32(24.0) && prod[[1,1]].is_nan());
+  }
+}
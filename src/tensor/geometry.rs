@@ -0,0 +1,565 @@
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Matrix rotating vectors in the plane anticlockwise by `theta` radians.
+  pub(super) fn rotation_2d(theta: T) -> Tensor<T,2>
+  {
+    let (sin,cos): (T,T)=theta.sin_cos();
+    let elems: [[T;2];2]=[[cos,T::default()-sin],[sin,cos]];
+    Tensor::<T,2>::from_fn([2,2],|[itr,jtr]| elems[itr][jtr])
+  }
+
+  /// Matrix rotating vectors by `theta` radians about `axis`, anticlockwise when looking down the axis
+  /// towards the origin, by Rodrigues' formula. The axis need not be of unit length, but fails unless
+  /// it has three elements and is not zero.
+  pub(super) fn rotation_3d_axis_angle(axis: &Tensor<T,1>, theta: T) -> Result<Tensor<T,2>,TensorError>
+  {
+    if axis.dim[0]!=3 { return Err(TensorError::LengthMismatch{lhs:3,rhs:axis.dim[0]}); }
+    let norm: T=axis.iter().fold(T::default(),|sum,&elem| sum+elem*elem).sqrt();
+    if norm==T::default() { return Err(TensorError::ZeroAxis); }
+
+    let k: [T;3]=[axis[0]/norm,axis[1]/norm,axis[2]/norm];
+    let (sin,cos): (T,T)=theta.sin_cos();
+    let one_cos: T=T::from_f64(1.0)-cos;
+    // The cross product matrix of the axis, scaled by the sine.
+    let cross: [[T;3];3]=[
+      [T::default(),T::default()-sin*k[2],sin*k[1]],
+      [sin*k[2],T::default(),T::default()-sin*k[0]],
+      [T::default()-sin*k[1],sin*k[0],T::default()],
+    ];
+    Ok(Tensor::<T,2>::from_fn([3,3],|[itr,jtr]| {
+      let diag: T=if itr==jtr { cos } else { T::default() };
+      diag+one_cos*k[itr]*k[jtr]+cross[itr][jtr]
+    }))
+  }
+
+  /// Matrix of the rotation by `roll` about the x axis, then `pitch` about the y axis and then `yaw`
+  /// about the z axis, all fixed, in radians, which is `Rz(yaw)*Ry(pitch)*Rx(roll)`.
+  ///
+  /// This is the aerospace convention, equivalently the rotations by yaw, pitch and roll in that order
+  /// about the axes of a body as it turns.
+  pub(super) fn rotation_3d_euler(roll: T, pitch: T, yaw: T) -> Tensor<T,2>
+  {
+    let (sr,cr): (T,T)=roll.sin_cos();
+    let (sp,cp): (T,T)=pitch.sin_cos();
+    let (sy,cy): (T,T)=yaw.sin_cos();
+    let elems: [[T;3];3]=[
+      [cy*cp,cy*sp*sr-sy*cr,cy*sp*cr+sy*sr],
+      [sy*cp,sy*sp*sr+cy*cr,sy*sp*cr-cy*sr],
+      [T::default()-sp,cp*sr,cp*cr],
+    ];
+    Tensor::<T,2>::from_fn([3,3],|[itr,jtr]| elems[itr][jtr])
+  }
+
+  /// The rows of `points`, each a point of two or three coordinates, mapped by the homogeneous transform
+  /// `self` of three or four rows and columns respectively. Every point is extended with a coordinate of
+  /// one, multiplied by the transform, and divided by the last coordinate of the product, `w`.
+  ///
+  /// Fails unless `self` is a transform and the points are of its dimension, or if a point is mapped to
+  /// infinity, with `|w|` at most `epsilon` times the largest coordinate of the product in absolute
+  /// value.
+  pub(super) fn transform_points(&self, points: &Tensor<T,2>) -> Result<Tensor<T,2>,TensorError>
+  {
+    let d: Idx=self.transform_dim()?;
+    if points.dim[1]!=d { return Err(TensorError::LengthMismatch{lhs:d,rhs:points.dim[1]}); }
+
+    let mut mapped: Tensor<T,2>=Tensor::<T,2>::new(points.dim);
+    for row in 0..points.dim[0]
+    {
+      let homogeneous=|itr: Idx| (0..d).fold(self[[itr,d]],|sum,jtr| sum+self[[itr,jtr]]*points[[row,jtr]]);
+      let w: T=homogeneous(d);
+      let scale: T=(0..=d).map(|itr| homogeneous(itr).abs()).fold(T::default(),|max,elem| if elem>max { elem } else { max });
+      if w.abs()<=T::epsilon()*scale { return Err(TensorError::PointAtInfinity{row:row}); }
+      (0..d).for_each(|itr| mapped[[row,itr]]=homogeneous(itr)/w);
+    }
+    Ok(mapped)
+  }
+
+  /// Transform applying `other` and then `self`, the matrix product `self*other`, failing unless both
+  /// are homogeneous transforms of the same dimension.
+  pub(super) fn compose(&self, other: &Tensor<T,2>) -> Result<Tensor<T,2>,TensorError>
+  {
+    self.transform_dim()?;
+    other.transform_dim()?;
+    if self.dim!=other.dim { return Err(TensorError::DimMismatch{lhs:self.dim.to_vec(),rhs:other.dim.to_vec()}); }
+    Ok(self.matmul(other))
+  }
+
+  /// Inverse of a rigid homogeneous transform, one of a rotation `R` followed by a translation `t`,
+  /// which is the rotation `R^T` followed by the translation `-R^T*t`, without a general inversion.
+  ///
+  /// The transform is assumed to be rigid, which is not checked, but fails unless it is of three or
+  /// four rows and columns.
+  pub(super) fn invert_rigid(&self) -> Result<Tensor<T,2>,TensorError>
+  {
+    let d: Idx=self.transform_dim()?;
+    Ok(Tensor::<T,2>::from_fn(self.dim,|[itr,jtr]| {
+      if itr==d { if jtr==d { T::from_f64(1.0) } else { T::default() } }
+      else if jtr==d { (0..d).fold(T::default(),|sum,ktr| sum-self[[ktr,itr]]*self[[ktr,d]]) }
+      else { self[[jtr,itr]] }
+    }))
+  }
+
+  /// Rotation `R` and translation `t` of the rigid motion `q=R*p+t` that best maps the rows `p` of `src`
+  /// onto the corresponding rows `q` of `dst` in the least squares sense, by the Kabsch algorithm.
+  ///
+  /// The rotation comes from the singular value decomposition of the covariance of the centred point
+  /// sets, by one-sided Jacobi rotations, with the direction of least variance flipped if that is what
+  /// keeps it from being a reflection. Fails unless both sets are of the same dimensions, or if there
+  /// are too few points, or they lie too close to a line, in three dimensions, to determine a rotation:
+  /// the covariance may have at most one singular value below `sqrt(epsilon)` times the largest.
+  pub(super) fn rigid_align(src: &Tensor<T,2>, dst: &Tensor<T,2>) -> Result<(Tensor<T,2>,Tensor<T,1>),TensorError>
+  {
+    if src.dim!=dst.dim { return Err(TensorError::DimMismatch{lhs:src.dim.to_vec(),rhs:dst.dim.to_vec()}); }
+    let (n,d): (Idx,Idx)=(src.dim[0],src.dim[1]);
+    if n<d { return Err(TensorError::DegeneratePoints); }
+
+    let zero: T=T::default();
+    let one: T=T::from_f64(1.0);
+    let centroid=|points: &Tensor<T,2>| -> Vec<T> {
+      (0..d).map(|jtr| (0..n).fold(zero,|sum,itr| sum+points[[itr,jtr]])/T::from_f64(n as f64)).collect()
+    };
+    let (src_mean,dst_mean): (Vec<T>,Vec<T>)=(centroid(src),centroid(dst));
+
+    // The covariance is reduced to `U*S` by rotating its columns, which accumulate into `V`.
+    let mut a: Vec<T>=(0..d*d).map(|ind| {
+      let (row,col): (Idx,Idx)=(ind/d,ind%d);
+      (0..n).fold(zero,|sum,itr| sum+(src[[itr,row]]-src_mean[row])*(dst[[itr,col]]-dst_mean[col]))
+    }).collect();
+    let mut v: Vec<T>=(0..d*d).map(|ind| if ind/d==ind%d { one } else { zero }).collect();
+    for _ in 0..MAX_JACOBI_SWEEPS
+    {
+      let mut rotated: bool=false;
+      for ptr in 0..d
+      {
+        for qtr in ptr+1..d
+        {
+          let (alpha,beta,gamma): (T,T,T)=(0..d).fold((zero,zero,zero),|(alpha,beta,gamma),row| {
+            let (ap,aq): (T,T)=(a[row*d+ptr],a[row*d+qtr]);
+            (alpha+ap*ap,beta+aq*aq,gamma+ap*aq)
+          });
+          if gamma.abs()<=T::epsilon()*(alpha*beta).sqrt() { continue; }
+          rotated=true;
+
+          let zeta: T=(beta-alpha)/(gamma+gamma);
+          let tan: T=one/(zeta.abs()+(one+zeta*zeta).sqrt());
+          let tan: T=if zeta<zero { zero-tan } else { tan };
+          let cos: T=one/(one+tan*tan).sqrt();
+          let sin: T=cos*tan;
+          for m in [&mut a,&mut v]
+          {
+            for row in 0..d
+            {
+              let (mp,mq): (T,T)=(m[row*d+ptr],m[row*d+qtr]);
+              m[row*d+ptr]=cos*mp-sin*mq;
+              m[row*d+qtr]=sin*mp+cos*mq;
+            }
+          }
+        }
+      }
+      if !rotated { break; }
+    }
+
+    let sigma: Vec<T>=(0..d).map(|col| (0..d).fold(zero,|sum,row| sum+a[row*d+col]*a[row*d+col]).sqrt()).collect();
+    let sigma_max: T=sigma.iter().fold(zero,|max,&elem| if elem>max { elem } else { max });
+    let tol: T=T::epsilon().sqrt()*sigma_max;
+    let small: Vec<Idx>=(0..d).filter(|&col| sigma[col]<=tol).collect();
+    if small.len()>1 { return Err(TensorError::DegeneratePoints); }
+
+    // A direction without variance leaves its column of `U` to be completed to an orthonormal basis.
+    let mut u: Vec<T>=(0..d*d).map(|ind| if sigma[ind%d]>tol { a[ind]/sigma[ind%d] } else { zero }).collect();
+    let flip: Idx=match small.first()
+    {
+      Some(&col) =>
+      {
+        complete_basis(&mut u,d,col);
+        col
+      },
+      None => (0..d).fold(0,|min,col| if sigma[col]<sigma[min] { col } else { min }),
+    };
+
+    let rotation=|sign: T| Tensor::<T,2>::from_fn([d,d],|[row,col]| {
+      (0..d).fold(zero,|sum,ktr| sum+v[row*d+ktr]*if ktr==flip { sign } else { one }*u[col*d+ktr])
+    });
+    let r: Tensor<T,2>=rotation(one);
+    let r: Tensor<T,2>=if determinant(r.data.to_vec(),d)<zero { rotation(zero-one) } else { r };
+    let t: Tensor<T,1>=Tensor::<T,1>::from_fn([d],|[row]| (0..d).fold(dst_mean[row],|sum,col| sum-r[[row,col]]*src_mean[col]));
+    Ok((r,t))
+  }
+
+  /// Number of coordinates of the points the homogeneous transform maps, failing unless it is of three
+  /// or four rows and columns.
+  fn transform_dim(&self) -> Result<Idx,TensorError>
+  {
+    match self.dim
+    {
+      [3,3] | [4,4] => Ok(self.dim[0]-1),
+      [rows,cols] => Err(TensorError::InvalidTransform{rows:rows,cols:cols}),
+    }
+  }
+}
+
+/// Maximum number of sweeps over all pairs of columns in the Jacobi singular value decomposition of
+/// `rigid_align`, which converges quadratically and so needs only a few for a small matrix.
+const MAX_JACOBI_SWEEPS: Idx=30;
+
+/// Replaces column `col` of the row-major `d` by `d` matrix `u`, whose other columns are orthonormal,
+/// by a unit vector orthogonal to them: the normalised component orthogonal to them of whichever unit
+/// axis has the largest such component.
+fn complete_basis<T>(u: &mut [T], d: Idx, col: Idx)
+where T: Float
+{
+  let zero: T=T::default();
+  let orthogonal=|axis: Idx| -> Vec<T> {
+    (0..d).map(|row| {
+      let unit: T=if row==axis { T::from_f64(1.0) } else { zero };
+      (0..d).filter(|&ktr| ktr!=col).fold(unit,|sum,ktr| sum-u[row*d+ktr]*u[axis*d+ktr])
+    }).collect()
+  };
+  let sq_norm=|w: &[T]| w.iter().fold(zero,|sum,&elem| sum+elem*elem);
+  let best: Vec<T>=(0..d).map(orthogonal).fold(Vec::new(),|best,w| if sq_norm(&w)>sq_norm(&best) { w } else { best });
+  let norm: T=sq_norm(&best).sqrt();
+  (0..d).for_each(|row| u[row*d+col]=best[row]/norm);
+}
+
+/// Determinant of the row-major `d` by `d` matrix `a`, by elimination with partial pivoting.
+fn determinant<T>(mut a: Vec<T>, d: Idx) -> T
+where T: Float
+{
+  let mut det: T=T::from_f64(1.0);
+  for ktr in 0..d
+  {
+    let pivot: Idx=(ktr..d).fold(ktr,|max,row| if a[row*d+ktr].abs()>a[max*d+ktr].abs() { row } else { max });
+    if a[pivot*d+ktr]==T::default() { return T::default(); }
+    if pivot!=ktr
+    {
+      (0..d).for_each(|col| a.swap(ktr*d+col,pivot*d+col));
+      det=T::default()-det;
+    }
+    det*=a[ktr*d+ktr];
+    for row in ktr+1..d
+    {
+      let factor: T=a[row*d+ktr]/a[ktr*d+ktr];
+      (ktr..d).for_each(|col| a[row*d+col]=a[row*d+col]-factor*a[ktr*d+col]);
+    }
+  }
+  det
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod geometry_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  use core::f64::consts::FRAC_PI_2;
+  use core::f64::consts::PI;
+
+  use super::super::Dim;
+
+  fn geometry_test_close(lhs: &Tensor<f64,2>, rhs: &Tensor<f64,2>) -> bool
+  {
+    lhs.dim==rhs.dim && (0..lhs.dim[0]).all(|itr| (0..lhs.dim[1]).all(|jtr| (lhs[[itr,jtr]]-rhs[[itr,jtr]]).abs()<1e-14))
+  }
+
+  fn geometry_test_identity(n: usize) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| (itr==jtr) as u8 as f64)
+  }
+
+  /// Column matrix of the elements of `elems`.
+  fn geometry_test_vector(elems: &[f64]) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([elems.len(),1],|[itr,_]| elems[itr])
+  }
+
+  fn geometry_test_axis(elems: &[f64]) -> Tensor<f64,1>
+  {
+    Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr])
+  }
+
+  #[rstest(theta,x,expected,
+    case(FRAC_PI_2,&[1.0,0.0],&[0.0,1.0]),
+    case(-FRAC_PI_2,&[0.0,1.0],&[1.0,0.0]),
+    case(PI,&[1.0,2.0],&[-1.0,-2.0]),
+    case(0.0,&[1.0,-3.0],&[1.0,-3.0]),
+  )]
+  fn geometry_test_rotation_2d(theta: f64, x: &[f64], expected: &[f64])
+  {
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_2d(theta);
+    assert!(geometry_test_close(&r.matmul(&geometry_test_vector(x)),&geometry_test_vector(expected)));
+    assert!(geometry_test_close(&r.matmul_t_a(&r),&geometry_test_identity(2)));
+  }
+
+  #[rstest(alpha,beta,case(0.4,-1.3),case(0.0,0.0),case(2.0,1.5),case(-0.7,0.7))]
+  fn geometry_test_rotation_2d_compose(alpha: f64, beta: f64)
+  {
+    let composed: Tensor<f64,2>=Tensor::<f64,2>::rotation_2d(alpha).matmul(&Tensor::<f64,2>::rotation_2d(beta));
+    assert!(geometry_test_close(&composed,&Tensor::<f64,2>::rotation_2d(alpha+beta)));
+  }
+
+  #[rstest(axis,theta,x,expected,
+    case(&[0.0,0.0,2.5],FRAC_PI_2,&[1.0,0.0,0.0],&[0.0,1.0,0.0]),
+    case(&[1.0,0.0,0.0],FRAC_PI_2,&[0.0,1.0,0.0],&[0.0,0.0,1.0]),
+    case(&[0.0,-1.0,0.0],FRAC_PI_2,&[1.0,0.0,0.0],&[0.0,0.0,1.0]),
+    case(&[1.0,-2.0,0.5],0.0,&[3.0,1.0,-1.0],&[3.0,1.0,-1.0]),
+  )]
+  fn geometry_test_rotation_3d_axis_angle(axis: &[f64], theta: f64, x: &[f64], expected: &[f64])
+  {
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_3d_axis_angle(&geometry_test_axis(axis),theta).unwrap();
+    assert!(geometry_test_close(&r.matmul(&geometry_test_vector(x)),&geometry_test_vector(expected)));
+  }
+
+  /// The rotation is orthogonal, leaves its axis in place and composes by adding the angles.
+  #[rstest(axis,theta,
+    case(&[1.0,-2.0,0.5],0.7),
+    case(&[0.0,0.0,2.5],FRAC_PI_2),
+    case(&[1.0,0.0,0.0],-1.2),
+    case(&[0.3,0.3,-3.0],2.9),
+    case(&[1.0,-2.0,0.5],0.0),
+  )]
+  fn geometry_test_rotation_3d_axis_angle_properties(axis: &[f64], theta: f64)
+  {
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_3d_axis_angle(&geometry_test_axis(axis),theta).unwrap();
+    assert!(geometry_test_close(&r.matmul_t_a(&r),&geometry_test_identity(3)));
+    assert!(geometry_test_close(&r.matmul(&geometry_test_vector(axis)),&geometry_test_vector(axis)));
+    let twice: Tensor<f64,2>=Tensor::<f64,2>::rotation_3d_axis_angle(&geometry_test_axis(axis),2.0*theta).unwrap();
+    assert!(geometry_test_close(&r.matmul(&r),&twice));
+  }
+
+  #[rstest(axis,expected,
+    case(&[0.0,0.0,0.0],TensorError::ZeroAxis),
+    case(&[0.0,0.0],TensorError::LengthMismatch{lhs:3,rhs:2}),
+    case(&[1.0,0.0,0.0,0.0],TensorError::LengthMismatch{lhs:3,rhs:4}),
+    case(&[],TensorError::LengthMismatch{lhs:3,rhs:0}),
+  )]
+  fn geometry_test_rotation_3d_axis_angle_errors(axis: &[f64], expected: TensorError)
+  {
+    assert!(Tensor::<f64,2>::rotation_3d_axis_angle(&geometry_test_axis(axis),1.0).err()==Some(expected));
+  }
+
+  #[rstest(roll,pitch,yaw,case(0.3,-1.1,2.0),case(0.0,0.0,0.0),case(FRAC_PI_2,0.0,0.0),case(-2.5,0.4,-0.2))]
+  fn geometry_test_rotation_3d_euler(roll: f64, pitch: f64, yaw: f64)
+  {
+    let about=|axis: usize, theta: f64| {
+      Tensor::<f64,2>::rotation_3d_axis_angle(&Tensor::<f64,1>::from_fn([3],|[itr]| (itr==axis) as u8 as f64),theta).unwrap()
+    };
+    let expected: Tensor<f64,2>=about(2,yaw).matmul(&about(1,pitch)).matmul(&about(0,roll));
+    assert!(geometry_test_close(&Tensor::<f64,2>::rotation_3d_euler(roll,pitch,yaw),&expected));
+  }
+
+  #[rstest(roll,pitch,yaw,x,expected,
+    case(0.0,0.0,FRAC_PI_2,&[1.0,0.0,0.0],&[0.0,1.0,0.0]),
+    case(FRAC_PI_2,0.0,0.0,&[0.0,1.0,0.0],&[0.0,0.0,1.0]),
+    case(0.0,FRAC_PI_2,0.0,&[0.0,0.0,1.0],&[1.0,0.0,0.0]),
+    case(0.0,0.0,0.0,&[1.0,2.0,3.0],&[1.0,2.0,3.0]),
+  )]
+  fn geometry_test_rotation_3d_euler_vector(roll: f64, pitch: f64, yaw: f64, x: &[f64], expected: &[f64])
+  {
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_3d_euler(roll,pitch,yaw);
+    assert!(geometry_test_close(&r.matmul(&geometry_test_vector(x)),&geometry_test_vector(expected)));
+  }
+
+  /// Rigid transform of the rotation by `yaw` about the z axis followed by the translation `t`.
+  fn geometry_test_rigid(yaw: f64, t: [f64;3]) -> Tensor<f64,2>
+  {
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_3d_euler(0.0,0.0,yaw);
+    Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| match (itr,jtr)
+    {
+      (3,3) => 1.0,
+      (3,_) => 0.0,
+      (_,3) => t[itr],
+      _ => r[[itr,jtr]],
+    })
+  }
+
+  /// Points mapped by the rotation by 90 degrees about the z axis followed by a translation.
+  #[rstest(point,expected,
+    case([0.0,0.0,0.0],[1.0,-2.0,0.5]),
+    case([1.0,0.0,0.0],[1.0,-1.0,0.5]),
+    case([0.0,2.0,0.0],[-1.0,-2.0,0.5]),
+    case([1.0,1.0,-1.0],[0.0,-1.0,-0.5]),
+  )]
+  fn geometry_test_transform_points(point: [f64;3], expected: [f64;3])
+  {
+    let t: Tensor<f64,2>=geometry_test_rigid(FRAC_PI_2,[1.0,-2.0,0.5]);
+    let points: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[_,jtr]| point[jtr]);
+    let mapped: Tensor<f64,2>=t.transform_points(&points).unwrap();
+    assert!(geometry_test_close(&mapped,&Tensor::<f64,2>::from_fn([2,3],|[_,jtr]| expected[jtr])));
+    assert!(geometry_test_close(&t.invert_rigid().unwrap().transform_points(&mapped).unwrap(),&points));
+  }
+
+  /// A projective transform divides by `w`, and fails for the points it maps to infinity, here those
+  /// with a first coordinate of -1.
+  #[rstest(point,expected,
+    case([1.0,3.0],Some([1.0,3.0])),
+    case([-0.5,1.0],Some([-2.0,4.0])),
+    case([0.0,-2.0],Some([0.0,-4.0])),
+    case([-1.0,1.0],None),
+    case([-1.0,0.0],None),
+  )]
+  fn geometry_test_transform_points_projective(point: [f64;2], expected: Option<[f64;2]>)
+  {
+    let projective: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| [[2.0,0.0,0.0],[0.0,2.0,0.0],[1.0,0.0,1.0]][itr][jtr]);
+    let points: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| if itr==0 { 1.0 } else { point[jtr] });
+    match expected
+    {
+      Some(expected) =>
+      {
+        let mapped: Tensor<f64,2>=projective.transform_points(&points).unwrap();
+        assert!(geometry_test_close(&mapped,&Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| if itr==0 { 1.0 } else { expected[jtr] })));
+      },
+      None => assert!(projective.transform_points(&points).err()==Some(TensorError::PointAtInfinity{row:1})),
+    }
+  }
+
+  #[rstest(transform,points,expected,
+    case([3,3],[4,3],Err(TensorError::LengthMismatch{lhs:2,rhs:3})),
+    case([4,4],[2,2],Err(TensorError::LengthMismatch{lhs:3,rhs:2})),
+    case([3,4],[4,3],Err(TensorError::InvalidTransform{rows:3,cols:4})),
+    case([2,2],[4,1],Err(TensorError::InvalidTransform{rows:2,cols:2})),
+    case([4,4],[0,3],Ok([0,3])),
+    case([3,3],[0,2],Ok([0,2])),
+  )]
+  fn geometry_test_transform_points_dims(transform: Dim<2>, points: Dim<2>, expected: Result<Dim<2>,TensorError>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(transform,|[itr,jtr]| (itr==jtr) as u8 as f64);
+    assert!(t.transform_points(&Tensor::<f64,2>::new(points)).map(|mapped| mapped.dim)==expected);
+  }
+
+  #[rstest(lhs_yaw,lhs_t,rhs_yaw,rhs_t,
+    case(FRAC_PI_2,[1.0,-2.0,0.5],FRAC_PI_2,[1.0,-2.0,0.5]),
+    case(0.3,[0.0,0.0,0.0],-1.2,[2.0,1.0,-1.0]),
+    case(0.0,[1.0,1.0,1.0],2.5,[0.0,0.0,0.0]),
+  )]
+  fn geometry_test_compose(lhs_yaw: f64, lhs_t: [f64;3], rhs_yaw: f64, rhs_t: [f64;3])
+  {
+    let (lhs,rhs): (Tensor<f64,2>,Tensor<f64,2>)=(geometry_test_rigid(lhs_yaw,lhs_t),geometry_test_rigid(rhs_yaw,rhs_t));
+    let points: Tensor<f64,2>=Tensor::<f64,2>::from_fn([5,3],|[itr,jtr]| (3*itr+jtr) as f64*0.25-1.0);
+    let composed: Tensor<f64,2>=lhs.compose(&rhs).unwrap();
+    assert!(geometry_test_close(&composed.transform_points(&points).unwrap(),&lhs.transform_points(&rhs.transform_points(&points).unwrap()).unwrap()));
+    assert!(geometry_test_close(&lhs.compose(&lhs.invert_rigid().unwrap()).unwrap(),&geometry_test_identity(4)));
+    assert!(geometry_test_close(&rhs.invert_rigid().unwrap().compose(&rhs).unwrap(),&geometry_test_identity(4)));
+  }
+
+  #[rstest(lhs,rhs,expected,
+    case([4,4],[3,3],TensorError::DimMismatch{lhs:vec![4,4],rhs:vec![3,3]}),
+    case([3,3],[4,4],TensorError::DimMismatch{lhs:vec![3,3],rhs:vec![4,4]}),
+    case([4,4],[4,3],TensorError::InvalidTransform{rows:4,cols:3}),
+    case([2,2],[4,4],TensorError::InvalidTransform{rows:2,cols:2}),
+  )]
+  fn geometry_test_compose_errors(lhs: Dim<2>, rhs: Dim<2>, expected: TensorError)
+  {
+    assert!(Tensor::<f64,2>::new(lhs).compose(&Tensor::<f64,2>::new(rhs)).err()==Some(expected));
+  }
+
+  #[rstest(dim,case([2,2]),case([4,3]),case([5,5]))]
+  fn geometry_test_invert_rigid_errors(dim: Dim<2>)
+  {
+    assert!(Tensor::<f64,2>::new(dim).invert_rigid().err()==Some(TensorError::InvalidTransform{rows:dim[0],cols:dim[1]}));
+  }
+
+  /// Points in three dimensions that do not all lie in a plane, as rows.
+  const CLOUD: [[f64;3];7]=[[0.0,0.0,0.0],[1.0,0.2,-0.3],[0.5,2.0,0.1],[-1.0,0.7,0.4],[0.3,-0.8,1.5],[2.0,1.0,1.0],[-0.6,-1.2,-0.9]];
+
+  /// The rows `start` to `end` of the points, with the coordinates from `d` on dropped and those from
+  /// `flat` on set to zero.
+  fn geometry_test_cloud(start: Idx, end: Idx, d: Idx, flat: Idx) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([end-start,d],|[itr,jtr]| if jtr<flat { CLOUD[start+itr][jtr] } else { 0.0 })
+  }
+
+  /// `points` moved by the rigid motion `q=r*p+t`, with `noise` times a fixed pattern of perturbations
+  /// added.
+  fn geometry_test_move(points: &Tensor<f64,2>, r: &Tensor<f64,2>, t: &[f64], noise: f64) -> Tensor<f64,2>
+  {
+    let d: Idx=points.dim[1];
+    Tensor::<f64,2>::from_fn(points.dim,|[itr,jtr]| {
+      let perturbation: f64=((7*itr+3*jtr)%5) as f64-2.0;
+      (0..d).fold(t[jtr],|sum,ktr| sum+r[[jtr,ktr]]*points[[itr,ktr]])+noise*perturbation
+    })
+  }
+
+  fn geometry_test_det_3(r: &Tensor<f64,2>) -> f64
+  {
+    r[[0,0]]*(r[[1,1]]*r[[2,2]]-r[[1,2]]*r[[2,1]])-r[[0,1]]*(r[[1,0]]*r[[2,2]]-r[[1,2]]*r[[2,0]])+r[[0,2]]*(r[[1,0]]*r[[2,1]]-r[[1,1]]*r[[2,0]])
+  }
+
+  /// Three points in a plane still determine the rotation, and so do points in the plane of the axes.
+  #[rstest(start,end,flat,noise,tol,
+    case(0,7,3,0.0,1e-14),
+    case(0,7,3,1e-6,1e-5),
+    case(1,4,3,0.0,1e-14),
+    case(0,5,2,0.0,1e-14),
+    case(2,7,3,1e-8,1e-7),
+  )]
+  fn geometry_test_rigid_align(start: Idx, end: Idx, flat: Idx, noise: f64, tol: f64)
+  {
+    let src: Tensor<f64,2>=geometry_test_cloud(start,end,3,flat);
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_3d_euler(0.4,-0.9,2.6);
+    let t: [f64;3]=[1.5,-3.0,0.25];
+
+    let (found_r,found_t): (Tensor<f64,2>,Tensor<f64,1>)=Tensor::<f64,2>::rigid_align(&src,&geometry_test_move(&src,&r,&t,noise)).unwrap();
+    assert!((0..3).all(|itr| (0..3).all(|jtr| (found_r[[itr,jtr]]-r[[itr,jtr]]).abs()<tol)));
+    assert!(found_t.iter().zip(t.iter()).all(|(x,y)| (x-y).abs()<tol));
+    assert!(geometry_test_close(&found_r.matmul_t_a(&found_r),&geometry_test_identity(3)) && (geometry_test_det_3(&found_r)-1.0).abs()<1e-14);
+  }
+
+  /// The best orthogonal map onto the mirror image is the reflection, which must not be returned.
+  #[rstest(axis,case(0),case(1),case(2))]
+  fn geometry_test_rigid_align_reflection(axis: Idx)
+  {
+    let src: Tensor<f64,2>=geometry_test_cloud(0,7,3,3);
+    let mirror: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| if itr!=jtr { 0.0 } else if itr==axis { -1.0 } else { 1.0 });
+    let (r,_): (Tensor<f64,2>,Tensor<f64,1>)=Tensor::<f64,2>::rigid_align(&src,&geometry_test_move(&src,&mirror,&[0.0;3],0.0)).unwrap();
+    assert!(geometry_test_close(&r.matmul_t_a(&r),&geometry_test_identity(3)) && (geometry_test_det_3(&r)-1.0).abs()<1e-14);
+  }
+
+  /// Two distinct points are enough in the plane.
+  #[rstest(start,end,theta,
+    case(0,7,-2.2),
+    case(1,3,-2.2),
+    case(2,6,0.9),
+    case(0,2,FRAC_PI_2),
+  )]
+  fn geometry_test_rigid_align_2d(start: Idx, end: Idx, theta: f64)
+  {
+    let src: Tensor<f64,2>=geometry_test_cloud(start,end,2,2);
+    let r: Tensor<f64,2>=Tensor::<f64,2>::rotation_2d(theta);
+    let (found_r,found_t): (Tensor<f64,2>,Tensor<f64,1>)=Tensor::<f64,2>::rigid_align(&src,&geometry_test_move(&src,&r,&[3.0,-1.0],0.0)).unwrap();
+    assert!(geometry_test_close(&found_r,&r) && (found_t[0]-3.0).abs()<1e-14 && (found_t[1]+1.0).abs()<1e-14);
+  }
+
+  #[rstest(src,
+    case(Tensor::<f64,2>::from_fn([4,3],|[itr,jtr]| itr as f64*[1.0,-2.0,0.5][jtr]+1.0)),
+    case(geometry_test_cloud(0,2,3,3)),
+    case(Tensor::<f64,2>::from_fn([5,3],|[_,jtr]| jtr as f64)),
+    case(geometry_test_cloud(0,7,3,1)),
+    case(geometry_test_cloud(0,1,2,2)),
+  )]
+  fn geometry_test_rigid_align_degenerate(src: Tensor<f64,2>)
+  {
+    assert!(Tensor::<f64,2>::rigid_align(&src,&src).err()==Some(TensorError::DegeneratePoints));
+  }
+
+  #[rstest(src,dst,case([7,3],[2,3]),case([4,2],[4,3]),case([0,3],[3,3]))]
+  fn geometry_test_rigid_align_dims(src: Dim<2>, dst: Dim<2>)
+  {
+    let err: Option<TensorError>=Tensor::<f64,2>::rigid_align(&Tensor::<f64,2>::new(src),&Tensor::<f64,2>::new(dst)).err();
+    assert!(err==Some(TensorError::DimMismatch{lhs:src.to_vec(),rhs:dst.to_vec()}));
+  }
+}
@@ -0,0 +1,178 @@
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+
+/// Failed gradient check, naming the element at the multi-index `index` whose analytic and numerical
+/// partial derivatives are furthest apart, and their relative error `rel_err`.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) struct GradCheckError
+{
+  pub(super) index: Vec<Idx>,
+  pub(super) analytic: f64,
+  pub(super) numerical: f64,
+  pub(super) rel_err: f64,
+}
+
+impl fmt::Display for GradCheckError
+{
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    write!(f,"The partial derivative at index {:?} is {} analytically but {} numerically, a relative error of {}.",self.index,self.analytic,self.numerical,self.rel_err)
+  }
+}
+
+#[cfg(feature="std")]
+impl std::error::Error for GradCheckError {}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Gradient of `f` at `x` by central differences with step `eps`, perturbing a single copy of `x`
+  /// one element at a time, for `2*x.len()` evaluations of `f`.
+  pub(super) fn numerical_gradient<F>(mut f: F, x: &Tensor<T,N>, eps: T) -> Tensor<T,N>
+  where F: FnMut(&Tensor<T,N>) -> T
+  {
+    let two_eps: T=eps+eps;
+    let mut xp: Tensor<T,N>=x.clone();
+    let mut grad: Tensor<T,N>=Tensor::<T,N>::new(x.dim).into_layout(x.layout);
+    for offset in 0..x.len()
+    {
+      let elem: T=xp.data[offset];
+      xp.data[offset]=elem+eps;
+      let upper: T=f(&xp);
+      xp.data[offset]=elem-eps;
+      let lower: T=f(&xp);
+      xp.data[offset]=elem;
+      grad.data[offset]=(upper-lower)/two_eps;
+    }
+    grad
+  }
+
+  /// Compares the gradient `analytic` of `f` at `x` with `numerical_gradient`, failing with the worst
+  /// element if any relative error exceeds `tol`.
+  ///
+  /// The relative error of an element is `|a-n|/max(|a|,|n|,1)`, which is an absolute error for
+  /// derivatives smaller than one so that those near zero do not fail on rounding alone. A NaN in
+  /// either gradient always fails.
+  pub(super) fn check_gradient<F>(f: F, analytic: &Tensor<T,N>, x: &Tensor<T,N>, eps: T, tol: T) -> Result<(),GradCheckError>
+  where F: FnMut(&Tensor<T,N>) -> T
+  {
+    if analytic.dim!=x.dim { panic!("The analytic gradient must be of the dimensions of the point to check it.") }
+
+    let numerical: Tensor<T,N>=Tensor::<T,N>::numerical_gradient(f,x,eps);
+    let analytic: Tensor<T,N>=analytic.to_layout(x.layout);
+    let one: T=T::from_f64(1.0);
+    let scale=|a: T, n: T| [a.abs(),n.abs()].iter().fold(one,|max,&elem| if elem>max { elem } else { max });
+    let worse=|rel_err: T, max: T| !max.is_nan() && (rel_err.is_nan() || rel_err>max);
+    let worst: Option<(Idx,T)>=analytic.data.iter().zip(numerical.data.iter())
+      .map(|(&a,&n)| (a-n).abs()/scale(a,n))
+      .enumerate()
+      .fold(None,|worst,(offset,rel_err)| match worst
+      {
+        Some((_,max)) if !worse(rel_err,max) => worst,
+        _ => Some((offset,rel_err)),
+      });
+
+    match worst
+    {
+      Some((offset,rel_err)) if rel_err.is_nan() || rel_err>tol => Err(GradCheckError{
+        index:x.unravel_offset(offset).to_vec(),
+        analytic:analytic.data[offset].to_f64(),
+        numerical:numerical.data[offset].to_f64(),
+        rel_err:rel_err.to_f64(),
+      }),
+      _ => Ok(()),
+    }
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod gradient_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec::Vec;
+
+  use super::super::Dim;
+  use super::super::Indices;
+  use super::super::Layout;
+
+  fn gradient_test_sum_sq(x: &Tensor<f64,2>) -> f64
+  {
+    x.iter().map(|elem| elem*elem).sum()
+  }
+
+  #[rstest(dim,layout,case([2,3],Layout::RowMajor),case([2,3],Layout::ColMajor),case([1,5],Layout::RowMajor),case([4,1],Layout::ColMajor))]
+  fn gradient_test_numerical_gradient(dim: Dim<2>, layout: Layout)
+  {
+    let x: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64-2.5).into_layout(layout);
+    let mut evals: Idx=0;
+    let grad: Tensor<f64,2>=Tensor::<f64,2>::numerical_gradient(|x| { evals+=1; gradient_test_sum_sq(x) },&x,1e-3);
+
+    // Central differences take two evaluations per element, and are exact for a quadratic.
+    assert!(evals==2*dim[0]*dim[1]);
+    assert!(grad.layout()==layout);
+    assert!(Indices::new(dim).all(|ind| (grad[ind]-2.0*x[ind]).abs()<1e-9));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn gradient_test_numerical_gradient_product(layout: Layout)
+  {
+    let x: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-2.5).into_layout(layout);
+    let grad: Tensor<f64,2>=Tensor::<f64,2>::numerical_gradient(|x| x[[1,0]]*x[[0,2]],&x,1e-3);
+
+    assert!(grad.layout()==layout);
+    assert!(Indices::new([2,3]).all(|[itr,jtr]| (grad[[itr,jtr]]-[[0.0,0.0,0.5],[-0.5,0.0,0.0]][itr][jtr]).abs()<1e-9));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn gradient_test_check_gradient(layout: Layout)
+  {
+    let x: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-2.5);
+    let analytic: Tensor<f64,2>=x.map(|elem| 2.0*elem);
+
+    assert!(Tensor::<f64,2>::check_gradient(gradient_test_sum_sq,&analytic.to_layout(layout),&x,1e-4,1e-6).is_ok());
+    assert!(Tensor::<f64,2>::check_gradient(gradient_test_sum_sq,&analytic,&x.to_layout(layout),1e-4,1e-6).is_ok());
+  }
+
+  /// The analytic gradient of the sum of squares at the point is `[[-5,-3,-1],[1,3,5]]`, here with one
+  /// element replaced by `value` and another slightly off, so that the check fails on the former.
+  #[rstest(ind,value,rel_err,
+    case([1,1],-3.0,2.0),
+    case([1,2],0.0,1.0),
+    case([0,2],1.0,2.0),
+    case([0,1],10.0,1.3),
+    case([1,1],f64::NAN,f64::NAN),
+  )]
+  fn gradient_test_check_gradient_wrong(ind: Dim<2>, value: f64, rel_err: f64)
+  {
+    let x: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64-2.5);
+    let mut wrong: Tensor<f64,2>=x.map(|elem| 2.0*elem);
+    let numerical: f64=wrong[ind];
+    wrong[ind]=value;
+    wrong[[0,0]]*=1.01;
+
+    let err: GradCheckError=Tensor::<f64,2>::check_gradient(gradient_test_sum_sq,&wrong,&x,1e-4,1e-6).unwrap_err();
+    assert!(err.index==ind.to_vec());
+    assert!(err.analytic==value || value.is_nan());
+    assert!((err.numerical-numerical).abs()<1e-9);
+    assert!((err.rel_err-rel_err).abs()<1e-9 || (err.rel_err.is_nan() && rel_err.is_nan()));
+  }
+
+  #[rstest(dim,case([3,2]),case([2,2]),case([1,6]))]
+  #[should_panic(expected="The analytic gradient must be of the dimensions of the point to check it.")]
+  fn gradient_test_check_gradient_dim(dim: Dim<2>)
+  {
+    let _=Tensor::<f64,2>::check_gradient(gradient_test_sum_sq,&Tensor::<f64,2>::new(dim),&Tensor::<f64,2>::new([2,3]),1e-4,1e-6);
+  }
+}
@@ -0,0 +1,168 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+use super::TotalOrder;
+use super::order::Side;
+
+/// Reduction applied to every group by `Tensor::group_reduce`.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) enum ReduceOp
+{
+  Sum,
+  /// Mean of the group, which is NaN for an empty group.
+  Mean,
+  /// Smallest element of the group, which is infinity for an empty group. NaN elements are skipped.
+  Min,
+  /// Largest element of the group, which is negative infinity for an empty group. NaN elements are
+  /// skipped.
+  Max,
+  /// Number of elements in the group.
+  Count,
+}
+
+impl<T> Tensor<T,1>
+where T: TotalOrder
+{
+  /// Index of the bin every element falls in, where bin `itr` holds the elements from `edges[itr-1]`,
+  /// inclusive, to `edges[itr]`, exclusive. Elements below the first edge fall in bin zero and those
+  /// from the last edge on in bin `edges.len()`.
+  ///
+  /// `edges` must be sorted, as for `searchsorted`.
+  pub(super) fn digitize(&self, edges: &Tensor<T,1>) -> Vec<Idx>
+  {
+    edges.searchsorted(self,Side::Right)
+  }
+}
+
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Reduces the elements of every group with `op`, where element `itr` belongs to group `groups[itr]`,
+  /// failing unless there is a group for every element and every group is less than `num_groups`.
+  pub(super) fn group_reduce(&self, groups: &[Idx], num_groups: Idx, op: ReduceOp) -> Result<Tensor<T,1>,TensorError>
+  {
+    if groups.len()!=self.dim[0] { return Err(TensorError::LengthMismatch{lhs:self.dim[0],rhs:groups.len()}); }
+    if let Some(index)=groups.iter().position(|&group| group>=num_groups) { return Err(TensorError::InvalidGroup{index:index}); }
+
+    let init: T=match op
+    {
+      ReduceOp::Sum | ReduceOp::Mean | ReduceOp::Count => T::default(),
+      ReduceOp::Min => T::infinity(),
+      ReduceOp::Max => T::default()-T::infinity(),
+    };
+    let mut reduced: Tensor<T,1>=Tensor::<T,1>::from_fn([num_groups],|_| init);
+    let mut counts: Vec<Idx>=vec![0;num_groups];
+    for (&elem,&group) in self.data.iter().zip(groups.iter())
+    {
+      let acc: &mut T=&mut reduced.data[group];
+      match op
+      {
+        ReduceOp::Sum | ReduceOp::Mean => *acc+=elem,
+        ReduceOp::Min => if elem<*acc { *acc=elem },
+        ReduceOp::Max => if elem>*acc { *acc=elem },
+        ReduceOp::Count => (),
+      }
+      counts[group]+=1;
+    }
+
+    match op
+    {
+      ReduceOp::Mean => reduced.data.iter_mut().zip(counts.iter()).for_each(|(acc,&count)| *acc=*acc/T::from_f64(count as f64)),
+      ReduceOp::Count => reduced.data.iter_mut().zip(counts.iter()).for_each(|(acc,&count)| *acc=T::from_f64(count as f64)),
+      _ => (),
+    }
+    Ok(reduced)
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod group_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn group_test_signal() -> Tensor<f64,1>
+  {
+    Tensor::<f64,1>::from_fn([12],|[itr]| [0.5,-1.5,3.0,2.0,7.5,-4.0,0.0,6.5,1.0,-2.5,9.0,4.5][itr])
+  }
+
+  #[rstest(edges,expected,
+    case(&[-2.0,0.0,3.0,6.0],&[2,1,3,2,4,0,2,4,2,0,4,3]),
+    case(&[0.0,3.0,6.0],&[1,0,2,1,3,0,1,3,1,0,3,2]),
+    case(&[-10.0],&[1;12]),
+    case(&[10.0],&[0;12]),
+    case(&[],&[0;12]),
+  )]
+  fn group_test_digitize(edges: &[f64], expected: &[Idx])
+  {
+    let edges: Tensor<f64,1>=Tensor::<f64,1>::from_fn([edges.len()],|[itr]| edges[itr]);
+    let bins: Vec<Idx>=group_test_signal().digitize(&edges);
+    assert!(bins==expected);
+    assert!(group_test_signal().iter().zip(bins.iter()).all(|(&elem,&bin)| {
+      (bin==0 || edges[bin-1]<=elem) && (bin==edges.len() || elem<edges[bin])
+    }));
+  }
+
+  /// Groups of the signal by the bins of the edges `[0,3,6]`, with an extra group that nothing falls
+  /// in, so that it is empty.
+  #[rstest(op,expected,
+    case(ReduceOp::Sum,[-8.0,3.5,7.5,23.0,0.0]),
+    case(ReduceOp::Mean,[-8.0/3.0,0.875,3.75,23.0/3.0,f64::NAN]),
+    case(ReduceOp::Min,[-4.0,0.0,3.0,6.5,f64::INFINITY]),
+    case(ReduceOp::Max,[-1.5,2.0,4.5,9.0,f64::NEG_INFINITY]),
+    case(ReduceOp::Count,[3.0,4.0,2.0,3.0,0.0]),
+  )]
+  fn group_test_group_reduce(op: ReduceOp, expected: [f64;5])
+  {
+    let signal: Tensor<f64,1>=group_test_signal();
+    let groups: Vec<Idx>=signal.digitize(&Tensor::<f64,1>::from_fn([3],|[itr]| [0.0,3.0,6.0][itr]));
+
+    let res: Tensor<f64,1>=signal.group_reduce(&groups,5,op).unwrap();
+    assert!(res.iter().zip(expected.iter()).all(|(&res,&expected)| res==expected || (res.is_nan() && expected.is_nan())));
+  }
+
+  #[rstest(index,group,case(7,5),case(0,100),case(11,5))]
+  fn group_test_group_reduce_invalid(index: Idx, group: Idx)
+  {
+    let mut groups: Vec<Idx>=vec![0;12];
+    groups[index]=group;
+    assert!(group_test_signal().group_reduce(&groups,5,ReduceOp::Sum).err()==Some(TensorError::InvalidGroup{index:index}));
+  }
+
+  #[rstest(len,case(11),case(13),case(0))]
+  fn group_test_group_reduce_len(len: Idx)
+  {
+    let groups: Vec<Idx>=vec![0;len];
+    assert!(group_test_signal().group_reduce(&groups,5,ReduceOp::Mean).err()==Some(TensorError::LengthMismatch{lhs:12,rhs:len}));
+  }
+
+  #[rstest(op,expected,
+    case(ReduceOp::Max,[1.0,3.0]),
+    case(ReduceOp::Min,[1.0,-2.0]),
+    case(ReduceOp::Sum,[f64::NAN,1.0]),
+    case(ReduceOp::Mean,[f64::NAN,0.5]),
+    case(ReduceOp::Count,[2.0,2.0]),
+  )]
+  fn group_test_group_reduce_nan(op: ReduceOp, expected: [f64;2])
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| [1.0,f64::NAN,-2.0,3.0][itr]);
+    let res: Tensor<f64,1>=t.group_reduce(&[0,0,1,1],2,op).unwrap();
+    assert!(res.iter().zip(expected.iter()).all(|(&res,&expected)| res==expected || (res.is_nan() && expected.is_nan())));
+  }
+
+  #[rstest(num_groups,case(0),case(3))]
+  fn group_test_group_reduce_empty(num_groups: Idx)
+  {
+    let res: Tensor<f64,1>=Tensor::<f64,1>::new([0]).group_reduce(&[],num_groups,ReduceOp::Count).unwrap();
+    assert!(res.dim==[num_groups] && res.iter().all(|&elem| elem==0.0));
+  }
+}
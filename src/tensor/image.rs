@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,3>
+where T: Clone
+{
+  /// Image of dimensions `[height,width,channels]` with its axes permuted to `[channels,height,width]`,
+  /// so that every channel is a contiguous plane.
+  pub(super) fn to_chw(&self) -> Tensor<T,3>
+  {
+    self.permute([2,0,1])
+  }
+
+  /// Image of dimensions `[channels,height,width]` with its axes permuted to `[height,width,channels]`,
+  /// so that the channels of every pixel are contiguous. This is the inverse of `to_chw`.
+  pub(super) fn to_hwc(&self) -> Tensor<T,3>
+  {
+    self.permute([1,2,0])
+  }
+
+  /// Channel `c` of an image of dimensions `[height,width,channels]`, failing unless there is such a
+  /// channel.
+  pub(super) fn channel(&self, c: Idx) -> Result<Tensor<T,2>,TensorError>
+  {
+    if c>=self.dim[2] { return Err(TensorError::ChannelOutOfRange{channel:c,channels:self.dim[2]}); }
+    Ok(Tensor::<T,2>::from_fn([self.dim[0],self.dim[1]],|[itr,jtr]| self[[itr,jtr,c]].clone()))
+  }
+
+  /// Image of dimensions `[height,width,channels]` with `f` applied to every channel on its own, which
+  /// may change the height and width, but must change them alike for every channel.
+  pub(super) fn map_channels<F>(&self, mut f: F) -> Tensor<T,3>
+  where F: FnMut(&Tensor<T,2>) -> Tensor<T,2>
+  {
+    let planes: Vec<Tensor<T,2>>=(0..self.dim[2]).map(|c| f(&self.channel(c).unwrap())).collect();
+    let [height,width]: [Idx;2]=planes.first().map_or([self.dim[0],self.dim[1]],|plane| plane.dim);
+    if planes.iter().any(|plane| plane.dim!=[height,width]) { panic!("Every channel must be mapped to a matrix of the same dimensions to reassemble an image.") }
+    Tensor::<T,3>::from_fn([height,width,self.dim[2]],|[itr,jtr,ktr]| planes[ktr][[itr,jtr]].clone())
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod image_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Indices;
+  use super::super::Layout;
+
+  /// Image whose elements encode their index.
+  fn image_test_hwc(dim: Dim<3>) -> Tensor<i32,3>
+  {
+    Tensor::<i32,3>::from_fn(dim,|[itr,jtr,ktr]| (100*ktr+10*itr+jtr) as i32)
+  }
+
+  #[rstest(dim,layout,
+    case([3,4,2],Layout::RowMajor),
+    case([3,4,2],Layout::ColMajor),
+    case([1,5,3],Layout::RowMajor),
+    case([4,1,1],Layout::ColMajor),
+    case([2,3,0],Layout::RowMajor),
+  )]
+  fn image_test_permute(dim: Dim<3>, layout: Layout)
+  {
+    let [height,width,channels]: Dim<3>=dim;
+    let chw: Tensor<i32,3>=image_test_hwc(dim).to_layout(layout).to_chw();
+    assert!(chw.dim==[channels,height,width]);
+    assert!(Indices::new(chw.dim).all(|[ktr,itr,jtr]| chw[[ktr,itr,jtr]]==(100*ktr+10*itr+jtr) as i32));
+
+    assert!(chw.to_hwc()==image_test_hwc(dim));
+    assert!(chw.to_hwc().to_chw()==chw);
+  }
+
+  #[rstest(dim,c,case([3,4,2],0),case([3,4,2],1),case([1,5,3],2),case([4,1,1],0))]
+  fn image_test_channel(dim: Dim<3>, c: Idx)
+  {
+    let plane: Tensor<i32,2>=image_test_hwc(dim).channel(c).unwrap();
+    assert!(plane.dim==[dim[0],dim[1]]);
+    assert!(Indices::new(plane.dim).all(|[itr,jtr]| plane[[itr,jtr]]==(100*c+10*itr+jtr) as i32));
+  }
+
+  #[rstest(dim,c,case([3,4,2],2),case([3,4,2],5),case([3,4,0],0))]
+  fn image_test_channel_out_of_range(dim: Dim<3>, c: Idx)
+  {
+    assert!(image_test_hwc(dim).channel(c).err()==Some(TensorError::ChannelOutOfRange{channel:c,channels:dim[2]}));
+  }
+
+  /// Every channel is mapped on its own, in order, without the others leaking into it.
+  #[rstest(dim,start,size,
+    case([3,4,2],[1,1],[2,2]),
+    case([3,4,2],[0,0],[3,4]),
+    case([3,4,3],[2,0],[1,4]),
+    case([2,5,1],[0,3],[2,1]),
+  )]
+  fn image_test_map_channels(dim: Dim<3>, start: Dim<2>, size: Dim<2>)
+  {
+    let t: Tensor<i32,3>=image_test_hwc(dim);
+    let mut seen: Vec<i32>=Vec::new();
+    let mapped: Tensor<i32,3>=t.map_channels(|plane| {
+      seen.push(plane[[0,0]]);
+      plane.crop(start,size).unwrap().map(|&elem| 2*elem)
+    });
+
+    assert!(seen.iter().copied().eq((0..dim[2]).map(|ktr| 100*ktr as i32)));
+    assert!(mapped.dim==[size[0],size[1],dim[2]]);
+    assert!((0..dim[2]).all(|ktr| mapped.channel(ktr).unwrap()==t.channel(ktr).unwrap().crop(start,size).unwrap().map(|&elem| 2*elem)));
+    assert!(t.map_channels(|plane| plane.clone())==t);
+  }
+
+  /// An image without channels is left as it is, as there is no channel to map to other dimensions.
+  #[rstest(dim,case([3,4,0]),case([0,0,0]),case([1,7,0]))]
+  fn image_test_map_channels_empty(dim: Dim<3>)
+  {
+    assert!(Tensor::<i32,3>::new(dim).map_channels(|plane| plane.crop([0,0],[1,1]).unwrap()).dim==dim);
+  }
+
+  #[rstest(dim,case([3,4,2]),case([2,3,3]),case([1,2,2]))]
+  #[should_panic(expected="Every channel must be mapped to a matrix of the same dimensions to reassemble an image.")]
+  fn image_test_map_channels_dim(dim: Dim<3>)
+  {
+    let _=image_test_hwc(dim).map_channels(|plane| if plane[[0,0]]==0 { plane.clone() } else { plane.transpose() });
+  }
+}
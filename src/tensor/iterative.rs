@@ -0,0 +1,386 @@
+use super::Float;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+use super::matfun::check_square;
+
+/// Outcome of an iterative solver, as returned by `Tensor::conjugate_gradient` and
+/// `Tensor::solve_stationary`.
+///
+/// A solver that runs out of iterations still returns its last iterate, with `converged` unset.
+#[derive(Debug,Clone)]
+pub(super) struct CgResult<T>
+{
+  pub(super) x: Tensor<T,1>,
+  pub(super) iterations: Idx,
+  /// Norm of the residual `b-A*x` of the returned iterate.
+  pub(super) residual_norm: T,
+  pub(super) converged: bool,
+}
+
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Solution `x` of `A*x=b` for a symmetric positive definite matrix `A` given only through
+  /// `matvec`, which multiplies a vector by `A`.
+  ///
+  /// Conjugate gradient iterations start from `x0`, or from zero if it is `None`, and stop once the
+  /// residual norm is at most `tol` times the norm of `b` or after `max_iter` iterations. Running out
+  /// of iterations is reported in the result rather than as an error, as is a search direction along
+  /// which `A` is not positive.
+  pub(super) fn conjugate_gradient<F>(matvec: F, b: &Tensor<T,1>, x0: Option<&Tensor<T,1>>, tol: T, max_iter: Idx) -> Result<CgResult<T>,TensorError>
+  where F: FnMut(&Tensor<T,1>) -> Tensor<T,1>
+  {
+    Tensor::<T,1>::conjugate_gradient_with_callback(matvec,b,x0,tol,max_iter,|_,_| {})
+  }
+
+  /// Variant of `conjugate_gradient` that calls `callback` with the number of iterations done and the
+  /// residual norm after every iteration.
+  pub(super) fn conjugate_gradient_with_callback<F,C>(mut matvec: F, b: &Tensor<T,1>, x0: Option<&Tensor<T,1>>, tol: T, max_iter: Idx, mut callback: C) -> Result<CgResult<T>,TensorError>
+  where F: FnMut(&Tensor<T,1>) -> Tensor<T,1>, C: FnMut(Idx,T)
+  {
+    let n: Idx=b.dim[0];
+    let mut x: Tensor<T,1>=match x0
+    {
+      Some(x0) if x0.dim[0]!=n => return Err(TensorError::LengthMismatch{lhs:n,rhs:x0.dim[0]}),
+      Some(x0) => x0.clone(),
+      None => Tensor::<T,1>::new([n]),
+    };
+    let mut apply=|v: &Tensor<T,1>| -> Result<Tensor<T,1>,TensorError> {
+      let res: Tensor<T,1>=matvec(v);
+      if res.dim[0]!=n { return Err(TensorError::LengthMismatch{lhs:n,rhs:res.dim[0]}); }
+      Ok(res)
+    };
+
+    let zero: T=T::default();
+    let one: T=T::from_f64(1.0);
+    let threshold: T=tol*b.dot(b).sqrt();
+    let mut r: Tensor<T,1>=b.clone();
+    if x0.is_some() { r.axpy(zero-one,&apply(&x)?); }
+    let mut p: Tensor<T,1>=r.clone();
+    let mut r_sq_norm: T=r.dot(&r);
+
+    let mut iterations: Idx=0;
+    while r_sq_norm.sqrt()>threshold && iterations<max_iter
+    {
+      let ap: Tensor<T,1>=apply(&p)?;
+      let curvature: T=p.dot(&ap);
+      if curvature<=zero { break; }
+
+      let alpha: T=r_sq_norm/curvature;
+      x.axpy(alpha,&p);
+      r.axpy(zero-alpha,&ap);
+      let next_sq_norm: T=r.dot(&r);
+      iterations+=1;
+      callback(iterations,next_sq_norm.sqrt());
+
+      let beta: T=next_sq_norm/r_sq_norm;
+      r_sq_norm=next_sq_norm;
+      p=p.map(|&elem| elem*beta);
+      p+=&r;
+    }
+
+    let residual_norm: T=r_sq_norm.sqrt();
+    Ok(CgResult{x:x,iterations:iterations,residual_norm:residual_norm,converged:residual_norm<=threshold})
+  }
+}
+
+/// Stationary iteration performed by every sweep of `Tensor::solve_stationary`.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) enum Stationary
+{
+  /// Updates every unknown from the previous iterate only.
+  Jacobi,
+  /// Updates the unknowns in order, each from the ones already updated in the same sweep.
+  GaussSeidel,
+}
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// One Jacobi sweep for `self*x=b`, solving every equation for its diagonal unknown with the other
+  /// unknowns taken from `x`.
+  pub(super) fn jacobi_step(&self, b: &Tensor<T,1>, x: &Tensor<T,1>) -> Result<Tensor<T,1>,TensorError>
+  {
+    let a: Tensor<T,2>=self.check_stationary(b,x)?;
+    Ok(jacobi_sweep(&a,b,x))
+  }
+
+  /// One Gauss-Seidel sweep for `self*x=b` in place, solving the equations in order for their
+  /// diagonal unknowns with the unknowns before it already updated.
+  pub(super) fn gauss_seidel_step(&self, b: &Tensor<T,1>, x: &mut Tensor<T,1>) -> Result<(),TensorError>
+  {
+    let a: Tensor<T,2>=self.check_stationary(b,x)?;
+    gauss_seidel_sweep(&a,b,x);
+    Ok(())
+  }
+
+  /// Solution `x` of `self*x=b` by repeated sweeps of `method`, starting from `x0`, or from zero if it
+  /// is `None`.
+  ///
+  /// The iterations stop once the residual norm is at most `tol` times the norm of `b` or after
+  /// `max_iter` sweeps. Both methods converge for a strictly diagonally dominant matrix, and
+  /// Gauss-Seidel also for a symmetric positive definite one. Running out of iterations is reported
+  /// in the result rather than as an error, but a residual norm that overflows to infinity or NaN is
+  /// `TensorError::Diverged`, which tells whether the matrix is diagonally dominant and symmetric.
+  pub(super) fn solve_stationary(&self, b: &Tensor<T,1>, x0: Option<&Tensor<T,1>>, method: Stationary, tol: T, max_iter: Idx) -> Result<CgResult<T>,TensorError>
+  {
+    let mut x: Tensor<T,1>=match x0
+    {
+      Some(x0) => x0.clone(),
+      None => Tensor::<T,1>::new([b.dim[0]]),
+    };
+    let a: Tensor<T,2>=self.check_stationary(b,&x)?;
+
+    let threshold: T=tol*b.dot(b).sqrt();
+    let mut residual_norm: T=residual_norm_of(&a,b,&x);
+    let mut iterations: Idx=0;
+    while residual_norm>threshold && iterations<max_iter
+    {
+      match method
+      {
+        Stationary::Jacobi => x=jacobi_sweep(&a,b,&x),
+        Stationary::GaussSeidel => gauss_seidel_sweep(&a,b,&mut x),
+      }
+      residual_norm=residual_norm_of(&a,b,&x);
+      iterations+=1;
+      if residual_norm.is_nan() || residual_norm==T::infinity()
+      {
+        return Err(TensorError::Diverged{iterations:iterations,dominant:a.is_diagonally_dominant(),symmetric:a.is_symmetric(T::default())});
+      }
+    }
+    Ok(CgResult{x:x,iterations:iterations,residual_norm:residual_norm,converged:residual_norm<=threshold})
+  }
+
+  /// Checks the dimensions of `self*x=b` and the diagonal of `self`, returning `self` in row-major
+  /// order so the sweeps can read it row by row.
+  fn check_stationary(&self, b: &Tensor<T,1>, x: &Tensor<T,1>) -> Result<Tensor<T,2>,TensorError>
+  {
+    check_square(self)?;
+    let n: Idx=self.dim[0];
+    for len in [b.dim[0],x.dim[0]].iter()
+    {
+      if *len!=n { return Err(TensorError::LengthMismatch{lhs:n,rhs:*len}); }
+    }
+    if let Some(row)=self.diagonal().iter().position(|&elem| elem==T::default())
+    {
+      return Err(TensorError::ZeroDiagonal{row:row});
+    }
+    Ok(self.to_layout(Layout::RowMajor))
+  }
+}
+
+/// Iterator over the rows of the row-major square matrix `a`.
+fn rows<T>(a: &Tensor<T,2>) -> impl Iterator<Item=&[T]>
+{
+  // The width is only zero for a square matrix without rows, for which any chunk size yields none.
+  a.data.chunks(a.dim[1].max(1))
+}
+
+/// Jacobi sweep for the checked row-major system `a*x=b`.
+fn jacobi_sweep<T>(a: &Tensor<T,2>, b: &Tensor<T,1>, x: &Tensor<T,1>) -> Tensor<T,1>
+where T: Float
+{
+  let mut res: Tensor<T,1>=x.clone();
+  for (itr,row) in rows(a).enumerate()
+  {
+    let off_diag: T=row.iter().zip(x.iter()).enumerate()
+      .filter(|&(jtr,_)| jtr!=itr)
+      .fold(T::default(),|sum,(_,(&a_ij,&x_j))| sum+a_ij*x_j);
+    res[itr]=(b[itr]-off_diag)/row[itr];
+  }
+  res
+}
+
+/// Gauss-Seidel sweep in place for the checked row-major system `a*x=b`.
+fn gauss_seidel_sweep<T>(a: &Tensor<T,2>, b: &Tensor<T,1>, x: &mut Tensor<T,1>)
+where T: Float
+{
+  for (itr,row) in rows(a).enumerate()
+  {
+    let off_diag: T=(0..row.len())
+      .filter(|&jtr| jtr!=itr)
+      .fold(T::default(),|sum,jtr| sum+row[jtr]*x[jtr]);
+    x[itr]=(b[itr]-off_diag)/row[itr];
+  }
+}
+
+/// Norm of the residual `b-a*x` of the row-major system `a*x=b`.
+fn residual_norm_of<T>(a: &Tensor<T,2>, b: &Tensor<T,1>, x: &Tensor<T,1>) -> T
+where T: Float
+{
+  rows(a).zip(b.iter())
+    .map(|(row,&b_i)| b_i-row.iter().zip(x.iter()).fold(T::default(),|sum,(&a_ij,&x_j)| sum+a_ij*x_j))
+    .fold(T::default(),|sum,r_i| sum+r_i*r_i)
+    .sqrt()
+}
+
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod iterative_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use super::super::Dim;
+
+  use alloc::vec::Vec;
+
+  fn iterative_test_matvec(a: &Tensor<f64,2>, x: &Tensor<f64,1>) -> Tensor<f64,1>
+  {
+    Tensor::<f64,1>::from_fn([a.dim[0]],|[itr]| (0..a.dim[1]).map(|jtr| a[[itr,jtr]]*x[jtr]).sum())
+  }
+
+  #[rstest(n,case(2),case(4),case(6),case(9))]
+  fn iterative_test_conjugate_gradient(n: Idx)
+  {
+    // M^T*M plus a multiple of the identity is symmetric positive definite.
+    let m: Tensor<f64,2>=Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| ((3*itr+5*jtr)%7) as f64-3.0);
+    let a: Tensor<f64,2>=m.matmul_t_a(&m)+Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| if itr==jtr { 1.0 } else { 0.0 });
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|[itr]| (itr as f64)-2.0);
+    let expected: Tensor<f64,1>=a.lstsq(&b).unwrap();
+
+    let mut residuals: Vec<f64>=Vec::new();
+    let res: CgResult<f64>=Tensor::<f64,1>::conjugate_gradient_with_callback(|x| iterative_test_matvec(&a,x),&b,None,1e-12,100,
+      |itr,norm| { assert!(itr==residuals.len()+1); residuals.push(norm); }).unwrap();
+    assert!(res.converged && res.residual_norm<=1e-12*b.dot(&b).sqrt());
+    assert!(res.iterations==residuals.len() && residuals.last()==Some(&res.residual_norm));
+    assert!(res.x.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-9));
+
+    // Starting from the solution needs no iterations.
+    let res: CgResult<f64>=Tensor::<f64,1>::conjugate_gradient(|x| iterative_test_matvec(&a,x),&b,Some(&res.x),1e-6,100).unwrap();
+    assert!(res.converged && res.iterations==0);
+  }
+
+  #[rstest(n,max_iterations,case(6,2),case(6,1),case(9,3))]
+  fn iterative_test_conjugate_gradient_not_converged(n: Idx, max_iterations: Idx)
+  {
+    let m: Tensor<f64,2>=Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| ((3*itr+5*jtr)%7) as f64-3.0);
+    let a: Tensor<f64,2>=m.matmul_t_a(&m)+Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| if itr==jtr { 1.0 } else { 0.0 });
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|[itr]| (itr as f64)-2.0);
+
+    let res: CgResult<f64>=Tensor::<f64,1>::conjugate_gradient(|x| iterative_test_matvec(&a,x),&b,None,1e-12,max_iterations).unwrap();
+    assert!(!res.converged && res.iterations==max_iterations && res.residual_norm>0.0);
+  }
+
+  #[rstest(start,len,rhs,case(Some(2),3,2),case(Some(5),3,5),case(None,4,4),case(None,1,1))]
+  fn iterative_test_conjugate_gradient_lengths(start: Option<Idx>, len: Idx, rhs: Idx)
+  {
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|_| 1.0);
+    let start: Option<Tensor<f64,1>>=start.map(|len| Tensor::<f64,1>::new([len]));
+
+    let res: Result<CgResult<f64>,TensorError>=Tensor::<f64,1>::conjugate_gradient(|_| Tensor::<f64,1>::new([len]),&b,start.as_ref(),1e-6,10);
+    assert!(res.err()==Some(TensorError::LengthMismatch{lhs:3,rhs:rhs}));
+  }
+
+  #[rstest(len,case(1),case(3))]
+  fn iterative_test_conjugate_gradient_zero(len: Idx)
+  {
+    // A zero right-hand side is solved by the zero starting vector.
+    let res: CgResult<f64>=Tensor::<f64,1>::conjugate_gradient(|x| x.clone(),&Tensor::<f64,1>::new([len]),None,1e-6,10).unwrap();
+    assert!(res.converged && res.iterations==0 && res.x.iter().all(|&elem| elem==0.0));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn iterative_test_steps(layout: Layout)
+  {
+    let elems: [[f64;4];4]=[[10.0,-1.0,2.0,0.0],[-1.0,11.0,-1.0,3.0],[2.0,-1.0,10.0,-1.0],[0.0,3.0,-1.0,8.0]];
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| elems[itr][jtr]).into_layout(layout);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| [6.0,25.0,-11.0,15.0][itr]);
+    let x: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+
+    // From zero, a Jacobi sweep divides `b` by the diagonal, and Gauss-Seidel uses the first update.
+    let jacobi: Tensor<f64,1>=a.jacobi_step(&b,&x).unwrap();
+    assert!(jacobi.iter().zip([0.6,25.0/11.0,-1.1,1.875].iter()).all(|(x,y)| (x-y).abs()<1e-15));
+    let mut gauss_seidel: Tensor<f64,1>=x.clone();
+    a.gauss_seidel_step(&b,&mut gauss_seidel).unwrap();
+    assert!(gauss_seidel[0]==0.6 && (gauss_seidel[1]-(25.0+0.6)/11.0).abs()<1e-15);
+  }
+
+  #[rstest(method,max_iterations,converged,
+    case(Stationary::Jacobi,100,true),
+    case(Stationary::GaussSeidel,100,true),
+    case(Stationary::Jacobi,3,false),
+    case(Stationary::GaussSeidel,2,false),
+  )]
+  fn iterative_test_solve_stationary(method: Stationary, max_iterations: Idx, converged: bool)
+  {
+    let elems: [[f64;4];4]=[[10.0,-1.0,2.0,0.0],[-1.0,11.0,-1.0,3.0],[2.0,-1.0,10.0,-1.0],[0.0,3.0,-1.0,8.0]];
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| elems[itr][jtr]);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| [6.0,25.0,-11.0,15.0][itr]);
+    let expected: Tensor<f64,1>=a.lstsq(&b).unwrap();
+
+    let res: CgResult<f64>=a.solve_stationary(&b,None,method,1e-12,max_iterations).unwrap();
+    assert!(res.converged==converged);
+    if converged
+    {
+      assert!(res.residual_norm<=1e-12*b.dot(&b).sqrt());
+      assert!(res.x.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-10));
+      // Gauss-Seidel uses every update as soon as it is made, and so converges in fewer sweeps.
+      let other: Stationary=if method==Stationary::Jacobi { Stationary::GaussSeidel } else { Stationary::Jacobi };
+      let other: CgResult<f64>=a.solve_stationary(&b,None,other,1e-12,max_iterations).unwrap();
+      assert!((method==Stationary::GaussSeidel)==(res.iterations<other.iterations));
+    }
+    else
+    {
+      assert!(res.iterations==max_iterations);
+    }
+  }
+
+  #[rstest(len,case(3),case(5))]
+  fn iterative_test_stationary_lengths(len: Idx)
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| if itr==jtr { 4.0 } else { 1.0 });
+    let b: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+    let mut x: Tensor<f64,1>=Tensor::<f64,1>::new([len]);
+
+    assert!(a.jacobi_step(&b,&x).err()==Some(TensorError::LengthMismatch{lhs:4,rhs:len}));
+    assert!(a.gauss_seidel_step(&b,&mut x).err()==Some(TensorError::LengthMismatch{lhs:4,rhs:len}));
+    assert!(a.solve_stationary(&x,None,Stationary::Jacobi,1e-6,10).err()==Some(TensorError::LengthMismatch{lhs:4,rhs:len}));
+  }
+
+  #[rstest(method,row,case(Stationary::GaussSeidel,2),case(Stationary::Jacobi,2),case(Stationary::Jacobi,0),case(Stationary::GaussSeidel,3))]
+  fn iterative_test_stationary_zero_diagonal(method: Stationary, row: Idx)
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| if itr!=jtr { 1.0 } else if itr==row { 0.0 } else { 4.0 });
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|_| 1.0);
+
+    let res: Result<CgResult<f64>,TensorError>=a.solve_stationary(&b,None,method,1e-6,10);
+    assert!(res.err()==Some(TensorError::ZeroDiagonal{row:row}));
+  }
+
+  #[rstest(dim,case([3,4]),case([4,3]),case([1,4]))]
+  fn iterative_test_stationary_not_square(dim: Dim<2>)
+  {
+    let b: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+    let res: Result<Tensor<f64,1>,TensorError>=Tensor::<f64,2>::new(dim).jacobi_step(&b,&b);
+    assert!(res.err()==Some(TensorError::NotSquare{rows:dim[0],cols:dim[1]}));
+  }
+
+  /// Neither matrix is diagonally dominant, so the iterates grow until the residual overflows.
+  #[rstest(elems,method,symmetric,suffix,
+    case([[1.0,3.0],[3.0,1.0]],Stationary::GaussSeidel,true,"not diagonally dominant and symmetric."),
+    case([[1.0,3.0],[3.0,1.0]],Stationary::Jacobi,true,"not diagonally dominant and symmetric."),
+    case([[1.0,3.0],[2.0,1.0]],Stationary::Jacobi,false,"not diagonally dominant and not symmetric."),
+  )]
+  fn iterative_test_stationary_diverged(elems: [[f64;2];2], method: Stationary, symmetric: bool, suffix: &str)
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| elems[itr][jtr]);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([2],|_| 1.0);
+
+    match a.solve_stationary(&b,None,method,1e-12,10000).err()
+    {
+      Some(err @ TensorError::Diverged{dominant:false,..}) =>
+      {
+        assert!(matches!(err,TensorError::Diverged{symmetric:s,..} if s==symmetric));
+        assert!(err.to_string().ends_with(suffix));
+      },
+      err => panic!("Expected a diverged iteration, got {:?}.",err),
+    }
+  }
+}
@@ -0,0 +1,395 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt::Write;
+
+use super::Dim;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+
+/// Element types that can be written to and read from JSON numbers.
+pub(super) trait JsonElement: Sized
+{
+  fn write_json(&self, out: &mut String);
+  /// Element given by the JSON number or `null` in `token`, if it can be represented.
+  fn parse_json(token: &str) -> Option<Self>;
+}
+
+macro_rules! impl_json_float {
+  ($($type:ty),*) => {
+    $(
+      impl JsonElement for $type
+      {
+        // The shortest representation that reads back exactly, which is valid JSON for finite elements.
+        fn write_json(&self, out: &mut String)
+        {
+          if self.is_finite() { write!(out,"{:?}",self).unwrap(); } else { out.push_str("null"); }
+        }
+
+        fn parse_json(token: &str) -> Option<$type>
+        {
+          if token=="null" { Some(<$type>::NAN) } else { token.parse().ok() }
+        }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_json_int {
+  ($($type:ty),*) => {
+    $(
+      impl JsonElement for $type
+      {
+        fn write_json(&self, out: &mut String)
+        {
+          write!(out,"{}",self).unwrap();
+        }
+
+        fn parse_json(token: &str) -> Option<$type>
+        {
+          token.parse().ok()
+        }
+      }
+    )*
+  };
+}
+
+impl_json_float!(f32,f64);
+impl_json_int!(i8,i16,i32,i64,u8,u16,u32,u64);
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: JsonElement + Clone
+{
+  /// The elements as JSON arrays nested `N` deep in row-major order, whatever the layout, without any
+  /// whitespace, such as `[[1.0,2.0],[3.0,4.0]]` for a matrix.
+  ///
+  /// Floats are written in their shortest form that reads back exactly, and as `null` if they are not
+  /// finite, which JSON cannot represent. A tensor of no dimensions is written as its element.
+  pub(super) fn to_json_string(&self) -> String
+  {
+    let mut out: String=String::new();
+    write_json_block(&self.to_layout(Layout::RowMajor).data,&self.dim,&mut out);
+    out
+  }
+
+  /// Tensor of the elements in JSON arrays nested `N` deep, as written by `to_json_string`, whose
+  /// dimensions are the lengths of the arrays. Whitespace between tokens is ignored, and numbers may
+  /// be in scientific notation. For floats, `null` stands for NaN.
+  ///
+  /// As the lengths below an empty array cannot be inferred, they are zero: a tensor of dimensions
+  /// `[0,3]` is read back as one of dimensions `[0,0]`.
+  ///
+  /// Fails on text that is not JSON of nested arrays of numbers, with the line and column of the
+  /// offending character, and on elements that cannot be represented in `T`, values not nested `N`
+  /// deep and arrays of a length other than the first at their depth, with the path of indices to the
+  /// offending value as well.
+  pub(super) fn from_json_str(s: &str) -> Result<Tensor<T,N>,TensorError>
+  {
+    let mut parser: JsonParser<T,N>=JsonParser{text:s,pos:0,dim:[None;N],data:Vec::new(),path:Vec::new()};
+    parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos!=s.len() { return Err(parser.syntax("the end of the text")); }
+
+    let mut dim: Dim<N>=[0;N];
+    dim.iter_mut().zip(parser.dim.iter()).for_each(|(len,parsed)| *len=parsed.unwrap_or(0));
+    Tensor::<T,N>::from_vec(dim,parser.data)
+  }
+}
+
+/// Writes the row-major elements `data` of a block of dimensions `dim` as nested arrays.
+fn write_json_block<T>(data: &[T], dim: &[Idx], out: &mut String)
+where T: JsonElement
+{
+  if dim.is_empty() { return data[0].write_json(out); }
+
+  let stride: Idx=dim[1..].iter().product();
+  out.push('[');
+  for itr in 0..dim[0]
+  {
+    if itr>0 { out.push(','); }
+    write_json_block(&data[itr*stride..(itr+1)*stride],&dim[1..],out);
+  }
+  out.push(']');
+}
+
+/// Parser of the JSON text `text` into a tensor of `N` dimensions, at byte `pos` of the text.
+struct JsonParser<'a,T,const N: Idx>
+{
+  text: &'a str,
+  pos: Idx,
+  /// Lengths of the arrays at every depth, set by the first array at that depth.
+  dim: [Option<Idx>;N],
+  /// Elements in row-major order.
+  data: Vec<T>,
+  /// Indices of the value being parsed in the arrays around it.
+  path: Vec<Idx>,
+}
+
+impl<'a,T,const N: Idx> JsonParser<'a,T,N>
+where T: JsonElement
+{
+  /// Parses the value at `depth` arrays deep, an array above depth `N` and a number at it.
+  fn value(&mut self, depth: Idx) -> Result<(),TensorError>
+  {
+    self.skip_whitespace();
+    match self.peek()
+    {
+      Some(b'[') if depth<N => self.array(depth),
+      Some(b'[') => Err(self.depth()),
+      Some(b'-') | Some(b'0'..=b'9') | Some(b'n') if depth<N => Err(self.depth()),
+      _ if depth<N => Err(self.syntax("'['")),
+      _ => self.number(),
+    }
+  }
+
+  /// Parses the array at `depth` starting at the current byte, whose length must equal that of the
+  /// first array at the same depth.
+  fn array(&mut self, depth: Idx) -> Result<(),TensorError>
+  {
+    let start: Idx=self.pos;
+    self.pos+=1;
+    self.skip_whitespace();
+    let mut len: Idx=0;
+    if self.peek()==Some(b']') { self.pos+=1; }
+    else
+    {
+      loop
+      {
+        self.path.push(len);
+        self.value(depth+1)?;
+        self.path.pop();
+        len+=1;
+
+        self.skip_whitespace();
+        match self.peek()
+        {
+          Some(b',') => self.pos+=1,
+          Some(b']') => { self.pos+=1; break; },
+          _ => return Err(self.syntax("',' or ']'")),
+        }
+      }
+    }
+
+    match self.dim[depth]
+    {
+      None => self.dim[depth]=Some(len),
+      Some(expected) if expected!=len =>
+      {
+        let (line,column): (Idx,Idx)=self.line_column(start);
+        return Err(TensorError::JsonRagged{path:self.path.clone(),len:len,expected:expected,line:line,column:column});
+      },
+      Some(_) => (),
+    }
+    Ok(())
+  }
+
+  /// Parses the number or `null` starting at the current byte as an element.
+  fn number(&mut self) -> Result<(),TensorError>
+  {
+    let start: Idx=self.pos;
+    if self.text[start..].starts_with("null") { self.pos+=4; }
+    else
+    {
+      if self.peek()==Some(b'-') { self.pos+=1; }
+      match self.peek()
+      {
+        Some(b'0') => self.pos+=1,
+        Some(b'1'..=b'9') => { self.digits(); },
+        _ => return Err(self.syntax("a number")),
+      }
+      if self.peek()==Some(b'.')
+      {
+        self.pos+=1;
+        if self.digits()==0 { return Err(self.syntax("a digit")); }
+      }
+      if let Some(b'e') | Some(b'E')=self.peek()
+      {
+        self.pos+=1;
+        if let Some(b'+') | Some(b'-')=self.peek() { self.pos+=1; }
+        if self.digits()==0 { return Err(self.syntax("a digit")); }
+      }
+    }
+
+    match T::parse_json(&self.text[start..self.pos])
+    {
+      Some(elem) => { self.data.push(elem); Ok(()) },
+      None =>
+      {
+        let (line,column): (Idx,Idx)=self.line_column(start);
+        Err(TensorError::JsonElement{path:self.path.clone(),line:line,column:column})
+      },
+    }
+  }
+
+  /// Skips decimal digits, returning how many there were.
+  fn digits(&mut self) -> Idx
+  {
+    let start: Idx=self.pos;
+    while let Some(b'0'..=b'9')=self.peek() { self.pos+=1; }
+    self.pos-start
+  }
+
+  fn skip_whitespace(&mut self)
+  {
+    while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r')=self.peek() { self.pos+=1; }
+  }
+
+  fn peek(&self) -> Option<u8>
+  {
+    self.text.as_bytes().get(self.pos).copied()
+  }
+
+  /// Line and column, both counted from one, of the character at byte `offset`.
+  fn line_column(&self, offset: Idx) -> (Idx,Idx)
+  {
+    let before: &str=&self.text[..offset];
+    let line_start: Idx=before.rfind('\n').map_or(0,|newline| newline+1);
+    (before.matches('\n').count()+1,before[line_start..].chars().count()+1)
+  }
+
+  /// Error for finding something other than `expected` at the current byte.
+  fn syntax(&self, expected: &'static str) -> TensorError
+  {
+    let (line,column): (Idx,Idx)=self.line_column(self.pos);
+    TensorError::JsonSyntax{expected:expected,line:line,column:column}
+  }
+
+  /// Error for the value at the current byte, which is nested more or less deeply than `N`.
+  fn depth(&self) -> TensorError
+  {
+    let (line,column): (Idx,Idx)=self.line_column(self.pos);
+    TensorError::JsonDepth{path:self.path.clone(),rank:N,line:line,column:column}
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod json_tests
+{
+  use super::*;
+  use super::super::error::TensorErrorKind;
+  use super::super::testing::assert_tensor_eq;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  #[rstest(json,expected,
+    case(|| Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr+1) as f64).to_json_string(),"[[1.0,2.0],[3.0,4.0]]"),
+    case(|| Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr+1) as f64).to_layout(Layout::ColMajor).to_json_string(),"[[1.0,2.0],[3.0,4.0]]"),
+    case(|| Tensor::<i32,3>::from_fn([2,1,3],|[itr,_,ktr]| 3*itr as i32-ktr as i32).to_json_string(),"[[[0,-1,-2]],[[3,2,1]]]"),
+    case(|| Tensor::<f32,1>::from_fn([5],|[itr]| [0.1,-0.0,1e-7,3e20,f32::INFINITY][itr]).to_json_string(),"[0.1,-0.0,1e-7,3e20,null]"),
+    case(|| Tensor::<u8,0>::from_fn([],|_| 7).to_json_string(),"7"),
+    case(|| Tensor::<f64,2>::new([2,0]).to_json_string(),"[[],[]]"),
+    case(|| Tensor::<f64,2>::new([0,3]).to_json_string(),"[]"),
+  )]
+  fn json_test_to_json_string(json: fn() -> String, expected: &str)
+  {
+    assert!(json()==expected);
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn json_test_round_trip(layout: Layout)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([3,2,4],|[itr,jtr,ktr]| ((12*itr+4*jtr+ktr) as f64*0.37).sin()*1e-3).to_layout(layout);
+    assert_tensor_eq!(Tensor::<f64,3>::from_json_str(&t.to_json_string()).unwrap(),t.to_layout(Layout::RowMajor));
+    let t: Tensor<i64,2>=Tensor::<i64,2>::from_fn([3,2],|[itr,jtr]| if itr==0 { i64::MIN } else { (itr*jtr) as i64 }).to_layout(layout);
+    assert_tensor_eq!(Tensor::<i64,2>::from_json_str(&t.to_json_string()).unwrap(),t.to_layout(Layout::RowMajor));
+  }
+
+  /// Whitespace may surround any token, and `null` reads as NaN.
+  #[rstest(text,dim,elems,
+    case(" [ [1e3 , -2.5E-1 ],\n\t[0 ,3.0e+0]\r\n]\n",[2,2],&[1000.0,-0.25,0.0,3.0]),
+    case("[[],[]]",[2,0],&[]),
+    case("[]",[0,0],&[]),
+    case("[[null,-0]]",[1,2],&[f64::NAN,0.0]),
+  )]
+  fn json_test_from_json_str(text: &str, dim: Dim<2>, elems: &[f64])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_json_str(text).unwrap();
+    assert!(t.dim==dim && t.iter().zip(elems.iter()).all(|(&elem,&expected)| elem==expected || elem.is_nan() && expected.is_nan()));
+  }
+
+  #[test]
+  fn json_test_from_json_str_int()
+  {
+    assert_tensor_eq!(Tensor::<i32,1>::from_json_str("[-0,12,-7]").unwrap(),Tensor::<i32,1>::from_fn([3],|[itr]| [0,12,-7][itr]));
+    assert!(Tensor::<u8,0>::from_json_str(" 7 ").unwrap()[[]]==7);
+  }
+
+  #[rstest(parse,expected,
+    case(|| Tensor::<f64,2>::from_json_str("[[1,2],\n [3]]").err(),TensorError::JsonRagged{path:vec![1],len:1,expected:2,line:2,column:2}),
+    case(|| Tensor::<i32,3>::from_json_str("[[[1],[2]],[[3],[4,5]]]").err(),TensorError::JsonRagged{path:vec![1,1],len:2,expected:1,line:1,column:17}),
+    case(|| Tensor::<i32,2>::from_json_str("[[],[1]]").err(),TensorError::JsonRagged{path:vec![1],len:1,expected:0,line:1,column:5}),
+  )]
+  fn json_test_ragged(parse: fn() -> Option<TensorError>, expected: TensorError)
+  {
+    assert!(parse()==Some(expected));
+  }
+
+  #[rstest(parse,expected,
+    case(|| Tensor::<f64,2>::from_json_str("[[1,2],3]").err(),TensorError::JsonDepth{path:vec![1],rank:2,line:1,column:8}),
+    case(|| Tensor::<f64,2>::from_json_str("[[1,[2]]]").err(),TensorError::JsonDepth{path:vec![0,1],rank:2,line:1,column:5}),
+    case(|| Tensor::<f64,1>::from_json_str("4").err(),TensorError::JsonDepth{path:vec![],rank:1,line:1,column:1}),
+    case(|| Tensor::<f64,0>::from_json_str("[4]").err(),TensorError::JsonDepth{path:vec![],rank:0,line:1,column:1}),
+  )]
+  fn json_test_depth(parse: fn() -> Option<TensorError>, expected: TensorError)
+  {
+    assert!(parse()==Some(expected));
+  }
+
+  /// The last case counts columns in characters rather than bytes.
+  #[rstest(text,expected,line,column,
+    case("[1 2]","',' or ']'",1,4),
+    case("[1,2","',' or ']'",1,5),
+    case("[1,]","a number",1,4),
+    case("[01]","',' or ']'",1,3),
+    case("[1.]","a digit",1,4),
+    case("[1e+]","a digit",1,5),
+    case("[+1]","a number",1,2),
+    case("[1]\n]","the end of the text",2,1),
+    case("","'['",1,1),
+    case("[\n  1,\u{e9}]","a number",2,5),
+  )]
+  fn json_test_syntax(text: &str, expected: &'static str, line: Idx, column: Idx)
+  {
+    assert!(Tensor::<f64,1>::from_json_str(text).err()==Some(TensorError::JsonSyntax{expected:expected,line:line,column:column}));
+  }
+
+  #[test]
+  fn json_test_syntax_nested()
+  {
+    assert!(Tensor::<f64,2>::from_json_str("[{}]").err()==Some(TensorError::JsonSyntax{expected:"'['",line:1,column:2}));
+  }
+
+  #[rstest(parse,expected,
+    case(|| Tensor::<i32,2>::from_json_str("[[1,2],[3,1.5]]").err(),TensorError::JsonElement{path:vec![1,1],line:1,column:11}),
+    case(|| Tensor::<u8,1>::from_json_str("[255,256]").err(),TensorError::JsonElement{path:vec![1],line:1,column:6}),
+    case(|| Tensor::<i8,1>::from_json_str("[null]").err(),TensorError::JsonElement{path:vec![0],line:1,column:2}),
+  )]
+  fn json_test_element(parse: fn() -> Option<TensorError>, expected: TensorError)
+  {
+    assert!(parse()==Some(expected));
+  }
+
+  #[rstest(parse,kind,message,
+    case(|| Tensor::<f64,2>::from_json_str("[[1,2],\n [3]]").unwrap_err(),TensorErrorKind::Shape,
+      "The array at [1] (line 2, column 2) holds 1 elements rather than the 2 of the first array at its depth."),
+    case(|| Tensor::<f64,2>::from_json_str("[[1,2],3]").unwrap_err(),TensorErrorKind::Shape,
+      "The value at [1] (line 1, column 8) is not nested 2 arrays deep, the rank of the tensor."),
+    case(|| Tensor::<f64,1>::from_json_str("[\n  1,\u{e9}]").unwrap_err(),TensorErrorKind::Format,
+      "Expected a number at line 2, column 5 of the JSON text."),
+    case(|| Tensor::<i32,2>::from_json_str("[[1,2],[3,1.5]]").unwrap_err(),TensorErrorKind::Dtype,
+      "The element at [1, 1] (line 1, column 11) cannot be represented in the element type."),
+  )]
+  fn json_test_error_message(parse: fn() -> TensorError, kind: TensorErrorKind, message: &str)
+  {
+    let err: TensorError=parse();
+    assert!(err.kind()==kind && format!("{}",err)==message);
+  }
+}
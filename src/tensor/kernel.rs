@@ -0,0 +1,266 @@
+use core::ops::AddAssign;
+use core::ops::MulAssign;
+
+use core::convert::TryInto;
+
+/// Number of elements processed per iteration by the float kernels.
+const LANES: usize=8;
+
+/// Slice-level inner loops of the element-wise operations.
+///
+/// The default methods are plain scalar loops. The float implementations override them with loops
+/// over `[T;LANES]` chunks, which the optimiser reliably turns into vector instructions, followed by
+/// a scalar loop over the tail.
+pub(super) trait Kernel: Sized + Clone + AddAssign + MulAssign
+{
+  fn add_assign_slice(lhs: &mut [Self], rhs: &[Self])
+  {
+    add_assign_naive(lhs,rhs);
+  }
+
+  fn mul_assign_slice(lhs: &mut [Self], rhs: &[Self])
+  {
+    mul_assign_naive(lhs,rhs);
+  }
+
+  /// Replaces every element of `lhs` by itself times the element of `a` plus the element of `b`.
+  fn mul_add_slice(lhs: &mut [Self], a: &[Self], b: &[Self])
+  {
+    for ((this,a),b) in lhs.iter_mut().zip(a.iter()).zip(b.iter())
+    {
+      *this*=a.clone();
+      *this+=b.clone();
+    }
+  }
+
+  /// Position and value of the first element of `elems` that is infinite or NaN. Elements of types
+  /// without such values never are, so the default does not look at them.
+  fn first_non_finite(_elems: &[Self]) -> Option<(usize,f64)>
+  {
+    None
+  }
+
+  /// Position of the first NaN element of `elems`.
+  fn first_nan(_elems: &[Self]) -> Option<usize>
+  {
+    None
+  }
+
+  /// Sum of the elements of `elems`, which is zero if there are none.
+  fn sum_slice(elems: &[Self]) -> Self
+  where Self: Default + core::ops::Add<Output=Self>
+  {
+    elems.iter().fold(Self::default(),|sum,elem| sum+elem.clone())
+  }
+
+  /// Adds `alpha` times every element of `x` to the element of `y`.
+  fn axpy_slice(y: &mut [Self], alpha: &Self, x: &[Self])
+  {
+    for (this,other) in y.iter_mut().zip(x.iter())
+    {
+      let mut prod: Self=alpha.clone();
+      prod*=other.clone();
+      *this+=prod;
+    }
+  }
+}
+
+fn add_assign_naive<T>(lhs: &mut [T], rhs: &[T])
+where T: Clone + AddAssign
+{
+  for (this,other) in lhs.iter_mut().zip(rhs.iter())
+  {
+    *this+=other.clone();
+  }
+}
+
+fn mul_assign_naive<T>(lhs: &mut [T], rhs: &[T])
+where T: Clone + MulAssign
+{
+  for (this,other) in lhs.iter_mut().zip(rhs.iter())
+  {
+    *this*=other.clone();
+  }
+}
+
+macro_rules! impl_kernel_float {
+  ($type:ty) => {
+    impl Kernel for $type
+    {
+      fn add_assign_slice(lhs: &mut [$type], rhs: &[$type])
+      {
+        assert!(lhs.len()==rhs.len());
+
+        let mut lhs_chunks=lhs.chunks_exact_mut(LANES);
+        let mut rhs_chunks=rhs.chunks_exact(LANES);
+        for (this,other) in (&mut lhs_chunks).zip(&mut rhs_chunks)
+        {
+          let this: &mut [$type;LANES]=this.try_into().unwrap();
+          let other: &[$type;LANES]=other.try_into().unwrap();
+          for itr in 0..LANES
+          {
+            this[itr]+=other[itr];
+          }
+        }
+        add_assign_naive(lhs_chunks.into_remainder(),rhs_chunks.remainder());
+      }
+
+      fn mul_assign_slice(lhs: &mut [$type], rhs: &[$type])
+      {
+        assert!(lhs.len()==rhs.len());
+
+        let mut lhs_chunks=lhs.chunks_exact_mut(LANES);
+        let mut rhs_chunks=rhs.chunks_exact(LANES);
+        for (this,other) in (&mut lhs_chunks).zip(&mut rhs_chunks)
+        {
+          let this: &mut [$type;LANES]=this.try_into().unwrap();
+          let other: &[$type;LANES]=other.try_into().unwrap();
+          for itr in 0..LANES
+          {
+            this[itr]*=other[itr];
+          }
+        }
+        mul_assign_naive(lhs_chunks.into_remainder(),rhs_chunks.remainder());
+      }
+
+      fn first_non_finite(elems: &[$type]) -> Option<(usize,f64)>
+      {
+        elems.iter().position(|elem| !elem.is_finite()).map(|pos| (pos,elems[pos] as f64))
+      }
+
+      fn first_nan(elems: &[$type]) -> Option<usize>
+      {
+        elems.iter().position(|elem| elem.is_nan())
+      }
+
+      /// Rounds only once per element. Without `std` this falls back to the default multiply then add.
+      #[cfg(feature="std")]
+      fn mul_add_slice(lhs: &mut [$type], a: &[$type], b: &[$type])
+      {
+        for ((this,a),b) in lhs.iter_mut().zip(a.iter()).zip(b.iter())
+        {
+          *this=this.mul_add(*a,*b);
+        }
+      }
+    }
+  };
+}
+
+impl_kernel_float!(f32);
+impl_kernel_float!(f64);
+
+impl Kernel for i8 {}
+impl Kernel for i16 {}
+impl Kernel for i32 {}
+impl Kernel for i64 {}
+impl Kernel for u8 {}
+impl Kernel for u16 {}
+impl Kernel for u32 {}
+impl Kernel for u64 {}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod kernel_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn kernel_test_data(len: usize, offset: f64) -> Vec<f64>
+  {
+    (0..len).map(|itr| itr as f64*0.5+offset).collect()
+  }
+
+  #[rstest(len,case(0),case(1),case(7),case(8),case(9),case(16),case(21))]
+  fn kernel_test_add_assign_slice(len: usize)
+  {
+    let mut lhs: Vec<f64>=kernel_test_data(len,1.25);
+    let rhs: Vec<f64>=kernel_test_data(len,-3.5);
+    let mut expected: Vec<f64>=lhs.clone();
+    add_assign_naive(&mut expected,&rhs);
+
+    f64::add_assign_slice(&mut lhs,&rhs);
+    assert!(lhs==expected);
+  }
+
+  #[rstest(len,case(0),case(1),case(7),case(8),case(9),case(16),case(21))]
+  fn kernel_test_mul_assign_slice(len: usize)
+  {
+    let mut lhs: Vec<f64>=kernel_test_data(len,1.25);
+    let rhs: Vec<f64>=kernel_test_data(len,-3.5);
+    let mut expected: Vec<f64>=lhs.clone();
+    mul_assign_naive(&mut expected,&rhs);
+
+    f64::mul_assign_slice(&mut lhs,&rhs);
+    assert!(lhs==expected);
+  }
+
+  #[rstest(len,case(3),case(13))]
+  fn kernel_test_f32_tail(len: usize)
+  {
+    let mut lhs: Vec<f32>=(0..len).map(|itr| itr as f32).collect();
+    let rhs: Vec<f32>=vec![2f32;len];
+
+    f32::add_assign_slice(&mut lhs,&rhs);
+    f32::mul_assign_slice(&mut lhs,&rhs);
+    for (itr,elem) in lhs.iter().enumerate()
+    {
+      assert!(*elem==(itr as f32+2f32)*2f32);
+    }
+  }
+}
+
+#[cfg(all(test,not(debug_assertions)))]
+mod kernel_benches
+{
+  extern crate test;
+
+  use super::*;
+  use super::super::Tensor;
+  use test::Bencher;
+
+  const BENCH_LEN: usize=10_000_000;
+
+  #[bench]
+  fn kernel_bench_add_naive(b: &mut Bencher)
+  {
+    let mut lhs: Vec<f64>=vec![1f64;BENCH_LEN];
+    let rhs: Vec<f64>=vec![2f64;BENCH_LEN];
+    b.iter(|| add_assign_naive(&mut lhs,&rhs));
+  }
+
+  #[bench]
+  fn kernel_bench_add_chunked(b: &mut Bencher)
+  {
+    let mut lhs: Vec<f64>=vec![1f64;BENCH_LEN];
+    let rhs: Vec<f64>=vec![2f64;BENCH_LEN];
+    b.iter(|| f64::add_assign_slice(&mut lhs,&rhs));
+  }
+
+  #[bench]
+  fn kernel_bench_mul_naive(b: &mut Bencher)
+  {
+    let mut lhs: Vec<f64>=vec![1f64;BENCH_LEN];
+    let rhs: Vec<f64>=vec![1f64;BENCH_LEN];
+    b.iter(|| mul_assign_naive(&mut lhs,&rhs));
+  }
+
+  #[bench]
+  fn kernel_bench_mul_chunked(b: &mut Bencher)
+  {
+    let mut lhs: Vec<f64>=vec![1f64;BENCH_LEN];
+    let rhs: Vec<f64>=vec![1f64;BENCH_LEN];
+    b.iter(|| f64::mul_assign_slice(&mut lhs,&rhs));
+  }
+
+  #[bench]
+  fn kernel_bench_add_tensor(b: &mut Bencher)
+  {
+    let mut t1: Tensor<f64,1>=Tensor::<f64,1>::new([BENCH_LEN]);
+    let t2: Tensor<f64,1>=Tensor::<f64,1>::new([BENCH_LEN]);
+    b.iter(|| t1+=&t2);
+  }
+}
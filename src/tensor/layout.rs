@@ -0,0 +1,215 @@
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Indices;
+use super::Scalar;
+use super::Tensor;
+
+/// Order in which the elements of a tensor are stored.
+///
+/// The layout only changes where an element lives in memory, never the element at a multi-index, so
+/// every operation gives the same logical result for either layout.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub(super) enum Layout
+{
+  /// The last axis varies fastest, as in C.
+  RowMajor,
+  /// The first axis varies fastest, as in Fortran.
+  ColMajor,
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Creates a tensor of default elements as `new` does, stored in column-major order.
+  pub(super) fn new_col_major(dim: Dim<N>) -> Tensor<T,N>
+  {
+    let mut t: Tensor<T,N>=Tensor::<T,N>::new(dim);
+    t.layout=Layout::ColMajor;
+    t
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Clone
+{
+  /// Tensor of the same elements stored in `layout`.
+  ///
+  /// If `self` is already stored in `layout` the element buffer is shared rather than copied.
+  pub(super) fn to_layout(&self, layout: Layout) -> Tensor<T,N>
+  {
+    if self.layout==layout { return self.clone(); }
+
+    let mut t: Tensor<T,N>=Tensor::<T,N>::new_uninit(self.dim)
+      .init(layout_indices(self.dim,layout).map(|ind| self[ind].clone()));
+    t.layout=layout;
+    t.names=self.names;
+    t
+  }
+
+  /// Consuming variant of `to_layout`, which reuses the element buffer if `self` is already stored in
+  /// `layout`.
+  pub(super) fn into_layout(self, layout: Layout) -> Tensor<T,N>
+  {
+    if self.layout==layout { self } else { self.to_layout(layout) }
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+{
+  /// Order in which the elements are stored.
+  pub(super) fn layout(&self) -> Layout
+  {
+    self.layout
+  }
+
+  /// Position in the element buffer of the element at `ind`, panicking if `ind` is out of bounds.
+  pub(super) fn offset(&self, ind: Dim<N>) -> Idx
+  {
+    let offset: Idx=self.dim.checked_index(ind);
+    match self.layout
+    {
+      Layout::RowMajor => offset,
+      Layout::ColMajor => reversed(self.dim).index(reversed(ind)),
+    }
+  }
+
+  /// Multi-index of the element at position `offset` in the element buffer, the inverse of `offset`.
+  pub(super) fn unravel_offset(&self, offset: Idx) -> Dim<N>
+  {
+    match self.layout
+    {
+      Layout::RowMajor => self.dim.unravel(offset),
+      Layout::ColMajor => reversed(reversed(self.dim).unravel(offset)),
+    }
+  }
+}
+
+/// Iterator over all indices of a tensor with dimensions `dim` in the order its elements are stored
+/// in `layout`.
+pub(super) fn layout_indices<const N: Idx>(dim: Dim<N>, layout: Layout) -> impl Iterator<Item=Dim<N>>
+{
+  let storage_dim: Dim<N>=match layout
+  {
+    Layout::RowMajor => dim,
+    Layout::ColMajor => reversed(dim),
+  };
+  Indices::new(storage_dim).map(move |ind| match layout
+  {
+    Layout::RowMajor => ind,
+    Layout::ColMajor => reversed(ind),
+  })
+}
+
+/// Strides of the elements of a tensor with dimensions `dim` stored in `layout`.
+pub(super) fn strides<const N: Idx>(dim: Dim<N>, layout: Layout) -> [isize;N]
+{
+  let mut res: [isize;N]=[0;N];
+  let mut stride: isize=1;
+  let mut set=|axis: Idx| {
+    res[axis]=stride;
+    stride*=dim[axis] as isize;
+  };
+  match layout
+  {
+    Layout::RowMajor => (0..N).rev().for_each(&mut set),
+    Layout::ColMajor => (0..N).for_each(&mut set),
+  }
+  res
+}
+
+/// `dim` with its axes in reverse order.
+fn reversed<const N: Idx>(mut dim: Dim<N>) -> Dim<N>
+{
+  dim.reverse();
+  dim
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod layout_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use super::super::storage::INLINE_LEN;
+
+  use alloc::format;
+
+  #[rstest(dim,case([2,3]),case([3,1]),case([1,5]),case([0,2]))]
+  fn layout_test_to_layout(dim: Dim<2>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64);
+    let col: Tensor<f64,2>=t.to_layout(Layout::ColMajor);
+
+    // The elements of a column-major tensor are stored a column at a time.
+    assert!(t.layout()==Layout::RowMajor && col.layout()==Layout::ColMajor);
+    assert!(col.iter().copied().eq((0..dim[1]).flat_map(|jtr| (0..dim[0]).map(move |itr| (dim[1]*itr+jtr) as f64))));
+    for ind in Indices::new(dim)
+    {
+      assert!(col[ind]==t[ind]);
+      assert!(col.offset(ind)==ind[0]+dim[0]*ind[1]);
+      assert!(col.unravel_offset(col.offset(ind))==ind);
+    }
+
+    let row: Tensor<f64,2>=col.to_layout(Layout::RowMajor);
+    assert!(row.iter().eq(t.iter()));
+    assert!(t.to_layout(Layout::RowMajor).shares_data(&t)==(t.len()>INLINE_LEN));
+  }
+
+  #[rstest(ind,offset,case([1,0,0],1),case([0,1,0],2),case([0,0,1],6),case([1,2,3],23))]
+  fn layout_test_new_col_major(ind: Dim<3>, offset: Idx)
+  {
+    let mut t: Tensor<i32,3>=Tensor::<i32,3>::new_col_major([2,3,4]);
+    t[ind]=1;
+
+    assert!(t.layout()==Layout::ColMajor);
+    assert!(t.iter().position(|&elem| elem==1)==Some(offset));
+  }
+
+  #[rstest(a_layout,b_layout,
+    case(Layout::RowMajor,Layout::RowMajor),
+    case(Layout::RowMajor,Layout::ColMajor),
+    case(Layout::ColMajor,Layout::RowMajor),
+    case(Layout::ColMajor,Layout::ColMajor),
+  )]
+  fn layout_test_matmul(a_layout: Layout, b_layout: Layout)
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (itr as f64)-0.5*(jtr as f64));
+    let b: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,2],|[itr,jtr]| (2*itr+jtr) as f64+1.0);
+    let expected: Tensor<f64,2>=a.matmul(&b);
+    let (a,b): (Tensor<f64,2>,Tensor<f64,2>)=(a.to_layout(a_layout),b.to_layout(b_layout));
+
+    assert!(a.matmul(&b).to_layout(Layout::RowMajor).iter().eq(expected.iter()));
+    assert!(b.matmul_t_a(&a.transpose()).transpose().to_layout(Layout::RowMajor).iter().eq(expected.iter()));
+
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new_col_major([3,2]);
+    a.gemm(&b,false,false,1.0,0.0,&mut out);
+    assert!(Indices::new([3,2]).all(|ind| out[ind]==expected[ind]));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn layout_test_mixed_ops(layout: Layout)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as f64);
+    let laid_out: Tensor<f64,2>=t.to_layout(layout);
+
+    // Results take the layout of the left operand.
+    let sum: Tensor<f64,2>=&laid_out+&t;
+    assert!(sum.layout()==layout);
+    assert!(sum.to_layout(Layout::RowMajor).iter().copied().eq([0.0,2.0,4.0,6.0,8.0,10.0].iter().copied()));
+    assert!(format!("{}",laid_out)==format!("{}",t));
+    assert!(laid_out.clone().flatten().iter().eq(t.iter()));
+    assert!(laid_out.crop([0,1],[2,2]).unwrap().to_layout(Layout::RowMajor).iter().eq(t.crop([0,1],[2,2]).unwrap().iter()));
+
+    let row: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64);
+    let (mut lhs,mut rhs): (Tensor<f64,2>,Tensor<f64,2>)=(t.clone(),laid_out.clone());
+    lhs.row_add_assign(1,&row);
+    rhs.row_add_assign(1,&row);
+    assert!(rhs.layout()==layout);
+    assert!(rhs.to_layout(Layout::RowMajor).iter().eq(lhs.iter()));
+  }
+}
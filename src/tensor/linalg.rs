@@ -0,0 +1,666 @@
+use alloc::vec::Vec;
+
+use super::CastFrom;
+use super::Idx;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+
+#[cfg(feature="blas")]
+use cblas_sys::CBLAS_LAYOUT;
+#[cfg(feature="blas")]
+use cblas_sys::CBLAS_TRANSPOSE;
+#[cfg(feature="blas")]
+use std::os::raw::c_int;
+
+/// Products needing at least this many multiply-adds go through the blocked kernel.
+const BLOCKED_MIN_WORK: usize=64*64*64;
+
+/// Rows of the left matrix packed per block.
+const BLOCK_M: usize=64;
+/// Length of the shared dimension packed per block.
+const BLOCK_K: usize=128;
+/// Columns of the right matrix packed per block.
+const BLOCK_N: usize=256;
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// Matrix product of `self` and `rhs`.
+  ///
+  /// Small products use a straightforward triple loop. Larger products copy tiles of both operands
+  /// into contiguous buffers sized to stay in cache and multiply those instead, which changes the
+  /// order in which the partial products are summed but not the result beyond rounding.
+  pub(super) fn matmul(&self, rhs: &Tensor<T,2>) -> Tensor<T,2>
+  {
+    self.matmul_op(rhs,false,false)
+  }
+
+  /// Matrix product of the transpose of `self` and `rhs`, without forming the transpose.
+  pub(super) fn matmul_t_a(&self, rhs: &Tensor<T,2>) -> Tensor<T,2>
+  {
+    self.matmul_op(rhs,true,false)
+  }
+
+  /// Matrix product of `self` and the transpose of `rhs`, without forming the transpose.
+  pub(super) fn matmul_t_b(&self, rhs: &Tensor<T,2>) -> Tensor<T,2>
+  {
+    self.matmul_op(rhs,false,true)
+  }
+
+  /// General matrix product `out = alpha*op(self)*op(rhs) + beta*out`, where `op` transposes its
+  /// argument if the corresponding flag is set.
+  ///
+  /// As in BLAS, `out` is not read when `beta` is zero, so it may contain anything on entry.
+  pub(super) fn gemm(&self, rhs: &Tensor<T,2>, trans_a: bool, trans_b: bool, alpha: T, beta: T, out: &mut Tensor<T,2>)
+  {
+    let a: MatrixRef<T>=MatrixRef::new(self,trans_a);
+    let b: MatrixRef<T>=MatrixRef::new(rhs,trans_b);
+    check_product_dim(&a,&b);
+    if out.dim!=[a.op_rows(),b.op_cols()]
+    {
+      panic!("The output matrix must have as many rows as the left matrix and as many columns as the right matrix.")
+    }
+
+    if beta==T::default()
+    {
+      out.data.iter_mut().for_each(|elem| *elem=T::default());
+    }
+    else
+    {
+      *out*=beta;
+    }
+    // A column-major `out` is stored as its transpose in row-major order, which is the product of the
+    // transposed operands in reverse order.
+    match out.layout
+    {
+      Layout::RowMajor => T::product(&a,&b,Some(&alpha),&mut out.data),
+      Layout::ColMajor => T::product(&b.transposed(),&a.transposed(),Some(&alpha),&mut out.data),
+    }
+  }
+
+  /// Transpose of `self`.
+  pub(super) fn transpose(&self) -> Tensor<T,2>
+  {
+    self.permute([1,0])
+  }
+
+  fn matmul_op(&self, rhs: &Tensor<T,2>, trans_a: bool, trans_b: bool) -> Tensor<T,2>
+  {
+    let a: MatrixRef<T>=MatrixRef::new(self,trans_a);
+    let b: MatrixRef<T>=MatrixRef::new(rhs,trans_b);
+    check_product_dim(&a,&b);
+
+    let mut t: Tensor<T,2>=Tensor::<T,2>::new([a.op_rows(),b.op_cols()]);
+    T::product(&a,&b,None,&mut t.data);
+    t
+  }
+}
+
+impl<T> Tensor<T,1>
+where T: Scalar
+{
+  /// Dot product of `self` and `rhs`.
+  pub(super) fn dot(&self, rhs: &Tensor<T,1>) -> T
+  {
+    if self.dim!=rhs.dim { panic!("Two vectors must be of the same length to take their dot product.") }
+
+    T::dot(&self.data,&rhs.data)
+  }
+
+  /// Dot product of `self` and `rhs` with every product formed and summed in the wider type `A`, so
+  /// rounding to the element type happens at most once, when the caller converts the result back.
+  pub(super) fn dot_acc<A>(&self, rhs: &Tensor<T,1>) -> A
+  where A: Scalar + CastFrom<T>
+  {
+    if self.dim!=rhs.dim { panic!("Two vectors must be of the same length to take their dot product.") }
+
+    self.data.iter()
+      .zip(rhs.data.iter())
+      .fold(A::default(),|sum,(x,y)| sum+A::cast_from(x.clone())*A::cast_from(y.clone()))
+  }
+}
+
+impl Tensor<f32,1>
+{
+  /// Dot product of `self` and `rhs` accumulated in `f64`.
+  pub(super) fn dot_f64(&self, rhs: &Tensor<f32,1>) -> f64
+  {
+    self.dot_acc(rhs)
+  }
+}
+
+/// Per element type entry points of the matrix kernels.
+///
+/// The default methods run the native kernels. With the `blas` feature enabled, `f32` and `f64`
+/// override them to call the corresponding CBLAS routines on the row-major data instead, which
+/// requires a BLAS implementation to be linked in, e.g. through `blas-src`.
+pub(super) trait MatrixKernel: Sized
+{
+  /// Adds `alpha*a*b` to the row-major matrix `c`, or `a*b` if no `alpha` is given.
+  fn product(a: &MatrixRef<Self>, b: &MatrixRef<Self>, alpha: Option<&Self>, c: &mut [Self])
+  where Self: Scalar
+  {
+    product_native(a,b,alpha,c);
+  }
+
+  /// Dot product of two slices of equal length.
+  fn dot(x: &[Self], y: &[Self]) -> Self
+  where Self: Scalar
+  {
+    x.iter().zip(y.iter()).fold(Self::default(),|sum,(x,y)| sum+x.clone()*y.clone())
+  }
+}
+
+#[cfg(not(feature="blas"))]
+impl MatrixKernel for f32 {}
+#[cfg(not(feature="blas"))]
+impl MatrixKernel for f64 {}
+
+#[cfg(feature="blas")]
+macro_rules! impl_matrix_kernel_blas {
+  ($type:ty,$gemm:path,$dot:path) => {
+    impl MatrixKernel for $type
+    {
+      fn product(a: &MatrixRef<$type>, b: &MatrixRef<$type>, alpha: Option<&$type>, c: &mut [$type])
+      {
+        let (m,k,n): (Idx,Idx,Idx)=(a.op_rows(),a.op_cols(),b.op_cols());
+        if m==0 || n==0 || k==0 { return; }
+
+        unsafe
+        {
+          $gemm(CBLAS_LAYOUT::CblasRowMajor,a.blas_trans(),b.blas_trans(),m as c_int,n as c_int,k as c_int
+            ,alpha.cloned().unwrap_or(1.0),a.data.as_ptr(),a.cols as c_int,b.data.as_ptr(),b.cols as c_int
+            ,1.0,c.as_mut_ptr(),n as c_int);
+        }
+      }
+
+      fn dot(x: &[$type], y: &[$type]) -> $type
+      {
+        unsafe { $dot(x.len() as c_int,x.as_ptr(),1,y.as_ptr(),1) }
+      }
+    }
+  };
+}
+
+#[cfg(feature="blas")]
+impl_matrix_kernel_blas!(f32,cblas_sys::cblas_sgemm,cblas_sys::cblas_sdot);
+#[cfg(feature="blas")]
+impl_matrix_kernel_blas!(f64,cblas_sys::cblas_dgemm,cblas_sys::cblas_ddot);
+
+impl MatrixKernel for i8 {}
+impl MatrixKernel for i16 {}
+impl MatrixKernel for i32 {}
+impl MatrixKernel for i64 {}
+impl MatrixKernel for u8 {}
+impl MatrixKernel for u16 {}
+impl MatrixKernel for u32 {}
+impl MatrixKernel for u64 {}
+
+/// Row-major matrix operand of a product, read as its transpose if `trans` is set.
+pub(super) struct MatrixRef<'a,T>
+{
+  data: &'a [T],
+  rows: Idx,
+  cols: Idx,
+  trans: bool,
+}
+
+impl<'a,T> MatrixRef<'a,T>
+where T: Scalar
+{
+  /// Operand `t`, or its transpose if `trans` is set. A column-major matrix is read as the transpose of
+  /// the row-major matrix its elements form.
+  fn new(t: &'a Tensor<T,2>, trans: bool) -> MatrixRef<'a,T>
+  {
+    match t.layout
+    {
+      Layout::RowMajor => MatrixRef{data:&t.data,rows:t.dim[0],cols:t.dim[1],trans:trans},
+      Layout::ColMajor => MatrixRef{data:&t.data,rows:t.dim[1],cols:t.dim[0],trans:!trans},
+    }
+  }
+
+  /// Transpose of the operand, reading the same elements.
+  fn transposed(&self) -> MatrixRef<'a,T>
+  {
+    MatrixRef{data:self.data,rows:self.rows,cols:self.cols,trans:!self.trans}
+  }
+
+  fn op_rows(&self) -> Idx
+  {
+    if self.trans { self.cols } else { self.rows }
+  }
+
+  fn op_cols(&self) -> Idx
+  {
+    if self.trans { self.rows } else { self.cols }
+  }
+
+  /// Element `[itr,jtr]` of the operand after the optional transposition.
+  fn get(&self, itr: Idx, jtr: Idx) -> &T
+  {
+    if self.trans { &self.data[jtr*self.cols+itr] } else { &self.data[itr*self.cols+jtr] }
+  }
+
+  #[cfg(feature="blas")]
+  fn blas_trans(&self) -> CBLAS_TRANSPOSE
+  {
+    if self.trans { CBLAS_TRANSPOSE::CblasTrans } else { CBLAS_TRANSPOSE::CblasNoTrans }
+  }
+}
+
+fn check_product_dim<T>(a: &MatrixRef<T>, b: &MatrixRef<T>)
+where T: Scalar
+{
+  if a.op_cols()!=b.op_rows()
+  {
+    panic!("The number of columns of the left matrix must equal the number of rows of the right matrix to multiply them.")
+  }
+}
+
+/// Adds `alpha*a*b` to the row-major matrix `c`, or `a*b` if no `alpha` is given, choosing the
+/// kernel by the amount of work.
+fn product_native<T>(a: &MatrixRef<T>, b: &MatrixRef<T>, alpha: Option<&T>, c: &mut [T])
+where T: Scalar
+{
+  if a.op_rows()*a.op_cols()*b.op_cols()<BLOCKED_MIN_WORK
+  {
+    product_naive(a,b,alpha,c);
+  }
+  else
+  {
+    product_blocked(a,b,alpha,c);
+  }
+}
+
+fn product_naive<T>(a: &MatrixRef<T>, b: &MatrixRef<T>, alpha: Option<&T>, c: &mut [T])
+where T: Scalar
+{
+  let (m,k,n): (Idx,Idx,Idx)=(a.op_rows(),a.op_cols(),b.op_cols());
+  for itr in 0..m
+  {
+    for jtr in 0..n
+    {
+      let mut sum: T=T::default();
+      for ptr in 0..k
+      {
+        sum+=a.get(itr,ptr).clone()*b.get(ptr,jtr).clone();
+      }
+      match alpha
+      {
+        Some(alpha) => c[itr*n+jtr]+=alpha.clone()*sum,
+        None => c[itr*n+jtr]+=sum,
+      }
+    }
+  }
+}
+
+/// Computes the same update as `product_naive`.
+///
+/// A `BLOCK_K`x`BLOCK_N` panel of `b` and a `BLOCK_M`x`BLOCK_K` block of `a` are packed into
+/// contiguous row-major buffers, resolving any transposition and folding `alpha` into `a`, after
+/// which every row of the block updates a contiguous row segment of `c` from contiguous rows of the
+/// packed panel.
+fn product_blocked<T>(a: &MatrixRef<T>, b: &MatrixRef<T>, alpha: Option<&T>, c: &mut [T])
+where T: Scalar
+{
+  let (m,k,n): (Idx,Idx,Idx)=(a.op_rows(),a.op_cols(),b.op_cols());
+  let mut a_pack: Vec<T>=Vec::with_capacity(BLOCK_M*BLOCK_K);
+  let mut b_pack: Vec<T>=Vec::with_capacity(BLOCK_K*BLOCK_N);
+
+  for jc in (0..n).step_by(BLOCK_N)
+  {
+    let nc: Idx=BLOCK_N.min(n-jc);
+    for pc in (0..k).step_by(BLOCK_K)
+    {
+      let kc: Idx=BLOCK_K.min(k-pc);
+      b_pack.clear();
+      for ptr in pc..pc+kc
+      {
+        b_pack.extend((jc..jc+nc).map(|jtr| b.get(ptr,jtr).clone()));
+      }
+
+      for ic in (0..m).step_by(BLOCK_M)
+      {
+        let mc: Idx=BLOCK_M.min(m-ic);
+        a_pack.clear();
+        for itr in ic..ic+mc
+        {
+          match alpha
+          {
+            Some(alpha) => a_pack.extend((pc..pc+kc).map(|ptr| alpha.clone()*a.get(itr,ptr).clone())),
+            None => a_pack.extend((pc..pc+kc).map(|ptr| a.get(itr,ptr).clone())),
+          }
+        }
+
+        for itr in 0..mc
+        {
+          let c_row: &mut [T]=&mut c[(ic+itr)*n+jc..(ic+itr)*n+jc+nc];
+          for ptr in 0..kc
+          {
+            let a_elem: &T=&a_pack[itr*kc+ptr];
+            for (c_elem,b_elem) in c_row.iter_mut().zip(b_pack[ptr*nc..(ptr+1)*nc].iter())
+            {
+              *c_elem+=a_elem.clone()*b_elem.clone();
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod linalg_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn linalg_test_matrix(dim: [Idx;2], seed: usize) -> Tensor<f64,2>
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::new(dim);
+    for (itr,elem) in t.data.iter_mut().enumerate()
+    {
+      *elem=((itr*7919+seed*104729)%1000) as f64/1000f64-0.5;
+    }
+    t
+  }
+
+  #[test]
+  fn linalg_test_matmul()
+  {
+    let mut t1: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    let mut t2: Tensor<f64,2>=Tensor::<f64,2>::new([3,2]);
+
+    t1[[0,0]]=1.0; t1[[0,1]]=2.0; t1[[0,2]]=3.0;
+    t1[[1,0]]=4.0; t1[[1,1]]=5.0; t1[[1,2]]=6.0;
+
+    t2[[0,0]]=7.0; t2[[0,1]]=8.0;
+    t2[[1,0]]=9.0; t2[[1,1]]=10.0;
+    t2[[2,0]]=11.0; t2[[2,1]]=12.0;
+
+    let t3: Tensor<f64,2>=t1.matmul(&t2);
+
+    assert!(t3.dim==[2,2]);
+    assert!(t3[[0,0]]==58.0);
+    assert!(t3[[0,1]]==64.0);
+    assert!(t3[[1,0]]==139.0);
+    assert!(t3[[1,1]]==154.0);
+  }
+
+  #[test]
+  #[should_panic(expected="The number of columns of the left matrix must equal the number of rows of the right matrix to multiply them.")]
+  fn linalg_test_matmul_dim()
+  {
+    let t1: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    let t2: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+
+    t1.matmul(&t2);
+  }
+
+  #[rstest(m,k,n,case(1,1,1),case(3,5,7),case(64,128,256),case(65,129,257),case(130,70,300))]
+  fn linalg_test_matmul_blocked(m: Idx, k: Idx, n: Idx)
+  {
+    let a: Tensor<f64,2>=linalg_test_matrix([m,k],1);
+    let b: Tensor<f64,2>=linalg_test_matrix([k,n],2);
+    let a_ref: MatrixRef<f64>=MatrixRef::new(&a,false);
+    let b_ref: MatrixRef<f64>=MatrixRef::new(&b,false);
+
+    let mut naive: Vec<f64>=vec![0f64;m*n];
+    let mut blocked: Vec<f64>=vec![0f64;m*n];
+    product_naive(&a_ref,&b_ref,None,&mut naive);
+    product_blocked(&a_ref,&b_ref,None,&mut blocked);
+
+    for (x,y) in naive.iter().zip(blocked.iter())
+    {
+      assert!((x-y).abs()<=1e-12*(k as f64));
+    }
+  }
+
+  #[test]
+  fn linalg_test_matmul_large()
+  {
+    let a: Tensor<f64,2>=linalg_test_matrix([100,90],3);
+    let b: Tensor<f64,2>=linalg_test_matrix([90,80],4);
+    let c: Tensor<f64,2>=a.matmul(&b);
+
+    for itr in 0..100
+    {
+      for jtr in 0..80
+      {
+        let expected: f64=(0..90).fold(0f64,|sum,ptr| sum+a[[itr,ptr]]*b[[ptr,jtr]]);
+        assert!((c[[itr,jtr]]-expected).abs()<=1e-12*90f64);
+      }
+    }
+  }
+
+  fn linalg_test_assert_close(t1: &Tensor<f64,2>, t2: &Tensor<f64,2>)
+  {
+    assert!(t1.dim==t2.dim);
+    for (x,y) in t1.data.iter().zip(t2.data.iter())
+    {
+      assert!((x-y).abs()<=1e-12*(1f64+y.abs()));
+    }
+  }
+
+  #[test]
+  fn linalg_test_transpose()
+  {
+    let t: Tensor<f64,2>=linalg_test_matrix([2,3],5);
+    let tt: Tensor<f64,2>=t.transpose();
+
+    assert!(tt.dim==[3,2]);
+    for itr in 0..2
+    {
+      for jtr in 0..3
+      {
+        assert!(tt[[jtr,itr]]==t[[itr,jtr]]);
+      }
+    }
+  }
+
+  #[rstest(m,k,n,case(4,3,5),case(70,90,80))]
+  fn linalg_test_matmul_t(m: Idx, k: Idx, n: Idx)
+  {
+    let a: Tensor<f64,2>=linalg_test_matrix([k,m],6);
+    let b: Tensor<f64,2>=linalg_test_matrix([k,n],7);
+    linalg_test_assert_close(&a.matmul_t_a(&b),&a.transpose().matmul(&b));
+
+    let a: Tensor<f64,2>=linalg_test_matrix([m,k],8);
+    let b: Tensor<f64,2>=linalg_test_matrix([n,k],9);
+    linalg_test_assert_close(&a.matmul_t_b(&b),&a.matmul(&b.transpose()));
+  }
+
+  #[rstest(trans_a,trans_b,m,k,n
+    ,case(false,false,4,3,5),case(true,false,4,3,5),case(false,true,4,3,5),case(true,true,4,3,5)
+    ,case(false,false,70,90,80),case(true,false,70,90,80),case(false,true,70,90,80),case(true,true,70,90,80)
+  )]
+  fn linalg_test_gemm(trans_a: bool, trans_b: bool, m: Idx, k: Idx, n: Idx)
+  {
+    let a: Tensor<f64,2>=linalg_test_matrix(if trans_a { [k,m] } else { [m,k] },10);
+    let b: Tensor<f64,2>=linalg_test_matrix(if trans_b { [n,k] } else { [k,n] },11);
+    let c: Tensor<f64,2>=linalg_test_matrix([m,n],12);
+    let (alpha,beta): (f64,f64)=(1.5,-0.25);
+
+    let op_a: Tensor<f64,2>=if trans_a { a.transpose() } else { a.clone() };
+    let op_b: Tensor<f64,2>=if trans_b { b.transpose() } else { b.clone() };
+    let expected: Tensor<f64,2>=op_a.matmul(&op_b)*alpha+c.clone()*beta;
+
+    let mut out: Tensor<f64,2>=c.clone();
+    a.gemm(&b,trans_a,trans_b,alpha,beta,&mut out);
+    linalg_test_assert_close(&out,&expected);
+  }
+
+  #[test]
+  fn linalg_test_gemm_beta_zero()
+  {
+    let a: Tensor<f64,2>=linalg_test_matrix([3,4],13);
+    let b: Tensor<f64,2>=linalg_test_matrix([4,2],14);
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    out+=f64::NAN;
+
+    b.gemm(&a,true,true,2.0,0.0,&mut out);
+    linalg_test_assert_close(&out,&(a.matmul(&b).transpose()*2.0));
+  }
+
+  #[rstest(trans_a,trans_b,case(false,false),case(true,false),case(false,true),case(true,true))]
+  fn linalg_test_matrix_kernel(trans_a: bool, trans_b: bool)
+  {
+    let a: Tensor<f64,2>=linalg_test_matrix(if trans_a { [7,9] } else { [9,7] },15);
+    let b: Tensor<f64,2>=linalg_test_matrix(if trans_b { [5,7] } else { [7,5] },16);
+    let a_ref: MatrixRef<f64>=MatrixRef::new(&a,trans_a);
+    let b_ref: MatrixRef<f64>=MatrixRef::new(&b,trans_b);
+    let (m,n): (Idx,Idx)=(a_ref.op_rows(),b_ref.op_cols());
+
+    let mut expected: Vec<f64>=vec![1f64;m*n];
+    let mut actual: Vec<f64>=vec![1f64;m*n];
+    product_naive(&a_ref,&b_ref,Some(&0.5),&mut expected);
+    f64::product(&a_ref,&b_ref,Some(&0.5),&mut actual);
+    for (x,y) in expected.iter().zip(actual.iter())
+    {
+      assert!((x-y).abs()<=1e-12);
+    }
+
+    let a_f32: Vec<f32>=a.data.iter().map(|elem| *elem as f32).collect();
+    let b_f32: Vec<f32>=b.data.iter().map(|elem| *elem as f32).collect();
+    let a_ref: MatrixRef<f32>=MatrixRef{data:&a_f32,rows:a_ref.rows,cols:a_ref.cols,trans:trans_a};
+    let b_ref: MatrixRef<f32>=MatrixRef{data:&b_f32,rows:b_ref.rows,cols:b_ref.cols,trans:trans_b};
+
+    let mut expected: Vec<f32>=vec![0f32;m*n];
+    let mut actual: Vec<f32>=vec![0f32;m*n];
+    product_naive(&a_ref,&b_ref,None,&mut expected);
+    f32::product(&a_ref,&b_ref,None,&mut actual);
+    for (x,y) in expected.iter().zip(actual.iter())
+    {
+      assert!((x-y).abs()<=1e-5);
+    }
+  }
+
+  #[test]
+  fn linalg_test_dot()
+  {
+    let t1: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| [1.5,-2.0,3.0][itr]);
+    let t2: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| [4.0,5.0,-0.5][itr]);
+
+    assert!(t1.dot(&t2)==6.0-10.0-1.5);
+
+    let mut t1: Tensor<f32,1>=Tensor::<f32,1>::new([19]);
+    let mut t2: Tensor<f32,1>=Tensor::<f32,1>::new([19]);
+    for itr in 0..19
+    {
+      t1[itr]=itr as f32*0.25;
+      t2[itr]=2f32-itr as f32*0.125;
+    }
+
+    let expected: f32=(0..19).fold(0f32,|sum,itr| sum+t1[itr]*t2[itr]);
+    assert!((t1.dot(&t2)-expected).abs()<=1e-4);
+  }
+
+  #[test]
+  #[should_panic(expected="Two vectors must be of the same length to take their dot product.")]
+  fn linalg_test_dot_dim()
+  {
+    let t1: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+    let t2: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+
+    t1.dot(&t2);
+  }
+
+  #[test]
+  fn linalg_test_dot_acc()
+  {
+    let t1: Tensor<f32,1>=Tensor::<f32,1>::from_fn([1000000],|_| 0.1);
+    let t2: Tensor<f32,1>=Tensor::<f32,1>::from_fn([1000000],|_| 1.0);
+    let expected: f64=1e6*0.1f32 as f64;
+
+    assert!((t1.dot(&t2) as f64-expected).abs()>100.0);
+    assert!((t1.dot_f64(&t2)-expected).abs()<1e-6);
+
+    let t: Tensor<i8,1>=Tensor::<i8,1>::from_fn([4],|_| 100);
+    assert!(t.dot_acc::<i32>(&t)==40000);
+  }
+
+  #[test]
+  #[should_panic(expected="Two vectors must be of the same length to take their dot product.")]
+  fn linalg_test_dot_acc_dim()
+  {
+    Tensor::<f32,1>::new([3]).dot_f64(&Tensor::<f32,1>::new([2]));
+  }
+
+  #[test]
+  #[should_panic(expected="The number of columns of the left matrix must equal the number of rows of the right matrix to multiply them.")]
+  fn linalg_test_gemm_dim()
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    let b: Tensor<f64,2>=Tensor::<f64,2>::new([3,4]);
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new([3,4]);
+
+    a.gemm(&b,true,false,1.0,0.0,&mut out);
+  }
+
+  #[test]
+  #[should_panic(expected="The output matrix must have as many rows as the left matrix and as many columns as the right matrix.")]
+  fn linalg_test_gemm_out_dim()
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+    let b: Tensor<f64,2>=Tensor::<f64,2>::new([4,3]);
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new([2,3]);
+
+    a.gemm(&b,false,true,1.0,0.0,&mut out);
+  }
+}
+
+#[cfg(all(test,not(debug_assertions)))]
+mod linalg_benches
+{
+  extern crate test;
+
+  use super::*;
+  use test::Bencher;
+
+  fn linalg_bench_operands(size: Idx) -> (Tensor<f64,2>,Tensor<f64,2>,Vec<f64>)
+  {
+    let mut a: Tensor<f64,2>=Tensor::<f64,2>::new([size,size]);
+    let mut b: Tensor<f64,2>=Tensor::<f64,2>::new([size,size]);
+    for (itr,(x,y)) in a.data.iter_mut().zip(b.data.iter_mut()).enumerate()
+    {
+      *x=(itr%17) as f64;
+      *y=(itr%13) as f64;
+    }
+    (a,b,vec![0f64;size*size])
+  }
+
+  macro_rules! linalg_bench_matmul {
+    ($size:literal,$naive:ident,$blocked:ident) => {
+      #[bench]
+      fn $naive(bench: &mut Bencher)
+      {
+        let (a,b,mut c)=linalg_bench_operands($size);
+        let (a,b)=(MatrixRef::new(&a,false),MatrixRef::new(&b,false));
+        bench.iter(|| {
+          c.iter_mut().for_each(|elem| *elem=0f64);
+          product_naive(&a,&b,None,&mut c)
+        });
+      }
+      #[bench]
+      fn $blocked(bench: &mut Bencher)
+      {
+        let (a,b,mut c)=linalg_bench_operands($size);
+        let (a,b)=(MatrixRef::new(&a,false),MatrixRef::new(&b,false));
+        bench.iter(|| {
+          c.iter_mut().for_each(|elem| *elem=0f64);
+          product_blocked(&a,&b,None,&mut c)
+        });
+      }
+    };
+  }
+
+  linalg_bench_matmul!(128,linalg_bench_matmul_naive_128,linalg_bench_matmul_blocked_128);
+  linalg_bench_matmul!(512,linalg_bench_matmul_naive_512,linalg_bench_matmul_blocked_512);
+  linalg_bench_matmul!(1024,linalg_bench_matmul_naive_1024,linalg_bench_matmul_blocked_1024);
+}
@@ -0,0 +1,408 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+use super::matfun::check_square;
+use super::matfun::norm_1;
+
+/// Diagnostics of the solution `x` of a linear system `a*x=b`, for judging how far to trust it.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) struct SolveDiagnostics<T>
+{
+  /// Euclidean norm of the residual `b-a*x`.
+  pub(super) residual_norm: T,
+  /// Estimate of the 1-norm condition number of the matrix, by which relative errors in `b` can be
+  /// amplified in `x`.
+  pub(super) condition_estimate: T,
+}
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Solution `x` of `self*x=b` for a square matrix, by LU factorisation with partial pivoting.
+  ///
+  /// Only a matrix for which elimination meets a pivot that is exactly zero is reported as singular, so
+  /// `condition_estimate` tells whether a solution of a nearly singular matrix can be trusted.
+  pub(super) fn solve(&self, b: &Tensor<T,1>) -> Result<Tensor<T,1>,TensorError>
+  {
+    let lu: Lu<T>=Lu::<T>::factor(self)?;
+    self.solve_factored(&lu,b)
+  }
+
+  /// Estimate of the 1-norm condition number `|self|*|self^-1|` of a square matrix, which is infinite
+  /// for a matrix that `solve` reports as singular.
+  ///
+  /// The norm of the inverse is estimated by Hager's method with Higham's refinements from the LU
+  /// factors and a few solves, rather than by forming the inverse. Up to rounding the estimate is never
+  /// above the condition number, and rarely more than a small factor below it.
+  pub(super) fn condition_estimate(&self) -> Result<T,TensorError>
+  {
+    match Lu::<T>::factor(self)
+    {
+      Ok(lu) => Ok(norm_1(self)*inv_norm_1_estimate(self.dim[0],|x,transpose| lu.solve(x,transpose))),
+      Err(TensorError::Singular) => Ok(T::infinity()),
+      Err(err) => Err(err),
+    }
+  }
+
+  /// Solution of `self*x=b` as `solve` gives, with the norm of its residual and the condition estimate
+  /// of the matrix, for which the matrix is factorised only once.
+  #[cfg(feature="std")]
+  pub(super) fn solve_with_diagnostics(&self, b: &Tensor<T,1>) -> Result<(Tensor<T,1>,SolveDiagnostics<T>),TensorError>
+  {
+    let lu: Lu<T>=Lu::<T>::factor(self)?;
+    let x: Tensor<T,1>=self.solve_factored(&lu,b)?;
+    let condition_estimate: T=norm_1(self)*inv_norm_1_estimate(self.dim[0],|x,transpose| lu.solve(x,transpose));
+    let diagnostics: SolveDiagnostics<T>=SolveDiagnostics{residual_norm:residual_norm(self,&x,b),condition_estimate:condition_estimate};
+    Ok((x,diagnostics))
+  }
+
+  /// Numerical rank of the matrix, the number of pivots above `tol` in absolute value under Gaussian
+  /// elimination with complete pivoting, which stops at the first pivot that is not.
+  ///
+  /// The default tolerance is `max(m,n)*epsilon*p` for a matrix of `m` rows and `n` columns, where `p`
+  /// is the first and largest pivot, the largest element of the matrix in absolute value.
+  pub(super) fn rank(&self, tol: Option<T>) -> Idx
+  {
+    let (m,n): (Idx,Idx)=(self.dim[0],self.dim[1]);
+    let mut a: Vec<T>=self.to_layout(Layout::RowMajor).data.to_vec();
+    let mut tol: Option<T>=tol;
+    for ktr in 0..m.min(n)
+    {
+      let (mut row,mut col,mut pivot): (Idx,Idx,T)=(ktr,ktr,T::default());
+      for itr in ktr..m
+      {
+        for jtr in ktr..n
+        {
+          if a[itr*n+jtr].abs()>pivot { (row,col,pivot)=(itr,jtr,a[itr*n+jtr].abs()); }
+        }
+      }
+      let tol: T=*tol.get_or_insert_with(|| T::from_f64(m.max(n) as f64)*T::epsilon()*pivot);
+      if pivot<=tol { return ktr; }
+
+      for jtr in 0..n
+      {
+        a.swap(ktr*n+jtr,row*n+jtr);
+      }
+      for itr in 0..m
+      {
+        a.swap(itr*n+ktr,itr*n+col);
+      }
+      for itr in ktr+1..m
+      {
+        let factor: T=a[itr*n+ktr]/a[ktr*n+ktr];
+        for jtr in ktr..n
+        {
+          a[itr*n+jtr]=a[itr*n+jtr]-factor*a[ktr*n+jtr];
+        }
+      }
+    }
+    m.min(n)
+  }
+
+  /// Solution of `self*x=b` from the LU factors `lu` of `self`.
+  fn solve_factored(&self, lu: &Lu<T>, b: &Tensor<T,1>) -> Result<Tensor<T,1>,TensorError>
+  {
+    if b.dim[0]!=self.dim[0] { return Err(TensorError::LengthMismatch{lhs:self.dim[0],rhs:b.dim[0]}); }
+
+    let mut x: Vec<T>=b.data.to_vec();
+    lu.solve(&mut x,false);
+    Tensor::<T,1>::from_vec([x.len()],x)
+  }
+}
+
+/// LU factors of the row permutation `P*a=L*U` of a square matrix `a`, with the unit lower triangular
+/// `L` below the diagonal of `lu` and `U` on and above it.
+struct Lu<T>
+{
+  n: Idx,
+  lu: Vec<T>,
+  /// Row `itr` of `P*a` is row `perm[itr]` of `a`.
+  perm: Vec<Idx>,
+}
+
+impl<T> Lu<T>
+where T: Float
+{
+  /// Factorises `a` by Gaussian elimination, choosing the largest pivot in every column, failing if
+  /// `a` is not square or a pivot is zero.
+  fn factor(a: &Tensor<T,2>) -> Result<Lu<T>,TensorError>
+  {
+    check_square(a)?;
+
+    let n: Idx=a.dim[0];
+    let mut lu: Vec<T>=a.to_layout(Layout::RowMajor).data.to_vec();
+    let mut perm: Vec<Idx>=(0..n).collect();
+    for ktr in 0..n
+    {
+      let pivot: Idx=(ktr..n).fold(ktr,|max,itr| if lu[itr*n+ktr].abs()>lu[max*n+ktr].abs() { itr } else { max });
+      if lu[pivot*n+ktr]==T::default() { return Err(TensorError::Singular); }
+      if pivot!=ktr
+      {
+        (0..n).for_each(|jtr| lu.swap(ktr*n+jtr,pivot*n+jtr));
+        perm.swap(ktr,pivot);
+      }
+
+      for itr in ktr+1..n
+      {
+        let factor: T=lu[itr*n+ktr]/lu[ktr*n+ktr];
+        lu[itr*n+ktr]=factor;
+        for jtr in ktr+1..n
+        {
+          lu[itr*n+jtr]=lu[itr*n+jtr]-factor*lu[ktr*n+jtr];
+        }
+      }
+    }
+    Ok(Lu{n:n,lu:lu,perm:perm})
+  }
+
+  /// Overwrites `x` with the solution of `a*y=x`, or of `a^T*y=x` if `transpose` is set.
+  fn solve(&self, x: &mut [T], transpose: bool)
+  {
+    let (n,lu): (Idx,&[T])=(self.n,&self.lu);
+    if transpose
+    {
+      // a^T=U^T*L^T*P, so solve with U^T forwards, then L^T backwards, and undo the permutation.
+      for itr in 0..n
+      {
+        x[itr]=(0..itr).fold(x[itr],|sum,jtr| sum-lu[jtr*n+itr]*x[jtr])/lu[itr*n+itr];
+      }
+      for itr in (0..n).rev()
+      {
+        x[itr]=(itr+1..n).fold(x[itr],|sum,jtr| sum-lu[jtr*n+itr]*x[jtr]);
+      }
+      let y: Vec<T>=x.to_vec();
+      self.perm.iter().zip(y.iter()).for_each(|(&row,&elem)| x[row]=elem);
+    }
+    else
+    {
+      let y: Vec<T>=self.perm.iter().map(|&row| x[row]).collect();
+      x.copy_from_slice(&y);
+      for itr in 0..n
+      {
+        x[itr]=(0..itr).fold(x[itr],|sum,jtr| sum-lu[itr*n+jtr]*x[jtr]);
+      }
+      for itr in (0..n).rev()
+      {
+        x[itr]=(itr+1..n).fold(x[itr],|sum,jtr| sum-lu[itr*n+jtr]*x[jtr])/lu[itr*n+itr];
+      }
+    }
+  }
+}
+
+/// Bound on the number of steps of Hager's method, which almost always stops after two or three.
+const MAX_ESTIMATE_STEPS: Idx=5;
+
+/// Lower bound on the 1-norm of the inverse of an `n` by `n` matrix `a` that is rarely far below it,
+/// from `solve`, which overwrites a vector `x` with `a^-1*x`, or `a^-T*x` if asked to transpose.
+pub(super) fn inv_norm_1_estimate<T,F>(n: Idx, mut solve: F) -> T
+where T: Float, F: FnMut(&mut [T],bool)
+{
+  let (zero,one): (T,T)=(T::default(),T::from_f64(1.0));
+  let norm=|x: &[T]| x.iter().fold(zero,|sum,elem| sum+elem.abs());
+  if n==0 { return zero; }
+
+  // Hager's method climbs `|a^-1*x|` over the unit ball of the 1-norm, whose maximum lies at a unit
+  // vector, until the gradient points to no better one.
+  let mut x: Vec<T>=vec![one/T::from_f64(n as f64);n];
+  let mut est: T=zero;
+  let mut last: Option<Idx>=None;
+  for _ in 0..MAX_ESTIMATE_STEPS
+  {
+    solve(&mut x,false);
+    let x_norm: T=norm(&x);
+    if last.is_some() && x_norm<=est { break; }
+    est=x_norm;
+
+    let mut z: Vec<T>=x.iter().map(|&elem| if elem>=zero { one } else { zero-one }).collect();
+    solve(&mut z,true);
+    let max: Idx=(0..n).fold(0,|max,itr| if z[itr].abs()>z[max].abs() { itr } else { max });
+    if last==Some(max) { break; }
+    last=Some(max);
+    x=vec![zero;n];
+    x[max]=one;
+  }
+
+  // Higham's alternating vector catches the matrices on which the climb stalls early.
+  let mut alt: Vec<T>=(0..n).map(|itr| {
+    let elem: T=one+T::from_f64(itr as f64/(n-1).max(1) as f64);
+    if itr%2==0 { elem } else { zero-elem }
+  }).collect();
+  solve(&mut alt,false);
+  let alt_est: T=T::from_f64(2.0)*norm(&alt)/T::from_f64(3.0*n as f64);
+  if alt_est>est { alt_est } else { est }
+}
+
+/// Euclidean norm of the residual `b-a*x`.
+#[cfg(feature="std")]
+pub(super) fn residual_norm<T>(a: &Tensor<T,2>, x: &Tensor<T,1>, b: &Tensor<T,1>) -> T
+where T: Float
+{
+  (0..a.dim[0])
+    .map(|itr| (0..a.dim[1]).fold(b[itr],|sum,jtr| sum-a[[itr,jtr]]*x[jtr]))
+    .fold(T::default(),|sum,r_i| sum+r_i*r_i)
+    .sqrt()
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod lu_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn lu_test_hilbert(n: Idx) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| 1.0/(itr+jtr+1) as f64)
+  }
+
+  /// Exact 1-norm condition number from the columns of the inverse, found by solving for every unit
+  /// vector.
+  fn lu_test_condition(a: &Tensor<f64,2>) -> f64
+  {
+    let n: Idx=a.dim[0];
+    let inv_norm: f64=(0..n)
+      .map(|jtr| a.solve(&Tensor::<f64,1>::from_fn([n],|[itr]| (itr==jtr) as u8 as f64)).unwrap().iter().map(|elem| elem.abs()).sum())
+      .fold(0.0,f64::max);
+    norm_1(a)*inv_norm
+  }
+
+  /// The first pivot of the first case is zero, so the rows must be exchanged.
+  #[rstest(a,layout,
+    case(Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| [[0.0,2.0,1.0],[1.0,1.0,0.0],[3.0,0.0,1.0]][itr][jtr]),Layout::RowMajor),
+    case(Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| [[0.0,2.0,1.0],[1.0,1.0,0.0],[3.0,0.0,1.0]][itr][jtr]),Layout::ColMajor),
+    case(Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| ((3*itr+5*jtr)%7) as f64-2.0),Layout::RowMajor),
+    case(Tensor::<f64,2>::from_fn([1,1],|_| -4.0),Layout::RowMajor),
+  )]
+  fn lu_test_solve(a: Tensor<f64,2>, layout: Layout)
+  {
+    let n: Idx=a.dim[0];
+    let x: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|[itr]| [1.0,-2.0,3.0,0.5][itr]);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|[itr]| (0..n).map(|jtr| a[[itr,jtr]]*x[jtr]).sum());
+    let a: Tensor<f64,2>=a.to_layout(layout);
+
+    assert!(a.solve(&b).unwrap().iter().zip(x.iter()).all(|(y,x)| (y-x).abs()<1e-14));
+    assert!(a.transpose().solve(&b).unwrap()==a.to_layout(Layout::RowMajor).transpose().solve(&b).unwrap());
+  }
+
+  #[rstest(a,len,error,
+    case(Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| (itr*jtr) as f64),3,TensorError::Singular),
+    case(Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| (itr==jtr) as u8 as f64),2,TensorError::LengthMismatch{lhs:3,rhs:2}),
+    case(Tensor::<f64,2>::new([2,3]),3,TensorError::NotSquare{rows:2,cols:3}),
+  )]
+  fn lu_test_solve_errors(a: Tensor<f64,2>, len: Idx, error: TensorError)
+  {
+    assert!(a.solve(&Tensor::<f64,1>::new([len])).err()==Some(error));
+  }
+
+  #[rstest(b,
+    case([1.0,-1.0,2.0,0.5]),
+    case([0.0,0.0,0.0,1.0]),
+    case([-3.0,2.0,7.0,1.0]),
+  )]
+  fn lu_test_transpose_solve(b: [f64;4])
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| ((3*itr+5*jtr)%7) as f64-2.0);
+    let lu: Lu<f64>=Lu::<f64>::factor(&a).unwrap();
+
+    let mut x: Vec<f64>=b.to_vec();
+    lu.solve(&mut x,true);
+    assert!((0..4).all(|jtr| ((0..4).map(|itr| a[[itr,jtr]]*x[itr]).sum::<f64>()-b[jtr]).abs()<1e-12));
+  }
+
+  #[rstest(n,
+    case(2),
+    case(3),
+    case(4),
+    case(5),
+    case(6),
+    case(7),
+    case(8),
+  )]
+  fn lu_test_condition_estimate(n: Idx)
+  {
+    let a: Tensor<f64,2>=lu_test_hilbert(n);
+    let (est,cond): (f64,f64)=(a.condition_estimate().unwrap(),lu_test_condition(&a));
+    assert!(est<=cond*(1.0+1e-6) && est>=cond/10.0);
+  }
+
+  #[rstest(a,expected,
+    case(Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| if itr==jtr { [1.0,10.0,100.0][itr] } else { 0.0 }),100.0),
+    case(Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| (itr==jtr) as u8 as f64),1.0),
+    case(Tensor::<f64,2>::new([3,3]),f64::INFINITY),
+  )]
+  fn lu_test_condition_estimate_exact(a: Tensor<f64,2>, expected: f64)
+  {
+    assert!(a.condition_estimate().unwrap()==expected);
+  }
+
+  #[test]
+  fn lu_test_condition_estimate_square()
+  {
+    assert!(Tensor::<f64,2>::new([3,2]).condition_estimate().err()==Some(TensorError::NotSquare{rows:3,cols:2}));
+  }
+
+  /// Matrix of `m` rows and `n` columns whose elements are small integers in no particular pattern.
+  fn lu_test_scrambled(m: Idx, n: Idx) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([m,n],|[itr,jtr]| ((7*itr+3*jtr+itr*jtr)%11) as f64-5.0+if itr==jtr { 20.0 } else { 0.0 })
+  }
+
+  #[rstest(n,
+    case(4),
+    case(5),
+    case(6),
+    case(7),
+    case(8),
+  )]
+  fn lu_test_rank(n: Idx)
+  {
+    let a: Tensor<f64,2>=lu_test_scrambled(n+2,n);
+    assert!(a.rank(None)==n && a.transpose().rank(None)==n && a.to_layout(Layout::ColMajor).rank(None)==n);
+
+    // Replacing a column by the sum of two others, or two columns by combinations of the rest, loses
+    // one rank for each.
+    let mut dependent: Tensor<f64,2>=a.clone();
+    (0..n+2).for_each(|itr| dependent[[itr,1]]=a[[itr,0]]+a[[itr,n-1]]);
+    assert!(dependent.rank(None)==n-1 && dependent.transpose().rank(None)==n-1);
+    (0..n+2).for_each(|itr| dependent[[itr,2]]=0.5*a[[itr,0]]-3.0*a[[itr,n-1]]);
+    assert!(dependent.rank(None)==n-2);
+
+    assert!(lu_test_hilbert(n).rank(None)==n);
+  }
+
+  #[rstest(a,tol,expected,
+    case(Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| if itr==1 && jtr==1 { 1.0+1e-6 } else { 1.0 }),None,2),
+    case(Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| if itr==1 && jtr==1 { 1.0+1e-6 } else { 1.0 }),Some(1e-5),1),
+    case(Tensor::<f64,2>::new([3,4]),None,0),
+    case(Tensor::<f64,2>::new([0,4]),None,0),
+    case(Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| ((itr+1)*(jtr+2)) as f64),None,1),
+  )]
+  fn lu_test_rank_deficient(a: Tensor<f64,2>, tol: Option<f64>, expected: Idx)
+  {
+    assert!(a.rank(tol)==expected && a.transpose().rank(tol)==expected);
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn lu_test_solve_with_diagnostics()
+  {
+    let a: Tensor<f64,2>=lu_test_hilbert(8);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([8],|_| 1.0);
+    let (x,diagnostics): (Tensor<f64,1>,SolveDiagnostics<f64>)=a.solve_with_diagnostics(&b).unwrap();
+
+    // The residual is small even though the matrix is so badly conditioned that `x` is not.
+    assert!(x==a.solve(&b).unwrap());
+    assert!(diagnostics.residual_norm<1e-6 && diagnostics.condition_estimate>1e9);
+    assert!(diagnostics.condition_estimate==a.condition_estimate().unwrap());
+    assert!(a.solve_with_diagnostics(&Tensor::<f64,1>::new([3])).err()==Some(TensorError::LengthMismatch{lhs:8,rhs:3}));
+  }
+}
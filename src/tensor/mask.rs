@@ -0,0 +1,139 @@
+use alloc::vec::Vec;
+
+use super::Dim;
+use super::Idx;
+use super::Indices;
+use super::Tensor;
+
+impl<const N: Idx> Tensor<bool,N>
+{
+  /// Mask that is set where both `self` and `rhs` are.
+  pub(super) fn and(&self, rhs: &Tensor<bool,N>) -> Tensor<bool,N>
+  {
+    self.combine(rhs,|lhs,rhs| lhs && rhs)
+  }
+
+  /// Mask that is set where `self`, `rhs` or both are.
+  pub(super) fn or(&self, rhs: &Tensor<bool,N>) -> Tensor<bool,N>
+  {
+    self.combine(rhs,|lhs,rhs| lhs || rhs)
+  }
+
+  /// Mask that is set where exactly one of `self` and `rhs` is.
+  pub(super) fn xor(&self, rhs: &Tensor<bool,N>) -> Tensor<bool,N>
+  {
+    self.combine(rhs,|lhs,rhs| lhs!=rhs)
+  }
+
+  /// Mask that is set where `self` is not.
+  pub(super) fn not(&self) -> Tensor<bool,N>
+  {
+    self.map(|&elem| !elem)
+  }
+
+  /// Number of set elements.
+  pub(super) fn count_true(&self) -> Idx
+  {
+    self.iter().filter(|&&elem| elem).count()
+  }
+
+  /// Multi-indices of the set elements in row-major order, whatever the layout of the mask.
+  pub(super) fn true_indices(&self) -> Vec<Dim<N>>
+  {
+    Indices::new(self.dim).filter(|&ind| self[ind]).collect()
+  }
+
+  /// Mask with `f` applied to the elements of `self` and `rhs` at every index, in the layout of `self`,
+  /// named as `adopt_names` names it.
+  fn combine<F>(&self, rhs: &Tensor<bool,N>, f: F) -> Tensor<bool,N>
+  where F: Fn(bool,bool) -> bool
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    {
+      if dim1!=dim2 { panic!("All dimensions of two masks must be of the same size to combine them.")}
+    }
+
+    let rhs: Tensor<bool,N>=rhs.to_layout(self.layout);
+    let mut t: Tensor<bool,N>=self.clone();
+    t.adopt_names(&rhs);
+    t.data.iter_mut().zip(rhs.data.iter()).for_each(|(this,&other)| *this=f(*this,other));
+    t
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod mask_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Layout;
+
+  fn mask_test_values() -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| [[0.5,-2.0,7.0,3.0],[-4.5,9.0,1.0,-1.0],[6.0,2.0,-3.5,8.5]][itr][jtr])
+  }
+
+  /// Mask of the values outside `lo` to `hi`, from the masks of those above and below in different
+  /// layouts.
+  #[rstest(lo,hi,expected,
+    case(-3.0,6.0,&[[0,2],[1,0],[1,1],[2,2],[2,3]]),
+    case(0.0,5.0,&[[0,1],[0,2],[1,0],[1,1],[1,3],[2,0],[2,2],[2,3]]),
+    case(-5.0,9.0,&[]),
+    case(10.0,-10.0,&[[0,0],[0,1],[0,2],[0,3],[1,0],[1,1],[1,2],[1,3],[2,0],[2,1],[2,2],[2,3]]),
+  )]
+  fn mask_test_compound(lo: f64, hi: f64, expected: &[Dim<2>])
+  {
+    let values: Tensor<f64,2>=mask_test_values();
+    let above: Tensor<bool,2>=values.map(|&elem| elem>hi);
+    let below: Tensor<bool,2>=values.to_layout(Layout::ColMajor).map(|&elem| elem<lo);
+
+    let outside: Tensor<bool,2>=above.or(&below);
+    assert!(outside.true_indices()==expected);
+    assert!(outside.count_true()==expected.len() && outside.layout()==Layout::RowMajor);
+    assert!(below.or(&above).layout()==Layout::ColMajor && below.or(&above).true_indices()==expected);
+
+    let inside: Tensor<bool,2>=outside.not();
+    assert!(inside.count_true()==12-expected.len() && inside.and(&outside).count_true()==0 && inside.or(&outside).count_true()==12);
+    assert!(inside.xor(&outside)==inside.or(&outside));
+  }
+
+  /// Combination of the mask of the values above 6 with the mask of the values that satisfy `pred`.
+  #[rstest(op,pred,layout,expected,
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.and(rhs),|&elem: &f64| elem<8.0,Layout::RowMajor,&[[0,2]]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.and(rhs),|&elem: &f64| elem< -10.0,Layout::ColMajor,&[]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.or(rhs),|&elem: &f64| elem>2.5,Layout::ColMajor,&[[0,2],[0,3],[1,1],[2,0],[2,3]]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.or(rhs),|&elem: &f64| elem< -4.0,Layout::RowMajor,&[[0,2],[1,0],[1,1],[2,3]]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.xor(rhs),|&elem: &f64| elem>2.5,Layout::RowMajor,&[[0,3],[2,0]]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.xor(rhs),|&elem: &f64| elem>6.0,Layout::ColMajor,&[]),
+  )]
+  fn mask_test_combine(op: fn(&Tensor<bool,2>,&Tensor<bool,2>) -> Tensor<bool,2>, pred: fn(&f64) -> bool, layout: Layout, expected: &[Dim<2>])
+  {
+    let values: Tensor<f64,2>=mask_test_values();
+    let above: Tensor<bool,2>=values.map(|&elem| elem>6.0);
+    assert!(op(&above,&values.to_layout(layout).map(pred)).true_indices()==expected);
+  }
+
+  #[rstest(dim,case([0,3]),case([3,0]),case([0,0]))]
+  fn mask_test_empty(dim: Dim<2>)
+  {
+    let t: Tensor<bool,2>=Tensor::<bool,2>::from_fn(dim,|_| true);
+    assert!(t.count_true()==0 && t.true_indices().is_empty() && t.not().is_empty());
+  }
+
+  #[rstest(op,lhs,rhs,
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.and(rhs),[2,3],[3,2]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.or(rhs),[2,3],[2,2]),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.xor(rhs),[0,3],[3,0]),
+  )]
+  #[should_panic(expected="All dimensions of two masks must be of the same size to combine them.")]
+  fn mask_test_dim(op: fn(&Tensor<bool,2>,&Tensor<bool,2>) -> Tensor<bool,2>, lhs: Dim<2>, rhs: Dim<2>)
+  {
+    let _=op(&Tensor::<bool,2>::from_fn(lhs,|_| true),&Tensor::<bool,2>::from_fn(rhs,|_| true));
+  }
+}
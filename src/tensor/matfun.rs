@@ -0,0 +1,220 @@
+use super::CastFrom;
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,2>
+where T: Clone
+{
+  /// Elements on the main diagonal, of which there are as many as the shorter axis is long.
+  pub(super) fn diagonal(&self) -> Tensor<T,1>
+  {
+    Tensor::<T,1>::from_fn([self.dim[0].min(self.dim[1])],|[itr]| self[[itr,itr]].clone())
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar + CastFrom<u8>
+{
+  /// Identity matrix of dimensions `[n,n]`.
+  pub(super) fn identity(n: Idx) -> Tensor<T,2>
+  {
+    Tensor::<T,2>::from_fn([n,n],|[itr,jtr]| T::cast_from((itr==jtr) as u8))
+  }
+
+  /// The square matrix `self` multiplied with itself `k` times, which is the identity for `k` zero.
+  ///
+  /// Computed by repeated squaring, which takes at most two matrix products per bit of `k`.
+  pub(super) fn matrix_power(&self, k: u32) -> Result<Tensor<T,2>,TensorError>
+  {
+    check_square(self)?;
+
+    let mut res: Tensor<T,2>=Tensor::<T,2>::identity(self.dim[0]);
+    let mut base: Tensor<T,2>=self.clone();
+    let mut k: u32=k;
+    while k>0
+    {
+      if k&1==1 { res=res.matmul(&base); }
+      k>>=1;
+      if k>0 { base=base.matmul(&base); }
+    }
+    Ok(res)
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Float + CastFrom<u8>
+{
+  /// Matrix exponential `e^self` of a square matrix, such that `e^(t*A)*x0` solves `x'=A*x` at time
+  /// `t`.
+  ///
+  /// The matrix is scaled down by a power of two until its 1-norm is at most one half, the exponential
+  /// of the scaled matrix is summed as a Taylor series until the terms no longer change it, and the
+  /// result is squared back up as many times as the matrix was halved.
+  ///
+  /// Fails if an element is not finite, as no number of halvings would bring the 1-norm down.
+  pub(super) fn expm(&self) -> Result<Tensor<T,2>,TensorError>
+  {
+    check_square(self)?;
+    self.assert_finite()?;
+
+    let half: T=T::from_f64(0.5);
+    let mut squarings: u32=0;
+    let mut scale: T=T::from_f64(1.0);
+    let mut norm: T=norm_1(self);
+    while norm>half
+    {
+      norm*=half;
+      scale*=half;
+      squarings+=1;
+    }
+    let a: Tensor<T,2>=self.map(|&elem| elem*scale);
+
+    let mut res: Tensor<T,2>=Tensor::<T,2>::identity(self.dim[0]);
+    let mut term: Tensor<T,2>=res.clone();
+    for ktr in 1..=MAX_TAYLOR_TERMS
+    {
+      let inv_k: T=T::from_f64(1.0/(ktr as f64));
+      term=term.matmul(&a).map(|&elem| elem*inv_k);
+      res+=&term;
+      if norm_1(&term)<=T::epsilon()*norm_1(&res) { break; }
+    }
+
+    for _ in 0..squarings
+    {
+      res=res.matmul(&res);
+    }
+    Ok(res)
+  }
+}
+
+/// Bound on the number of Taylor terms summed by `expm`, of which far fewer are needed for a matrix
+/// of 1-norm at most one half.
+const MAX_TAYLOR_TERMS: u32=30;
+
+/// Fails unless `t` is a square matrix.
+pub(super) fn check_square<T>(t: &Tensor<T,2>) -> Result<(),TensorError>
+{
+  if !t.is_square() { return Err(TensorError::NotSquare{rows:t.dim[0],cols:t.dim[1]}); }
+  Ok(())
+}
+
+/// Largest sum of the absolute values of a column.
+pub(super) fn norm_1<T>(t: &Tensor<T,2>) -> T
+where T: Float
+{
+  (0..t.dim[1])
+    .map(|jtr| (0..t.dim[0]).fold(T::default(),|sum,itr| sum+t[[itr,jtr]].abs()))
+    .fold(T::default(),|max,sum| if sum>max { sum } else { max })
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod matfun_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  fn matfun_test_assert_close(t: &Tensor<f64,2>, expected: &[[f64;2];2], tol: f64)
+  {
+    for (itr,jtr) in (0..2).flat_map(|itr| (0..2).map(move |jtr| (itr,jtr)))
+    {
+      assert!((t[[itr,jtr]]-expected[itr][jtr]).abs()<tol);
+    }
+  }
+
+  /// Powers of the Fibonacci matrix hold consecutive Fibonacci numbers.
+  #[rstest(power,expected,
+    case(0,[1,0,0,1]),
+    case(1,[1,1,1,0]),
+    case(2,[2,1,1,1]),
+    case(7,[21,13,13,8]),
+    case(10,[89,55,55,34]),
+  )]
+  fn matfun_test_matrix_power(power: u32, expected: [u64;4])
+  {
+    let fib: Tensor<u64,2>=Tensor::<u64,2>::from_fn([2,2],|[itr,jtr]| (itr+jtr<2) as u64);
+    assert!(fib.matrix_power(power).unwrap().iter().copied().eq(expected.iter().copied()));
+    assert!(fib.to_layout(Layout::ColMajor).matrix_power(power).unwrap()==fib.matrix_power(power).unwrap());
+  }
+
+  #[rstest(power,case(0),case(2))]
+  fn matfun_test_matrix_power_square(power: u32)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::new([2,3]);
+    assert!(t.matrix_power(power).err()==Some(TensorError::NotSquare{rows:2,cols:3}));
+  }
+
+  #[rstest(dim,case([2,3]),case([3,2]),case([3,3]),case([0,2]))]
+  fn matfun_test_diagonal(dim: Dim<2>)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as i32);
+    let len: Idx=dim[0].min(dim[1]);
+    assert!(t.diagonal().dim==[len] && t.diagonal().iter().enumerate().all(|(itr,&elem)| elem==((dim[1]+1)*itr) as i32));
+    assert!(t.to_layout(Layout::ColMajor).transpose().diagonal()==t.diagonal());
+  }
+
+  #[test]
+  fn matfun_test_diagonal_identity()
+  {
+    assert!(Tensor::<i32,2>::identity(3).diagonal().iter().all(|&elem| elem==1));
+  }
+
+  #[rstest(diag,exp_diag,
+    case([0.0,1.0,-2.0],[1.0,core::f64::consts::E,0.1353352832366127]),
+    case([0.5,0.0,0.0],[1.6487212707001282,1.0,1.0]),
+    case([-10.0,10.0,3.0],[4.5399929762484854e-5,22026.465794806718,20.085536923187668]),
+  )]
+  fn matfun_test_expm_diagonal(diag: [f64;3], exp_diag: [f64;3])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| if itr==jtr { diag[itr] } else { 0.0 });
+
+    let exp: Tensor<f64,2>=t.expm().unwrap();
+    for (itr,jtr) in (0..3).flat_map(|itr| (0..3).map(move |jtr| (itr,jtr)))
+    {
+      let expected: f64=if itr==jtr { exp_diag[itr] } else { 0.0 };
+      assert!((exp[[itr,jtr]]-expected).abs()<1e-14*expected.max(1.0));
+    }
+  }
+
+  /// The exponential of the generator of rotations is a rotation, and ten radians takes several
+  /// squarings to reach.
+  #[rstest(angle,cos,sin,tol,
+    case(1.0,0.5403023058681398,0.8414709848078965,1e-14),
+    case(-0.5,0.8775825618903728,-0.479425538604203,1e-14),
+    case(10.0,-0.8390715290764524,-0.5440211108893698,1e-12),
+  )]
+  fn matfun_test_expm_rotation(angle: f64, cos: f64, sin: f64, tol: f64)
+  {
+    let generator: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| [[0.0,-angle],[angle,0.0]][itr][jtr]);
+    matfun_test_assert_close(&generator.expm().unwrap(),&[[cos,0.0-sin],[sin,cos]],tol);
+  }
+
+  #[rstest(elem,case(f64::INFINITY),case(f64::NEG_INFINITY),case(f64::NAN))]
+  fn matfun_test_expm_non_finite(elem: f64)
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::identity(2);
+    t[[1,0]]=elem;
+    match t.expm()
+    {
+      Err(TensorError::NonFinite{index,value}) => assert!(index==[1,0] && (value==elem || value.is_nan() && elem.is_nan())),
+      res => panic!("{:?}",res),
+    }
+  }
+
+  #[test]
+  fn matfun_test_expm_errors()
+  {
+    assert!(Tensor::<f64,2>::new([3,2]).expm().err()==Some(TensorError::NotSquare{rows:3,cols:2}));
+    assert!(Tensor::<f64,2>::new([0,0]).expm().unwrap().is_empty());
+  }
+}
@@ -0,0 +1,274 @@
+use alloc::vec::Vec;
+
+use super::CastFrom;
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl Tensor<u64,2>
+{
+  /// Confusion matrix of the class labels `pred` predicted for samples whose true labels are `truth`,
+  /// counting the samples of true class `i` predicted as class `j` at `[i,j]`.
+  ///
+  /// Every label must be an integer less than `num_classes`, and both tensors of the same length.
+  pub(super) fn confusion_matrix<L>(pred: &Tensor<L,1>, truth: &Tensor<L,1>, num_classes: Idx) -> Result<Tensor<u64,2>,TensorError>
+  where L: Scalar, u64: CastFrom<L>
+  {
+    let labels=|t: &Tensor<L,1>| -> Result<Vec<Idx>,TensorError> {
+      t.iter()
+        .enumerate()
+        .map(|(itr,label)| u64::try_cast_from(label.clone()).map(|label| label as Idx).ok_or(TensorError::InvalidLabel{index:itr}))
+        .collect()
+    };
+    Tensor::<u64,2>::confusion_matrix_from_indices(&labels(pred)?,&labels(truth)?,num_classes)
+  }
+
+  /// Variant of `confusion_matrix` for labels given as indices.
+  pub(super) fn confusion_matrix_from_indices(pred: &[Idx], truth: &[Idx], num_classes: Idx) -> Result<Tensor<u64,2>,TensorError>
+  {
+    if pred.len()!=truth.len() { return Err(TensorError::LengthMismatch{lhs:pred.len(),rhs:truth.len()}); }
+
+    let mut res: Tensor<u64,2>=Tensor::<u64,2>::new([num_classes,num_classes]);
+    for (itr,(&pred,&truth)) in pred.iter().zip(truth.iter()).enumerate()
+    {
+      if pred>=num_classes || truth>=num_classes { return Err(TensorError::InvalidLabel{index:itr}); }
+      res[[truth,pred]]+=1;
+    }
+    Ok(res)
+  }
+
+  /// Fraction of the samples counted in the confusion matrix that are predicted correctly.
+  ///
+  /// As for `precision_recall_f1`, the fraction is taken to be zero when there are no samples.
+  pub(super) fn accuracy(&self) -> f64
+  {
+    let correct: u64=(0..self.dim[0].min(self.dim[1])).map(|itr| self[[itr,itr]]).sum();
+    ratio(correct,self.sum())
+  }
+
+  /// Precision, recall and F1 score of `class` from the confusion matrix.
+  ///
+  /// A ratio whose denominator is zero is taken to be zero rather than NaN: the precision of a class
+  /// that is never predicted, the recall of a class that never occurs, and the F1 score if both
+  /// precision and recall are zero.
+  pub(super) fn precision_recall_f1(&self, class: Idx) -> (f64,f64,f64)
+  {
+    if class>=self.dim[0] || class>=self.dim[1] { panic!("The class must be less than the number of classes to compute its metrics.") }
+
+    let true_pos: u64=self[[class,class]];
+    let pred_pos: u64=(0..self.dim[0]).map(|itr| self[[itr,class]]).sum();
+    let actual_pos: u64=(0..self.dim[1]).map(|jtr| self[[class,jtr]]).sum();
+    let (precision,recall): (f64,f64)=(ratio(true_pos,pred_pos),ratio(true_pos,actual_pos));
+    let f1: f64=if precision+recall==0.0 { 0.0 } else { 2.0*precision*recall/(precision+recall) };
+    (precision,recall,f1)
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Mean squared error of the predictions `self` of `target`, which is NaN if either holds a NaN or
+  /// they are empty.
+  pub(super) fn mse(&self, target: &Tensor<T,N>) -> Result<T,TensorError>
+  {
+    Ok(self.sum_residuals(target,|diff| diff*diff)?/T::from_f64(self.len() as f64))
+  }
+
+  /// Mean absolute error of the predictions `self` of `target`, with the NaN policy of `mse`.
+  pub(super) fn mae(&self, target: &Tensor<T,N>) -> Result<T,TensorError>
+  {
+    Ok(self.sum_residuals(target,|diff| diff.abs())?/T::from_f64(self.len() as f64))
+  }
+
+  /// Root mean squared error of the predictions `self` of `target`, with the NaN policy of `mse`.
+  #[cfg(feature="std")]
+  pub(super) fn rmse(&self, target: &Tensor<T,N>) -> Result<T,TensorError>
+  {
+    Ok(self.mse(target)?.sqrt())
+  }
+
+  /// Sum of `f` applied to every residual `target-self`, failing unless both are of the same
+  /// dimensions.
+  fn sum_residuals<F>(&self, target: &Tensor<T,N>, f: F) -> Result<T,TensorError>
+  where F: Fn(T) -> T
+  {
+    if self.dim!=target.dim { return Err(TensorError::DimMismatch{lhs:self.dim.to_vec(),rhs:target.dim.to_vec()}); }
+
+    let target: Tensor<T,N>=target.to_layout(self.layout);
+    Ok(self.data.iter().zip(target.data.iter()).fold(T::default(),|sum,(&pred,&target)| sum+f(target-pred)))
+  }
+}
+
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Coefficient of determination of the predictions `self` of `target`, the fraction of the variance
+  /// of `target` that the predictions explain.
+  ///
+  /// A constant `target` has no variance to explain, for which the coefficient is taken to be one if
+  /// the predictions are perfect and zero otherwise. It is NaN if either holds a NaN or they are empty.
+  pub(super) fn r_squared(&self, target: &Tensor<T,1>) -> Result<T,TensorError>
+  {
+    let sum_sq_res: T=self.sum_residuals(target,|diff| diff*diff)?;
+    if self.is_empty() { return Ok(T::nan()); }
+
+    let mean: T=target.mean();
+    let sum_sq_tot: T=target.iter().fold(T::default(),|sum,&elem| sum+(elem-mean)*(elem-mean));
+    let (zero,one): (T,T)=(T::default(),T::from_f64(1.0));
+    Ok(match (sum_sq_tot==zero,sum_sq_res==zero)
+    {
+      (true,true) => one,
+      (true,false) => zero,
+      (false,_) => one-sum_sq_res/sum_sq_tot,
+    })
+  }
+}
+
+/// `num/den`, or zero if `den` is zero.
+fn ratio(num: u64, den: u64) -> f64
+{
+  if den==0 { 0.0 } else { num as f64/den as f64 }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod metrics_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  use super::super::Layout;
+
+  fn metrics_test_labels(labels: &[i32]) -> Tensor<i32,1>
+  {
+    Tensor::<i32,1>::from_fn([labels.len()],|[itr]| labels[itr])
+  }
+
+  #[test]
+  fn metrics_test_confusion_matrix()
+  {
+    let pred: Tensor<i32,1>=metrics_test_labels(&[0,1,1,2,2,2,0,1]);
+    let truth: Tensor<i32,1>=metrics_test_labels(&[0,1,2,2,2,1,1,1]);
+
+    let cm: Tensor<u64,2>=Tensor::<u64,2>::confusion_matrix(&pred,&truth,4).unwrap();
+    assert!(cm.dim==[4,4]);
+    assert!(cm.iter().copied().eq([1,0,0,0,1,2,1,0,0,1,2,0,0,0,0,0].iter().copied()));
+    assert!(cm==Tensor::<u64,2>::confusion_matrix_from_indices(&[0,1,1,2,2,2,0,1],&[0,1,2,2,2,1,1,1],4).unwrap());
+    assert!(cm.accuracy()==5.0/8.0);
+    assert!(Tensor::<u64,2>::new([2,2]).accuracy()==0.0);
+  }
+
+  /// Class 3 is neither predicted nor present.
+  #[rstest(class,precision,recall,f1,
+    case(0,0.5,1.0,2.0/3.0),
+    case(1,2.0/3.0,0.5,4.0/7.0),
+    case(2,2.0/3.0,2.0/3.0,2.0/3.0),
+    case(3,0.0,0.0,0.0),
+  )]
+  fn metrics_test_precision_recall_f1(class: Idx, precision: f64, recall: f64, f1: f64)
+  {
+    let cm: Tensor<u64,2>=Tensor::<u64,2>::confusion_matrix_from_indices(&[0,1,1,2,2,2,0,1],&[0,1,2,2,2,1,1,1],4).unwrap();
+
+    let res: (f64,f64,f64)=cm.precision_recall_f1(class);
+    assert!((res.0-precision).abs()<1e-15 && (res.1-recall).abs()<1e-15 && (res.2-f1).abs()<1e-15);
+  }
+
+  #[rstest(pred,truth,index,
+    case(&[0,1,3],&[0,-1,1],1),
+    case(&[0,1,3],&[0,1,3],2),
+    case(&[-2,0,0],&[0,0,0],0),
+  )]
+  fn metrics_test_confusion_matrix_errors(pred: &[i32], truth: &[i32], index: Idx)
+  {
+    let pred: Tensor<i32,1>=metrics_test_labels(pred);
+    let truth: Tensor<i32,1>=metrics_test_labels(truth);
+
+    assert!(Tensor::<u64,2>::confusion_matrix(&pred,&truth,3).err()==Some(TensorError::InvalidLabel{index:index}));
+  }
+
+  #[test]
+  fn metrics_test_confusion_matrix_len()
+  {
+    assert!(Tensor::<u64,2>::confusion_matrix_from_indices(&[0,1],&[0],2).err()==Some(TensorError::LengthMismatch{lhs:2,rhs:1}));
+  }
+
+  /// The residuals of the first case are 0.5, 0, -2 and 1.
+  #[rstest(pred,target,layout,mse,mae,
+    case([1.0,2.0,3.0,5.0],[1.5,2.0,1.0,6.0],Layout::RowMajor,5.25/4.0,3.5/4.0),
+    case([1.0,2.0,3.0,5.0],[1.5,2.0,1.0,6.0],Layout::ColMajor,5.25/4.0,3.5/4.0),
+    case([1.0,2.0,3.0,5.0],[1.0,2.0,3.0,5.0],Layout::RowMajor,0.0,0.0),
+    case([0.0,0.0,0.0,0.0],[1.0,-1.0,2.0,-2.0],Layout::ColMajor,2.5,1.5),
+  )]
+  fn metrics_test_regression(pred: [f64;4], target: [f64;4], layout: Layout, mse: f64, mae: f64)
+  {
+    let pred: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| pred[2*itr+jtr]).to_layout(layout);
+    let target: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| target[2*itr+jtr]);
+
+    assert!(pred.mse(&target).unwrap()==mse);
+    assert!(pred.mae(&target).unwrap()==mae);
+  }
+
+  #[test]
+  fn metrics_test_regression_nan()
+  {
+    let pred: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| [[1.0,2.0],[3.0,5.0]][itr][jtr]);
+    let mut nan: Tensor<f64,2>=pred.clone();
+    nan[[1,0]]=f64::NAN;
+
+    assert!(pred.mse(&nan).unwrap().is_nan() && pred.mae(&nan).unwrap().is_nan());
+    assert!(Tensor::<f64,2>::new([0,2]).mae(&Tensor::<f64,2>::new([0,2])).unwrap().is_nan());
+    assert!(pred.mse(&Tensor::<f64,2>::new([4,1])).err()==Some(TensorError::DimMismatch{lhs:vec![2,2],rhs:vec![4,1]}));
+  }
+
+  #[rstest(offsets,expected,
+    case([3.0,-3.0,3.0,-3.0],3.0),
+    case([0.0,0.0,0.0,0.0],0.0),
+    case([2.0,2.0,-2.0,-2.0],2.0),
+  )]
+  #[cfg(feature="std")]
+  fn metrics_test_rmse(offsets: [f64;4], expected: f64)
+  {
+    let pred: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| itr as f64);
+    let target: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| itr as f64+offsets[itr]);
+    assert!(pred.rmse(&target).unwrap()==expected);
+  }
+
+  /// The target of the first cases has mean 3 and a sum of squared deviations of 14. A constant
+  /// target is fitted perfectly only by itself.
+  #[rstest(pred,target,expected,
+    case([1.0,2.0,3.0,6.0],[1.0,2.0,3.0,6.0],1.0),
+    case([1.0,3.0,3.0,5.0],[1.0,2.0,3.0,6.0],1.0-2.0/14.0),
+    case([3.0,3.0,3.0,3.0],[1.0,2.0,3.0,6.0],0.0),
+    case([2.0,2.0,2.0,2.0],[2.0,2.0,2.0,2.0],1.0),
+    case([1.0,3.0,3.0,5.0],[2.0,2.0,2.0,2.0],0.0),
+  )]
+  fn metrics_test_r_squared(pred: [f64;4], target: [f64;4], expected: f64)
+  {
+    let pred: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| pred[itr]);
+    let target: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| target[itr]);
+
+    assert!((pred.r_squared(&target).unwrap()-expected).abs()<1e-15);
+  }
+
+  #[test]
+  fn metrics_test_r_squared_empty()
+  {
+    assert!(Tensor::<f64,1>::new([0]).r_squared(&Tensor::<f64,1>::new([0])).unwrap().is_nan());
+    assert!(Tensor::<f64,1>::new([4]).r_squared(&Tensor::<f64,1>::new([3])).err()==Some(TensorError::DimMismatch{lhs:vec![4],rhs:vec![3]}));
+  }
+
+  #[test]
+  #[should_panic(expected="The class must be less than the number of classes to compute its metrics.")]
+  fn metrics_test_precision_recall_f1_class()
+  {
+    Tensor::<u64,2>::new([2,2]).precision_recall_f1(2);
+  }
+}
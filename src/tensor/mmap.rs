@@ -0,0 +1,369 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Index;
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+use super::npy::NpyElement;
+use super::npy::NpyHeader;
+use super::npy::io_error;
+use super::view::TensorView;
+use super::view::ViewIter;
+
+/// Read-only tensor whose elements are those of a memory-mapped file, borrowed from the mapping rather
+/// than loaded, so that opening even a large file is immediate and only the pages read are loaded.
+///
+/// The elements are stored little-endian in row-major order. The byte order and the alignment of the
+/// elements are checked when the file is opened, which fails rather than reads elements of the wrong
+/// type. The file must not be modified while it is mapped, as the elements would change underneath the
+/// tensor. `view` gives the read-only operations of a `TensorView`, and `to_owned` copies the elements
+/// into a `Tensor` when they are to be changed.
+pub(super) struct MmapTensor<T,const N: Idx>
+{
+  map: Mmap,
+  dim: Dim<N>,
+  /// Number of elements, checked on opening to fit in the mapping.
+  len: Idx,
+  /// Position of the first element in the mapping, in bytes.
+  offset: Idx,
+  elem: PhantomData<T>,
+}
+
+impl<T,const N: Idx> MmapTensor<T,N>
+where T: Scalar + NpyElement
+{
+  /// Maps the .npy file at `path`, failing if its header is malformed, if its elements are not of type
+  /// `T`, or are not a tensor of `N` dimensions in row-major order, or of more bytes than can be
+  /// addressed, or if it holds fewer elements than its header states, which fails at the offset of the
+  /// end of the file.
+  pub(super) fn open_npy<P>(path: P) -> Result<MmapTensor<T,N>,TensorError>
+  where P: AsRef<Path>
+  {
+    let map: Mmap=map_file(path)?;
+    let mut reader: &[u8]=&map;
+    let header: NpyHeader=NpyHeader::read(&mut reader)?;
+    if header.descr!=T::DESCR { return Err(TensorError::NpyDtype{descr:header.descr}); }
+    if header.shape.len()!=N || header.fortran_order { return Err(TensorError::NpyShape{shape:header.shape}); }
+
+    let mut dim: Dim<N>=[0;N];
+    dim.copy_from_slice(&header.shape);
+    let len: Idx=dim.iter().try_fold(1,|len: Idx,&dim| len.checked_mul(dim)).filter(|len| len.checked_mul(T::SIZE).is_some())
+      .ok_or(TensorError::NpyShape{shape:header.shape})?;
+    let offset: Idx=map.len()-reader.len();
+    if reader.len()<len*T::SIZE { return Err(TensorError::io(io::ErrorKind::UnexpectedEof.into(),Some(map.len()))); }
+    MmapTensor::<T,N>::from_map(map,dim,len,offset)
+  }
+
+  /// Maps the file at `path` as the raw elements of a tensor of dimensions `dim`, without a header,
+  /// failing unless the file holds exactly as many elements as the dimensions describe, or at the offset
+  /// of a last element that is cut short.
+  pub(super) fn open_raw<P>(path: P, dim: Dim<N>) -> Result<MmapTensor<T,N>,TensorError>
+  where P: AsRef<Path>
+  {
+    let map: Mmap=map_file(path)?;
+    let len: Idx=map.len()/T::SIZE;
+    if len*T::SIZE!=map.len() { return Err(TensorError::io(io::ErrorKind::InvalidData.into(),Some(len*T::SIZE))); }
+    // The size saturates rather than overflows, as no file holds that many elements.
+    let size: Idx=dim.iter().fold(1,|size: Idx,&dim| size.saturating_mul(dim));
+    if len!=size { return Err(TensorError::SizeMismatch{size:size,len:len}); }
+    MmapTensor::<T,N>::from_map(map,dim,len,0)
+  }
+
+  fn from_map(map: Mmap, dim: Dim<N>, len: Idx, offset: Idx) -> Result<MmapTensor<T,N>,TensorError>
+  {
+    if cfg!(target_endian="big") && T::SIZE>1 { return Err(TensorError::ByteOrder); }
+    if map[offset..].as_ptr().align_offset(core::mem::align_of::<T>())!=0 { return Err(TensorError::Misaligned); }
+    Ok(MmapTensor{map:map,dim:dim,len:len,offset:offset,elem:PhantomData})
+  }
+
+  /// Dimensions of the tensor.
+  pub(super) fn dim(&self) -> Dim<N>
+  {
+    self.dim
+  }
+
+  /// The mapped elements in row-major order.
+  fn data(&self) -> &[T]
+  {
+    let bytes: &[u8]=&self.map[self.offset..self.offset+self.len*T::SIZE];
+    // The bytes were checked to be aligned and in the byte order of `T` on opening, and every pattern
+    // of bits is a valid `NpyElement`.
+    unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const T,bytes.len()/T::SIZE) }
+  }
+
+  /// View of the mapped elements.
+  pub(super) fn view(&self) -> TensorView<'_,T,N>
+  {
+    TensorView::<T,N>::from_slice(self.dim,self.data()).unwrap()
+  }
+
+  /// Iterator over the elements in row-major order.
+  pub(super) fn iter(&self) -> ViewIter<'_,T,N>
+  {
+    self.view().iter()
+  }
+
+  /// Sum of all elements.
+  pub(super) fn sum(&self) -> T
+  {
+    self.view().sum()
+  }
+
+  /// Copies the mapped elements into a new tensor.
+  pub(super) fn to_owned(&self) -> Tensor<T,N>
+  {
+    self.view().to_owned()
+  }
+}
+
+impl<T,const N: Idx> Index<Dim<N>> for MmapTensor<T,N>
+where T: Scalar + NpyElement
+{
+  type Output=T;
+  fn index(&self, ind: Dim<N>) -> &Self::Output
+  {
+    &self.data()[self.dim.checked_index(ind)]
+  }
+}
+
+impl<T> Index<Idx> for MmapTensor<T,1>
+where T: Scalar + NpyElement
+{
+  type Output=T;
+  fn index(&self, ind: Idx) -> &Self::Output
+  {
+    &self[[ind]]
+  }
+}
+
+impl<T,const N: Idx> fmt::Display for MmapTensor<T,N>
+where T: Scalar + NpyElement + fmt::Display
+{
+  /// Writes the mapped elements in the same format as a `Tensor`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    self.view().fmt(f)
+  }
+}
+
+/// Maps the whole of the file at `path` read-only.
+fn map_file<P>(path: P) -> Result<Mmap,TensorError>
+where P: AsRef<Path>
+{
+  let file: File=File::open(path).map_err(io_error)?;
+  // Mapping is only sound while the file is not modified, which the documentation of `MmapTensor`
+  // leaves to the caller.
+  unsafe { Mmap::map(&file) }.map_err(io_error)
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod mmap_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::format;
+  use alloc::string::String;
+  use alloc::vec;
+  use alloc::vec::Vec;
+
+  use core::sync::atomic::AtomicUsize;
+  use core::sync::atomic::Ordering;
+
+  use std::io::Write;
+  use std::path::PathBuf;
+
+  use super::super::Indices;
+  use super::super::Layout;
+  use super::super::error::TensorErrorKind;
+
+  /// Number of temporary files created, which tells apart those of the cases of a test run together.
+  static TEMP_FILES: AtomicUsize=AtomicUsize::new(0);
+
+  /// File in the temporary directory, removed when dropped, named after the test that uses it.
+  struct TempFile(PathBuf);
+
+  impl TempFile
+  {
+    fn new(name: &str, bytes: &[u8]) -> TempFile
+    {
+      let count: usize=TEMP_FILES.fetch_add(1,Ordering::Relaxed);
+      let path: PathBuf=std::env::temp_dir().join(format!("lemma_{}_{}_{}",std::process::id(),count,name));
+      File::create(&path).unwrap().write_all(bytes).unwrap();
+      TempFile(path)
+    }
+  }
+
+  impl Drop for TempFile
+  {
+    fn drop(&mut self)
+    {
+      let _=std::fs::remove_file(&self.0);
+    }
+  }
+
+  fn mmap_test_tensor(dim: Dim<3>) -> Tensor<f64,3>
+  {
+    Tensor::<f64,3>::from_fn(dim,|[itr,jtr,ktr]| (20*itr+5*jtr+ktr) as f64*0.25-7.0)
+  }
+
+  fn mmap_test_npy<const N: Idx>(name: &str, t: &Tensor<f64,N>) -> TempFile
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    t.write_npy(&mut bytes).unwrap();
+    TempFile::new(name,&bytes)
+  }
+
+  #[rstest(dim,layout,
+    case([3,4,5],Layout::RowMajor),
+    case([3,4,5],Layout::ColMajor),
+    case([1,1,1],Layout::RowMajor),
+    case([2,1,7],Layout::ColMajor),
+    case([0,4,5],Layout::RowMajor),
+  )]
+  fn mmap_test_open_npy(dim: Dim<3>, layout: Layout)
+  {
+    let t: Tensor<f64,3>=mmap_test_tensor(dim);
+    let file: TempFile=mmap_test_npy("mmap_test_open_npy.npy",&t.to_layout(layout));
+    let mapped: MmapTensor<f64,3>=MmapTensor::<f64,3>::open_npy(&file.0).unwrap();
+
+    assert!(mapped.dim()==dim);
+    assert!(Indices::new(dim).all(|ind| mapped[ind]==t[ind]));
+    assert!(mapped.sum()==t.sum() && mapped.iter().eq(t.iter()));
+    assert!(mapped.to_owned()==t && format!("{}",mapped)==format!("{}",t));
+    assert!(mapped.view().to_owned()==t);
+  }
+
+  /// Opening a file of elements of type `f64` in three dimensions.
+  #[rstest(open,expected,
+    case(|path: &Path| MmapTensor::<f64,2>::open_npy(path).err(),TensorError::NpyShape{shape:vec![3,4,5]}),
+    case(|path: &Path| MmapTensor::<f64,4>::open_npy(path).err(),TensorError::NpyShape{shape:vec![3,4,5]}),
+    case(|path: &Path| MmapTensor::<f32,3>::open_npy(path).err(),TensorError::NpyDtype{descr:String::from("<f8")}),
+    case(|path: &Path| MmapTensor::<i64,3>::open_npy(path).err(),TensorError::NpyDtype{descr:String::from("<f8")}),
+  )]
+  fn mmap_test_open_npy_type(open: fn(&Path) -> Option<TensorError>, expected: TensorError)
+  {
+    let file: TempFile=mmap_test_npy("mmap_test_open_npy_type.npy",&mmap_test_tensor([3,4,5]));
+    assert!(open(&file.0)==Some(expected));
+  }
+
+  /// A file cut short fails at its end.
+  #[rstest(cut,case(1),case(8),case(479),case(480))]
+  fn mmap_test_open_npy_truncated(cut: Idx)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    mmap_test_tensor([3,4,5]).write_npy(&mut bytes).unwrap();
+    let end: Idx=bytes.len()-cut;
+    let truncated: TempFile=TempFile::new("mmap_test_open_npy_truncated.npy",&bytes[..end]);
+    assert!(matches!(MmapTensor::<f64,3>::open_npy(&truncated.0),Err(TensorError::Io{kind:io::ErrorKind::UnexpectedEof,offset:Some(offset),..}) if offset==end));
+  }
+
+  #[test]
+  fn mmap_test_open_npy_missing()
+  {
+    let file: TempFile=mmap_test_npy("mmap_test_open_npy_missing.npy",&mmap_test_tensor([3,4,5]));
+    let missing: TensorError=MmapTensor::<f64,3>::open_npy(file.0.with_extension("missing")).err().unwrap();
+    assert!(missing.kind()==TensorErrorKind::NotFound && matches!(missing,TensorError::Io{offset:None,..}));
+  }
+
+  /// Elements that start three bytes into a page cannot be read in place unless they are bytes.
+  #[rstest(descr,open,expected,
+    case("<f8",|path: &Path| MmapTensor::<f64,1>::open_npy(path).err(),Some(TensorError::Misaligned)),
+    case("<f4",|path: &Path| MmapTensor::<f32,1>::open_npy(path).err(),Some(TensorError::Misaligned)),
+    case("<i2",|path: &Path| MmapTensor::<i16,1>::open_npy(path).err(),Some(TensorError::Misaligned)),
+    case("|u1",|path: &Path| MmapTensor::<u8,1>::open_npy(path).err(),None),
+  )]
+  fn mmap_test_open_npy_misaligned(descr: &str, open: fn(&Path) -> Option<TensorError>, expected: Option<TensorError>)
+  {
+    let mut header: Vec<u8>=b"\x93NUMPY\x01\x00\x39\x00".to_vec();
+    header.extend_from_slice(format!("{{'descr': '{}', 'fortran_order': False, 'shape': (1,)}} \n",descr).as_bytes());
+    header.extend_from_slice(&[0;8]);
+    let misaligned: TempFile=TempFile::new("mmap_test_open_npy_misaligned.npy",&header);
+    assert!(open(&misaligned.0)==expected);
+  }
+
+  /// Shapes of more elements, or of more bytes, than can be addressed must not wrap around to a length
+  /// that the file holds.
+  #[rstest(shape,open,
+    case(vec![2305843009213693953],|path: &Path| MmapTensor::<f64,1>::open_npy(path).err()),
+    case(vec![Idx::MAX],|path: &Path| MmapTensor::<f64,1>::open_npy(path).err()),
+    case(vec![4294967296,4294967297],|path: &Path| MmapTensor::<f64,2>::open_npy(path).err()),
+    case(vec![2,4294967296,2147483648],|path: &Path| MmapTensor::<f64,3>::open_npy(path).err()),
+  )]
+  fn mmap_test_open_npy_overflow(shape: Vec<Idx>, open: fn(&Path) -> Option<TensorError>)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    NpyHeader{descr:String::from("<f8"),fortran_order:false,shape:shape.clone()}.write(&mut bytes).unwrap();
+    bytes.extend_from_slice(&1.5f64.to_le_bytes());
+    let file: TempFile=TempFile::new("mmap_test_open_npy_overflow.npy",&bytes);
+    assert!(open(&file.0)==Some(TensorError::NpyShape{shape:shape}));
+  }
+
+  fn mmap_test_raw() -> Vec<u8>
+  {
+    (0..12).flat_map(|itr: i32| (itr-6).to_le_bytes().to_vec()).collect()
+  }
+
+  #[rstest(dim,case([3,4]),case([12,1]),case([1,12]),case([2,6]),case([6,2]))]
+  fn mmap_test_open_raw(dim: Dim<2>)
+  {
+    let file: TempFile=TempFile::new("mmap_test_open_raw.bin",&mmap_test_raw());
+    let mapped: MmapTensor<i32,2>=MmapTensor::<i32,2>::open_raw(&file.0,dim).unwrap();
+
+    assert!(mapped.dim()==dim && mapped.sum()==-6);
+    assert!(Indices::new(dim).all(|[itr,jtr]| mapped[[itr,jtr]]==(dim[1]*itr+jtr) as i32-6));
+    assert!(mapped.to_owned()==Tensor::<i32,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as i32-6));
+  }
+
+  #[rstest(ind,expected,case(0,-6),case(7,1),case(11,5))]
+  fn mmap_test_open_raw_index(ind: Idx, expected: i32)
+  {
+    let file: TempFile=TempFile::new("mmap_test_open_raw_index.bin",&mmap_test_raw());
+    assert!(MmapTensor::<i32,1>::open_raw(&file.0,[12]).unwrap()[ind]==expected);
+  }
+
+  #[rstest(dim,size,case([4,4],16),case([2,2],4),case([0,5],0),case([Idx::MAX/2+1,2],Idx::MAX))]
+  fn mmap_test_open_raw_size(dim: Dim<2>, size: Idx)
+  {
+    let file: TempFile=TempFile::new("mmap_test_open_raw_size.bin",&mmap_test_raw());
+    assert!(MmapTensor::<i32,2>::open_raw(&file.0,dim).err()==Some(TensorError::SizeMismatch{size:size,len:12}));
+  }
+
+  /// A last element that is cut short fails at its start.
+  #[rstest(len,offset,case(10,8),case(3,0),case(47,44))]
+  fn mmap_test_open_raw_odd(len: Idx, offset: Idx)
+  {
+    let odd: TempFile=TempFile::new("mmap_test_open_raw_odd.bin",&mmap_test_raw()[..len]);
+    assert!(matches!(MmapTensor::<i32,1>::open_raw(&odd.0,[len/4]),Err(TensorError::Io{kind:io::ErrorKind::InvalidData,offset:Some(found),..}) if found==offset));
+  }
+
+  #[rstest(dim,case([6,1]),case([3,2]),case([1,6]))]
+  fn mmap_test_open_raw_wide(dim: Dim<2>)
+  {
+    let file: TempFile=TempFile::new("mmap_test_open_raw_wide.bin",&mmap_test_raw());
+    assert!(MmapTensor::<i64,2>::open_raw(&file.0,dim).unwrap().dim()==dim);
+  }
+
+  #[rstest(ind,case([3,0,0]),case([0,4,0]),case([2,3,5]))]
+  #[should_panic(expected="is out of bounds for axis")]
+  fn mmap_test_index_bounds(ind: Dim<3>)
+  {
+    let file: TempFile=mmap_test_npy("mmap_test_index_bounds.npy",&mmap_test_tensor([3,4,5]));
+    let _=MmapTensor::<f64,3>::open_npy(&file.0).unwrap()[ind];
+  }
+}
@@ -0,0 +1,204 @@
+use core::cmp::Ordering;
+
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+/// Element types with a Euclidean remainder, which unlike `%` never takes the sign of the dividend.
+pub(super) trait Modular: Scalar + Copy + PartialOrd
+{
+  /// Remainder of `self` divided by `m`, in `[0,|m|)`. `m` must not be zero.
+  fn rem_euclid(self, m: Self) -> Self;
+  /// `self` shifted by a whole number of periods `hi-lo` into `[lo,hi)`, which must not be empty.
+  fn wrap(self, lo: Self, hi: Self) -> Self;
+}
+
+macro_rules! impl_modular_float {
+  ($type:ty) => {
+    impl Modular for $type
+    {
+      fn rem_euclid(self, m: $type) -> $type
+      {
+        // A tiny negative remainder rounds up to `|m|` when it is shifted, which lies outside the range.
+        let abs: $type=if m<0.0 { -m } else { m };
+        let rem: $type=self%m;
+        let rem: $type=if rem<0.0 { rem+abs } else { rem };
+        if rem==abs { 0.0 } else { rem }
+      }
+
+      fn wrap(self, lo: $type, hi: $type) -> $type
+      {
+        let elem: $type=lo+(self-lo).rem_euclid(hi-lo);
+        if elem>=hi { lo } else { elem }
+      }
+    }
+  };
+}
+
+impl_modular_float!(f32);
+impl_modular_float!(f64);
+
+macro_rules! impl_modular_int {
+  ($($type:ty),*) => {
+    $(
+      impl Modular for $type
+      {
+        fn rem_euclid(self, m: $type) -> $type { self.wrapping_rem_euclid(m) }
+
+        // The differences may not fit in the type itself, but always fit in an `i128`.
+        fn wrap(self, lo: $type, hi: $type) -> $type
+        {
+          (lo as i128+(self as i128-lo as i128).rem_euclid(hi as i128-lo as i128)) as $type
+        }
+      }
+    )*
+  };
+}
+
+impl_modular_int!(i8,i16,i32,i64,u8,u16,u32,u64);
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Modular
+{
+  /// Euclidean remainder of every element divided by `m`, in `[0,|m|)` even for negative elements,
+  /// failing if `m` is zero.
+  pub(super) fn rem_euclid_scalar(&self, m: T) -> Result<Tensor<T,N>,TensorError>
+  {
+    if m==T::default() { return Err(TensorError::ZeroModulus); }
+    Ok(self.map(|&elem| elem.rem_euclid(m)))
+  }
+
+  /// Every element shifted by a whole number of periods `hi-lo` into `[lo,hi)`, as for wrapping angles
+  /// into `[-pi,pi)`, failing unless `lo` is less than `hi`. NaN stays NaN.
+  pub(super) fn wrap_to_range(&self, lo: T, hi: T) -> Result<Tensor<T,N>,TensorError>
+  {
+    if lo.partial_cmp(&hi)!=Some(Ordering::Less) { return Err(TensorError::EmptyRange); }
+    Ok(self.map(|&elem| elem.wrap(lo,hi)))
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod modular_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Layout;
+
+  /// The remainder takes the sign of neither operand, unlike that of `%`.
+  #[rstest(modulus,layout,expected,
+    case(3,Layout::RowMajor,[1,1,1,1,1,1]),
+    case(-5,Layout::RowMajor,[2,0,3,1,4,2]),
+    case(5,Layout::RowMajor,[2,0,3,1,4,2]),
+    case(5,Layout::ColMajor,[2,0,3,1,4,2]),
+    case(1,Layout::ColMajor,[0,0,0,0,0,0]),
+  )]
+  fn modular_test_rem_euclid_scalar_int(modulus: i32, layout: Layout, expected: [i32;6])
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| 3*(3*itr as i32+jtr as i32)-8);
+    assert!(t.iter().copied().eq([-8,-5,-2,1,4,7].iter().copied()));
+
+    assert!(t.to_layout(layout).rem_euclid_scalar(modulus).unwrap().to_layout(Layout::RowMajor).iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[test]
+  fn modular_test_rem_euclid_scalar_types()
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| 3*(3*itr as i32+jtr as i32)-8);
+    assert!((t.clone()%5).iter().copied().eq([-3,0,-2,1,4,2].iter().copied()));
+    assert!(t.rem_euclid_scalar(0).err()==Some(TensorError::ZeroModulus));
+    assert!(Tensor::<i8,1>::from_fn([2],|[itr]| [i8::MIN,-1][itr]).rem_euclid_scalar(-1).unwrap().iter().all(|&elem| elem==0));
+
+    let u: Tensor<u8,1>=Tensor::<u8,1>::from_fn([4],|[itr]| [0,7,250,255][itr]);
+    assert!(u.rem_euclid_scalar(7).unwrap().iter().copied().eq([0,0,5,3].iter().copied()));
+  }
+
+  #[rstest(modulus,expected,
+    case(2.5,[0.0,0.0,0.0,0.5,0.0]),
+    case(-2.0,[0.5,1.5,0.0,0.5,0.5]),
+    case(4.0,[0.5,1.5,0.0,0.5,2.5]),
+  )]
+  fn modular_test_rem_euclid_scalar_float(modulus: f64, expected: [f64;5])
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([6],|[itr]| [-7.5,-2.5,-0.0,0.5,2.5,f64::NAN][itr]);
+
+    let r: Tensor<f64,1>=t.rem_euclid_scalar(modulus).unwrap();
+    assert!(r.iter().take(5).copied().eq(expected.iter().copied()) && r[5].is_nan());
+  }
+
+  #[test]
+  fn modular_test_rem_euclid_scalar_float_range()
+  {
+    // The remainder of a tiny negative number rounds up to the modulus unless it is kept in range.
+    assert!(Tensor::<f64,1>::from_fn([1],|_| -1e-20).rem_euclid_scalar(1.0).unwrap()[0]==0.0);
+    assert!(Tensor::<f64,1>::new([2]).rem_euclid_scalar(0.0).err()==Some(TensorError::ZeroModulus));
+  }
+
+  #[rstest(lo,hi,
+    case(-core::f64::consts::PI,core::f64::consts::PI),
+    case(0.0,2.0*core::f64::consts::PI),
+    case(-1.0,0.5),
+  )]
+  fn modular_test_wrap_to_range_float(lo: f64, hi: f64)
+  {
+    let pi: f64=core::f64::consts::PI;
+    let eps: f64=1e-12;
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([8],|[itr]| [-pi-eps,-pi,-pi+eps,pi-eps,pi,pi+eps,-3.0*pi-eps,f64::NAN][itr]);
+
+    // Wrapping moves each element by a whole number of periods into the range.
+    let w: Tensor<f64,1>=t.wrap_to_range(lo,hi).unwrap();
+    assert!(w.iter().take(7).all(|&elem| (lo..hi).contains(&elem)));
+    assert!(w.iter().zip(t.iter()).take(7).all(|(elem,t)| { let periods: f64=(elem-t)/(hi-lo); (periods-periods.round()).abs()<1e-9 }));
+    assert!(w[7].is_nan());
+  }
+
+  #[test]
+  fn modular_test_wrap_to_range_float_edges()
+  {
+    let pi: f64=core::f64::consts::PI;
+    let eps: f64=1e-12;
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([7],|[itr]| [-pi-eps,-pi,-pi+eps,pi-eps,pi,pi+eps,-3.0*pi-eps][itr]);
+
+    let expected: [f64;7]=[pi-eps,-pi,-pi+eps,pi-eps,-pi,-pi+eps,pi-eps];
+    assert!(t.wrap_to_range(-pi,pi).unwrap().iter().zip(expected.iter()).all(|(elem,exp)| (elem-exp).abs()<1e-14));
+  }
+
+  #[rstest(lo,hi,
+    case(core::f64::consts::PI,-core::f64::consts::PI),
+    case(core::f64::consts::PI,core::f64::consts::PI),
+    case(f64::NAN,core::f64::consts::PI),
+    case(0.0,f64::NAN),
+  )]
+  fn modular_test_wrap_to_range_empty(lo: f64, hi: f64)
+  {
+    assert!(Tensor::<f64,1>::new([3]).wrap_to_range(lo,hi).err()==Some(TensorError::EmptyRange));
+  }
+
+  #[rstest(lo,hi,expected,
+    case(-180,180,[179,-180,-179,179,-180,-179,-180]),
+    case(0,360,[179,180,181,179,180,181,180]),
+    case(-1,1,[-1,0,-1,-1,0,-1,0]),
+  )]
+  fn modular_test_wrap_to_range_int(lo: i32, hi: i32, expected: [i32;7])
+  {
+    let t: Tensor<i32,1>=Tensor::<i32,1>::from_fn([7],|[itr]| [-181,-180,-179,179,180,181,-540][itr]);
+    assert!(t.wrap_to_range(lo,hi).unwrap().iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[test]
+  fn modular_test_wrap_to_range_int_types()
+  {
+    // Neither the period nor the distance of an element from the range need fit in the element type.
+    let i: Tensor<i8,1>=Tensor::<i8,1>::from_fn([3],|[itr]| [-128,0,127][itr]);
+    assert!(i.wrap_to_range(-100,100).unwrap().iter().copied().eq([72,0,-73].iter().copied()));
+    let u: Tensor<u8,1>=Tensor::<u8,1>::from_fn([4],|[itr]| [0,9,10,255][itr]);
+    assert!(u.wrap_to_range(10,20).unwrap().iter().copied().eq([10,19,10,15].iter().copied()));
+    assert!(u.wrap_to_range(20,10).err()==Some(TensorError::EmptyRange));
+  }
+}
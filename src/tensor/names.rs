@@ -0,0 +1,327 @@
+use alloc::string::String;
+
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl<T,const N: Idx> Tensor<T,N>
+{
+  /// Tensor of the same elements with axis `itr` named `names[itr]`, which must be distinct.
+  ///
+  /// The names are kept by operations that keep the axes, follow them through a permutation, and
+  /// lose the name of an axis that is reduced.
+  pub(super) fn with_axis_names(mut self, names: [&'static str;N]) -> Tensor<T,N>
+  {
+    for (itr,name) in names.iter().enumerate()
+    {
+      if names[..itr].contains(name) { panic!("The names of the axes of a tensor must be distinct.") }
+    }
+    self.names=Some(names);
+    self
+  }
+
+  /// Names of the axes, unless they are unnamed.
+  pub(super) fn axis_names(&self) -> Option<[&'static str;N]>
+  {
+    self.names
+  }
+
+  /// Index of the axis named `name`, failing unless there is such an axis.
+  pub(super) fn axis(&self, name: &str) -> Result<Idx,TensorError>
+  {
+    self.names.and_then(|names| names.iter().position(|&axis| axis==name))
+      .ok_or_else(|| TensorError::UnknownAxis{name:String::from(name)})
+  }
+
+  /// Names of the axes of the result of an element-wise operation between `self` and `rhs`: those of
+  /// either if the other is unnamed, failing if both are named differently.
+  pub(super) fn merge_names(&self, rhs: &Tensor<T,N>) -> Result<Option<[&'static str;N]>,TensorError>
+  {
+    match (self.names,rhs.names)
+    {
+      (Some(lhs),Some(rhs)) if lhs!=rhs => Err(TensorError::AxisNames{lhs:lhs.to_vec(),rhs:rhs.to_vec()}),
+      (lhs,rhs) => Ok(lhs.or(rhs)),
+    }
+  }
+
+  /// Names `self` with the names of `rhs` if it is unnamed, panicking if both are named differently.
+  pub(super) fn adopt_names(&mut self, rhs: &Tensor<T,N>)
+  {
+    match self.merge_names(rhs)
+    {
+      Ok(names) => self.names=names,
+      Err(_) => panic!("The axes of two tensors must have the same names to combine them."),
+    }
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Variant of `reduce_axis` along the axis named `name`.
+  pub(super) fn reduce_axis_named<F,const M: Idx>(&self, name: &str, init: T, f: F) -> Result<Tensor<T,M>,TensorError>
+  where F: FnMut(T,&T) -> T
+  {
+    Ok(self.reduce_axis(self.axis(name)?,init,f))
+  }
+
+  /// Variant of `sum_axis` along the axis named `name`.
+  pub(super) fn sum_axis_named<const M: Idx>(&self, name: &str) -> Result<Tensor<T,M>,TensorError>
+  {
+    Ok(self.sum_axis(self.axis(name)?))
+  }
+
+  /// Variant of `sum_axis_keepdims` along the axis named `name`.
+  pub(super) fn sum_axis_keepdims_named(&self, name: &str) -> Result<Tensor<T,N>,TensorError>
+  {
+    Ok(self.sum_axis_keepdims(self.axis(name)?))
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Variant of `max_axis` along the axis named `name`.
+  pub(super) fn max_axis_named<const M: Idx>(&self, name: &str) -> Result<Tensor<T,M>,TensorError>
+  {
+    Ok(self.max_axis(self.axis(name)?))
+  }
+
+  /// Variant of `mean_axis_keepdims` along the axis named `name`.
+  pub(super) fn mean_axis_keepdims_named(&self, name: &str) -> Result<Tensor<T,N>,TensorError>
+  {
+    Ok(self.mean_axis_keepdims(self.axis(name)?))
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod names_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  const NAMES: [&str;3]=["time","channel","trial"];
+
+  fn names_test_signal() -> Tensor<f64,3>
+  {
+    Tensor::<f64,3>::from_fn([4,2,3],|[itr,jtr,ktr]| (6*itr+3*jtr+ktr) as f64).with_axis_names(NAMES)
+  }
+
+  #[rstest(name,expected,
+    case("time",Ok(0)),
+    case("channel",Ok(1)),
+    case("trial",Ok(2)),
+    case("space",Err(TensorError::UnknownAxis{name:String::from("space")})),
+    case("",Err(TensorError::UnknownAxis{name:String::new()})),
+  )]
+  fn names_test_axis(name: &str, expected: Result<Idx,TensorError>)
+  {
+    assert!(names_test_signal().axis(name)==expected);
+  }
+
+  #[rstest(name,case("time"),case("space"))]
+  fn names_test_axis_unnamed(name: &str)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::new([4,2,3]);
+    assert!(t.axis_names().is_none() && t.axis(name)==Err(TensorError::UnknownAxis{name:String::from(name)}));
+  }
+
+  #[rstest(names,case(["time","time","trial"]),case(["time","channel","time"]),case(["time","trial","trial"]))]
+  #[should_panic(expected="The names of the axes of a tensor must be distinct.")]
+  fn names_test_duplicate(names: [&'static str;3])
+  {
+    Tensor::<f64,3>::new([2,2,2]).with_axis_names(names);
+  }
+
+  #[rstest(axes,expected,
+    case([2,0,1],["trial","time","channel"]),
+    case([0,1,2],["time","channel","trial"]),
+    case([1,2,0],["channel","trial","time"]),
+    case([2,1,0],["trial","channel","time"]),
+  )]
+  fn names_test_permute(axes: Dim<3>, expected: [&'static str;3])
+  {
+    let permuted: Tensor<f64,3>=names_test_signal().permute(axes);
+    assert!(permuted.dim==[[4,2,3][axes[0]],[4,2,3][axes[1]],[4,2,3][axes[2]]] && permuted.axis_names()==Some(expected));
+  }
+
+  /// Reducing by name reduces the same axis whatever its position, compared with the reduction of the
+  /// tensor before it is permuted.
+  #[rstest(axes,name,reduce,reference,
+    case([2,0,1],"time",|t: &Tensor<f64,3>, name: &str| t.sum_axis_named::<2>(name),|t: &Tensor<f64,3>, axis: Idx| t.sum_axis::<2>(axis)),
+    case([2,0,1],"trial",|t: &Tensor<f64,3>, name: &str| t.max_axis_named::<2>(name),|t: &Tensor<f64,3>, axis: Idx| t.max_axis::<2>(axis)),
+    case([0,1,2],"channel",|t: &Tensor<f64,3>, name: &str| t.sum_axis_named::<2>(name),|t: &Tensor<f64,3>, axis: Idx| t.sum_axis::<2>(axis)),
+    case([1,2,0],"time",|t: &Tensor<f64,3>, name: &str| t.max_axis_named::<2>(name),|t: &Tensor<f64,3>, axis: Idx| t.max_axis::<2>(axis)),
+    case([2,1,0],"channel",|t: &Tensor<f64,3>, name: &str| t.reduce_axis_named::<_,2>(name,0.0,|sum,&elem| sum+elem),|t: &Tensor<f64,3>, axis: Idx| t.sum_axis::<2>(axis)),
+  )]
+  fn names_test_reduce(axes: Dim<3>, name: &str, reduce: fn(&Tensor<f64,3>,&str) -> Result<Tensor<f64,2>,TensorError>, reference: fn(&Tensor<f64,3>,Idx) -> Tensor<f64,2>)
+  {
+    let t: Tensor<f64,3>=names_test_signal();
+    let permuted: Tensor<f64,3>=t.permute(axes);
+    let res: Tensor<f64,2>=reduce(&permuted,name).unwrap();
+    let expected: Tensor<f64,2>=reference(&t,t.axis(name).unwrap());
+
+    let names: [&str;2]=res.axis_names().unwrap();
+    assert!(names.iter().copied().eq(permuted.axis_names().unwrap().iter().copied().filter(|&axis| axis!=name)));
+    assert!(expected.axis_names().unwrap().iter().copied().eq(NAMES.iter().copied().filter(|&axis| axis!=name)));
+    let swapped: bool=names!=expected.axis_names().unwrap();
+    assert!(res.indexed_iter().all(|([itr,jtr],&elem)| elem==if swapped { expected[[jtr,itr]] } else { expected[[itr,jtr]] }));
+  }
+
+  #[rstest(axes,name,keepdims,
+    case([2,0,1],"channel",|t: &Tensor<f64,3>, name: &str| t.mean_axis_keepdims_named(name)),
+    case([0,1,2],"time",|t: &Tensor<f64,3>, name: &str| t.mean_axis_keepdims_named(name)),
+    case([1,2,0],"trial",|t: &Tensor<f64,3>, name: &str| t.sum_axis_keepdims_named(name)),
+    case([2,1,0],"time",|t: &Tensor<f64,3>, name: &str| t.sum_axis_keepdims_named(name)),
+  )]
+  fn names_test_reduce_keepdims(axes: Dim<3>, name: &str, keepdims: fn(&Tensor<f64,3>,&str) -> Result<Tensor<f64,3>,TensorError>)
+  {
+    let permuted: Tensor<f64,3>=names_test_signal().permute(axes);
+    let res: Tensor<f64,3>=keepdims(&permuted,name).unwrap();
+    let axis: Idx=permuted.axis(name).unwrap();
+    assert!(res.dim[axis]==1 && (0..3).all(|itr| itr==axis || res.dim[itr]==permuted.dim[itr]));
+    assert!(res.axis_names()==permuted.axis_names());
+  }
+
+  #[rstest(reduce,
+    case(|t: &Tensor<f64,3>, name: &str| t.sum_axis_named::<2>(name).err()),
+    case(|t: &Tensor<f64,3>, name: &str| t.max_axis_named::<2>(name).err()),
+    case(|t: &Tensor<f64,3>, name: &str| t.sum_axis_keepdims_named(name).err()),
+    case(|t: &Tensor<f64,3>, name: &str| t.mean_axis_keepdims_named(name).err()),
+  )]
+  fn names_test_reduce_unknown(reduce: fn(&Tensor<f64,3>,&str) -> Option<TensorError>)
+  {
+    assert!(reduce(&names_test_signal().permute([2,0,1]),"space")==Some(TensorError::UnknownAxis{name:String::from("space")}));
+    assert!(reduce(&Tensor::<f64,3>::new([4,2,3]),"time")==Some(TensorError::UnknownAxis{name:String::from("time")}));
+  }
+
+  #[rstest(op,
+    case(|t: &Tensor<f64,3>| t.clone().axis_names()),
+    case(|t: &Tensor<f64,3>| t.map(|&elem| elem*2.0).axis_names()),
+    case(|t: &Tensor<f64,3>| t.clone().split_first_axis(1).0.axis_names()),
+    case(|t: &Tensor<f64,3>| t.clone().split_first_axis(1).1.axis_names()),
+    case(|t: &Tensor<f64,3>| t.cast::<f32>().axis_names()),
+    case(|t: &Tensor<f64,3>| t.try_cast::<u8>().unwrap().axis_names()),
+    case(|t: &Tensor<f64,3>| t.to_layout(Layout::ColMajor).try_cast::<i32>().unwrap().axis_names()),
+    case(|t: &Tensor<f64,3>| t.to_layout(Layout::ColMajor).axis_names()),
+  )]
+  fn names_test_propagation(op: fn(&Tensor<f64,3>) -> Option<[&'static str;3]>)
+  {
+    assert!(op(&names_test_signal())==Some(NAMES));
+  }
+
+  #[rstest(axes,case([2,0]),case([0,2]),case([1,0]))]
+  fn names_test_transpose(axes: [Idx;2])
+  {
+    let t: Tensor<f64,2>=names_test_signal().sum_axis_named::<2>(NAMES[3-axes[0]-axes[1]]).unwrap();
+    let names: [&str;2]=t.axis_names().unwrap();
+    assert!(t.transpose().axis_names()==Some([names[1],names[0]]));
+  }
+
+  /// A named and an unnamed operand adopt the names, in either order.
+  #[rstest(op,lhs_named,rhs_named,
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| lhs+rhs,true,false),
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| lhs*rhs,false,true),
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| lhs.clone()+rhs.clone(),true,true),
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| lhs+rhs,false,false),
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| rhs*lhs,false,true),
+  )]
+  fn names_test_binary(op: fn(&Tensor<f64,3>,&Tensor<f64,3>) -> Tensor<f64,3>, lhs_named: bool, rhs_named: bool)
+  {
+    let name=|t: Tensor<f64,3>, named: bool| if named { t.with_axis_names(NAMES) } else { t };
+    let lhs: Tensor<f64,3>=name(Tensor::<f64,3>::from_fn([4,2,3],|_| 1.0),lhs_named);
+    let rhs: Tensor<f64,3>=name(Tensor::<f64,3>::from_fn([4,2,3],|_| 2.0),rhs_named);
+    assert!(op(&lhs,&rhs).axis_names()==if lhs_named || rhs_named { Some(NAMES) } else { None });
+  }
+
+  /// Bitwise operators name their results alike.
+  #[rstest(op,lhs_named,rhs_named,
+    case(|lhs: Tensor<u8,3>, rhs: Tensor<u8,3>| lhs&rhs,false,true),
+    case(|lhs: Tensor<u8,3>, rhs: Tensor<u8,3>| lhs^rhs,true,false),
+    case(|lhs: Tensor<u8,3>, rhs: Tensor<u8,3>| lhs|rhs,true,true),
+    case(|lhs: Tensor<u8,3>, rhs: Tensor<u8,3>| lhs<<&rhs,false,false),
+    case(|lhs: Tensor<u8,3>, rhs: Tensor<u8,3>| lhs>>rhs,false,true),
+  )]
+  fn names_test_binary_bitwise(op: fn(Tensor<u8,3>,Tensor<u8,3>) -> Tensor<u8,3>, lhs_named: bool, rhs_named: bool)
+  {
+    let name=|t: Tensor<u8,3>, named: bool| if named { t.with_axis_names(NAMES) } else { t };
+    let bits: Tensor<u8,3>=Tensor::<u8,3>::from_fn([4,2,3],|[itr,jtr,ktr]| (itr+jtr+ktr) as u8);
+    assert!(op(name(bits.clone(),lhs_named),name(bits,rhs_named)).axis_names()==if lhs_named || rhs_named { Some(NAMES) } else { None });
+  }
+
+  /// Combinations of masks name their results alike.
+  #[rstest(op,lhs_named,rhs_named,
+    case(|lhs: &Tensor<bool,3>, rhs: &Tensor<bool,3>| lhs.and(rhs),false,true),
+    case(|lhs: &Tensor<bool,3>, rhs: &Tensor<bool,3>| lhs.or(rhs),true,false),
+    case(|lhs: &Tensor<bool,3>, rhs: &Tensor<bool,3>| lhs.xor(rhs),true,true),
+    case(|lhs: &Tensor<bool,3>, rhs: &Tensor<bool,3>| lhs.and(rhs),false,false),
+  )]
+  fn names_test_binary_mask(op: fn(&Tensor<bool,3>,&Tensor<bool,3>) -> Tensor<bool,3>, lhs_named: bool, rhs_named: bool)
+  {
+    let name=|t: Tensor<bool,3>, named: bool| if named { t.with_axis_names(NAMES) } else { t };
+    let mask: Tensor<bool,3>=Tensor::<bool,3>::from_fn([4,2,3],|[itr,jtr,ktr]| (itr+jtr+ktr)%2==0);
+    assert!(op(&name(mask.clone(),lhs_named),&name(mask,rhs_named)).axis_names()==if lhs_named || rhs_named { Some(NAMES) } else { None });
+  }
+
+  #[rstest(lhs,rhs,expected,
+    case(Some(NAMES),Some(["trial","channel","time"]),Err(TensorError::AxisNames{lhs:vec!["time","channel","trial"],rhs:vec!["trial","channel","time"]})),
+    case(Some(NAMES),Some(["time","channel","space"]),Err(TensorError::AxisNames{lhs:vec!["time","channel","trial"],rhs:vec!["time","channel","space"]})),
+    case(Some(NAMES),Some(NAMES),Ok(Some(NAMES))),
+    case(None,Some(NAMES),Ok(Some(NAMES))),
+    case(Some(NAMES),None,Ok(Some(NAMES))),
+    case(None,None,Ok(None)),
+  )]
+  fn names_test_merge_names(lhs: Option<[&'static str;3]>, rhs: Option<[&'static str;3]>, expected: Result<Option<[&'static str;3]>,TensorError>)
+  {
+    let name=|names: Option<[&'static str;3]>| match names
+    {
+      Some(names) => Tensor::<f64,3>::new([4,2,3]).with_axis_names(names),
+      None => Tensor::<f64,3>::new([4,2,3]),
+    };
+    assert!(name(lhs).merge_names(&name(rhs))==expected);
+  }
+
+  #[rstest(op,
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| lhs+rhs),
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| rhs*lhs),
+    case(|lhs: &Tensor<f64,3>, rhs: &Tensor<f64,3>| lhs.clone()*rhs.clone()),
+  )]
+  #[should_panic(expected="The axes of two tensors must have the same names to combine them.")]
+  fn names_test_binary_conflict(op: fn(&Tensor<f64,3>,&Tensor<f64,3>) -> Tensor<f64,3>)
+  {
+    let _=op(&names_test_signal(),&Tensor::<f64,3>::new([4,2,3]).with_axis_names(["trial","channel","time"]));
+  }
+
+  #[rstest(op,
+    case(|lhs: Tensor<u8,2>, rhs: Tensor<u8,2>| lhs|rhs),
+    case(|lhs: Tensor<u8,2>, rhs: Tensor<u8,2>| lhs&rhs),
+    case(|lhs: Tensor<u8,2>, rhs: Tensor<u8,2>| lhs<<rhs),
+  )]
+  #[should_panic(expected="The axes of two tensors must have the same names to combine them.")]
+  fn names_test_binary_conflict_bitwise(op: fn(Tensor<u8,2>,Tensor<u8,2>) -> Tensor<u8,2>)
+  {
+    let _=op(Tensor::<u8,2>::new([2,3]).with_axis_names(["row","col"]),Tensor::<u8,2>::new([2,3]).with_axis_names(["col","row"]));
+  }
+
+  #[rstest(op,
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.and(rhs)),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.or(rhs)),
+    case(|lhs: &Tensor<bool,2>, rhs: &Tensor<bool,2>| lhs.xor(rhs)),
+  )]
+  #[should_panic(expected="The axes of two tensors must have the same names to combine them.")]
+  fn names_test_binary_conflict_mask(op: fn(&Tensor<bool,2>,&Tensor<bool,2>) -> Tensor<bool,2>)
+  {
+    let mask: Tensor<bool,2>=Tensor::<bool,2>::from_fn([2,3],|_| true);
+    let _=op(&mask.clone().with_axis_names(["row","col"]),&mask.with_axis_names(["col","row"]));
+  }
+}
@@ -0,0 +1,337 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::convert::TryInto;
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+
+/// Magic string that every .npy file starts with.
+const MAGIC: &[u8;6]=b"\x93NUMPY";
+
+/// Element types that can be stored in .npy files, as little-endian bytes.
+pub(super) trait NpyElement: Sized
+{
+  /// Type descriptor of the element in the header of an .npy file.
+  const DESCR: &'static str;
+  /// Number of bytes of an element.
+  const SIZE: Idx;
+  fn from_le_slice(bytes: &[u8]) -> Self;
+  fn extend_le(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_npy_element {
+  ($($type:ty => $descr:literal),*) => {
+    $(
+      impl NpyElement for $type
+      {
+        const DESCR: &'static str=$descr;
+        const SIZE: Idx=core::mem::size_of::<$type>();
+        fn from_le_slice(bytes: &[u8]) -> $type { <$type>::from_le_bytes(bytes.try_into().unwrap()) }
+        fn extend_le(&self, out: &mut Vec<u8>) { out.extend_from_slice(&self.to_le_bytes()); }
+      }
+    )*
+  };
+}
+
+impl_npy_element!(f32 => "<f4",f64 => "<f8",i8 => "|i1",i16 => "<i2",i32 => "<i4",i64 => "<i8",u8 => "|u1",u16 => "<u2",u32 => "<u4",u64 => "<u8");
+#[cfg(feature="half")]
+impl_npy_element!(half::f16 => "<f2");
+
+/// Header of an .npy file.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) struct NpyHeader
+{
+  pub(super) descr: String,
+  pub(super) fortran_order: bool,
+  pub(super) shape: Vec<Idx>,
+}
+
+impl NpyHeader
+{
+  /// Reads the header of an .npy file of any version from `r`, leaving it at the first byte of the
+  /// elements.
+  pub(super) fn read<R>(r: &mut R) -> Result<NpyHeader,TensorError>
+  where R: Read
+  {
+    NpyHeader::read_len(r).map(|(header,_)| header)
+  }
+
+  /// Variant of `read` that also returns the number of bytes read, the offset of the first element.
+  pub(super) fn read_len<R>(r: &mut R) -> Result<(NpyHeader,Idx),TensorError>
+  where R: Read
+  {
+    let mut preamble: [u8;8]=[0;8];
+    r.read_exact(&mut preamble).map_err(io_error_at(0))?;
+    if preamble[..6]!=MAGIC[..] { return Err(TensorError::NpyFormat); }
+
+    // Version 1 stores the length of the header in two bytes, later versions in four.
+    let (len,start): (Idx,Idx)=match preamble[6]
+    {
+      1 =>
+      {
+        let mut len: [u8;2]=[0;2];
+        r.read_exact(&mut len).map_err(io_error_at(8))?;
+        (u16::from_le_bytes(len) as Idx,10)
+      },
+      2 | 3 =>
+      {
+        let mut len: [u8;4]=[0;4];
+        r.read_exact(&mut len).map_err(io_error_at(8))?;
+        (u32::from_le_bytes(len) as Idx,12)
+      },
+      _ => return Err(TensorError::NpyFormat),
+    };
+    let mut header: Vec<u8>=vec![0;len];
+    r.read_exact(&mut header).map_err(io_error_at(start))?;
+    let header: String=String::from_utf8(header).map_err(|_| TensorError::NpyFormat)?;
+
+    let descr: &str=npy_value(&header,"descr").and_then(|value| value.strip_prefix('\'')).and_then(|value| value.split('\'').next())
+      .ok_or(TensorError::NpyFormat)?;
+    let fortran_order: bool=match npy_value(&header,"fortran_order")
+    {
+      Some(value) if value.starts_with("True") => true,
+      Some(value) if value.starts_with("False") => false,
+      _ => return Err(TensorError::NpyFormat),
+    };
+    let shape: Vec<Idx>=npy_value(&header,"shape").and_then(|value| value.strip_prefix('(')).and_then(|value| value.split(')').next())
+      .ok_or(TensorError::NpyFormat)?
+      .split(',')
+      .map(|dim| dim.trim())
+      .filter(|dim| !dim.is_empty())
+      .map(|dim| dim.parse().map_err(|_| TensorError::NpyFormat))
+      .collect::<Result<Vec<Idx>,TensorError>>()?;
+    Ok((NpyHeader{descr:String::from(descr),fortran_order:fortran_order,shape:shape},start+len))
+  }
+
+  /// Writes the header as version 1.0 of the format, padded so that the elements start at a multiple
+  /// of 64 bytes.
+  pub(super) fn write<W>(&self, w: &mut W) -> Result<(),TensorError>
+  where W: Write
+  {
+    let shape: String=match self.shape.len()
+    {
+      1 => format!("({},)",self.shape[0]),
+      _ => format!("({})",self.shape.iter().map(|dim| format!("{}",dim)).collect::<Vec<String>>().join(", ")),
+    };
+    let order: &str=if self.fortran_order { "True" } else { "False" };
+    let mut header: String=format!("{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",self.descr,order,shape);
+    let unpadded: Idx=MAGIC.len()+4+header.len()+1;
+    header.push_str(&" ".repeat((64-unpadded%64)%64));
+    header.push('\n');
+
+    w.write_all(MAGIC).map_err(io_error)?;
+    w.write_all(&[1,0]).map_err(io_error)?;
+    w.write_all(&(header.len() as u16).to_le_bytes()).map_err(io_error)?;
+    w.write_all(header.as_bytes()).map_err(io_error)
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: NpyElement + Clone
+{
+  /// Writes the tensor to `w` in the .npy format, with the elements in row-major order whatever the
+  /// layout of the tensor.
+  pub(super) fn write_npy<W>(&self, mut w: W) -> Result<(),TensorError>
+  where W: Write
+  {
+    let header: NpyHeader=NpyHeader{descr:String::from(T::DESCR),fortran_order:false,shape:self.dim.to_vec()};
+    header.write(&mut w)?;
+
+    let mut bytes: Vec<u8>=Vec::with_capacity(self.len()*T::SIZE);
+    self.to_layout(Layout::RowMajor).data.iter().for_each(|elem| elem.extend_le(&mut bytes));
+    w.write_all(&bytes).map_err(io_error)
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: NpyElement
+{
+  /// Folds `f` over the rows of the matrix stored in .npy format in `reader`, starting from `init`,
+  /// `chunk_rows` rows at a time, so that only one chunk is ever held in memory. The last chunk holds
+  /// the rows that remain and so may be shorter.
+  ///
+  /// Fails if `chunk_rows` is zero, if the header is malformed, if the elements are not of type `T`,
+  /// or are not a matrix in row-major order of no more bytes than can be addressed, or if reading fails, including when the file holds fewer
+  /// elements than its header states, with the offset of the first byte of the chunk that could not be
+  /// read.
+  pub(super) fn stream_reduce_npy<R,A,F>(mut reader: R, chunk_rows: Idx, init: A, mut f: F) -> Result<A,TensorError>
+  where R: Read, F: FnMut(A,&Tensor<T,2>) -> A
+  {
+    if chunk_rows==0 { return Err(TensorError::ZeroFactor{axis:0}); }
+    let (header,offset): (NpyHeader,Idx)=NpyHeader::read_len(&mut reader)?;
+    if header.descr!=T::DESCR { return Err(TensorError::NpyDtype{descr:header.descr}); }
+    if header.shape.len()!=2 || header.fortran_order { return Err(TensorError::NpyShape{shape:header.shape}); }
+
+    let (rows,cols): (Idx,Idx)=(header.shape[0],header.shape[1]);
+    if rows.checked_mul(cols).and_then(|len| len.checked_mul(T::SIZE)).and_then(|len| len.checked_add(offset)).is_none()
+    {
+      return Err(TensorError::NpyShape{shape:header.shape});
+    }
+    let mut acc: A=init;
+    let mut bytes: Vec<u8>=Vec::new();
+    for start in (0..rows).step_by(chunk_rows)
+    {
+      let len: Idx=chunk_rows.min(rows-start);
+      bytes.resize(len*cols*T::SIZE,0);
+      reader.read_exact(&mut bytes).map_err(io_error_at(offset+start*cols*T::SIZE))?;
+      let chunk: Tensor<T,2>=Tensor::<T,2>::from_vec([len,cols],bytes.chunks_exact(T::SIZE).map(T::from_le_slice).collect())?;
+      acc=f(acc,&chunk);
+    }
+    Ok(acc)
+  }
+}
+
+/// Value of the entry `key` in the header dictionary `header`, with everything after it.
+fn npy_value<'a>(header: &'a str, key: &str) -> Option<&'a str>
+{
+  let start: Idx=header.find(&format!("'{}'",key))?+key.len()+2;
+  header[start..].trim_start().strip_prefix(':').map(|value| value.trim_start())
+}
+
+pub(super) fn io_error(err: io::Error) -> TensorError
+{
+  TensorError::io(err,None)
+}
+
+/// Conversion of an I/O error in a read that started at byte `offset` of the input.
+pub(super) fn io_error_at(offset: Idx) -> impl Fn(io::Error) -> TensorError
+{
+  move |err| TensorError::io(err,Some(offset))
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod npy_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use super::super::Dim;
+  use super::super::error::TensorErrorKind;
+
+  use std::error::Error;
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn npy_test_write_npy(layout: Layout)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as i32-1);
+    let mut bytes: Vec<u8>=Vec::new();
+    t.to_layout(layout).write_npy(&mut bytes).unwrap();
+
+    let header: &[u8]=b"{'descr': '<i4', 'fortran_order': False, 'shape': (2, 3), }";
+    assert!(bytes[..10]==*b"\x93NUMPY\x01\x00\x76\x00" && bytes[10..10+header.len()]==*header);
+    assert!(bytes[10+header.len()..127].iter().all(|&byte| byte==b' ') && bytes[127]==b'\n');
+    assert!(bytes[128..]==[255,255,255,255,0,0,0,0,1,0,0,0,2,0,0,0,3,0,0,0,4,0,0,0]);
+
+    let header: NpyHeader=NpyHeader::read(&mut &bytes[..]).unwrap();
+    assert!(header==NpyHeader{descr:String::from("<i4"),fortran_order:false,shape:vec![2,3]});
+  }
+
+  #[rstest(len,expected_len,case(0,128),case(5,133),case(200,328))]
+  fn npy_test_write_npy_len(len: Idx, expected_len: usize)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<u8,1>::new([len]).write_npy(&mut bytes).unwrap();
+    assert!(NpyHeader::read(&mut &bytes[..]).unwrap().shape==[len] && bytes.len()==expected_len);
+  }
+
+  #[rstest(dim,rows,expected_chunks,
+    case([10,3],1,vec![1;10]),
+    case([10,3],4,vec![4,4,2]),
+    case([10,3],10,vec![10]),
+    case([10,3],25,vec![10]),
+    case([1,4],3,vec![1]),
+    case([0,3],4,vec![]),
+  )]
+  fn npy_test_stream_reduce_npy(dim: Dim<2>, rows: Idx, expected_chunks: Vec<Idx>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64*0.5-4.0);
+    let mut bytes: Vec<u8>=Vec::new();
+    t.write_npy(&mut bytes).unwrap();
+
+    // The chunks are the consecutive rows of the whole, so their elements follow its own.
+    let (elems,chunks): (Vec<f64>,Vec<Idx>)=Tensor::<f64,2>::stream_reduce_npy(&bytes[..],rows,(Vec::new(),Vec::new()),|(mut elems,mut chunks),chunk| {
+      assert!(chunk.dim[1]==dim[1]);
+      elems.extend(chunk.iter());
+      chunks.push(chunk.dim[0]);
+      (elems,chunks)
+    }).unwrap();
+    assert!(chunks==expected_chunks);
+    assert!(elems.iter().eq(t.iter()));
+  }
+
+  #[test]
+  #[cfg(feature="half")]
+  fn npy_test_f16()
+  {
+    use half::f16;
+
+    let t: Tensor<f16,2>=Tensor::<f16,2>::from_fn([3,2],|[itr,jtr]| f16::from_f32((2*itr+jtr) as f32*0.25-0.5));
+    let mut bytes: Vec<u8>=Vec::new();
+    t.to_layout(Layout::ColMajor).write_npy(&mut bytes).unwrap();
+    assert!(NpyHeader::read(&mut &bytes[..]).unwrap()==NpyHeader{descr:String::from("<f2"),fortran_order:false,shape:vec![3,2]});
+    assert!(bytes[128..130]==f16::from_f32(-0.5).to_le_bytes() && bytes.len()==128+6*2);
+
+    let rows: Vec<Tensor<f16,2>>=Tensor::<f16,2>::stream_reduce_npy(&bytes[..],2,Vec::new(),|mut rows,chunk| { rows.push(chunk.clone()); rows }).unwrap();
+    assert!(rows.len()==2 && rows[0].iter().chain(rows[1].iter()).eq(t.iter()));
+    assert!(Tensor::<f32,2>::stream_reduce_npy(&bytes[..],2,0,|count,_| count+1).err()==Some(TensorError::NpyDtype{descr:String::from("<f2")}));
+  }
+
+  #[rstest(descr,shape,rows,expected,
+    case("<f4",vec![3,3],4,TensorError::NpyDtype{descr:String::from("<f4")}),
+    case("<i8",vec![3,3],4,TensorError::NpyDtype{descr:String::from("<i8")}),
+    case("<f8",vec![3,3],0,TensorError::ZeroFactor{axis:0}),
+    case("<f8",vec![2,2,2],4,TensorError::NpyShape{shape:vec![2,2,2]}),
+    case("<f8",vec![6],4,TensorError::NpyShape{shape:vec![6]}),
+    case("<f8",vec![4294967296,4294967297],4,TensorError::NpyShape{shape:vec![4294967296,4294967297]}),
+    case("<f8",vec![1,2305843009213693953],4,TensorError::NpyShape{shape:vec![1,2305843009213693953]}),
+  )]
+  fn npy_test_stream_reduce_npy_header(descr: &str, shape: Vec<Idx>, rows: Idx, expected: TensorError)
+  {
+    // The header is refused before any data is read, even for shapes of more elements, or of more bytes, than can
+    // be addressed.
+    let mut bytes: Vec<u8>=Vec::new();
+    NpyHeader{descr:String::from(descr),fortran_order:false,shape:shape}.write(&mut bytes).unwrap();
+    bytes.extend_from_slice(&1.5f64.to_le_bytes());
+
+    assert!(Tensor::<f64,2>::stream_reduce_npy(&bytes[..],rows,0,|count,_| count+1).err()==Some(expected));
+  }
+
+  #[rstest(bytes,expected,
+    case(b"\x93NUMPY\x01\x00\x3c\x00{'descr': '<f8', 'fortran_order': True, 'shape': (1, 1), }\n\0\0\0\0\0\0\0\0",TensorError::NpyShape{shape:vec![1,1]}),
+    case(b"\x93NUMPY\x01\x00\x3b\x00{'descr': <f8, 'fortran_order': False, 'shape': (1, 1), }\n\0\0\0\0\0\0\0\0",TensorError::NpyFormat),
+    case(b"NUMPY\x01\x00\x3c\x00{'descr': '<f8', 'fortran_order': False, 'shape': (1, 1), }\n\0\0\0\0\0\0\0\0",TensorError::NpyFormat),
+    case(b"\x93NUMPY\x09\x00\x3c\x00{'descr': '<f8', 'fortran_order': False, 'shape': (1, 1), }\n\0\0\0\0\0\0\0\0",TensorError::NpyFormat),
+  )]
+  fn npy_test_stream_reduce_npy_malformed(bytes: &[u8], expected: TensorError)
+  {
+    let err: TensorError=Tensor::<f64,2>::stream_reduce_npy(bytes,4,0,|count,_| count+1).unwrap_err();
+    assert!(err.kind()==expected.kind() && err.source().is_none());
+    assert!(err==expected);
+  }
+
+  #[rstest(len,offset,case(367,320),case(300,224),case(128,128),case(50,10),case(9,8))]
+  fn npy_test_truncated(len: usize, offset: Idx)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<f64,2>::from_fn([10,3],|[itr,jtr]| (3*itr+jtr) as f64).write_npy(&mut bytes).unwrap();
+
+    // The offset is that of the read cut short: a chunk of rows, the header or its length.
+    let err: TensorError=Tensor::<f64,2>::stream_reduce_npy(&bytes[..len],4,0,|count,_| count+1).unwrap_err();
+    assert!(err.kind()==TensorErrorKind::UnexpectedEof && err.source().is_some());
+    assert!(matches!(err,TensorError::Io{kind:io::ErrorKind::UnexpectedEof,offset:Some(at),..} if at==offset));
+    assert!(format!("{}",err).starts_with(&format!("Reading or writing the tensor failed at byte {}: ",offset)));
+  }
+}
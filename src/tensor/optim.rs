@@ -0,0 +1,223 @@
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Plain gradient descent step, subtracting `lr*grad` from the parameters `self` in place.
+  pub(super) fn sgd_step(&mut self, grad: &Tensor<T,N>, lr: T) -> Result<(),TensorError>
+  {
+    check_dim(self,grad)?;
+    self.axpy(T::default()-lr,grad);
+    Ok(())
+  }
+
+  /// Gradient descent step with momentum, which first updates `velocity` to `momentum*velocity+grad`
+  /// and then subtracts `lr*velocity` from the parameters `self` in place.
+  ///
+  /// The velocity starts out as zeros of the dimensions of the parameters and is carried from one step
+  /// to the next.
+  pub(super) fn sgd_momentum_step(&mut self, grad: &Tensor<T,N>, velocity: &mut Tensor<T,N>, lr: T, momentum: T) -> Result<(),TensorError>
+  {
+    check_dim(self,grad)?;
+    check_dim(self,velocity)?;
+    velocity.data.iter_mut().for_each(|elem| *elem*=momentum);
+    velocity.axpy(T::from_f64(1.0),grad);
+    self.axpy(T::default()-lr,velocity);
+    Ok(())
+  }
+}
+
+/// State of the Adam optimiser for parameters of dimensions `dim`, holding the running estimates of the
+/// first and second moments of the gradient.
+#[cfg(feature="std")]
+#[derive(Debug,Clone)]
+pub(super) struct AdamState<T,const N: Idx>
+{
+  /// Decay rate of the estimate of the first moment.
+  pub(super) beta1: T,
+  /// Decay rate of the estimate of the second moment.
+  pub(super) beta2: T,
+  /// Term added to the root of the second moment to keep the step finite.
+  pub(super) epsilon: T,
+  m: Tensor<T,N>,
+  v: Tensor<T,N>,
+  /// `beta1` and `beta2` raised to the number of steps taken, for the bias correction.
+  beta_pows: (T,T),
+}
+
+#[cfg(feature="std")]
+impl<T,const N: Idx> AdamState<T,N>
+where T: Float
+{
+  /// State for parameters of dimensions `dim` with the customary decay rates of 0.9 and 0.999 and an
+  /// `epsilon` of 1e-8.
+  pub(super) fn new(dim: [Idx;N]) -> AdamState<T,N>
+  {
+    AdamState::<T,N>::with_hyperparameters(dim,T::from_f64(0.9),T::from_f64(0.999),T::from_f64(1e-8))
+  }
+
+  /// State for parameters of dimensions `dim` with the given decay rates and `epsilon`.
+  pub(super) fn with_hyperparameters(dim: [Idx;N], beta1: T, beta2: T, epsilon: T) -> AdamState<T,N>
+  {
+    let one: T=T::from_f64(1.0);
+    AdamState{beta1:beta1,beta2:beta2,epsilon:epsilon,m:Tensor::<T,N>::new(dim),v:Tensor::<T,N>::new(dim),beta_pows:(one,one)}
+  }
+
+  /// Adam step, updating the moment estimates with `grad` and moving the parameters `param` in place
+  /// by `lr` times the bias corrected first moment over the root of the bias corrected second moment.
+  pub(super) fn adam_step(&mut self, param: &mut Tensor<T,N>, grad: &Tensor<T,N>, lr: T) -> Result<(),TensorError>
+  {
+    check_dim(param,grad)?;
+    check_dim(param,&self.m)?;
+
+    // The moments follow the layout of the parameters, so the update is a single pass over the buffers.
+    let grad: Tensor<T,N>=grad.to_layout(param.layout);
+    if self.m.layout!=param.layout
+    {
+      self.m=self.m.to_layout(param.layout);
+      self.v=self.v.to_layout(param.layout);
+    }
+
+    let one: T=T::from_f64(1.0);
+    self.beta_pows=(self.beta_pows.0*self.beta1,self.beta_pows.1*self.beta2);
+    let (corr1,corr2): (T,T)=(one-self.beta_pows.0,one-self.beta_pows.1);
+    let (beta1,beta2,epsilon): (T,T,T)=(self.beta1,self.beta2,self.epsilon);
+
+    let moments=self.m.data.iter_mut().zip(self.v.data.iter_mut());
+    for ((elem,&g),(m,v)) in param.data.iter_mut().zip(grad.data.iter()).zip(moments)
+    {
+      *m=beta1**m+(one-beta1)*g;
+      *v=beta2**v+(one-beta2)*g*g;
+      *elem=*elem-lr*(*m/corr1)/((*v/corr2).sqrt()+epsilon);
+    }
+    Ok(())
+  }
+}
+
+/// Fails unless `lhs` and `rhs` are of the same dimensions.
+fn check_dim<T,const N: Idx>(lhs: &Tensor<T,N>, rhs: &Tensor<T,N>) -> Result<(),TensorError>
+{
+  if lhs.dim!=rhs.dim { return Err(TensorError::DimMismatch{lhs:lhs.dim.to_vec(),rhs:rhs.dim.to_vec()}); }
+  Ok(())
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod optim_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  use super::super::Layout;
+
+  /// Gradient of the bowl `sum((itr+1)*(x[itr]-itr)^2)`, whose minimum lies at `x[itr]=itr`.
+  fn optim_test_bowl_grad(x: &Tensor<f64,1>) -> Tensor<f64,1>
+  {
+    Tensor::<f64,1>::from_fn(x.dim,|[itr]| 2.0*(itr+1) as f64*(x[itr]-itr as f64))
+  }
+
+  fn optim_test_err(x: &Tensor<f64,1>) -> f64
+  {
+    x.iter().enumerate().map(|(itr,elem)| (elem-itr as f64).abs()).fold(0.0,f64::max)
+  }
+
+  #[rstest(lr,steps,case(0.1,200),case(0.05,400),case(0.2,200))]
+  fn optim_test_sgd(lr: f64, steps: usize)
+  {
+    let mut x: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+    for _ in 0..steps
+    {
+      let grad: Tensor<f64,1>=optim_test_bowl_grad(&x);
+      x.sgd_step(&grad,lr).unwrap();
+    }
+    assert!(optim_test_err(&x)<1e-8);
+  }
+
+  #[test]
+  fn optim_test_sgd_step()
+  {
+    let mut single: Tensor<f64,1>=Tensor::<f64,1>::from_fn([2],|[itr]| itr as f64);
+    single.sgd_step(&Tensor::<f64,1>::from_fn([2],|_| 4.0),0.5).unwrap();
+    assert!(single.iter().copied().eq([-2.0,-1.0].iter().copied()));
+    assert!(single.sgd_step(&Tensor::<f64,1>::new([3]),0.1).err()==Some(TensorError::DimMismatch{lhs:vec![2],rhs:vec![3]}));
+  }
+
+  #[rstest(lr,momentum,steps,case(0.05,0.5,200),case(0.02,0.9,400),case(0.1,0.0,200))]
+  fn optim_test_sgd_momentum(lr: f64, momentum: f64, steps: usize)
+  {
+    let mut x: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+    let mut velocity: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+    for _ in 0..steps
+    {
+      let grad: Tensor<f64,1>=optim_test_bowl_grad(&x);
+      x.sgd_momentum_step(&grad,&mut velocity,lr,momentum).unwrap();
+    }
+    assert!(optim_test_err(&x)<1e-8);
+  }
+
+  /// The velocity accumulates the gradients, so a constant gradient moves the parameters ever faster.
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn optim_test_sgd_momentum_velocity(layout: Layout)
+  {
+    let mut x: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]);
+    let mut velocity: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]).to_layout(layout);
+    let grad: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr) as f64);
+    x.sgd_momentum_step(&grad,&mut velocity,1.0,0.5).unwrap();
+    x.sgd_momentum_step(&grad,&mut velocity,1.0,0.5).unwrap();
+    assert!(x==grad.map(|elem| -2.5*elem) && velocity==grad.map(|elem| 1.5*elem));
+
+    let mut wrong: Tensor<f64,2>=Tensor::<f64,2>::new([2,1]);
+    assert!(x.sgd_momentum_step(&grad,&mut wrong,1.0,0.5).err()==Some(TensorError::DimMismatch{lhs:vec![2,2],rhs:vec![2,1]}));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn optim_test_adam()
+  {
+    let mut x: Tensor<f64,1>=Tensor::<f64,1>::new([4]);
+    let mut state: AdamState<f64,1>=AdamState::<f64,1>::new([4]);
+    for _ in 0..2000
+    {
+      let grad: Tensor<f64,1>=optim_test_bowl_grad(&x);
+      state.adam_step(&mut x,&grad,0.01).unwrap();
+    }
+    assert!(optim_test_err(&x)<1e-3);
+  }
+
+  /// The bias correction makes the first step move every parameter by `lr` against its gradient,
+  /// whatever the decay rates.
+  #[rstest(beta1,beta2,lr,
+    case(0.8,0.9,0.1),
+    case(0.9,0.999,0.01),
+    case(0.0,0.5,1.0),
+  )]
+  #[cfg(feature="std")]
+  fn optim_test_adam_first_step(beta1: f64, beta2: f64, lr: f64)
+  {
+    let mut x: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]).to_layout(Layout::ColMajor);
+    let mut state: AdamState<f64,2>=AdamState::<f64,2>::with_hyperparameters([2,2],beta1,beta2,0.0);
+    let grad: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| if itr==jtr { 3.0 } else { -0.5 });
+    state.adam_step(&mut x,&grad,lr).unwrap();
+    assert!(x.iter().zip(grad.iter()).all(|(elem,g)| (elem+lr*g.signum()).abs()<1e-15));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn optim_test_adam_dims()
+  {
+    let mut x: Tensor<f64,2>=Tensor::<f64,2>::new([2,2]);
+    let mut state: AdamState<f64,2>=AdamState::<f64,2>::new([2,2]);
+    assert!(state.adam_step(&mut x,&Tensor::<f64,2>::new([1,4]),0.1).err()==Some(TensorError::DimMismatch{lhs:vec![2,2],rhs:vec![1,4]}));
+    let mut wrong: Tensor<f64,2>=Tensor::<f64,2>::new([4,1]);
+    assert!(state.adam_step(&mut wrong,&Tensor::<f64,2>::new([4,1]),0.1).err()==Some(TensorError::DimMismatch{lhs:vec![4,1],rhs:vec![2,2]}));
+  }
+}
@@ -0,0 +1,356 @@
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
+
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+/// Total order of the elements of a type, which for floats is that of `total_cmp`: negative NaN, the
+/// negative numbers, -0.0, 0.0, the positive numbers, then positive NaN.
+pub(super) trait TotalOrder
+{
+  fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl TotalOrder for f32
+{
+  fn total_cmp(&self, other: &f32) -> Ordering { f32::total_cmp(self,other) }
+}
+
+impl TotalOrder for f64
+{
+  fn total_cmp(&self, other: &f64) -> Ordering { f64::total_cmp(self,other) }
+}
+
+macro_rules! impl_total_order_int {
+  ($($type:ty),*) => {
+    $(
+      impl TotalOrder for $type
+      {
+        fn total_cmp(&self, other: &$type) -> Ordering { self.cmp(other) }
+      }
+    )*
+  };
+}
+
+impl_total_order_int!(i8,i16,i32,i64,u8,u16,u32,u64);
+
+/// Which end of a run of equal elements `Tensor::searchsorted` inserts an equal query at.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) enum Side
+{
+  /// Before the equal elements, at the first index whose element is not less than the query.
+  Left,
+  /// After the equal elements, at the first index whose element is greater than the query.
+  Right,
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar + TotalOrder
+{
+  /// Distinct elements in ascending order. Floats equal under `total_cmp` only are distinct, so
+  /// -0.0 and 0.0 are both kept.
+  pub(super) fn unique(&self) -> Tensor<T,1>
+  {
+    let counts: Vec<(T,Idx)>=self.value_counts();
+    Tensor::<T,1>::new_uninit([counts.len()]).init(counts.into_iter().map(|(elem,_)| elem))
+  }
+
+  /// Distinct elements in ascending order with the number of times each occurs.
+  pub(super) fn value_counts(&self) -> Vec<(T,Idx)>
+  {
+    self.group_sorted(|elem,first| elem.total_cmp(first)==Ordering::Equal)
+  }
+
+  /// Sorts the elements and counts the runs of them for which `same` holds with the first element of
+  /// the run.
+  fn group_sorted<F>(&self, same: F) -> Vec<(T,Idx)>
+  where F: Fn(&T,&T) -> bool
+  {
+    let mut sorted: Vec<T>=self.data.to_vec();
+    sorted.sort_unstable_by(|lhs,rhs| lhs.total_cmp(rhs));
+
+    let mut counts: Vec<(T,Idx)>=Vec::new();
+    for elem in sorted
+    {
+      match counts.last_mut()
+      {
+        Some((first,count)) if same(&elem,first) => *count+=1,
+        _ => counts.push((elem,1)),
+      }
+    }
+    counts
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float + TotalOrder
+{
+  /// Distinct elements in ascending order, where elements within `eps` above the smallest element of a
+  /// group are merged into that group and represented by its smallest element.
+  pub(super) fn unique_tol(&self, eps: T) -> Tensor<T,1>
+  {
+    let counts: Vec<(T,Idx)>=self.value_counts_tol(eps);
+    Tensor::<T,1>::new_uninit([counts.len()]).init(counts.into_iter().map(|(elem,_)| elem))
+  }
+
+  /// Groups of elements as for `unique_tol`, with the number of elements in each group.
+  pub(super) fn value_counts_tol(&self, eps: T) -> Vec<(T,Idx)>
+  {
+    self.group_sorted(|&elem,&first| elem.total_cmp(&first)==Ordering::Equal || elem-first<=eps)
+  }
+}
+
+impl<T> Tensor<T,1>
+where T: Scalar + TotalOrder
+{
+  /// The `k` largest elements in descending order under `total_cmp` with their indices, of which equal
+  /// elements keep the lower index first, failing if there are fewer than `k` elements. Positive NaN
+  /// ranks above every number.
+  ///
+  /// Only the `k` largest elements are sorted, for a cost linear in the length for small `k`.
+  pub(super) fn top_k(&self, k: Idx) -> Result<(Tensor<T,1>,Vec<Idx>),TensorError>
+  {
+    if k>self.dim[0] { return Err(TensorError::SelectionSize{k:k,len:self.dim[0]}); }
+    let ind: Vec<Idx>=top_k_indices(&self.data,k);
+    Ok((Tensor::<T,1>::from_fn([k],|[itr]| self.data[ind[itr]].clone()),ind))
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar + TotalOrder
+{
+  /// `top_k` of every lane along `axis`, as matrices of the elements and of their indices within their
+  /// lane, with `axis` of length `k`.
+  pub(super) fn top_k_axis(&self, axis: Idx, k: Idx) -> Result<(Tensor<T,2>,Tensor<Idx,2>),TensorError>
+  {
+    if axis>=2 { panic!("The axis must be less than the number of dimensions of the tensor.") }
+    if k>self.dim[axis] { return Err(TensorError::SelectionSize{k:k,len:self.dim[axis]}); }
+
+    let lane_ind=|lane: Idx, itr: Idx| if axis==0 { [itr,lane] } else { [lane,itr] };
+    let inds: Vec<Vec<Idx>>=(0..self.dim[1-axis]).map(|lane| {
+      let elems: Vec<T>=(0..self.dim[axis]).map(|itr| self[lane_ind(lane,itr)].clone()).collect();
+      top_k_indices(&elems,k)
+    }).collect();
+
+    let mut dim: [Idx;2]=self.dim;
+    dim[axis]=k;
+    let ind: Tensor<Idx,2>=Tensor::<Idx,2>::from_fn(dim,|[itr,jtr]| if axis==0 { inds[jtr][itr] } else { inds[itr][jtr] });
+    let top: Tensor<T,2>=Tensor::<T,2>::from_fn(dim,|[itr,jtr]| {
+      let (lane,pos): (Idx,Idx)=if axis==0 { (jtr,itr) } else { (itr,jtr) };
+      self[lane_ind(lane,inds[lane][pos])].clone()
+    });
+    Ok((top,ind))
+  }
+}
+
+impl<T> Tensor<T,1>
+where T: TotalOrder
+{
+  /// For every element of `values`, the index at which inserting it keeps `self` sorted in ascending
+  /// order under `total_cmp`, by binary search, with `side` deciding where among equal elements it goes.
+  ///
+  /// `self` must be sorted, which is only checked, by a panic, in debug builds.
+  pub(super) fn searchsorted(&self, values: &Tensor<T,1>, side: Side) -> Vec<Idx>
+  {
+    debug_assert!(self.data.windows(2).all(|pair| pair[0].total_cmp(&pair[1])!=Ordering::Greater),"The tensor must be sorted to search it.");
+
+    values.iter().map(|value| match side
+    {
+      Side::Left => self.data.partition_point(|elem| elem.total_cmp(value)==Ordering::Less),
+      Side::Right => self.data.partition_point(|elem| elem.total_cmp(value)!=Ordering::Greater),
+    }).collect()
+  }
+}
+
+/// Indices of the `k` largest of `elems` as for `top_k`, which must not exceed the number of elements.
+fn top_k_indices<T>(elems: &[T], k: Idx) -> Vec<Idx>
+where T: TotalOrder
+{
+  if k==0 { return Vec::new(); }
+  let cmp=|lhs: &Idx, rhs: &Idx| elems[*rhs].total_cmp(&elems[*lhs]).then(lhs.cmp(rhs));
+  let mut ind: Vec<Idx>=(0..elems.len()).collect();
+  ind.select_nth_unstable_by(k-1,cmp);
+  ind.truncate(k);
+  ind.sort_unstable_by(cmp);
+  ind
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod order_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn order_test_unique(layout: Layout)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,4],|[itr,jtr]| [[3,-1,3,0],[0,7,-1,3]][itr][jtr]).to_layout(layout);
+
+    assert!(t.unique().iter().copied().eq([-1,0,3,7].iter().copied()));
+    assert!(t.value_counts()==[(-1,2),(0,2),(3,3),(7,1)]);
+  }
+
+  #[test]
+  fn order_test_unique_float()
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([6],|[itr]| [1.5,f64::NAN,-0.0,0.0,1.5,f64::NEG_INFINITY][itr]);
+    let counts: Vec<(f64,usize)>=t.value_counts();
+    assert!(counts.len()==5);
+    assert!(counts[0]==(f64::NEG_INFINITY,1));
+    assert!(counts[1].0==0.0 && counts[1].0.is_sign_negative() && counts[1].1==1);
+    assert!(counts[2].0==0.0 && counts[2].0.is_sign_positive() && counts[2].1==1);
+    assert!(counts[3]==(1.5,2));
+    assert!(counts[4].0.is_nan() && counts[4].1==1);
+  }
+
+  /// NaN is never within tolerance of anything and so always makes its own group.
+  #[rstest(tol,len,case(1e-12,6),case(1e-4,5),case(1e-3,4),case(1.5,3))]
+  fn order_test_unique_tol(tol: f64, len: Idx)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([7],|[itr]| [0.3,0.1+0.2,1.0,0.2999,1.00005,2.0,f64::NAN][itr]);
+
+    assert!(t.unique().dim==[7]);
+    assert!(t.unique_tol(tol).dim==[len]);
+    assert!(t.value_counts_tol(tol).len()==len && t.value_counts_tol(tol).iter().map(|&(_,count)| count).sum::<usize>()==7);
+    assert!(t.value_counts_tol(tol)[len-1].0.is_nan());
+  }
+
+  #[test]
+  fn order_test_value_counts_tol()
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([7],|[itr]| [0.3,0.1+0.2,1.0,0.2999,1.00005,2.0,f64::NAN][itr]);
+
+    assert!(t.value_counts_tol(1e-3)[..3]==[(0.2999,3),(1.0,2),(2.0,1)]);
+  }
+
+  #[test]
+  fn order_test_top_k()
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([8],|[itr]| [0.5,-3.0,2.0,-3.0,2.0,7.5,f64::NAN,2.0][itr]);
+    let (top,ind): (Tensor<f64,1>,Vec<usize>)=t.top_k(4).unwrap();
+    assert!(top[0].is_nan() && top.iter().skip(1).copied().eq([7.5,2.0,2.0].iter().copied()) && ind==[6,5,2,4]);
+  }
+
+  /// Ties keep the lower index first however many of them make the cut.
+  #[rstest(k,
+    case(0),
+    case(1),
+    case(2),
+    case(3),
+    case(4),
+    case(5),
+    case(6),
+    case(7),
+  )]
+  fn order_test_top_k_ties(k: Idx)
+  {
+    let t: Tensor<i32,1>=Tensor::<i32,1>::from_fn([7],|[itr]| [4,1,4,9,1,4,1][itr]);
+    let mut sorted: Vec<usize>=(0..7).collect();
+    sorted.sort_by(|&lhs,&rhs| t[rhs].cmp(&t[lhs]));
+
+    let (top,ind): (Tensor<i32,1>,Vec<usize>)=t.top_k(k).unwrap();
+    assert!(top.dim==[k]);
+    assert!(ind==sorted[..k] && top.iter().copied().eq(ind.iter().map(|&itr| t[itr])));
+    assert!(ind==[3,0,2,5,1,4,6][..k]);
+  }
+
+  #[rstest(len,k,case(7,8),case(0,1),case(1,2))]
+  fn order_test_top_k_size(len: Idx, k: Idx)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::new([len]);
+
+    assert!(t.top_k(k).err()==Some(TensorError::SelectionSize{k:k,len:len}));
+    assert!(t.top_k(len).unwrap().1.len()==len);
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn order_test_top_k_axis(layout: Layout)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| [[5,-1,5,2],[0,8,3,8],[7,7,-2,1]][itr][jtr]).to_layout(layout);
+
+    let (top,ind): (Tensor<i32,2>,Tensor<usize,2>)=t.top_k_axis(1,2).unwrap();
+    assert!(top.dim==[3,2] && (0..3).all(|itr| top[[itr,0]]==[5,8,7][itr] && top[[itr,1]]==[5,8,7][itr]));
+    assert!((0..3).all(|itr| ind[[itr,0]]==[0,1,0][itr] && ind[[itr,1]]==[2,3,1][itr]));
+
+    let (top,ind): (Tensor<i32,2>,Tensor<usize,2>)=t.top_k_axis(0,2).unwrap();
+    assert!(top.dim==[2,4] && (0..4).all(|jtr| top[[0,jtr]]==[7,8,5,8][jtr] && top[[1,jtr]]==[5,7,3,2][jtr]));
+    assert!((0..4).all(|jtr| ind[[0,jtr]]==[2,1,0,1][jtr] && ind[[1,jtr]]==[0,2,1,0][jtr]));
+  }
+
+  #[rstest(axis,k,dim,case(0,0,[0,4]),case(1,0,[3,0]),case(0,3,[3,4]),case(1,4,[3,4]))]
+  fn order_test_top_k_axis_dim(axis: Idx, k: Idx, dim: Dim<2>)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| (itr*jtr) as i32);
+
+    assert!(t.top_k_axis(axis,k).unwrap().0.dim==dim && t.top_k_axis(axis,k).unwrap().1.dim==dim);
+  }
+
+  #[rstest(axis,k,len,case(0,4,3),case(1,5,4),case(0,usize::MAX,3))]
+  fn order_test_top_k_axis_size(axis: Idx, k: Idx, len: Idx)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| (itr*jtr) as i32);
+
+    assert!(t.top_k_axis(axis,k).err()==Some(TensorError::SelectionSize{k:k,len:len}));
+  }
+
+  /// Every query lies between the elements either side of where it is inserted.
+  #[rstest(side,expected,case(Side::Left,[2,0,5,0,6,2,1,6]),case(Side::Right,[5,1,6,0,6,2,2,6]))]
+  fn order_test_searchsorted(side: Side, expected: [Idx;8])
+  {
+    let edges: Tensor<f64,1>=Tensor::<f64,1>::from_fn([6],|[itr]| [-1.0,0.0,2.5,2.5,2.5,10.0][itr]);
+    let values: Tensor<f64,1>=Tensor::<f64,1>::from_fn([8],|[itr]| [2.5,-1.0,10.0,-7.0,11.0,1.0,0.0,f64::NAN][itr]);
+
+    assert!(edges.searchsorted(&values,side)==expected);
+    for (&value,&ind) in values.iter().take(7).zip(expected.iter())
+    {
+      assert!(ind==0 || edges[ind-1]<=value && (side==Side::Right || edges[ind-1]<value));
+      assert!(ind==6 || value<=edges[ind] && (side==Side::Left || value<edges[ind]));
+    }
+  }
+
+  #[rstest(side,len,expected,
+    case(Side::Left,3,&[0,0,3]),
+    case(Side::Right,3,&[0,3,3]),
+    case(Side::Left,0,&[0,0,0]),
+    case(Side::Right,0,&[0,0,0]),
+  )]
+  fn order_test_searchsorted_int(side: Side, len: Idx, expected: &[Idx])
+  {
+    let ints: Tensor<u8,1>=Tensor::<u8,1>::from_fn([len],|_| 3);
+    let queries: Tensor<u8,1>=Tensor::<u8,1>::from_fn([3],|[itr]| [0,3,255][itr]);
+
+    assert!(ints.searchsorted(&queries,side)==expected);
+    assert!(ints.searchsorted(&Tensor::<u8,1>::new([0]),side).is_empty());
+  }
+
+  #[test]
+  #[cfg(debug_assertions)]
+  #[should_panic(expected="The tensor must be sorted to search it.")]
+  fn order_test_searchsorted_unsorted()
+  {
+    let t: Tensor<i32,1>=Tensor::<i32,1>::from_fn([3],|[itr]| [1,3,2][itr]);
+    let _=t.searchsorted(&t,Side::Left);
+  }
+
+  #[rstest(dim,case([0,3]),case([3,0]),case([0,0]))]
+  fn order_test_unique_empty(dim: Dim<2>)
+  {
+    let t: Tensor<f32,2>=Tensor::<f32,2>::new(dim);
+
+    assert!(t.unique().dim==[0]);
+    assert!(t.value_counts().is_empty());
+    assert!(t.unique_tol(0.5).dim==[0]);
+  }
+}
@@ -0,0 +1,166 @@
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+
+/// Fewest elements worth handing to a thread of their own; smaller tensors are processed on the
+/// calling thread, or by fewer threads than requested.
+const MIN_CHUNK: Idx=1024;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar + Send + Sync
+{
+  /// Applies `f` to every element in place, as `for_each` over `iter_mut` would, splitting the
+  /// elements into up to `threads` contiguous chunks processed on threads of their own.
+  ///
+  /// The threads are scoped to the call, so no thread pool is needed. Every element is passed to `f`
+  /// exactly once, so the result is that of the sequential loop, which is used instead if `threads` is
+  /// at most one or the tensor has too few elements to be worth splitting.
+  pub(super) fn par_map_inplace_scoped<F>(&mut self, threads: Idx, f: F)
+  where F: Fn(&mut T) + Sync
+  {
+    let chunk: Option<Idx>=chunk_len(self.len(),threads);
+    let data: &mut [T]=&mut self.data;
+    match chunk
+    {
+      None => data.iter_mut().for_each(f),
+      Some(chunk) =>
+      {
+        let f: &F=&f;
+        std::thread::scope(|scope| {
+          for part in data.chunks_mut(chunk)
+          {
+            scope.spawn(move || part.iter_mut().for_each(f));
+          }
+        });
+      },
+    }
+  }
+
+  /// Applies `f` to every element in place and the element at the same index in `rhs`, which must be
+  /// of the same dimensions, splitting the work across up to `threads` threads as
+  /// `par_map_inplace_scoped` does.
+  pub(super) fn par_zip_inplace_scoped<F>(&mut self, rhs: &Tensor<T,N>, threads: Idx, f: F)
+  where F: Fn(&mut T,&T) + Sync
+  {
+    if self.dim!=rhs.dim { panic!("All dimensions of two tensors must be of the same size to combine them.") }
+
+    self.adopt_names(rhs);
+    let rhs: Tensor<T,N>=rhs.to_layout(self.layout);
+    let chunk: Option<Idx>=chunk_len(self.len(),threads);
+    let data: &mut [T]=&mut self.data;
+    match chunk
+    {
+      None => data.iter_mut().zip(rhs.data.iter()).for_each(|(this,other)| f(this,other)),
+      Some(chunk) =>
+      {
+        let f: &F=&f;
+        std::thread::scope(|scope| {
+          for (part,other) in data.chunks_mut(chunk).zip(rhs.data.chunks(chunk))
+          {
+            scope.spawn(move || part.iter_mut().zip(other.iter()).for_each(|(this,other)| f(this,other)));
+          }
+        });
+      },
+    }
+  }
+
+  /// Adds `rhs` to `self` in place across up to `threads` threads, with the result of `+=`.
+  pub(super) fn par_add_assign_scoped(&mut self, rhs: &Tensor<T,N>, threads: Idx)
+  {
+    self.par_zip_inplace_scoped(rhs,threads,|this,other| *this+=other.clone());
+  }
+}
+
+/// Length of the chunks `len` elements are split into for `threads` threads, none of which is
+/// shorter than `MIN_CHUNK` but the last, unless they are better not split at all.
+fn chunk_len(len: Idx, threads: Idx) -> Option<Idx>
+{
+  let threads: Idx=threads.min(len/MIN_CHUNK);
+  if threads<=1 { None } else { Some((len-1)/threads+1) }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod parallel_tests
+{
+  use super::*;
+  use super::super::testing::assert_tensor_eq;
+  use rstest::rstest;
+
+  use std::collections::HashSet;
+  use std::sync::Mutex;
+  use std::thread::ThreadId;
+
+  /// Chunks are at least `MIN_CHUNK` long, and no chunking is done where one thread would take it all.
+  #[rstest(len,threads,expected,
+    case(10*MIN_CHUNK,4,Some(5*MIN_CHUNK/2)),
+    case(10*MIN_CHUNK+1,4,Some(5*MIN_CHUNK/2+1)),
+    case(3*MIN_CHUNK-1,8,Some(3*MIN_CHUNK/2)),
+    case(2*MIN_CHUNK-1,8,None),
+    case(10*MIN_CHUNK,1,None),
+    case(10*MIN_CHUNK,0,None),
+    case(0,4,None),
+  )]
+  fn parallel_test_chunk_len(len: Idx, threads: Idx, expected: Option<Idx>)
+  {
+    assert!(chunk_len(len,threads)==expected);
+  }
+
+  /// Every element records the thread it was processed on, slowly enough that the threads overlap.
+  #[rstest(threads,case(2),case(4))]
+  fn parallel_test_map_slow(threads: Idx)
+  {
+    let mut t: Tensor<u64,2>=Tensor::<u64,2>::from_fn([4,MIN_CHUNK],|[itr,jtr]| (itr*MIN_CHUNK+jtr) as u64);
+    let seen: Mutex<Vec<ThreadId>>=Mutex::new(Vec::new());
+    t.par_map_inplace_scoped(threads,|elem| {
+      std::thread::sleep(std::time::Duration::from_micros(1));
+      seen.lock().unwrap().push(std::thread::current().id());
+      *elem*=2;
+    });
+
+    let seen: Vec<ThreadId>=seen.into_inner().unwrap();
+    let used: HashSet<ThreadId>=seen.iter().copied().collect();
+    assert!(seen.len()==4*MIN_CHUNK && used.len()==threads && !used.contains(&std::thread::current().id()));
+    assert!(t.iter().enumerate().all(|(itr,&elem)| elem==2*itr as u64));
+  }
+
+  #[rstest(len,threads,case(4*MIN_CHUNK,1),case(MIN_CHUNK,4),case(0,4))]
+  fn parallel_test_sequential(len: Idx, threads: Idx)
+  {
+    let mut t: Tensor<u64,1>=Tensor::<u64,1>::new([len]);
+    let seen: Mutex<HashSet<ThreadId>>=Mutex::new(HashSet::new());
+    t.par_map_inplace_scoped(threads,|elem| { seen.lock().unwrap().insert(std::thread::current().id()); *elem+=1; });
+    assert!(seen.into_inner().unwrap().iter().all(|&id| id==std::thread::current().id()));
+    assert!(t.iter().all(|&elem| elem==1));
+  }
+
+  #[rstest(threads,case(1),case(3),case(7))]
+  fn parallel_test_matches_sequential(threads: Idx)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([37,29,31],|[itr,jtr,ktr]| ((itr*31+jtr*7+ktr) as f64).sin());
+    let rhs: Tensor<f64,3>=Tensor::<f64,3>::from_fn([37,29,31],|[itr,jtr,ktr]| ((itr+jtr*13+ktr*3) as f64).cos()).to_layout(super::super::Layout::ColMajor);
+
+    let mut expected: Tensor<f64,3>=t.map(|&elem| elem.exp()*0.5-elem);
+    let mut res: Tensor<f64,3>=t.clone();
+    res.par_map_inplace_scoped(threads,|elem| *elem=elem.exp()*0.5-*elem);
+    assert_tensor_eq!(res,expected);
+
+    expected+=&rhs;
+    res.par_add_assign_scoped(&rhs,threads);
+    assert_tensor_eq!(res,expected);
+
+    res.par_zip_inplace_scoped(&rhs,threads,|this,other| *this=*this*other+1.0);
+    assert_tensor_eq!(res,expected*&rhs+1.0);
+  }
+
+  #[test]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to combine them.")]
+  fn parallel_test_zip_dims()
+  {
+    Tensor::<f64,2>::new([2,3]).par_add_assign_scoped(&Tensor::<f64,2>::new([3,2]),2);
+  }
+}
@@ -0,0 +1,171 @@
+use super::Idx;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// Matrix with a row for every `patch` of elements, moving the patch by `stride` along each axis,
+  /// holding the elements of the patch in row-major order.
+  ///
+  /// The rows are in row-major order of the patch positions, and only patches that lie entirely
+  /// within the tensor are extracted, as in `pool2d`. A correlation with a kernel of dimensions
+  /// `patch` is then the product of the result with the kernel flattened to a column. Fails if the
+  /// patch or stride is zero, or if the patch is longer than the tensor along either axis.
+  pub(super) fn extract_patches(&self, patch: [Idx;2], stride: [Idx;2]) -> Result<Tensor<T,2>,TensorError>
+  {
+    let counts: [Idx;2]=patch_counts(self.dim,patch,stride)?;
+    let t: Tensor<T,2>=self.to_layout(Layout::RowMajor);
+    let cols: Idx=self.dim[1];
+    Ok(Tensor::<T,2>::from_fn([counts[0]*counts[1],patch[0]*patch[1]],|[itr,jtr]| {
+      let row: Idx=itr/counts[1]*stride[0]+jtr/patch[1];
+      let col: Idx=itr%counts[1]*stride[1]+jtr%patch[1];
+      t.data[row*cols+col].clone()
+    }))
+  }
+
+  /// Tensor of dimensions `dim` with the rows of `patches`, laid out as by `extract_patches`, added
+  /// back at the positions of their patches, so elements covered by several patches receive the sum
+  /// of their contributions and elements covered by none are zero.
+  ///
+  /// Fails as `extract_patches` does, or if `patches` is not of the dimensions `extract_patches`
+  /// gives for `dim`.
+  pub(super) fn fold_patches(patches: &Tensor<T,2>, dim: [Idx;2], patch: [Idx;2], stride: [Idx;2]) -> Result<Tensor<T,2>,TensorError>
+  {
+    let counts: [Idx;2]=patch_counts(dim,patch,stride)?;
+    let expected: [Idx;2]=[counts[0]*counts[1],patch[0]*patch[1]];
+    if patches.dim!=expected { return Err(TensorError::DimMismatch{lhs:patches.dim.to_vec(),rhs:expected.to_vec()}); }
+
+    let mut t: Tensor<T,2>=Tensor::<T,2>::new(dim);
+    for ([itr,jtr],elem) in patches.indexed_iter()
+    {
+      let row: Idx=itr/counts[1]*stride[0]+jtr/patch[1];
+      let col: Idx=itr%counts[1]*stride[1]+jtr%patch[1];
+      t.data[row*dim[1]+col]+=elem.clone();
+    }
+    Ok(t)
+  }
+}
+
+/// Number of patches along each axis of a tensor of dimensions `dim`.
+fn patch_counts(dim: [Idx;2], patch: [Idx;2], stride: [Idx;2]) -> Result<[Idx;2],TensorError>
+{
+  let mut counts: [Idx;2]=[0;2];
+  for axis in 0..2
+  {
+    if patch[axis]==0 || stride[axis]==0 { return Err(TensorError::ZeroFactor{axis:axis}); }
+    if patch[axis]>dim[axis] { return Err(TensorError::OutOfBounds{axis:axis}); }
+    counts[axis]=(dim[axis]-patch[axis])/stride[axis]+1;
+  }
+  Ok(counts)
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod patch_tests
+{
+  use super::*;
+  use super::super::Dim;
+  use super::super::stencil::Boundary;
+  use super::super::testing::assert_tensor_allclose;
+  use super::super::testing::assert_tensor_eq;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  /// Direct correlation of `t` with `kernel` over every position where the kernel fits, moving it by
+  /// `stride`.
+  fn patch_test_correlate(t: &Tensor<f64,2>, kernel: &Tensor<f64,2>, stride: [Idx;2]) -> Tensor<f64,2>
+  {
+    let dim: [Idx;2]=[(t.dim[0]-kernel.dim[0])/stride[0]+1,(t.dim[1]-kernel.dim[1])/stride[1]+1];
+    Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| kernel.indexed_iter().map(|([ktr,ltr],&weight)| weight*t[[itr*stride[0]+ktr,jtr*stride[1]+ltr]]).sum())
+  }
+
+  #[rstest(size,stride,layout,rows,
+    case([2,2],[1,2],Layout::RowMajor,vec![vec![0,1,4,5],vec![2,3,6,7],vec![4,5,8,9],vec![6,7,10,11]]),
+    case([3,1],[1,3],Layout::ColMajor,vec![vec![0,4,8],vec![3,7,11]]),
+    case([3,4],[5,5],Layout::RowMajor,vec![(0..12).collect()]),
+    case([1,1],[2,2],Layout::ColMajor,vec![vec![0],vec![2],vec![8],vec![10]]),
+  )]
+  fn patch_test_extract(size: [Idx;2], stride: [Idx;2], layout: Layout, rows: Vec<Vec<i32>>)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as i32).to_layout(layout);
+    let rows: Vec<&[i32]>=rows.iter().map(|row| row.as_slice()).collect();
+    assert_tensor_eq!(t.extract_patches(size,stride).unwrap(),Tensor::<i32,2>::from_rows(&rows).unwrap());
+  }
+
+  #[rstest(size,stride,error,
+    case([4,1],[1,1],TensorError::OutOfBounds{axis:0}),
+    case([1,5],[1,1],TensorError::OutOfBounds{axis:1}),
+    case([1,1],[1,0],TensorError::ZeroFactor{axis:1}),
+    case([1,1],[0,1],TensorError::ZeroFactor{axis:0}),
+  )]
+  fn patch_test_extract_errors(size: [Idx;2], stride: [Idx;2], error: TensorError)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as i32);
+    assert!(t.extract_patches(size,stride)==Err(error));
+  }
+
+  #[rstest(stride,case([1,1]),case([2,1]),case([2,3]),case([4,4]))]
+  fn patch_test_conv2d(stride: [Idx;2])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,7],|[itr,jtr]| ((3*itr+5*jtr)%11) as f64-4.0);
+    let kernel: Tensor<f64,2>=Tensor::<f64,2>::from_rows(&[&[1.0,-2.0,0.5],&[0.0,3.0,1.0],&[-1.0,2.0,-0.5]]).unwrap();
+    let column: Tensor<f64,2>=Tensor::<f64,2>::from_vec([9,1],kernel.iter().copied().collect()).unwrap();
+
+    let direct: Tensor<f64,2>=patch_test_correlate(&t,&kernel,stride);
+    let product: Tensor<f64,2>=t.extract_patches([3,3],stride).unwrap().matmul(&column);
+    let res: Tensor<f64,2>=Tensor::<f64,2>::from_vec(direct.dim,product.iter().copied().collect()).unwrap();
+    assert_tensor_allclose!(res,direct,1e-12);
+  }
+
+  #[test]
+  fn patch_test_conv2d_stencil()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,7],|[itr,jtr]| ((3*itr+5*jtr)%11) as f64-4.0);
+    let kernel: Tensor<f64,2>=Tensor::<f64,2>::from_rows(&[&[1.0,-2.0,0.5],&[0.0,3.0,1.0],&[-1.0,2.0,-0.5]]).unwrap();
+    let column: Tensor<f64,2>=Tensor::<f64,2>::from_vec([9,1],kernel.iter().copied().collect()).unwrap();
+
+    // With a unit stride the correlation is the interior of the stencil applied to the whole grid.
+    let product: Tensor<f64,2>=t.extract_patches([3,3],[1,1]).unwrap().matmul(&column);
+    let stencil: Tensor<f64,2>=t.apply_stencil(&kernel,Boundary::Dirichlet(0.0));
+    assert!(product.iter().zip((1..5).flat_map(|itr| (1..6).map(move |jtr| [itr,jtr]))).all(|(&elem,ind)| (elem-stencil[ind]).abs()<1e-12));
+  }
+
+  /// Patches that tile the tensor fold back to it.
+  #[rstest(size,case([2,3]),case([2,2]),case([1,1]),case([4,6]))]
+  fn patch_test_fold(size: [Idx;2])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,6],|[itr,jtr]| (6*itr+jtr) as f64);
+
+    let patches: Tensor<f64,2>=t.extract_patches(size,size).unwrap();
+    assert_tensor_eq!(Tensor::<f64,2>::fold_patches(&patches,[4,6],size,size).unwrap(),t);
+  }
+
+  #[test]
+  fn patch_test_fold_overlap()
+  {
+    // Overlapping patches of ones count how many patches cover every element, and elements past the
+    // last patch are covered by none.
+    let ones: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,4],|_| 1.0);
+    assert_tensor_eq!(Tensor::<f64,2>::fold_patches(&ones,[4,5],[2,2],[1,2]).unwrap(),Tensor::<f64,2>::from_rows(&[
+      &[1.0,1.0,1.0,1.0,0.0],&[2.0,2.0,2.0,2.0,0.0],&[2.0,2.0,2.0,2.0,0.0],&[1.0,1.0,1.0,1.0,0.0],
+    ]).unwrap());
+  }
+
+  #[rstest(dim,error,
+    case([4,6],TensorError::DimMismatch{lhs:vec![6,4],rhs:vec![9,4]}),
+    case([1,6],TensorError::OutOfBounds{axis:0}),
+  )]
+  fn patch_test_fold_errors(dim: Dim<2>, error: TensorError)
+  {
+    let ones: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,4],|_| 1.0);
+    assert!(Tensor::<f64,2>::fold_patches(&ones,dim,[2,2],[1,2])==Err(error));
+  }
+}
@@ -0,0 +1,265 @@
+use alloc::vec::Vec;
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+use super::Float;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Writes the matrix as a binary greyscale PGM image (P5) with one row of pixels per row.
+  ///
+  /// Elements are taken to lie in `[0,1]` and scaled to `[0,255]`, clamping those outside, unless
+  /// `normalize` is set, in which case the smallest element maps to 0 and the largest to 255, and
+  /// every element to 0 if they are equal. NaN maps to 0.
+  pub(super) fn write_pgm<W>(&self, mut w: W, normalize: bool) -> io::Result<()>
+  where W: Write
+  {
+    write!(w,"P5\n{} {}\n255\n",self.dim[1],self.dim[0])?;
+    let elems: Vec<f64>=self.to_layout(Layout::RowMajor).data.iter().map(|elem| elem.to_f64()).collect();
+    w.write_all(&to_pixels(&elems,normalize))
+  }
+}
+
+impl<T> Tensor<T,3>
+where T: Float
+{
+  /// Writes an image of dimensions `[height,width,3]` as a binary colour PPM image (P6), with the
+  /// channels taken as red, green and blue and scaled as for `write_pgm`, normalising over all channels
+  /// together. Fails unless there are three channels.
+  pub(super) fn write_ppm<W>(&self, mut w: W, normalize: bool) -> io::Result<()>
+  where W: Write
+  {
+    if self.dim[2]!=3 { return Err(io::Error::new(io::ErrorKind::InvalidInput,TensorError::ChannelCount{channels:self.dim[2],expected:3})); }
+
+    write!(w,"P6\n{} {}\n255\n",self.dim[1],self.dim[0])?;
+    let elems: Vec<f64>=self.to_layout(Layout::RowMajor).data.iter().map(|elem| elem.to_f64()).collect();
+    w.write_all(&to_pixels(&elems,normalize))
+  }
+}
+
+impl Tensor<f64,2>
+{
+  /// Reads a binary greyscale PGM image (P5) with a maximum value of at most 65535, scaling every
+  /// pixel by the maximum value into `[0,1]`. Comments in the header are skipped.
+  pub(super) fn read_pgm<R>(r: R) -> io::Result<Tensor<f64,2>>
+  where R: Read
+  {
+    let (height,width,pixels): (Idx,Idx,Vec<f64>)=read_pnm(r,b"P5",1)?;
+    Tensor::<f64,2>::from_vec([height,width],pixels).map_err(|err| io::Error::new(io::ErrorKind::InvalidData,err))
+  }
+}
+
+impl Tensor<f64,3>
+{
+  /// Reads a binary colour PPM image (P6) as for `read_pgm`, giving a tensor of dimensions
+  /// `[height,width,3]`.
+  pub(super) fn read_ppm<R>(r: R) -> io::Result<Tensor<f64,3>>
+  where R: Read
+  {
+    let (height,width,pixels): (Idx,Idx,Vec<f64>)=read_pnm(r,b"P6",3)?;
+    Tensor::<f64,3>::from_vec([height,width,3],pixels).map_err(|err| io::Error::new(io::ErrorKind::InvalidData,err))
+  }
+}
+
+/// Bytes of the intensities `elems` for `write_pgm` and `write_ppm`.
+fn to_pixels(elems: &[f64], normalize: bool) -> Vec<u8>
+{
+  let (min,max): (f64,f64)=if normalize
+  {
+    elems.iter().filter(|elem| !elem.is_nan()).fold((f64::INFINITY,f64::NEG_INFINITY),|(min,max),&elem| (min.min(elem),max.max(elem)))
+  }
+  else { (0.0,1.0) };
+  let range: f64=max-min;
+  elems.iter().map(|&elem| {
+    let scaled: f64=if range>0.0 { (elem-min)/range } else { 0.0 };
+    // The cast saturates, so it clamps, and takes NaN to 0.
+    (scaled*255.0+0.5) as u8
+  }).collect()
+}
+
+/// Height, width and pixels, scaled into `[0,1]`, of a binary PNM image with the magic number `magic`
+/// and `channels` samples per pixel.
+fn read_pnm<R>(mut r: R, magic: &[u8;2], channels: Idx) -> io::Result<(Idx,Idx,Vec<f64>)>
+where R: Read
+{
+  let invalid=|msg: &str| io::Error::new(io::ErrorKind::InvalidData,msg);
+  let mut bytes: Vec<u8>=Vec::new();
+  r.read_to_end(&mut bytes)?;
+  if bytes.len()<2 || bytes[..2]!=magic[..] { return Err(invalid("The image does not start with the expected magic number.")); }
+
+  let mut pos: Idx=2;
+  let mut fields: [Idx;3]=[0;3];
+  for field in fields.iter_mut()
+  {
+    // Whitespace and comments, which run to the end of the line, may separate the fields.
+    loop
+    {
+      match bytes.get(pos)
+      {
+        Some(byte) if byte.is_ascii_whitespace() => pos+=1,
+        Some(b'#') => while bytes.get(pos).is_some_and(|&byte| byte!=b'\n') { pos+=1; },
+        _ => break,
+      }
+    }
+    let start: Idx=pos;
+    while bytes.get(pos).is_some_and(|byte| byte.is_ascii_digit()) { pos+=1; }
+    *field=core::str::from_utf8(&bytes[start..pos]).ok().and_then(|digits| digits.parse().ok())
+      .ok_or_else(|| invalid("The header of the image is malformed."))?;
+  }
+  let [width,height,maxval]: [Idx;3]=fields;
+  if maxval==0 || maxval>65535 { return Err(invalid("The maximum value of the image must lie between 1 and 65535.")); }
+  // A single whitespace character separates the header from the pixels.
+  if !bytes.get(pos).is_some_and(|byte| byte.is_ascii_whitespace()) { return Err(invalid("The header of the image is malformed.")); }
+  pos+=1;
+
+  let sample_len: Idx=if maxval<256 { 1 } else { 2 };
+  // Dimensions too large to count the bytes of cannot be those of an image that was read whole.
+  let end: Idx=height.checked_mul(width).and_then(|len| len.checked_mul(channels*sample_len)).and_then(|len| len.checked_add(pos))
+    .ok_or_else(|| invalid("The header of the image is malformed."))?;
+  let data: &[u8]=bytes.get(pos..end).ok_or_else(|| invalid("The image holds fewer pixels than its header states."))?;
+  let pixels: Vec<f64>=data.chunks_exact(sample_len)
+    .map(|sample| sample.iter().fold(0,|val,&byte| 256*val+byte as Idx) as f64/maxval as f64)
+    .collect();
+  Ok((height,width,pixels))
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod pnm_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::format;
+
+  use super::super::Dim;
+
+  /// Image whose intensities are multiples of 51 out of 255, so that they are exactly representable in
+  /// bytes.
+  fn pnm_test_gradient(dim: Dim<2>) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (51*((itr+2*jtr)%6)) as f64/255.0)
+  }
+
+  #[rstest(dim,layout,
+    case([3,6],Layout::RowMajor),
+    case([3,6],Layout::ColMajor),
+    case([1,1],Layout::RowMajor),
+    case([5,2],Layout::ColMajor),
+    case([0,4],Layout::RowMajor),
+  )]
+  fn pnm_test_pgm(dim: Dim<2>, layout: Layout)
+  {
+    let t: Tensor<f64,2>=pnm_test_gradient(dim);
+    let mut bytes: Vec<u8>=Vec::new();
+    t.to_layout(layout).write_pgm(&mut bytes,false).unwrap();
+
+    let header: Vec<u8>=format!("P5\n{} {}\n255\n",dim[1],dim[0]).into_bytes();
+    assert!(bytes[..header.len()]==header[..] && bytes.len()==header.len()+dim[0]*dim[1]);
+    assert!(bytes[header.len()..].iter().zip(t.iter()).all(|(&byte,&elem)| byte as f64==255.0*elem));
+    assert!(Tensor::<f64,2>::read_pgm(&bytes[..]).unwrap()==t);
+  }
+
+  /// Normalising stretches the range, and without it values outside `[0,1]` are clamped.
+  #[rstest(f,normalize,expected,
+    case(|elem: f64| elem,false,[0,51,102,153,204,255]),
+    case(|elem: f64| elem,true,[0,51,102,153,204,255]),
+    case(|elem: f64| 10.0*elem-3.0,true,[0,51,102,153,204,255]),
+    case(|elem: f64| -elem,true,[255,204,153,102,51,0]),
+    case(|elem: f64| 2.0*elem-0.4,false,[0,0,102,204,255,255]),
+    case(|elem: f64| if elem>0.55 && elem<0.65 { f64::NAN } else { elem },false,[0,51,102,0,204,255]),
+    case(|elem: f64| if elem>0.15 && elem<0.25 { f64::NAN } else { elem },true,[0,0,102,153,204,255]),
+  )]
+  fn pnm_test_pgm_scale(f: fn(f64) -> f64, normalize: bool, expected: [u8;6])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([1,6],|[_,jtr]| (51*jtr) as f64/255.0);
+    let mut bytes: Vec<u8>=Vec::new();
+    t.map(|&elem| f(elem)).write_pgm(&mut bytes,normalize).unwrap();
+    assert!(bytes[11..]==expected);
+  }
+
+  #[rstest(value,case(0.7),case(0.0),case(-3.0))]
+  fn pnm_test_pgm_flat(value: f32)
+  {
+    let mut bytes: Vec<u8>=Vec::new();
+    Tensor::<f32,2>::from_fn([2,2],|_| value).write_pgm(&mut bytes,true).unwrap();
+    assert!(bytes[11..]==[0,0,0,0]);
+  }
+
+  #[rstest(bytes,dim,expected,
+    case(b"P5\n# written by hand\n3 2 # width and height\n# the maximum\n100\n\x00\x0a\x14\x32\x4b\x64",[2,3],&[0.0,0.1,0.2,0.5,0.75,1.0]),
+    case(b"P5 2 1 1000\n\x03\xe8\x01\xf4",[1,2],&[1.0,0.5]),
+    case(b"P5\t1\r2\n4\n\x02\x01",[2,1],&[0.5,0.25]),
+    case(b"P5\n0 3\n255\n",[3,0],&[]),
+  )]
+  fn pnm_test_read_pgm(bytes: &[u8], dim: Dim<2>, expected: &[f64])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::read_pgm(bytes).unwrap();
+    assert!(t.dim==dim && t.iter().copied().eq(expected.iter().copied()));
+  }
+
+  /// Dimensions whose product overflows are rejected rather than wrapped.
+  #[rstest(bytes,msg,
+    case(b"P6\n1 1\n255\n\x00","The image does not start with the expected magic number."),
+    case(b"P","The image does not start with the expected magic number."),
+    case(b"P5\n2 x\n255\n\x00\x00","The header of the image is malformed."),
+    case(b"P5\n1 1\n255","The header of the image is malformed."),
+    case(b"P5 18446744073709551615 2 255\n\x00","The header of the image is malformed."),
+    case(b"P5\n2 2\n255\n\x00\x00","The image holds fewer pixels than its header states."),
+    case(b"P5\n1 1\n256\n\x00","The image holds fewer pixels than its header states."),
+    case(b"P5\n1 1\n0\n\x00","The maximum value of the image must lie between 1 and 65535."),
+    case(b"P5\n1 1\n65536\n\x00\x00","The maximum value of the image must lie between 1 and 65535."),
+  )]
+  fn pnm_test_read_pgm_errors(bytes: &[u8], msg: &str)
+  {
+    let err: io::Error=Tensor::<f64,2>::read_pgm(bytes).unwrap_err();
+    assert!(err.kind()==io::ErrorKind::InvalidData && err.to_string()==msg);
+  }
+
+  #[rstest(dim,layout,
+    case([3,6],Layout::RowMajor),
+    case([3,6],Layout::ColMajor),
+    case([1,1],Layout::ColMajor),
+    case([2,0],Layout::RowMajor),
+  )]
+  fn pnm_test_ppm(dim: Dim<2>, layout: Layout)
+  {
+    let t: Tensor<f64,3>=Tensor::<f64,3>::from_fn([dim[0],dim[1],3],|[itr,jtr,ktr]| {
+      let level: Idx=51*((itr+2*jtr)%6);
+      if ktr==1 { (255-level) as f64/255.0 } else { (level*(ktr/2)) as f64/255.0 }
+    });
+    let mut bytes: Vec<u8>=Vec::new();
+    t.to_layout(layout).write_ppm(&mut bytes,false).unwrap();
+
+    let header: Vec<u8>=format!("P6\n{} {}\n255\n",dim[1],dim[0]).into_bytes();
+    assert!(bytes[..header.len()]==header[..] && bytes.len()==header.len()+3*dim[0]*dim[1]);
+    assert!(bytes[header.len()..].iter().zip(t.iter()).all(|(&byte,&elem)| byte as f64==255.0*elem));
+    assert!(Tensor::<f64,3>::read_ppm(&bytes[..]).unwrap()==t);
+  }
+
+  #[rstest(bytes,case(b"P6 4294967296 4294967296 65535\n\x00"),case(b"P5\n1 1\n255\n\x00\x00\x00"),case(b"P6\n1 1\n255\n\x00\x00"))]
+  fn pnm_test_read_ppm_errors(bytes: &[u8])
+  {
+    assert!(Tensor::<f64,3>::read_ppm(bytes).unwrap_err().kind()==io::ErrorKind::InvalidData);
+  }
+
+  #[rstest(channels,case(4),case(1),case(0))]
+  fn pnm_test_write_ppm_channels(channels: Idx)
+  {
+    let mut rejected: Vec<u8>=Vec::new();
+    let err: io::Error=Tensor::<f64,3>::new([2,2,channels]).write_ppm(&mut rejected,false).unwrap_err();
+    assert!(err.kind()==io::ErrorKind::InvalidInput && rejected.is_empty());
+    assert!(*err.into_inner().unwrap().downcast::<TensorError>().unwrap()==TensorError::ChannelCount{channels:channels,expected:3});
+  }
+}
@@ -0,0 +1,110 @@
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+
+#[cfg(feature="std")]
+use super::Float;
+#[cfg(feature="std")]
+use super::TensorError;
+
+impl<T> Tensor<T,1>
+where T: Scalar
+{
+  /// Evaluates the polynomial with coefficients `coeffs` at every element of `x` by Horner's method.
+  ///
+  /// The coefficients are ordered from the highest power down to the constant term, as returned by
+  /// `polyfit`.
+  pub(super) fn polyval(coeffs: &Tensor<T,1>, x: &Tensor<T,1>) -> Tensor<T,1>
+  {
+    x.map(|x| coeffs.data.iter().fold(T::default(),|res,coeff| res*x.clone()+coeff.clone()))
+  }
+}
+
+#[cfg(feature="std")]
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Coefficients of the polynomial of degree `degree` that fits the points `(x,y)` best in the least
+  /// squares sense, ordered from the highest power down to the constant term as `polyval` expects.
+  ///
+  /// At least `degree+1` points with distinct `x` are needed to determine the coefficients.
+  pub(super) fn polyfit(x: &Tensor<T,1>, y: &Tensor<T,1>, degree: Idx) -> Result<Tensor<T,1>,TensorError>
+  {
+    if x.dim!=y.dim { return Err(TensorError::LengthMismatch{lhs:x.dim[0],rhs:y.dim[0]}); }
+    if degree>=x.dim[0] { return Err(TensorError::Underdetermined{unknowns:degree+1,equations:x.dim[0]}); }
+
+    let vander: Tensor<T,2>=Tensor::<T,2>::from_fn([x.dim[0],degree+1],|[itr,jtr]| {
+      (jtr..degree).fold(T::from_f64(1.0),|pow,_| pow*x[itr])
+    });
+    vander.lstsq(y)
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod poly_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  /// The coefficients run from the highest power down.
+  #[rstest(coeffs,expected,
+    case(&[2.0,-3.0,1.0],&[6.0,1.0,0.0,3.0]),
+    case(&[1.0,0.0],&[-1.0,0.0,1.0,2.0]),
+    case(&[5.0],&[5.0,5.0,5.0,5.0]),
+    case(&[],&[0.0,0.0,0.0,0.0]),
+  )]
+  fn poly_test_polyval(coeffs: &[f64], expected: &[f64])
+  {
+    let coeffs: Tensor<f64,1>=Tensor::<f64,1>::from_fn([coeffs.len()],|[itr]| coeffs[itr]);
+    let x: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| itr as f64-1.0);
+
+    let y: Tensor<f64,1>=Tensor::<f64,1>::polyval(&coeffs,&x);
+    assert!(y.iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[rstest(coeffs,expected,case(&[3,4],&[4,7]),case(&[2,0,-1],&[-1,1]),case(&[],&[0,0]))]
+  fn poly_test_polyval_int(coeffs: &[i64], expected: &[i64])
+  {
+    let coeffs: Tensor<i64,1>=Tensor::<i64,1>::from_fn([coeffs.len()],|[itr]| coeffs[itr]);
+    let x: Tensor<i64,1>=Tensor::<i64,1>::from_fn([2],|[itr]| itr as i64);
+
+    assert!(Tensor::<i64,1>::polyval(&coeffs,&x).iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[rstest(coeffs,
+    case(&[1.5,-2.0,0.25]),
+    case(&[0.5,0.0,-1.0,2.0]),
+    case(&[-3.0,1.0]),
+    case(&[4.0]),
+  )]
+  #[cfg(feature="std")]
+  fn poly_test_polyfit(coeffs: &[f64])
+  {
+    let expected: Tensor<f64,1>=Tensor::<f64,1>::from_fn([coeffs.len()],|[itr]| coeffs[itr]);
+    let x: Tensor<f64,1>=Tensor::<f64,1>::from_fn([7],|[itr]| itr as f64*0.5-1.0);
+    let y: Tensor<f64,1>=Tensor::<f64,1>::polyval(&expected,&x);
+
+    let coeffs: Tensor<f64,1>=Tensor::<f64,1>::polyfit(&x,&y,coeffs.len()-1).unwrap();
+    assert!(coeffs.dim==expected.dim);
+    assert!(coeffs.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-13));
+    let fit: Tensor<f64,1>=Tensor::<f64,1>::polyval(&coeffs,&x);
+    assert!(fit.iter().zip(y.iter()).all(|(x,y)| (x-y).abs()<1e-13));
+  }
+
+  #[rstest(x,y,degree,error,
+    case(Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64),Tensor::<f64,1>::new([4]),1,TensorError::LengthMismatch{lhs:3,rhs:4}),
+    case(Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64),Tensor::<f64,1>::new([3]),3,TensorError::Underdetermined{unknowns:4,equations:3}),
+    case(Tensor::<f64,1>::new([0]),Tensor::<f64,1>::new([0]),0,TensorError::Underdetermined{unknowns:1,equations:0}),
+    case(Tensor::<f64,1>::new([3]),Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64),1,TensorError::Singular),
+  )]
+  #[cfg(feature="std")]
+  fn poly_test_polyfit_errors(x: Tensor<f64,1>, y: Tensor<f64,1>, degree: usize, error: TensorError)
+  {
+    assert!(Tensor::<f64,1>::polyfit(&x,&y,degree).err()==Some(error));
+  }
+}
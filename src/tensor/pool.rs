@@ -0,0 +1,101 @@
+use core::ops::Div;
+
+use super::CastFrom;
+use super::Idx;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+
+/// Reduction applied to every window by `Tensor::pool2d`.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) enum PoolOp
+{
+  Max,
+  /// Mean of the window, which for integer elements is rounded towards zero.
+  Mean,
+  Sum,
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar + PartialOrd + Div<Output=T> + CastFrom<u64>
+{
+  /// Reduces every `window` of elements with `op`, moving the window by `stride` along each axis.
+  ///
+  /// Only windows that lie entirely within the tensor are reduced, so an axis of length `len` has
+  /// length `(len-window)/stride+1`, rounded down, in the result, or zero if the window is longer.
+  pub(super) fn pool2d(&self, window: [Idx;2], stride: [Idx;2], op: PoolOp) -> Tensor<T,2>
+  {
+    if window.contains(&0) || stride.contains(&0) { panic!("The window and stride must not be zero to pool a tensor.") }
+
+    let mut dim: [Idx;2]=[0;2];
+    for axis in 0..2
+    {
+      if self.dim[axis]>=window[axis] { dim[axis]=(self.dim[axis]-window[axis])/stride[axis]+1; }
+    }
+    let count: T=T::cast_from((window[0]*window[1]) as u64);
+    let t: Tensor<T,2>=self.to_layout(Layout::RowMajor);
+    let cols: Idx=self.dim[1];
+    Tensor::<T,2>::from_fn(dim,|[itr,jtr]| {
+      let (row,col): (Idx,Idx)=(itr*stride[0],jtr*stride[1]);
+      let mut elems=(row..row+window[0]).flat_map(|ktr| t.data[ktr*cols+col..ktr*cols+col+window[1]].iter().cloned());
+      let first: T=elems.next().unwrap();
+      match op
+      {
+        PoolOp::Max => elems.fold(first,|max,elem| if elem>max { elem } else { max }),
+        PoolOp::Sum => elems.fold(first,|sum,elem| sum+elem),
+        PoolOp::Mean => elems.fold(first,|sum,elem| sum+elem)/count.clone(),
+      }
+    })
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod pool_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use super::super::Dim;
+
+  #[rstest(window,stride,op,dim,expected,
+    case([2,2],[2,2],PoolOp::Max,[2,2],&[6.0,8.0,16.0,18.0]),
+    case([2,2],[2,2],PoolOp::Sum,[2,2],&[12.0,20.0,52.0,60.0]),
+    case([2,2],[2,2],PoolOp::Mean,[2,2],&[3.0,5.0,13.0,15.0]),
+    case([2,3],[1,2],PoolOp::Max,[3,2],&[7.0,9.0,12.0,14.0,17.0,19.0]),
+    case([3,1],[2,3],PoolOp::Sum,[1,2],&[15.0,24.0]),
+    case([4,5],[7,7],PoolOp::Mean,[1,1],&[9.5]),
+    case([1,1],[3,4],PoolOp::Max,[2,2],&[0.0,4.0,15.0,19.0]),
+    case([5,1],[1,1],PoolOp::Mean,[0,5],&[]),
+  )]
+  fn pool_test_pool2d(window: Dim<2>, stride: Dim<2>, op: PoolOp, dim: Dim<2>, expected: &[f64])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,5],|[itr,jtr]| (5*itr+jtr) as f64);
+    let res: Tensor<f64,2>=t.pool2d(window,stride,op);
+
+    assert!(res.dim==dim);
+    assert!(res.iter().eq(expected.iter()));
+  }
+
+  #[rstest(window,op,expected,
+    case([2,2],PoolOp::Max,&[200,4]),
+    case([1,2],PoolOp::Mean,&[100,3,8,0]),
+    case([1,2],PoolOp::Sum,&[201,7,16,0]),
+  )]
+  fn pool_test_pool2d_int(window: Dim<2>, op: PoolOp, expected: &[u8])
+  {
+    let t: Tensor<u8,2>=Tensor::<u8,2>::from_fn([2,4],|[itr,jtr]| [[1,200,3,4],[7,9,0,0]][itr][jtr]);
+
+    assert!(t.pool2d(window,window,op).iter().eq(expected.iter()));
+  }
+
+  #[rstest(window,stride,case([2,2],[0,1]),case([0,2],[1,1]),case([2,0],[2,2]))]
+  #[should_panic(expected="The window and stride must not be zero to pool a tensor.")]
+  fn pool_test_pool2d_zero(window: Dim<2>, stride: Dim<2>)
+  {
+    Tensor::<f64,2>::new([4,5]).pool2d(window,stride,PoolOp::Sum);
+  }
+}
@@ -0,0 +1,286 @@
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+use super::lu::SolveDiagnostics;
+use super::lu::inv_norm_1_estimate;
+use super::lu::residual_norm;
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Least squares solution `x` minimising `|self*x-b|` for a matrix `self` with at least as many rows
+  /// as columns.
+  ///
+  /// The system is reduced to triangular form with Householder reflections, which never forms
+  /// `self^T*self` and so does not square the condition number as the normal equations would. A
+  /// matrix whose columns are linearly dependent, up to rounding, is reported as singular.
+  pub(super) fn lstsq(&self, b: &Tensor<T,1>) -> Result<Tensor<T,1>,TensorError>
+  {
+    let (r,qtb): (Vec<T>,Vec<T>)=self.householder(b)?;
+    Ok(back_substitute(&r,&qtb,self.dim[1]))
+  }
+
+  /// Least squares solution of `self*x=b` as `lstsq` gives, with the norm of its residual and an
+  /// estimate of the 1-norm condition number of the triangular factor `R` of `self`.
+  ///
+  /// Since `R` has the same singular values as `self`, its condition number stands in for that of the
+  /// rectangular matrix, which has no inverse to take the norm of.
+  pub(super) fn lstsq_with_diagnostics(&self, b: &Tensor<T,1>) -> Result<(Tensor<T,1>,SolveDiagnostics<T>),TensorError>
+  {
+    let n: Idx=self.dim[1];
+    let (r,qtb): (Vec<T>,Vec<T>)=self.householder(b)?;
+    let x: Tensor<T,1>=back_substitute(&r,&qtb,n);
+
+    let r_norm: T=(0..n)
+      .map(|jtr| (0..=jtr).fold(T::default(),|sum,itr| sum+r[itr*n+jtr].abs()))
+      .fold(T::default(),|max,sum| if sum>max { sum } else { max });
+    let condition_estimate: T=r_norm*inv_norm_1_estimate(n,|x,transpose| triangular_solve(&r,x,n,transpose));
+    let diagnostics: SolveDiagnostics<T>=SolveDiagnostics{residual_norm:residual_norm(self,&x,b),condition_estimate:condition_estimate};
+    Ok((x,diagnostics))
+  }
+
+  /// Matrix of orthonormal columns spanning the same space as the columns of `self`, each the
+  /// corresponding column of `self` with its components along the columns before it removed.
+  ///
+  /// The components are removed by modified Gram-Schmidt, one at a time from the updated column, which
+  /// loses far less orthogonality to rounding than classical Gram-Schmidt. Rather than dropping a
+  /// column, which would change which column of the result corresponds to which column of `self`, a
+  /// column left with no more than `m*epsilon` of its norm by the removal, for a matrix of `m` rows,
+  /// is reported as singular.
+  pub(super) fn orthonormalize(&self) -> Result<Tensor<T,2>,TensorError>
+  {
+    let (m,n): (Idx,Idx)=(self.dim[0],self.dim[1]);
+    let mut q: Vec<T>=self.to_layout(Layout::ColMajor).data.to_vec();
+    let zero: T=T::default();
+    let tol: T=T::from_f64(m as f64)*T::epsilon();
+    let norm=|col: &[T]| col.iter().fold(zero,|sum,&elem| sum+elem*elem).sqrt();
+    for jtr in 0..n
+    {
+      let (prev,rest): (&mut [T],&mut [T])=q.split_at_mut(jtr*m);
+      let col: &mut [T]=&mut rest[..m];
+      let orig_norm: T=norm(col);
+      for basis in prev.chunks_exact(m)
+      {
+        let proj: T=basis.iter().zip(col.iter()).fold(zero,|sum,(&b,&c)| sum+b*c);
+        col.iter_mut().zip(basis.iter()).for_each(|(c,&b)| *c=*c-proj*b);
+      }
+
+      let col_norm: T=norm(col);
+      if col_norm<=tol*orig_norm || orig_norm==zero { return Err(TensorError::Singular); }
+      col.iter_mut().for_each(|c| *c=*c/col_norm);
+    }
+    Ok(Tensor::<T,2>::from_fn([m,n],|[itr,jtr]| q[jtr*m+itr]))
+  }
+
+  /// Householder reduction of `self` and `b`, giving the row-major elements of `Q^T*self`, whose first
+  /// rows hold the triangular factor `R`, and of `Q^T*b`.
+  fn householder(&self, b: &Tensor<T,1>) -> Result<(Vec<T>,Vec<T>),TensorError>
+  {
+    let (m,n): (Idx,Idx)=(self.dim[0],self.dim[1]);
+    if m!=b.dim[0] { return Err(TensorError::LengthMismatch{lhs:m,rhs:b.dim[0]}); }
+    if n>m { return Err(TensorError::Underdetermined{unknowns:n,equations:m}); }
+
+    let mut a: Vec<T>=self.to_layout(Layout::RowMajor).data.to_vec();
+    let mut b: Vec<T>=b.data.to_vec();
+    let zero: T=T::default();
+    let two: T=T::from_f64(2.0);
+    for jtr in 0..n
+    {
+      let norm: T=(jtr..m).fold(zero,|sum,itr| sum+a[itr*n+jtr]*a[itr*n+jtr]).sqrt();
+      if norm==zero { return Err(TensorError::Singular); }
+
+      // Reflect the column onto the axis with the sign that avoids cancellation in `v[0]`.
+      let alpha: T=if a[jtr*n+jtr]>=zero { zero-norm } else { norm };
+      let mut v: Vec<T>=(jtr..m).map(|itr| a[itr*n+jtr]).collect();
+      v[0]=v[0]-alpha;
+      let v_sq_norm: T=v.iter().fold(zero,|sum,&elem| sum+elem*elem);
+
+      for ktr in jtr..n
+      {
+        let proj: T=two*(jtr..m).fold(zero,|sum,itr| sum+v[itr-jtr]*a[itr*n+ktr])/v_sq_norm;
+        for itr in jtr..m
+        {
+          a[itr*n+ktr]=a[itr*n+ktr]-proj*v[itr-jtr];
+        }
+      }
+      let proj: T=two*(jtr..m).fold(zero,|sum,itr| sum+v[itr-jtr]*b[itr])/v_sq_norm;
+      for itr in jtr..m
+      {
+        b[itr]=b[itr]-proj*v[itr-jtr];
+      }
+    }
+
+    let scale: T=(0..n).fold(zero,|max,itr| if a[itr*n+itr].abs()>max { a[itr*n+itr].abs() } else { max });
+    let tol: T=T::epsilon()*T::from_f64(m as f64)*scale;
+    if (0..n).any(|itr| a[itr*n+itr].abs()<=tol) { return Err(TensorError::Singular); }
+    Ok((a,b))
+  }
+}
+
+/// Solution of `R*x=y` for the triangular factor `R` in the first rows of the row-major elements `r`
+/// with `n` columns.
+fn back_substitute<T>(r: &[T], y: &[T], n: Idx) -> Tensor<T,1>
+where T: Float
+{
+  let mut x: Vec<T>=y[..n].to_vec();
+  triangular_solve(r,&mut x,n,false);
+  Tensor::<T,1>::from_fn([n],|[itr]| x[itr])
+}
+
+/// Overwrites `x` with the solution of `R*y=x`, or of `R^T*y=x` if `transpose` is set, for the
+/// triangular factor `R` as in `back_substitute`.
+fn triangular_solve<T>(r: &[T], x: &mut [T], n: Idx, transpose: bool)
+where T: Float
+{
+  if transpose
+  {
+    for itr in 0..n
+    {
+      x[itr]=(0..itr).fold(x[itr],|sum,jtr| sum-r[jtr*n+itr]*x[jtr])/r[itr*n+itr];
+    }
+  }
+  else
+  {
+    for itr in (0..n).rev()
+    {
+      x[itr]=(itr+1..n).fold(x[itr],|sum,jtr| sum-r[itr*n+jtr]*x[jtr])/r[itr*n+itr];
+    }
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod qr_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+
+  /// The best fitting line through (0,1), (1,2), (2,2) and (3,4) is 0.9+0.9t.
+  #[rstest(a,b,expected,
+    case(Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| [[2.0,1.0,-1.0],[-3.0,-1.0,2.0],[-2.0,1.0,2.0]][itr][jtr]),&[8.0,-11.0,-3.0],&[2.0,3.0,-1.0]),
+    case(Tensor::<f64,2>::from_fn([4,2],|[itr,jtr]| if jtr==0 { 1.0 } else { itr as f64 }),&[1.0,2.0,2.0,4.0],&[0.9,0.9]),
+    case(Tensor::<f64,2>::from_fn([4,2],|[itr,jtr]| if jtr==0 { 1.0 } else { itr as f64 }),&[3.0,1.0,-1.0,-3.0],&[3.0,-2.0]),
+    case(Tensor::<f64,2>::from_fn([3,1],|[itr,_]| (itr+1) as f64),&[1.0,2.0,4.0],&[17.0/14.0]),
+  )]
+  fn qr_test_lstsq(a: Tensor<f64,2>, b: &[f64], expected: &[f64])
+  {
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([b.len()],|[itr]| b[itr]);
+
+    for a in [a.clone(),a.to_layout(Layout::ColMajor)].iter()
+    {
+      let x: Tensor<f64,1>=a.lstsq(&b).unwrap();
+      assert!(x.dim==[expected.len()]);
+      assert!(x.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-12));
+    }
+  }
+
+  #[test]
+  fn qr_test_lstsq_with_diagnostics()
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,2],|[itr,jtr]| if jtr==0 { 1.0 } else { itr as f64 });
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| [1.0,2.0,2.0,4.0][itr]);
+    let (x,diagnostics): (Tensor<f64,1>,SolveDiagnostics<f64>)=a.lstsq_with_diagnostics(&b).unwrap();
+
+    // The residuals of the line 0.9+0.9t are 0.1, 0.2, -0.7 and 0.4.
+    assert!(x==a.lstsq(&b).unwrap());
+    assert!((diagnostics.residual_norm-0.7f64.sqrt()).abs()<1e-12);
+
+    // Scaling the second column makes the problem worse conditioned by about the same factor.
+    let scaled: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,2],|[itr,jtr]| if jtr==0 { a[[itr,0]] } else { 1e-6*a[[itr,1]] });
+    let (_,worse): (Tensor<f64,1>,SolveDiagnostics<f64>)=scaled.lstsq_with_diagnostics(&b).unwrap();
+    assert!(worse.condition_estimate>1e5*diagnostics.condition_estimate && diagnostics.condition_estimate>1.0);
+
+    // For a square matrix the triangular factor has the same 2-norm condition number, so the two
+    // 1-norm estimates agree to within a factor of the dimension.
+    let square: Tensor<f64,2>=Tensor::<f64,2>::from_fn([5,5],|[itr,jtr]| 1.0/(itr+jtr+1) as f64);
+    let (_,qr): (Tensor<f64,1>,SolveDiagnostics<f64>)=square.lstsq_with_diagnostics(&Tensor::<f64,1>::new([5])).unwrap();
+    let lu: f64=square.condition_estimate().unwrap();
+    assert!(qr.condition_estimate<5.0*lu && lu<5.0*qr.condition_estimate);
+  }
+
+  fn qr_test_close(lhs: &Tensor<f64,2>, rhs: &Tensor<f64,2>, tol: f64) -> bool
+  {
+    lhs.dim==rhs.dim && (0..lhs.dim[0]).all(|itr| (0..lhs.dim[1]).all(|jtr| (lhs[[itr,jtr]]-rhs[[itr,jtr]]).abs()<tol))
+  }
+
+  #[rstest(m,n,
+    case(3,3),
+    case(5,3),
+    case(8,6),
+    case(12,12),
+    case(4,1),
+  )]
+  fn qr_test_orthonormalize(m: Idx, n: Idx)
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([m,n],|[itr,jtr]| ((7*itr+3*jtr+itr*jtr)%11) as f64-5.0+if itr==jtr { 0.5 } else { 0.0 });
+    let q: Tensor<f64,2>=a.orthonormalize().unwrap();
+    let identity: Tensor<f64,2>=Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| (itr==jtr) as u8 as f64);
+
+    assert!(qr_test_close(&q.matmul_t_a(&q),&identity,1e-12));
+    assert!(qr_test_close(&q.matmul(&q.matmul_t_a(&a)),&a,1e-12));
+    assert!(q.to_layout(Layout::RowMajor)==a.to_layout(Layout::ColMajor).orthonormalize().unwrap().to_layout(Layout::RowMajor));
+
+    // Every column only depends on the columns of `self` up to it, so the projection is triangular.
+    assert!(q.matmul_t_a(&a).is_triangular(true,1e-12));
+  }
+
+  #[test]
+  fn qr_test_orthonormalize_rotation()
+  {
+    // A rotation whose columns have drifted from orthonormality is pulled back onto a rotation close by.
+    let (sin,cos): (f64,f64)=0.3f64.sin_cos();
+    let rotation: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| [[cos,0.0-sin,0.0],[sin,cos,0.0],[0.0,0.0,1.0]][itr][jtr]);
+    let drifted: Tensor<f64,2>=rotation.map(|elem| elem*1.001)+Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| 1e-4*((itr+2*jtr)%3) as f64);
+
+    let q: Tensor<f64,2>=drifted.orthonormalize().unwrap();
+    let identity: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| (itr==jtr) as u8 as f64);
+    assert!(qr_test_close(&q.matmul_t_a(&q),&identity,1e-15) && qr_test_close(&q,&rotation,1e-3));
+    assert!(!qr_test_close(&drifted.matmul_t_a(&drifted),&identity,1e-3));
+  }
+
+  #[test]
+  fn qr_test_orthonormalize_dependent()
+  {
+    let mut a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,3],|[itr,jtr]| ((itr+1)*(itr+jtr)) as f64+(itr==jtr) as u8 as f64);
+    assert!(a.orthonormalize().is_ok());
+    (0..4).for_each(|itr| a[[itr,2]]=a[[itr,0]]-2.0*a[[itr,1]]);
+    assert!(a.orthonormalize().err()==Some(TensorError::Singular));
+  }
+
+  #[rstest(a,
+    case(Tensor::<f64,2>::new([3,2])),
+    case(Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| (itr==jtr) as u8 as f64)),
+    case(Tensor::<f64,2>::from_fn([3,2],|[itr,_]| itr as f64)),
+  )]
+  fn qr_test_orthonormalize_errors(a: Tensor<f64,2>)
+  {
+    assert!(a.orthonormalize().err()==Some(TensorError::Singular));
+  }
+
+  #[rstest(dim,case([3,0]),case([0,0]))]
+  fn qr_test_orthonormalize_empty(dim: Dim<2>)
+  {
+    assert!(Tensor::<f64,2>::new(dim).orthonormalize().unwrap().dim==dim);
+  }
+
+  #[rstest(a,len,error,
+    case(Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| (itr+1) as f64*(jtr+1) as f64),3,TensorError::Singular),
+    case(Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| (itr+1) as f64*(jtr+1) as f64),2,TensorError::LengthMismatch{lhs:3,rhs:2}),
+    case(Tensor::<f64,2>::new([2,3]),2,TensorError::Underdetermined{unknowns:3,equations:2}),
+    case(Tensor::<f64,2>::new([3,3]),3,TensorError::Singular),
+  )]
+  fn qr_test_lstsq_errors(a: Tensor<f64,2>, len: Idx, error: TensorError)
+  {
+    assert!(a.lstsq(&Tensor::<f64,1>::new([len])).err()==Some(error));
+  }
+}
@@ -0,0 +1,170 @@
+use super::Float;
+use super::Idx;
+use super::Tensor;
+
+/// Affine map between the integers `0..=255` and the reals, under which the integer `q` stands for
+/// `scale*(q-zero_point)`.
+///
+/// The zero point is not restricted to `0..=255`, so elements far from zero still use every integer.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) struct QuantParams
+{
+  pub(super) scale: f64,
+  pub(super) zero_point: i64,
+}
+
+impl QuantParams
+{
+  /// Parameters mapping `min` to zero and `max` to 255, so that any value between them is quantized
+  /// with an error of at most half of `scale`.
+  ///
+  /// If `max` does not exceed `min`, as for a constant tensor, `scale` is one rather than zero and
+  /// `min` is quantized exactly if it is an integer.
+  pub(super) fn from_range(min: f64, max: f64) -> QuantParams
+  {
+    let scale: f64=if max>min { (max-min)/255.0 } else { 1.0 };
+    QuantParams{scale:scale,zero_point:round(-min/scale) as i64}
+  }
+
+  /// Integer standing for `val`, which is clamped to the representable range first.
+  fn quantize(&self, val: f64) -> u8
+  {
+    let q: f64=round(val/self.scale)+self.zero_point as f64;
+    if q<=0.0 { 0 } else if q>=255.0 { 255 } else { q as u8 }
+  }
+
+  /// Value the integer `q` stands for.
+  fn dequantize(&self, q: u8) -> f64
+  {
+    self.scale*(q as i64-self.zero_point) as f64
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Quantizes the elements onto `0..=255` with the parameters `QuantParams::from_range` gives for
+  /// their minimum and maximum, returning the result and the parameters.
+  ///
+  /// Every element is quantized with an error of at most half of the scale, which is one for a
+  /// constant or empty tensor. NaN elements are ignored in finding the range and quantized to zero.
+  pub(super) fn quantize_u8(&self) -> (Tensor<u8,N>,QuantParams)
+  {
+    let (min,max): (f64,f64)=self.data.iter()
+      .map(|&elem| elem.to_f64())
+      .filter(|elem| !elem.is_nan())
+      .fold((f64::INFINITY,f64::NEG_INFINITY),|(min,max),elem| (min.min(elem),max.max(elem)));
+    let params: QuantParams=if min<=max { QuantParams::from_range(min,max) } else { QuantParams::from_range(0.0,0.0) };
+    (self.quantize_u8_with(params),params)
+  }
+
+  /// Quantizes the elements onto `0..=255` with `params`, clamping those outside the range they cover.
+  pub(super) fn quantize_u8_with(&self, params: QuantParams) -> Tensor<u8,N>
+  {
+    self.map(|&elem| if elem.is_nan() { 0 } else { params.quantize(elem.to_f64()) })
+  }
+}
+
+impl<const N: Idx> Tensor<u8,N>
+{
+  /// Values the quantized elements stand for under `params`, the inverse of `quantize_u8` up to its
+  /// rounding.
+  pub(super) fn dequantize(&self, params: QuantParams) -> Tensor<f64,N>
+  {
+    self.map(|&q| params.dequantize(q))
+  }
+}
+
+/// `val` rounded to the nearest integer, with halves rounded away from zero, without `std`.
+fn round(val: f64) -> f64
+{
+  let trunc: f64=val as i64 as f64;
+  if val-trunc>=0.5 { trunc+1.0 } else if trunc-val>=0.5 { trunc-1.0 } else { trunc }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod quantize_tests
+{
+  use super::*;
+  use super::super::testing::assert_tensor_eq;
+  use rstest::rstest;
+
+  /// Halves round away from zero.
+  #[rstest(val,expected,
+    case(0.4,0.0),
+    case(0.5,1.0),
+    case(2.5,3.0),
+    case(-0.5,-1.0),
+    case(-1.4,-1.0),
+    case(-2.6,-3.0),
+  )]
+  fn quantize_test_round(val: f64, expected: f64)
+  {
+    assert!(round(val)==expected);
+  }
+
+  /// The second case is far from zero, where a zero point within 0..=255 would waste most of the
+  /// integers.
+  #[rstest(t,
+    case(Tensor::<f64,2>::from_fn([17,23],|[itr,jtr]| ((itr*23+jtr) as f64*0.37).sin()*3.0-1.0)),
+    case(Tensor::<f64,2>::from_fn([5,40],|[itr,jtr]| 1000.0+(itr*40+jtr) as f64/199.0)),
+    case(Tensor::<f64,2>::from_fn([4,4],|[itr,jtr]| -((itr*4+jtr) as f64)*1e-3)),
+  )]
+  fn quantize_test_round_trip(t: Tensor<f64,2>)
+  {
+    let (q,params): (Tensor<u8,2>,QuantParams)=t.quantize_u8();
+    let res: Tensor<f64,2>=q.dequantize(params);
+    assert!(res.dim==t.dim && t.iter().zip(res.iter()).all(|(&elem,&res)| (elem-res).abs()<=params.scale/2.0+1e-9));
+    assert!(q.iter().min()==Some(&0) && q.iter().max()==Some(&255));
+  }
+
+  #[test]
+  fn quantize_test_round_trip_exact()
+  {
+    let t: Tensor<f32,1>=Tensor::<f32,1>::from_fn([256],|[itr]| itr as f32-100.0);
+    let (q,params): (Tensor<u8,1>,QuantParams)=t.quantize_u8();
+    assert!(params==QuantParams{scale:1.0,zero_point:100} && q.iter().enumerate().all(|(itr,&q)| q as Idx==itr));
+  }
+
+  #[rstest(val,case(0.0),case(3.0),case(-7.25))]
+  fn quantize_test_constant(val: f64)
+  {
+    let (q,params): (Tensor<u8,2>,QuantParams)=Tensor::<f64,2>::from_fn([2,3],|_| val).quantize_u8();
+    assert!(params.scale==1.0 && q.dequantize(params).iter().all(|&res| (res-val).abs()<=0.5));
+  }
+
+  #[test]
+  fn quantize_test_empty()
+  {
+    let (q,params): (Tensor<u8,2>,QuantParams)=Tensor::<f64,2>::new([0,3]).quantize_u8();
+    assert!(params==QuantParams{scale:1.0,zero_point:0} && q.dim==[0,3]);
+  }
+
+  /// Elements outside the range saturate and NaN takes the lowest level.
+  #[rstest(elem,expected,
+    case(-2.0,0),
+    case(-1.0,0),
+    case(0.0,128),
+    case(1.0,255),
+    case(3.0,255),
+    case(f64::NAN,0),
+  )]
+  fn quantize_test_with(elem: f64, expected: u8)
+  {
+    let params: QuantParams=QuantParams::from_range(-1.0,1.0);
+    assert_tensor_eq!(Tensor::<f64,1>::from_fn([2],|_| elem).quantize_u8_with(params),Tensor::<u8,1>::from_fn([2],|_| expected));
+  }
+
+  #[test]
+  fn quantize_test_nan()
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([5],|[itr]| [-2.0,-1.0,0.0,1.0,f64::NAN][itr]);
+    let (q,params): (Tensor<u8,1>,QuantParams)=t.quantize_u8();
+    assert!(params==QuantParams::from_range(-2.0,1.0) && q[4]==0);
+  }
+}
@@ -0,0 +1,270 @@
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Shuffles the slices along `axis` in place, so that every slice keeps its elements but ends up at
+  /// a uniformly random position.
+  ///
+  /// A single Fisher-Yates sequence of swaps is drawn and applied to every lane along `axis`, so the
+  /// rows of a `[samples,features]` matrix shuffled along axis 0 keep their features together.
+  pub(super) fn shuffle_axis<R>(&mut self, axis: Idx, rng: &mut R)
+  where R: Rng + ?Sized
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    let swaps: Vec<(Idx,Idx)>=fisher_yates(self.dim[axis],rng);
+    for mut lane in self.lanes_mut(axis)
+    {
+      swaps.iter().for_each(|&(itr,jtr)| lane.swap(itr,jtr));
+    }
+  }
+
+  /// Tensor of `k` slices along `axis` drawn uniformly at random, with or without replacement.
+  ///
+  /// Drawing without replacement fails if `k` exceeds the length of the axis, and drawing with
+  /// replacement if the axis is empty while `k` is not zero.
+  pub(super) fn sample_axis<R>(&self, axis: Idx, k: Idx, replace: bool, rng: &mut R) -> Result<Tensor<T,N>,TensorError>
+  where R: Rng + ?Sized
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    let len: Idx=self.dim[axis];
+    if (!replace && k>len) || (replace && len==0 && k>0) { return Err(TensorError::SampleSize{k:k,len:len}); }
+
+    let indices: Vec<Idx>=if replace
+    {
+      (0..k).map(|_| rng.gen_range(0..len)).collect()
+    }
+    else
+    {
+      // Only the first `k` steps of a Fisher-Yates shuffle are needed to draw `k` indices.
+      let mut indices: Vec<Idx>=(0..len).collect();
+      for itr in 0..k
+      {
+        indices.swap(itr,rng.gen_range(itr..len));
+      }
+      indices.truncate(k);
+      indices
+    };
+    Ok(self.select_axis(axis,&indices))
+  }
+
+  /// Tensor of the slices along `axis` at `indices`, in that order.
+  fn select_axis(&self, axis: Idx, indices: &[Idx]) -> Tensor<T,N>
+  {
+    let mut dim: [Idx;N]=self.dim;
+    dim[axis]=indices.len();
+    Tensor::<T,N>::from_fn(dim,|mut ind| {
+      ind[axis]=indices[ind[axis]];
+      self[ind].clone()
+    })
+  }
+}
+
+/// Training samples, test samples, training targets and test targets, as returned by
+/// `train_test_split`.
+pub(super) type TrainTestSplit<T>=(Tensor<T,2>,Tensor<T,2>,Tensor<T,1>,Tensor<T,1>);
+
+/// Splits the samples of `x` and their targets `y` at random into a training and a test set, in that
+/// order, with the fraction `test_fraction` of the samples, rounded up, in the test set.
+///
+/// A single permutation of the samples is drawn and applied to both `x` and `y`, so every row of `x`
+/// stays paired with its target. The fraction must lie strictly between zero and one.
+pub(super) fn train_test_split<T,R>(x: &Tensor<T,2>, y: &Tensor<T,1>, test_fraction: f64, rng: &mut R) -> Result<TrainTestSplit<T>,TensorError>
+where T: Scalar, R: Rng + ?Sized
+{
+  if !(0.0<test_fraction && test_fraction<1.0) { return Err(TensorError::InvalidFraction{fraction:test_fraction}); }
+  if x.dim[0]!=y.dim[0] { return Err(TensorError::LengthMismatch{lhs:x.dim[0],rhs:y.dim[0]}); }
+
+  let n: Idx=x.dim[0];
+  let perm: Vec<Idx>=random_permutation(n,rng);
+  let (test,train): (&[Idx],&[Idx])=perm.split_at(ceil(n as f64*test_fraction));
+  Ok((x.select_axis(0,train),x.select_axis(0,test),y.select_axis(0,train),y.select_axis(0,test)))
+}
+
+/// Smallest integer not less than the non-negative `val`, which `core` does not provide for floats.
+fn ceil(val: f64) -> Idx
+{
+  let floor: Idx=val as Idx;
+  if (floor as f64)<val { floor+1 } else { floor }
+}
+
+/// Uniformly random permutation of `0..n`.
+pub(super) fn random_permutation<R>(n: Idx, rng: &mut R) -> Vec<Idx>
+where R: Rng + ?Sized
+{
+  let mut perm: Vec<Idx>=(0..n).collect();
+  fisher_yates(n,rng).into_iter().for_each(|(itr,jtr)| perm.swap(itr,jtr));
+  perm
+}
+
+/// Swaps that shuffle a sequence of length `n` uniformly when applied in order.
+fn fisher_yates<R>(n: Idx, rng: &mut R) -> Vec<(Idx,Idx)>
+where R: Rng + ?Sized
+{
+  (1..n).rev().map(|itr| (itr,rng.gen_range(0..itr+1))).collect()
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod random_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Indices;
+  use super::super::Layout;
+
+  /// SplitMix64 generator, so the tests are deterministic without depending on the generators of
+  /// `rand`.
+  struct TestRng(u64);
+
+  impl rand::RngCore for TestRng
+  {
+    fn next_u32(&mut self) -> u32
+    {
+      (self.next_u64()>>32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64
+    {
+      self.0=self.0.wrapping_add(0x9e3779b97f4a7c15);
+      let mut z: u64=self.0;
+      z=(z^(z>>30)).wrapping_mul(0xbf58476d1ce4e5b9);
+      z=(z^(z>>27)).wrapping_mul(0x94d049bb133111eb);
+      z^(z>>31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8])
+    {
+      for chunk in dest.chunks_mut(8)
+      {
+        let bytes: [u8;8]=self.next_u64().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+      }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(),rand::Error>
+    {
+      self.fill_bytes(dest);
+      Ok(())
+    }
+  }
+
+  #[rstest(layout,seed,case(Layout::RowMajor,7),case(Layout::ColMajor,7),case(Layout::RowMajor,1),case(Layout::ColMajor,42))]
+  fn random_test_shuffle_axis(layout: Layout, seed: u64)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([8,3],|[itr,jtr]| (10*itr+jtr) as i32);
+    let mut shuffled: Tensor<i32,2>=t.to_layout(layout);
+    shuffled.shuffle_axis(0,&mut TestRng(seed));
+
+    // Every sample appears exactly once, with its features still attached, in the same order whatever
+    // the layout.
+    let mut firsts: Vec<i32>=(0..8).map(|itr| shuffled[[itr,0]]).collect();
+    assert!((0..8).all(|itr| (0..3).all(|jtr| shuffled[[itr,jtr]]==firsts[itr]+jtr as i32)));
+    assert!(firsts!=(0..8).map(|itr| 10*itr).collect::<Vec<i32>>());
+    firsts.sort_unstable();
+    assert!(firsts==(0..8).map(|itr| 10*itr).collect::<Vec<i32>>());
+    let mut row_major: Tensor<i32,2>=t.clone();
+    row_major.shuffle_axis(0,&mut TestRng(seed));
+    assert!(shuffled==row_major);
+
+    let mut cols: Tensor<i32,2>=t.to_layout(layout);
+    cols.shuffle_axis(1,&mut TestRng(seed));
+    assert!((0..8).all(|itr| (0..3).all(|jtr| cols[[itr,jtr]]-cols[[0,jtr]]==10*itr as i32)));
+  }
+
+  #[rstest(len,axis,k,replace,
+    case(8,0,5,false),
+    case(8,0,8,false),
+    case(8,0,12,true),
+    case(8,1,10,true),
+    case(8,1,2,false),
+    case(0,0,0,true),
+  )]
+  fn random_test_sample_axis(len: Idx, axis: Idx, k: Idx, replace: bool)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([len,3],|[itr,jtr]| (10*itr+jtr) as i32);
+    let sample: Tensor<i32,2>=t.sample_axis(axis,k,replace,&mut TestRng(3)).unwrap();
+
+    let mut dim: Dim<2>=t.dim;
+    dim[axis]=k;
+    assert!(sample.dim==dim);
+    assert!(sample==t.sample_axis(axis,k,replace,&mut TestRng(3)).unwrap());
+
+    // Every sample is a whole slice of the tensor, and without replacement none is drawn twice.
+    let mut drawn: Vec<i32>=(0..k).map(|itr| if axis==0 { sample[[itr,0]] } else { sample[[0,itr]] }).collect();
+    assert!(Indices::new(dim).all(|[itr,jtr]| sample[[itr,jtr]]==drawn[if axis==0 { itr } else { jtr }]+if axis==0 { jtr as i32 } else { 10*itr as i32 }));
+    drawn.sort_unstable();
+    drawn.dedup();
+    assert!(replace || drawn.len()==k);
+  }
+
+  #[rstest(len,k,replace,case(8,9,false),case(3,4,false),case(0,1,true),case(0,1,false))]
+  fn random_test_sample_axis_size(len: Idx, k: Idx, replace: bool)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::new([len,3]);
+    assert!(t.sample_axis(0,k,replace,&mut TestRng(3)).err()==Some(TensorError::SampleSize{k:k,len:len}));
+  }
+
+  #[rstest(fraction,n_test,case(0.3,3),case(0.5,4),case(0.1,1),case(0.25,2),case(0.9,8))]
+  fn random_test_train_test_split(fraction: f64, n_test: Idx)
+  {
+    let x: Tensor<i32,2>=Tensor::<i32,2>::from_fn([8,3],|[itr,jtr]| (10*itr+jtr) as i32);
+    let y: Tensor<i32,1>=Tensor::<i32,1>::from_fn([8],|[itr]| (10*itr) as i32);
+    let n_train: Idx=8-n_test;
+
+    let (x_train,x_test,y_train,y_test): TrainTestSplit<i32>=train_test_split(&x,&y,fraction,&mut TestRng(5)).unwrap();
+    assert!(x_train.dim==[n_train,3] && x_test.dim==[n_test,3] && y_train.dim==[n_train] && y_test.dim==[n_test]);
+    assert!((0..n_train).all(|itr| x_train[[itr,0]]==y_train[itr]) && (0..n_test).all(|itr| x_test[[itr,0]]==y_test[itr]));
+
+    // Together the two sets hold every sample exactly once.
+    let mut rows: Vec<Vec<i32>>=(0..n_train).map(|itr| (0..3).map(|jtr| x_train[[itr,jtr]]).collect())
+      .chain((0..n_test).map(|itr| (0..3).map(|jtr| x_test[[itr,jtr]]).collect()))
+      .collect();
+    rows.sort_unstable();
+    assert!(rows==(0..8).map(|itr| (0..3).map(|jtr| x[[itr,jtr]]).collect()).collect::<Vec<Vec<i32>>>());
+
+    let again: TrainTestSplit<i32>=train_test_split(&x,&y,fraction,&mut TestRng(5)).unwrap();
+    assert!(again==(x_train,x_test,y_train,y_test));
+  }
+
+  #[rstest(fraction,case(0.0),case(1.0),case(-0.5),case(1.5),case(f64::NAN))]
+  fn random_test_train_test_split_fraction(fraction: f64)
+  {
+    let res: Result<TrainTestSplit<i32>,TensorError>=train_test_split(&Tensor::<i32,2>::new([8,3]),&Tensor::<i32,1>::new([8]),fraction,&mut TestRng(5));
+    assert!(matches!(res,Err(TensorError::InvalidFraction{..})));
+  }
+
+  #[rstest(len,case(7),case(9),case(0))]
+  fn random_test_train_test_split_lengths(len: Idx)
+  {
+    let res: Result<TrainTestSplit<i32>,TensorError>=train_test_split(&Tensor::<i32,2>::new([8,3]),&Tensor::<i32,1>::new([len]),0.5,&mut TestRng(5));
+    assert!(res.err()==Some(TensorError::LengthMismatch{lhs:8,rhs:len}));
+  }
+
+  #[rstest(n,case(0),case(1),case(2),case(20))]
+  fn random_test_random_permutation(n: Idx)
+  {
+    let perm: Vec<Idx>=random_permutation(n,&mut TestRng(11));
+    assert!(perm==random_permutation(n,&mut TestRng(11)));
+    assert!(n<20 || perm!=random_permutation(n,&mut TestRng(12)));
+
+    let mut sorted: Vec<Idx>=perm.clone();
+    sorted.sort_unstable();
+    assert!(sorted==(0..n).collect::<Vec<Idx>>());
+  }
+}
@@ -0,0 +1,468 @@
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Indices;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Block of dimensions `size` whose first element is the element of `self` at `start`.
+  pub(super) fn crop(&self, start: Dim<N>, size: Dim<N>) -> Result<Tensor<T,N>,TensorError>
+  {
+    check_block(&self.dim,&start,&size)?;
+
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    let mut res: Tensor<T,N>=Tensor::<T,N>::new(size);
+    copy_block(&t.data,self.dim,start,&mut res.data,size,[0;N],size);
+    Ok(res)
+  }
+
+  /// Tensor of dimensions `into_dim` filled with `fill` except for the block starting at `at`, which
+  /// holds `self`. This is the inverse of `crop`.
+  pub(super) fn embed(&self, into_dim: Dim<N>, at: Dim<N>, fill: T) -> Result<Tensor<T,N>,TensorError>
+  {
+    check_block(&into_dim,&at,&self.dim)?;
+
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    let mut res: Tensor<T,N>=Tensor::<T,N>::from_fn(into_dim,|_| fill.clone());
+    copy_block(&t.data,self.dim,[0;N],&mut res.data,into_dim,at,self.dim);
+    Ok(res)
+  }
+
+  /// Every `step`th slice along `axis`, starting with the first, so `axis` has length `len/step`
+  /// rounded up.
+  pub(super) fn step_by_axis(&self, axis: Idx, step: Idx) -> Result<Tensor<T,N>,TensorError>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    let mut factors: Dim<N>=[1;N];
+    factors[axis]=step;
+    self.downsample(factors)
+  }
+
+  /// Every `factors[axis]`th slice along every axis simultaneously.
+  pub(super) fn downsample(&self, factors: Dim<N>) -> Result<Tensor<T,N>,TensorError>
+  {
+    if let Some(axis)=factors.iter().position(|&factor| factor==0) { return Err(TensorError::ZeroFactor{axis:axis}); }
+
+    let mut dim: Dim<N>=self.dim;
+    let mut steps: Dim<N>=factors;
+    let mut stride: Idx=1;
+    for axis in (0..N).rev()
+    {
+      dim[axis]=self.dim[axis].div_ceil(factors[axis]);
+      steps[axis]*=stride;
+      stride*=self.dim[axis];
+    }
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    let offsets: StridedOffsets<N>=StridedOffsets{dim:dim,steps:steps,ind:[0;N],offset:0,left:dim.size()};
+    Ok(Tensor::<T,N>::new_uninit(dim).init(offsets.map(|offset| t.data[offset].clone())))
+  }
+
+  /// Repeats every slice along `axis` `factor` times in a row, so `axis` has length `len*factor`.
+  pub(super) fn upsample_repeat(&self, axis: Idx, factor: Idx) -> Result<Tensor<T,N>,TensorError>
+  {
+    self.upsample(axis,factor,true)
+  }
+
+  /// Inserts `factor-1` slices of zeros after every slice along `axis`, so `axis` has length
+  /// `len*factor`.
+  pub(super) fn upsample_zero(&self, axis: Idx, factor: Idx) -> Result<Tensor<T,N>,TensorError>
+  {
+    self.upsample(axis,factor,false)
+  }
+
+  fn upsample(&self, axis: Idx, factor: Idx, repeat: bool) -> Result<Tensor<T,N>,TensorError>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+    if factor==0 { return Err(TensorError::ZeroFactor{axis:axis}); }
+
+    let (outer,len,inner): (Idx,Idx,Idx)=(self.dim[..axis].iter().product(),self.dim[axis],self.dim[axis+1..].iter().product());
+    let mut dim: Dim<N>=self.dim;
+    dim[axis]*=factor;
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    let data: &[T]=&t.data;
+    let elems=(0..outer).flat_map(move |itr| (0..len*factor).flat_map(move |jtr| {
+      let start: Idx=(itr*len+jtr/factor)*inner;
+      let keep: bool=repeat || jtr%factor==0;
+      (start..start+inner).map(move |ktr| if keep { data[ktr].clone() } else { T::default() })
+    }));
+    Ok(Tensor::<T,N>::new_uninit(dim).init(elems))
+  }
+
+  /// Copy of `self` repeated along every axis of length one to the length of that axis in `dim`.
+  pub(super) fn broadcast_to(&self, dim: Dim<N>) -> Tensor<T,N>
+  {
+    if self.dim.iter().zip(dim.iter()).any(|(&len,&target)| len!=target && len!=1) { panic!("Every axis of a tensor must be of length one or of the target length to broadcast it.") }
+
+    Tensor::<T,N>::from_fn(dim,|mut ind| {
+      ind.iter_mut().zip(self.dim.iter()).filter(|(_,&len)| len==1).for_each(|(ind,_)| *ind=0);
+      self[ind].clone()
+    })
+  }
+
+  /// Copy of `self` with the order of the elements along `axis` reversed.
+  pub(super) fn flip(&self, axis: Idx) -> Tensor<T,N>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    Tensor::<T,N>::from_fn(self.dim,|mut ind| {
+      ind[axis]=self.dim[axis]-1-ind[axis];
+      self[ind].clone()
+    })
+  }
+
+  /// Iterator over consecutive chunks of `chunk` slices along `axis`, the last of which holds the
+  /// remaining slices if the length of `axis` is not a multiple of `chunk`.
+  ///
+  /// Chunks along the first axis of a row-major tensor share its element buffer; other chunks are
+  /// copies.
+  pub(super) fn axis_chunks(&self, axis: Idx, chunk: Idx) -> Result<AxisChunks<'_,T,N>,TensorError>
+  {
+    self.chunks(axis,chunk,false)
+  }
+
+  /// Iterator over consecutive chunks of exactly `chunk` slices along `axis`, as `axis_chunks`, that
+  /// skips the remaining slices if the length of `axis` is not a multiple of `chunk`.
+  pub(super) fn axis_exact_chunks(&self, axis: Idx, chunk: Idx) -> Result<AxisChunks<'_,T,N>,TensorError>
+  {
+    self.chunks(axis,chunk,true)
+  }
+
+  fn chunks(&self, axis: Idx, chunk: Idx, exact: bool) -> Result<AxisChunks<'_,T,N>,TensorError>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+    if chunk==0 { return Err(TensorError::ZeroFactor{axis:axis}); }
+
+    let len: Idx=self.dim[axis];
+    let end: Idx=if exact { len-len%chunk } else { len };
+    Ok(AxisChunks{tensor:self,axis:axis,chunk:chunk,start:0,end:end})
+  }
+}
+
+/// Iterator over consecutive chunks of a tensor along an axis, returned by `Tensor::axis_chunks` and
+/// `Tensor::axis_exact_chunks`.
+pub(super) struct AxisChunks<'a,T: Scalar, const N: Idx>
+{
+  tensor: &'a Tensor<T,N>,
+  axis: Idx,
+  chunk: Idx,
+  start: Idx,
+  end: Idx,
+}
+
+impl<'a,T,const N: Idx> Iterator for AxisChunks<'a,T,N>
+where T: Scalar
+{
+  type Item=Tensor<T,N>;
+  fn next(&mut self) -> Option<Tensor<T,N>>
+  {
+    if self.start>=self.end { return None; }
+
+    let size: Idx=self.chunk.min(self.end-self.start);
+    let res: Tensor<T,N>=if self.axis==0 && self.tensor.layout==Layout::RowMajor
+    {
+      let (_,rest): (Tensor<T,N>,Tensor<T,N>)=self.tensor.clone().split_first_axis(self.start);
+      rest.split_first_axis(size).0
+    }
+    else
+    {
+      let mut start: Dim<N>=[0;N];
+      let mut dim: Dim<N>=self.tensor.dim;
+      start[self.axis]=self.start;
+      dim[self.axis]=size;
+      self.tensor.crop(start,dim).unwrap()
+    };
+    self.start+=size;
+    Some(res)
+  }
+
+  fn size_hint(&self) -> (Idx,Option<Idx>)
+  {
+    let left: Idx=(self.end-self.start).div_ceil(self.chunk);
+    (left,Some(left))
+  }
+}
+
+impl<'a,T,const N: Idx> ExactSizeIterator for AxisChunks<'a,T,N>
+where T: Scalar
+{}
+
+/// Iterator over the offsets of the elements of a tensor that are selected with a fixed step along
+/// every axis, in row-major order of the selection.
+///
+/// The offset is updated as the index into the selection of dimensions `dim` advances, stepping by
+/// `steps[axis]` along `axis` and rewinding an axis when it wraps, rather than being computed anew
+/// from the index.
+struct StridedOffsets<const N: Idx>
+{
+  dim: Dim<N>,
+  steps: Dim<N>,
+  ind: Dim<N>,
+  offset: Idx,
+  left: Idx,
+}
+
+impl<const N: Idx> Iterator for StridedOffsets<N>
+{
+  type Item=Idx;
+  fn next(&mut self) -> Option<Idx>
+  {
+    if self.left==0 { return None; }
+    self.left-=1;
+
+    let current: Idx=self.offset;
+    for axis in (0..N).rev()
+    {
+      self.ind[axis]+=1;
+      self.offset+=self.steps[axis];
+      if self.ind[axis]<self.dim[axis] { break; }
+      self.offset-=self.dim[axis]*self.steps[axis];
+      self.ind[axis]=0;
+    }
+    Some(current)
+  }
+}
+
+/// Checks that the block of dimensions `size` starting at `start` lies within dimensions `dim`.
+pub(super) fn check_block<const N: Idx>(dim: &Dim<N>, start: &Dim<N>, size: &Dim<N>) -> Result<(),TensorError>
+{
+  match (0..N).find(|&axis| start[axis]+size[axis]>dim[axis])
+  {
+    Some(axis) => Err(TensorError::OutOfBounds{axis:axis}),
+    None => Ok(()),
+  }
+}
+
+/// Copies the block of dimensions `size` at `src_start` of the row-major `src` of dimensions
+/// `src_dim` to `dst_start` of `dst`, one contiguous run along the last axis at a time.
+fn copy_block<T,const N: Idx>(src: &[T], src_dim: Dim<N>, src_start: Dim<N>, dst: &mut [T], dst_dim: Dim<N>, dst_start: Dim<N>, size: Dim<N>)
+where T: Clone
+{
+  if size.size()==0 { return; }
+  if N==0 { dst[0]=src[0].clone(); return; }
+
+  let len: Idx=size[N-1];
+  let mut outer: Dim<N>=size;
+  outer[N-1]=1;
+  for ind in Indices::new(outer)
+  {
+    let mut src_ind: Dim<N>=src_start;
+    let mut dst_ind: Dim<N>=dst_start;
+    for axis in 0..N
+    {
+      src_ind[axis]+=ind[axis];
+      dst_ind[axis]+=ind[axis];
+    }
+    let (src_offset,dst_offset): (Idx,Idx)=(src_dim.index(src_ind),dst_dim.index(dst_ind));
+    dst[dst_offset..dst_offset+len].clone_from_slice(&src[src_offset..src_offset+len]);
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod region_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec::Vec;
+
+  #[rstest(start,len,case([1,2,1],[2,2,3]),case([0,0,0],[3,4,5]),case([2,3,4],[1,1,1]),case([3,0,5],[0,4,0]))]
+  fn region_test_crop(start: Dim<3>, len: Dim<3>)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let block: Tensor<i32,3>=t.crop(start,len).unwrap();
+
+    assert!(block.dim==len);
+    assert!(Indices::new(len).all(|[itr,jtr,ktr]| block[[itr,jtr,ktr]]==t[[start[0]+itr,start[1]+jtr,start[2]+ktr]]));
+  }
+
+  #[rstest(dim,start,expected,
+    case([3,4],[1,1],&[-1.0,-1.0,-1.0,-1.0,-1.0,1.0,2.0,-1.0,-1.0,3.0,4.0,-1.0]),
+    case([2,3],[0,1],&[-1.0,1.0,2.0,-1.0,3.0,4.0]),
+    case([2,2],[0,0],&[1.0,2.0,3.0,4.0]),
+  )]
+  fn region_test_embed(dim: Dim<2>, start: Dim<2>, expected: &[f64])
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,2],|[itr,jtr]| (2*itr+jtr+1) as f64);
+    let padded: Tensor<f64,2>=t.embed(dim,start,-1.0).unwrap();
+
+    assert!(padded.dim==dim);
+    assert!(padded.iter().eq(expected.iter()));
+  }
+
+  #[rstest(dim,start,case([5,6,8],[2,1,3]),case([3,4,5],[0,0,0]),case([4,4,5],[1,0,0]))]
+  fn region_test_round_trip(dim: Dim<3>, start: Dim<3>)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let embedded: Tensor<i32,3>=t.embed(dim,start,0).unwrap();
+    let cropped: Tensor<i32,3>=embedded.crop(start,[3,4,5]).unwrap();
+
+    assert!(cropped.dim==t.dim);
+    assert!(cropped.iter().eq(t.iter()));
+    assert!(embedded.sum()==t.sum());
+  }
+
+  #[rstest(start,len,axis,case([0,3,0],[1,2,1],1),case([0,0,1],[1,1,5],2),case([4,0,0],[0,1,1],0))]
+  fn region_test_crop_out_of_bounds(start: Dim<3>, len: Dim<3>, axis: Idx)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::new([3,4,5]);
+    assert!(t.crop(start,len).err()==Some(TensorError::OutOfBounds{axis:axis}));
+  }
+
+  #[rstest(dim,start,axis,case([4,4,4],[1,0,0],2),case([3,5,5],[1,0,0],0),case([3,4,6],[0,1,0],1))]
+  fn region_test_embed_out_of_bounds(dim: Dim<3>, start: Dim<3>, axis: Idx)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::new([3,4,5]);
+    assert!(t.embed(dim,start,0).err()==Some(TensorError::OutOfBounds{axis:axis}));
+  }
+
+  #[rstest(axis,step,dim,case(1,3,[3,2,5]),case(2,2,[3,4,3]),case(0,1,[3,4,5]),case(0,7,[1,4,5]),case(2,5,[3,4,1]))]
+  fn region_test_step_by_axis(axis: Idx, step: Idx, dim: Dim<3>)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let res: Tensor<i32,3>=t.step_by_axis(axis,step).unwrap();
+
+    assert!(res.dim==dim);
+    for mut ind in Indices::new(dim)
+    {
+      let elem: i32=res[ind];
+      ind[axis]*=step;
+      assert!(elem==t[ind]);
+    }
+  }
+
+  #[rstest(factors,dim,case([2,3,2],[2,2,3]),case([1,1,1],[3,4,5]),case([3,4,5],[1,1,1]),case([5,1,7],[1,4,1]))]
+  fn region_test_downsample(factors: Dim<3>, dim: Dim<3>)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let res: Tensor<i32,3>=t.downsample(factors).unwrap();
+
+    assert!(res.dim==dim);
+    assert!(Indices::new(dim).all(|[itr,jtr,ktr]| res[[itr,jtr,ktr]]==t[[factors[0]*itr,factors[1]*jtr,factors[2]*ktr]]));
+  }
+
+  #[rstest(len,factor,expected_len,case(7,3,3),case(6,3,2),case(1,4,1),case(0,2,0))]
+  fn region_test_downsample_len(len: Idx, factor: Idx, expected_len: Idx)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([len,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    let res: Tensor<f64,2>=t.downsample([factor,2]).unwrap();
+
+    assert!(res.dim==[expected_len,2]);
+    assert!(Indices::new(res.dim).all(|[itr,jtr]| res[[itr,jtr]]==(4*factor*itr+2*jtr) as f64));
+  }
+
+  #[rstest(axis,case(0),case(1),case(2))]
+  fn region_test_zero_factor(axis: Idx)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::new([3,4,5]);
+    let mut factors: Dim<3>=[2,2,2];
+    factors[axis]=0;
+
+    assert!(t.downsample(factors).err()==Some(TensorError::ZeroFactor{axis:axis}));
+    assert!(t.step_by_axis(axis,0).err()==Some(TensorError::ZeroFactor{axis:axis}));
+    assert!(t.upsample_repeat(axis,0).err()==Some(TensorError::ZeroFactor{axis:axis}));
+    assert!(t.upsample_zero(axis,0).err()==Some(TensorError::ZeroFactor{axis:axis}));
+    assert!(t.axis_chunks(axis,0).err()==Some(TensorError::ZeroFactor{axis:axis}));
+    assert!(t.axis_exact_chunks(axis,0).err()==Some(TensorError::ZeroFactor{axis:axis}));
+  }
+
+  #[rstest(axis,factor,dim,case(0,1,[2,3]),case(0,2,[4,3]),case(1,2,[2,6]),case(1,3,[2,9]),case(0,3,[6,3]))]
+  fn region_test_upsample(axis: Idx, factor: Idx, dim: Dim<2>)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (10*itr+jtr) as i32+1);
+    let repeated: Tensor<i32,2>=t.upsample_repeat(axis,factor).unwrap();
+    let zeroed: Tensor<i32,2>=t.upsample_zero(axis,factor).unwrap();
+
+    assert!(repeated.dim==dim && zeroed.dim==dim);
+    for ind in Indices::new(dim)
+    {
+      let mut from: Dim<2>=ind;
+      from[axis]/=factor;
+      assert!(repeated[ind]==t[from]);
+      assert!(zeroed[ind]==if ind[axis]%factor==0 { t[from] } else { 0 });
+    }
+    let mut factors: Dim<2>=[1,1];
+    factors[axis]=factor;
+    assert!(zeroed.downsample(factors).unwrap().iter().eq(t.iter()));
+  }
+
+  #[rstest(dim,case([2,3]),case([2,1]),case([2,0]))]
+  fn region_test_broadcast_to(dim: Dim<2>)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,1],|[itr,_]| itr as i32+1);
+    let res: Tensor<i32,2>=t.broadcast_to(dim);
+
+    assert!(res.dim==dim);
+    assert!(Indices::new(dim).all(|[itr,jtr]| res[[itr,jtr]]==t[[itr,0]]));
+  }
+
+  #[rstest(axis,case(0),case(1),case(2))]
+  fn region_test_flip(axis: Idx)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let res: Tensor<i32,3>=t.flip(axis);
+
+    for mut ind in Indices::new(t.dim)
+    {
+      let elem: i32=res[ind];
+      ind[axis]=t.dim[axis]-1-ind[axis];
+      assert!(elem==t[ind]);
+    }
+    assert!(res.flip(axis).iter().eq(t.iter()));
+  }
+
+  #[rstest(layout,axis,size,lens,
+    case(Layout::RowMajor,0,2,vec![2,1]),
+    case(Layout::RowMajor,2,2,vec![2,2,1]),
+    case(Layout::RowMajor,1,10,vec![4]),
+    case(Layout::RowMajor,1,1,vec![1,1,1,1]),
+    case(Layout::ColMajor,0,2,vec![2,1]),
+    case(Layout::ColMajor,2,3,vec![3,2]),
+  )]
+  fn region_test_axis_chunks(layout: Layout, axis: Idx, size: Idx, lens: Vec<Idx>)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let laid_out: Tensor<i32,3>=t.to_layout(layout);
+    let crop=|itr: Idx| {
+      let (mut start,mut len): (Dim<3>,Dim<3>)=([0,0,0],t.dim);
+      start[axis]=size*itr;
+      len[axis]=lens[itr];
+      t.crop(start,len).unwrap()
+    };
+
+    let chunks: AxisChunks<i32,3>=laid_out.axis_chunks(axis,size).unwrap();
+    assert!(chunks.len()==lens.len() && chunks.size_hint()==(lens.len(),Some(lens.len())));
+    for (itr,chunk) in chunks.enumerate()
+    {
+      assert!(chunk.shares_data(&laid_out)==(axis==0 && layout==Layout::RowMajor));
+      assert!(chunk.to_layout(Layout::RowMajor).iter().eq(crop(itr).iter()));
+    }
+
+    let mut chunks: AxisChunks<i32,3>=laid_out.axis_chunks(axis,size).unwrap();
+    assert!(chunks.nth(lens.len()-1).unwrap().to_layout(Layout::RowMajor).iter().eq(crop(lens.len()-1).iter()));
+    assert!(chunks.next().is_none());
+  }
+
+  #[rstest(axis,size,count,case(2,2,2),case(0,4,0),case(1,2,2),case(0,1,3),case(2,5,1))]
+  fn region_test_axis_exact_chunks(axis: Idx, size: Idx, count: Idx)
+  {
+    let t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([3,4,5],|[itr,jtr,ktr]| (100*itr+10*jtr+ktr) as i32);
+    let mut dim: Dim<3>=t.dim;
+    dim[axis]=size;
+
+    let chunks: AxisChunks<i32,3>=t.axis_exact_chunks(axis,size).unwrap();
+    assert!(chunks.len()==count);
+    let chunks: Vec<Tensor<i32,3>>=chunks.collect();
+    assert!(chunks.len()==count && chunks.iter().all(|chunk| chunk.dim==dim));
+  }
+}
@@ -0,0 +1,211 @@
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+/// Element-wise mean and variance of a stream of tensors, accumulated one sample at a time without
+/// holding the samples.
+///
+/// The dimensions of the samples are fixed by the first one, before which the mean and variance are
+/// empty tensors.
+#[derive(Debug,Clone)]
+pub(super) struct RunningStats<T,const N: Idx>
+{
+  count: Idx,
+  mean: Tensor<T,N>,
+  /// Sum of the squared deviations from the mean of every element.
+  m2: Tensor<T,N>,
+}
+
+impl<T,const N: Idx> RunningStats<T,N>
+where T: Float
+{
+  /// Accumulator that has not seen any samples.
+  pub(super) fn new() -> RunningStats<T,N>
+  {
+    RunningStats{count:0,mean:Tensor::<T,N>::new([0;N]),m2:Tensor::<T,N>::new([0;N])}
+  }
+
+  /// Number of samples accumulated.
+  pub(super) fn count(&self) -> Idx
+  {
+    self.count
+  }
+
+  /// Adds `sample` to the statistics by Welford's algorithm, failing unless it is of the dimensions of
+  /// the samples before it.
+  pub(super) fn update(&mut self, sample: &Tensor<T,N>) -> Result<(),TensorError>
+  {
+    if self.count==0
+    {
+      self.mean=sample.clone();
+      self.m2=Tensor::<T,N>::new(sample.dim).into_layout(sample.layout);
+      self.count=1;
+      return Ok(());
+    }
+    if sample.dim!=self.mean.dim { return Err(TensorError::DimMismatch{lhs:self.mean.dim.to_vec(),rhs:sample.dim.to_vec()}); }
+
+    self.count+=1;
+    let count: T=T::from_f64(self.count as f64);
+    let sample: Tensor<T,N>=sample.to_layout(self.mean.layout);
+    for ((mean,m2),&elem) in self.mean.data.iter_mut().zip(self.m2.data.iter_mut()).zip(sample.data.iter())
+    {
+      let delta: T=elem-*mean;
+      *mean+=delta/count;
+      *m2+=delta*(elem-*mean);
+    }
+    Ok(())
+  }
+
+  /// Element-wise mean of the samples.
+  pub(super) fn mean(&self) -> Tensor<T,N>
+  {
+    self.mean.clone()
+  }
+
+  /// Element-wise variance of the samples with `ddof` delta degrees of freedom, dividing by
+  /// `count-ddof`, which is NaN unless there are more than `ddof` samples.
+  pub(super) fn var(&self, ddof: Idx) -> Tensor<T,N>
+  {
+    let den: T=if self.count>ddof { T::from_f64((self.count-ddof) as f64) } else { T::nan() };
+    self.m2.map(|&m2| m2/den)
+  }
+
+  /// Combines the statistics of `other` into `self` as if its samples had been added to `self`, by the
+  /// pairwise update of Chan et al., failing unless both are of the same dimensions.
+  pub(super) fn merge(&mut self, other: &RunningStats<T,N>) -> Result<(),TensorError>
+  {
+    if other.count==0 { return Ok(()); }
+    if self.count==0
+    {
+      *self=other.clone();
+      return Ok(());
+    }
+    if other.mean.dim!=self.mean.dim { return Err(TensorError::DimMismatch{lhs:self.mean.dim.to_vec(),rhs:other.mean.dim.to_vec()}); }
+
+    let (count_a,count_b): (T,T)=(T::from_f64(self.count as f64),T::from_f64(other.count as f64));
+    let count: T=count_a+count_b;
+    let (mean_b,m2_b): (Tensor<T,N>,Tensor<T,N>)=(other.mean.to_layout(self.mean.layout),other.m2.to_layout(self.mean.layout));
+    let others=mean_b.data.iter().zip(m2_b.data.iter());
+    for ((mean,m2),(&mean_b,&m2_b)) in self.mean.data.iter_mut().zip(self.m2.data.iter_mut()).zip(others)
+    {
+      let delta: T=mean_b-*mean;
+      *mean+=delta*count_b/count;
+      *m2+=m2_b+delta*delta*count_a*count_b/count;
+    }
+    self.count+=other.count;
+    Ok(())
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod running_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec;
+  use alloc::vec::Vec;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  fn running_test_samples(n: Idx) -> Vec<Tensor<f64,2>>
+  {
+    (0..n).map(|ktr| Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| {
+      let x: f64=(ktr*ktr) as f64*0.5+(3*itr+jtr) as f64;
+      if (itr+jtr)%2==0 { x } else { 1e6-x }
+    })).collect()
+  }
+
+  /// Mean and variance with `ddof` of every element over the samples, computed in two passes.
+  fn running_test_batch(samples: &[Tensor<f64,2>], ddof: Idx) -> (Tensor<f64,2>,Tensor<f64,2>)
+  {
+    let n: f64=samples.len() as f64;
+    let mean: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|ind| samples.iter().map(|s| s[ind]).sum::<f64>()/n);
+    let var: Tensor<f64,2>=Tensor::<f64,2>::from_fn([2,3],|ind| {
+      samples.iter().map(|s| (s[ind]-mean[ind])*(s[ind]-mean[ind])).sum::<f64>()/(n-ddof as f64)
+    });
+    (mean,var)
+  }
+
+  fn running_test_close(lhs: &Tensor<f64,2>, rhs: &Tensor<f64,2>) -> bool
+  {
+    (0..2).all(|itr| (0..3).all(|jtr| (lhs[[itr,jtr]]-rhs[[itr,jtr]]).abs()<=1e-9*rhs[[itr,jtr]].abs().max(1.0)))
+  }
+
+  fn running_test_accumulate(samples: &[Tensor<f64,2>], layout: Layout) -> RunningStats<f64,2>
+  {
+    let mut stats: RunningStats<f64,2>=RunningStats::<f64,2>::new();
+    samples.iter().for_each(|sample| stats.update(&sample.to_layout(layout)).unwrap());
+    stats
+  }
+
+  #[rstest(n,layout,ddof,
+    case(7,Layout::RowMajor,0),
+    case(7,Layout::ColMajor,1),
+    case(7,Layout::RowMajor,3),
+    case(2,Layout::ColMajor,1),
+    case(1,Layout::RowMajor,0),
+  )]
+  fn running_test_update(n: Idx, layout: Layout, ddof: Idx)
+  {
+    let samples: Vec<Tensor<f64,2>>=running_test_samples(n);
+    let stats: RunningStats<f64,2>=running_test_accumulate(&samples,layout);
+    let (mean,var): (Tensor<f64,2>,Tensor<f64,2>)=running_test_batch(&samples,ddof);
+
+    assert!(stats.count()==n);
+    assert!(running_test_close(&stats.mean(),&mean) && running_test_close(&stats.var(ddof),&var));
+  }
+
+  #[rstest(n,ddof,case(0,0),case(1,1),case(7,7),case(7,9))]
+  fn running_test_var_nan(n: Idx, ddof: Idx)
+  {
+    let stats: RunningStats<f64,2>=running_test_accumulate(&running_test_samples(n),Layout::RowMajor);
+
+    assert!(stats.count()==n && stats.mean().is_empty()==(n==0));
+    assert!(stats.var(ddof).iter().all(|elem| elem.is_nan()));
+  }
+
+  #[rstest(dim,case([3,2]),case([1,6]),case([2,2]))]
+  fn running_test_update_dims(dim: Dim<2>)
+  {
+    let mut stats: RunningStats<f64,2>=running_test_accumulate(&running_test_samples(7),Layout::RowMajor);
+
+    assert!(stats.update(&Tensor::<f64,2>::new(dim)).err()==Some(TensorError::DimMismatch{lhs:vec![2,3],rhs:dim.to_vec()}));
+    assert!(stats.count()==7);
+  }
+
+  /// Merging at `split`, where either side may not have seen any samples.
+  #[rstest(split,layout,
+    case(0,Layout::RowMajor),
+    case(2,Layout::ColMajor),
+    case(3,Layout::RowMajor),
+    case(6,Layout::ColMajor),
+    case(7,Layout::RowMajor),
+  )]
+  fn running_test_merge(split: Idx, layout: Layout)
+  {
+    let samples: Vec<Tensor<f64,2>>=running_test_samples(7);
+    let (mean,var): (Tensor<f64,2>,Tensor<f64,2>)=running_test_batch(&samples,1);
+
+    let mut merged: RunningStats<f64,2>=running_test_accumulate(&samples[..split],Layout::RowMajor);
+    merged.merge(&running_test_accumulate(&samples[split..],layout)).unwrap();
+    assert!(merged.count()==7 && running_test_close(&merged.mean(),&mean) && running_test_close(&merged.var(1),&var));
+  }
+
+  #[rstest(dim,case([3,2]),case([1,6]),case([2,2]))]
+  fn running_test_merge_dims(dim: Dim<2>)
+  {
+    let mut merged: RunningStats<f64,2>=running_test_accumulate(&running_test_samples(7),Layout::RowMajor);
+    let other: RunningStats<f64,2>=running_test_accumulate(&[Tensor::<f64,2>::new(dim)],Layout::RowMajor);
+
+    assert!(merged.merge(&other).err()==Some(TensorError::DimMismatch{lhs:vec![2,3],rhs:dim.to_vec()}));
+    assert!(merged.count()==7);
+  }
+}
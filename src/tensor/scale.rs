@@ -0,0 +1,291 @@
+use super::Dim;
+use super::Dimension;
+use super::Float;
+use super::Idx;
+use super::layout::layout_indices;
+use super::Tensor;
+
+/// Parameters fitted by `standardize` or `min_max_scale`, with which `apply_scale` repeats the same
+/// transform on another tensor.
+///
+/// The parameters are tensors of the dimensions of the fitted tensor with the reduced `axis`, or
+/// every axis if none was given, of length one.
+#[derive(Clone)]
+pub(super) enum ScaleParams<T: Float, const N: Idx>
+{
+  /// Mean and population standard deviation.
+  Standard{axis: Option<Idx>, mean: Tensor<T,N>, std: Tensor<T,N>},
+  /// Minimum and maximum, and the interval `[lo,hi]` they are mapped to.
+  MinMax{axis: Option<Idx>, min: Tensor<T,N>, max: Tensor<T,N>, lo: T, hi: T},
+}
+
+impl<T,const N: Idx> ScaleParams<T,N>
+where T: Float
+{
+  fn axis(&self) -> Option<Idx>
+  {
+    match self
+    {
+      ScaleParams::Standard{axis,..} | ScaleParams::MinMax{axis,..} => *axis,
+    }
+  }
+
+  fn dim(&self) -> Dim<N>
+  {
+    match self
+    {
+      ScaleParams::Standard{mean,..} => mean.dim,
+      ScaleParams::MinMax{min,..} => min.dim,
+    }
+  }
+
+  /// Transforms `elem` with the parameters at the multi-index `ind` of the parameter tensors.
+  fn transform(&self, elem: T, ind: Dim<N>) -> T
+  {
+    match self
+    {
+      ScaleParams::Standard{mean,std,..} =>
+      {
+        if std[ind]==T::default() { elem-mean[ind] } else { (elem-mean[ind])/std[ind] }
+      },
+      ScaleParams::MinMax{min,max,lo,hi,..} =>
+      {
+        let range: T=max[ind]-min[ind];
+        if range==T::default() { *lo } else { *lo+(elem-min[ind])*(*hi-*lo)/range }
+      },
+    }
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Subtracts the mean and divides by the population standard deviation of the elements along
+  /// `axis`, or of all elements if no axis is given, returning the result and the fitted parameters.
+  ///
+  /// Where the standard deviation is zero the elements are only centered.
+  pub(super) fn standardize(&self, axis: Option<Idx>) -> (Tensor<T,N>,ScaleParams<T,N>)
+  {
+    let mut res: Tensor<T,N>=self.clone();
+    let params: ScaleParams<T,N>=res.standardize_mut(axis);
+    (res,params)
+  }
+
+  /// In-place variant of `standardize`.
+  pub(super) fn standardize_mut(&mut self, axis: Option<Idx>) -> ScaleParams<T,N>
+  {
+    let count: T=T::from_f64(self.reduced_len(axis) as f64);
+    let mut mean: Tensor<T,N>=Tensor::<T,N>::new(reduced_dim(self.dim,axis));
+    for (ind,&elem) in layout_indices(self.dim,self.layout).zip(self.data.iter())
+    {
+      mean[param_index(ind,axis)]+=elem;
+    }
+    for elem in mean.data.iter_mut()
+    {
+      *elem=*elem/count;
+    }
+
+    let mut std: Tensor<T,N>=Tensor::<T,N>::new(mean.dim);
+    for (ind,&elem) in layout_indices(self.dim,self.layout).zip(self.data.iter())
+    {
+      let pind: Dim<N>=param_index(ind,axis);
+      std[pind]+=(elem-mean[pind])*(elem-mean[pind]);
+    }
+    for elem in std.data.iter_mut()
+    {
+      *elem=(*elem/count).sqrt();
+    }
+
+    let params: ScaleParams<T,N>=ScaleParams::Standard{axis:axis,mean:mean,std:std};
+    self.apply_scale_mut(&params);
+    params
+  }
+
+  /// Maps the elements linearly from the interval between their minimum and maximum along `axis`, or
+  /// over all elements if no axis is given, onto `[lo,hi]`, returning the result and the fitted
+  /// parameters.
+  ///
+  /// Where the minimum equals the maximum the elements are all mapped to `lo`.
+  pub(super) fn min_max_scale(&self, lo: T, hi: T, axis: Option<Idx>) -> (Tensor<T,N>,ScaleParams<T,N>)
+  {
+    let mut res: Tensor<T,N>=self.clone();
+    let params: ScaleParams<T,N>=res.min_max_scale_mut(lo,hi,axis);
+    (res,params)
+  }
+
+  /// In-place variant of `min_max_scale`.
+  pub(super) fn min_max_scale_mut(&mut self, lo: T, hi: T, axis: Option<Idx>) -> ScaleParams<T,N>
+  {
+    self.reduced_len(axis);
+    let pdim: Dim<N>=reduced_dim(self.dim,axis);
+    let mut min: Tensor<T,N>=Tensor::<T,N>::from_fn(pdim,|_| T::nan());
+    let mut max: Tensor<T,N>=Tensor::<T,N>::from_fn(pdim,|_| T::nan());
+    for (ind,&elem) in layout_indices(self.dim,self.layout).zip(self.data.iter())
+    {
+      let pind: Dim<N>=param_index(ind,axis);
+      if min[pind].is_nan() || elem<min[pind] { min[pind]=elem; }
+      if max[pind].is_nan() || elem>max[pind] { max[pind]=elem; }
+    }
+
+    let params: ScaleParams<T,N>=ScaleParams::MinMax{axis:axis,min:min,max:max,lo:lo,hi:hi};
+    self.apply_scale_mut(&params);
+    params
+  }
+
+  /// Applies the transform fitted by `standardize` or `min_max_scale` to `self`, which must match the
+  /// fitted tensor along every axis but the reduced one.
+  pub(super) fn apply_scale(&self, params: &ScaleParams<T,N>) -> Tensor<T,N>
+  {
+    let mut res: Tensor<T,N>=self.clone();
+    res.apply_scale_mut(params);
+    res
+  }
+
+  /// In-place variant of `apply_scale`.
+  pub(super) fn apply_scale_mut(&mut self, params: &ScaleParams<T,N>)
+  {
+    let axis: Option<Idx>=params.axis();
+    if let Some(axis)=axis
+    {
+      for (itr,(dim1,dim2)) in self.dim.iter().zip(params.dim().iter()).enumerate()
+      {
+        if itr!=axis && dim1!=dim2 { panic!("All dimensions but the reduced axis of a tensor must be of the size the scale was fitted to to scale it.") }
+      }
+    }
+
+    let dim: Dim<N>=self.dim;
+    for (ind,elem) in layout_indices(dim,self.layout).zip(self.data.iter_mut())
+    {
+      *elem=params.transform(*elem,param_index(ind,axis));
+    }
+  }
+
+  /// Number of elements each parameter is fitted to.
+  fn reduced_len(&self, axis: Option<Idx>) -> Idx
+  {
+    match axis
+    {
+      Some(axis) if axis>=N => panic!("The axis must be less than the number of dimensions of the tensor."),
+      Some(axis) => self.dim[axis],
+      None => self.dim.size(),
+    }
+  }
+}
+
+/// Dimensions `dim` with `axis`, or every axis if none is given, reduced to length one.
+fn reduced_dim<const N: Idx>(mut dim: Dim<N>, axis: Option<Idx>) -> Dim<N>
+{
+  match axis
+  {
+    Some(axis) => { dim[axis]=1; dim },
+    None => [1;N],
+  }
+}
+
+/// Multi-index of the parameters that apply to the element at `ind`.
+fn param_index<const N: Idx>(mut ind: Dim<N>, axis: Option<Idx>) -> Dim<N>
+{
+  match axis
+  {
+    Some(axis) => { ind[axis]=0; ind },
+    None => [0;N],
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod scale_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn scale_test_close(t: &Tensor<f64,2>, expected: &[f64]) -> bool
+  {
+    t.len()==expected.len() && t.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-12)
+  }
+
+  #[rstest(axis,expected,dim,mean,std,
+    case(0,&[-1.5f64.sqrt(),0.0,0.0,0.0,1.5f64.sqrt(),0.0],[1,2],&[2.0,10.0],&[(2.0f64/3.0).sqrt(),0.0]),
+    case(1,&[-1.0,1.0,-1.0,1.0,-1.0,1.0],[3,1],&[5.5,6.0,6.5],&[4.5,4.0,3.5]),
+  )]
+  fn scale_test_standardize_axis(axis: Idx, expected: &[f64], dim: Dim<2>, mean: &[f64], std: &[f64])
+  {
+    let data: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| if jtr==0 { (itr+1) as f64 } else { 10.0 });
+    let (t,params): (Tensor<f64,2>,ScaleParams<f64,2>)=data.standardize(Some(axis));
+
+    assert!(t.dim==[3,2]);
+    assert!(scale_test_close(&t,expected));
+    match params
+    {
+      ScaleParams::Standard{axis:fitted,mean:fitted_mean,std:fitted_std} =>
+      {
+        assert!(fitted==Some(axis));
+        assert!(fitted_mean.dim==dim && fitted_std.dim==dim);
+        assert!(scale_test_close(&fitted_mean,mean) && scale_test_close(&fitted_std,std));
+      },
+      _ => panic!(),
+    }
+  }
+
+  #[rstest(dim,case([2,2]),case([1,4]),case([4,1]),case([3,5]))]
+  fn scale_test_standardize_global(dim: Dim<2>)
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64);
+    let ptr: *const f64=t.data.as_ptr();
+    let params: ScaleParams<f64,2>=t.standardize_mut(None);
+
+    // The elements are 0 to n-1, of mean (n-1)/2 and variance (n^2-1)/12.
+    let len: f64=(dim[0]*dim[1]) as f64;
+    let std: f64=((len*len-1.0)/12.0).sqrt();
+    let expected: Vec<f64>=(0..dim[0]*dim[1]).map(|itr| (itr as f64-(len-1.0)/2.0)/std).collect();
+    assert!(t.data.as_ptr()==ptr);
+    assert!(scale_test_close(&t,&expected));
+    assert!(params.dim()==[1,1]);
+  }
+
+  #[rstest(lo,hi,axis,expected,
+    case(-1.0,1.0,Some(0),&[-1.0,-1.0,0.0,-1.0,1.0,-1.0]),
+    case(0.0,1.0,None,&[0.0,1.0,1.0/9.0,1.0,2.0/9.0,1.0]),
+    case(0.0,1.0,Some(1),&[0.0,1.0,0.0,1.0,0.0,1.0]),
+    case(2.0,4.0,Some(0),&[2.0,2.0,3.0,2.0,4.0,2.0]),
+  )]
+  fn scale_test_min_max_scale(lo: f64, hi: f64, axis: Option<Idx>, expected: &[f64])
+  {
+    let data: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| if jtr==0 { (itr+1) as f64 } else { 10.0 });
+    let (t,_): (Tensor<f64,2>,ScaleParams<f64,2>)=data.min_max_scale(lo,hi,axis);
+
+    assert!(scale_test_close(&t,expected));
+  }
+
+  #[rstest(fit,other,expected,
+    case(|t: &Tensor<f64,2>| t.standardize(Some(0)).1,&[[4.0,12.0]],&[2.0*1.5f64.sqrt(),2.0]),
+    case(|t: &Tensor<f64,2>| t.min_max_scale(-1.0,1.0,Some(0)).1,&[[1.0,2.0],[5.0,6.0]],&[-1.0,-1.0,3.0,-1.0]),
+    case(|t: &Tensor<f64,2>| t.min_max_scale(0.0,1.0,None).1,&[[1.0,10.0],[-8.0,19.0]],&[0.0,1.0,-1.0,2.0]),
+  )]
+  fn scale_test_apply_scale(fit: fn(&Tensor<f64,2>) -> ScaleParams<f64,2>, other: &[[f64;2]], expected: &[f64])
+  {
+    let data: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,2],|[itr,jtr]| if jtr==0 { (itr+1) as f64 } else { 10.0 });
+    let other: Tensor<f64,2>=Tensor::<f64,2>::from_fn([other.len(),2],|[itr,jtr]| other[itr][jtr]);
+
+    assert!(scale_test_close(&other.apply_scale(&fit(&data)),expected));
+  }
+
+  #[rstest(dim,case([3,3]),case([1,1]))]
+  #[should_panic(expected="All dimensions but the reduced axis of a tensor must be of the size the scale was fitted to to scale it.")]
+  fn scale_test_apply_scale_dims(dim: Dim<2>)
+  {
+    let (_,params): (Tensor<f64,2>,ScaleParams<f64,2>)=Tensor::<f64,2>::new([3,2]).standardize(Some(0));
+    Tensor::<f64,2>::new(dim).apply_scale(&params);
+  }
+
+  #[rstest(axis,case(2),case(5))]
+  #[should_panic(expected="The axis must be less than the number of dimensions of the tensor.")]
+  fn scale_test_axis(axis: Idx)
+  {
+    Tensor::<f64,2>::new([3,2]).min_max_scale(0.0,1.0,Some(axis));
+  }
+}
@@ -0,0 +1,113 @@
+use alloc::vec::Vec;
+
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// Adds every element of `values` to the element at the multi-index at the same position of
+  /// `indices`, so values at repeated multi-indices accumulate.
+  ///
+  /// Fails, leaving the tensor as it was, if the lengths of `indices` and `values` differ or any
+  /// multi-index lies outside the tensor.
+  pub(super) fn scatter_add(&mut self, indices: &[[Idx;N]], values: &[T]) -> Result<(),TensorError>
+  {
+    if indices.len()!=values.len() { return Err(TensorError::LengthMismatch{lhs:indices.len(),rhs:values.len()}); }
+    if let Some(pos)=indices.iter().position(|ind| ind.iter().zip(self.dim.iter()).any(|(ind,len)| ind>=len))
+    {
+      return Err(TensorError::IndexOutOfBounds{index:pos});
+    }
+
+    for (&ind,value) in indices.iter().zip(values.iter())
+    {
+      self[ind]+=value.clone();
+    }
+    Ok(())
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// Matrix of dimensions `dim` assembled from the triplets `(rows[itr],cols[itr],vals[itr])`, with
+  /// the values of repeated coordinates summed and every other element zero.
+  ///
+  /// Fails if the three slices are not of the same length or, naming the triplet, if any coordinates
+  /// lie outside the matrix.
+  pub(super) fn from_triplets(dim: [Idx;2], rows: &[Idx], cols: &[Idx], vals: &[T]) -> Result<Tensor<T,2>,TensorError>
+  {
+    if rows.len()!=cols.len() { return Err(TensorError::LengthMismatch{lhs:rows.len(),rhs:cols.len()}); }
+
+    let indices: Vec<[Idx;2]>=rows.iter().zip(cols.iter()).map(|(&row,&col)| [row,col]).collect();
+    let mut res: Tensor<T,2>=Tensor::<T,2>::new(dim);
+    res.scatter_add(&indices,vals)?;
+    Ok(res)
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod scatter_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Layout;
+
+  #[test]
+  fn scatter_test_from_triplets()
+  {
+    // Two linear elements on the nodes 0-1 and 1-2 share node 1, whose diagonal entry accumulates.
+    let rows: [Idx;8]=[0,0,1,1,1,1,2,2];
+    let cols: [Idx;8]=[0,1,0,1,1,2,1,2];
+    let vals: [f64;8]=[1.0,-1.0,-1.0,1.0,1.0,-1.0,-1.0,1.0];
+    let k: Tensor<f64,2>=Tensor::<f64,2>::from_triplets([3,3],&rows,&cols,&vals).unwrap();
+    assert!(k.iter().copied().eq([1.0,-1.0,0.0,-1.0,2.0,-1.0,0.0,-1.0,1.0].iter().copied()));
+  }
+
+  #[test]
+  fn scatter_test_from_triplets_empty()
+  {
+    let empty: Tensor<i32,2>=Tensor::<i32,2>::from_triplets([2,2],&[],&[],&[]).unwrap();
+    assert!(empty.dim==[2,2] && empty.iter().all(|&elem| elem==0));
+  }
+
+  #[rstest(dim,rows,cols,vals,error,
+    case([2,2],&[0,1][..],&[0][..],&[1.0,2.0][..],TensorError::LengthMismatch{lhs:2,rhs:1}),
+    case([2,2],&[0,1][..],&[0,1][..],&[1.0][..],TensorError::LengthMismatch{lhs:2,rhs:1}),
+    case([2,3],&[0,1,1][..],&[2,0,3][..],&[1.0,2.0,3.0][..],TensorError::IndexOutOfBounds{index:2}),
+    case([2,3],&[0,2][..],&[2,0][..],&[1.0,2.0][..],TensorError::IndexOutOfBounds{index:1}),
+  )]
+  fn scatter_test_from_triplets_errors(dim: [Idx;2], rows: &[Idx], cols: &[Idx], vals: &[f64], error: TensorError)
+  {
+    assert!(Tensor::<f64,2>::from_triplets(dim,rows,cols,vals).err()==Some(error));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn scatter_test_scatter_add(layout: Layout)
+  {
+    let mut t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([2,2,3],|[itr,jtr,ktr]| (6*itr+3*jtr+ktr) as i32).to_layout(layout);
+    t.scatter_add(&[[1,0,2],[0,1,1],[1,0,2]],&[10,-4,5]).unwrap();
+    assert!(t[[1,0,2]]==23 && t[[0,1,1]]==0 && t[[0,0,0]]==0 && t.sum()==66+11);
+  }
+
+  /// A failed scatter leaves the tensor untouched, even when earlier indices were valid.
+  #[rstest(indices,vals,error,
+    case(&[[0,0,0],[0,2,0]][..],&[1,1][..],TensorError::IndexOutOfBounds{index:1}),
+    case(&[[0,0,0]][..],&[1,1][..],TensorError::LengthMismatch{lhs:1,rhs:2}),
+  )]
+  fn scatter_test_scatter_add_errors(indices: &[[Idx;3]], vals: &[i32], error: TensorError)
+  {
+    let mut t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([2,2,3],|[itr,jtr,ktr]| (6*itr+3*jtr+ktr) as i32).to_layout(Layout::ColMajor);
+    let before: Tensor<i32,3>=t.clone();
+    assert!(t.scatter_add(indices,vals).err()==Some(error));
+    assert!(t==before);
+  }
+}
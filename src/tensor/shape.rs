@@ -0,0 +1,110 @@
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Layout;
+use super::Tensor;
+use super::TensorError;
+
+/// Dimensions given by `spec` for `total` elements, where at most one entry may be -1, which stands for
+/// the length that makes the dimensions hold `total` elements.
+///
+/// Fails if more than one entry is -1, if an entry is otherwise negative, if no length makes the
+/// dimensions hold `total` elements, or if every length is given and they do not.
+pub(super) fn infer_dim<const M: Idx>(total: Idx, spec: [isize;M]) -> Result<Dim<M>,TensorError>
+{
+  let inferred: Idx=spec.iter().filter(|&&extent| extent==-1).count();
+  if inferred>1 { return Err(TensorError::InferredAxes{count:inferred}); }
+  if let Some(axis)=spec.iter().position(|&extent| extent< -1) { return Err(TensorError::InvalidExtent{axis:axis,extent:spec[axis]}); }
+
+  let mut dim: Dim<M>=[0;M];
+  spec.iter().enumerate().filter(|(_,&extent)| extent>=0).for_each(|(axis,&extent)| dim[axis]=extent as Idx);
+  let known: Idx=spec.iter().filter(|&&extent| extent>=0).map(|&extent| extent as Idx).product();
+  match spec.iter().position(|&extent| extent==-1)
+  {
+    Some(axis) => dim[axis]=total.checked_div(known).filter(|&len| len*known==total).ok_or(TensorError::Indivisible{size:total,known:known})?,
+    None => if known!=total { return Err(TensorError::SizeMismatch{size:known,len:total}); },
+  }
+  Ok(dim)
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Clone
+{
+  /// Tensor of dimensions `dim` with the elements of `self` in row-major order, sharing the element
+  /// buffer unless `self` has a column-major layout, failing unless `dim` holds as many elements.
+  pub(super) fn reshape<const M: Idx>(&self, dim: Dim<M>) -> Result<Tensor<T,M>,TensorError>
+  {
+    if dim.size()!=self.len() { return Err(TensorError::SizeMismatch{size:dim.size(),len:self.len()}); }
+
+    let t: Tensor<T,N>=self.to_layout(Layout::RowMajor);
+    Ok(Tensor{data:t.data,dim:dim,layout:Layout::RowMajor,names:None})
+  }
+
+  /// Variant of `reshape` with the dimensions given by `spec` as for `infer_dim`, so that one length
+  /// may be left to be inferred as -1.
+  pub(super) fn reshape_infer<const M: Idx>(&self, spec: [isize;M]) -> Result<Tensor<T,M>,TensorError>
+  {
+    self.reshape(infer_dim(self.len(),spec)?)
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod shape_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest(total,spec,expected,
+    case(12,[-1,3],Ok([4,3])),
+    case(12,[3,4],Ok([3,4])),
+    case(0,[-1,3],Ok([0,3])),
+    case(12,[-1,-1],Err(TensorError::InferredAxes{count:2})),
+    case(12,[-1,5],Err(TensorError::Indivisible{size:12,known:5})),
+    case(12,[-1,0],Err(TensorError::Indivisible{size:12,known:0})),
+    case(12,[2,-2],Err(TensorError::InvalidExtent{axis:1,extent:-2})),
+    case(12,[5,2],Err(TensorError::SizeMismatch{size:10,len:12})),
+  )]
+  fn shape_test_infer_dim(total: Idx, spec: [isize;2], expected: Result<Dim<2>,TensorError>)
+  {
+    assert!(infer_dim(total,spec)==expected);
+  }
+
+  #[test]
+  fn shape_test_infer_dim_rank()
+  {
+    assert!(infer_dim(12,[2,-1,3])==Ok([2,2,3]) && infer_dim(12,[-1])==Ok([12]) && infer_dim(1,[])==Ok([]));
+  }
+
+  #[test]
+  fn shape_test_reshape()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    let res: Tensor<f64,3>=t.reshape_infer([2,-1,2]).unwrap();
+    assert!(res.dim==[2,3,2] && res.data.ptr_eq(&t.data));
+    assert!(res.indexed_iter().all(|([itr,jtr,ktr],&elem)| elem==(6*itr+2*jtr+ktr) as f64));
+  }
+
+  #[test]
+  fn shape_test_reshape_col_major()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    let res: Tensor<f64,2>=t.to_layout(Layout::ColMajor).reshape_infer([-1,6]).unwrap();
+    assert!(res.dim==[2,6] && res.layout==Layout::RowMajor && res.iter().copied().eq((0..12).map(|itr| itr as f64)));
+  }
+
+  #[rstest(reshape,error,
+    case(|t: &Tensor<f64,2>| t.reshape([5,2]).err(),TensorError::SizeMismatch{size:10,len:12}),
+    case(|t: &Tensor<f64,2>| t.reshape_infer([-1,-1,3]).err(),TensorError::InferredAxes{count:2}),
+    case(|t: &Tensor<f64,2>| t.reshape_infer([-1,5]).err(),TensorError::Indivisible{size:12,known:5}),
+  )]
+  fn shape_test_reshape_errors(reshape: fn(&Tensor<f64,2>) -> Option<TensorError>, error: TensorError)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64);
+    assert!(reshape(&t)==Some(error));
+  }
+}
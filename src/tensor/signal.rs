@@ -0,0 +1,421 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Autocorrelation of the signal at lags `0..=max_lag`, as `xcorrelate` of the signal with itself.
+  pub(super) fn autocorrelate(&self, max_lag: Idx, normalize: bool) -> Result<Tensor<T,1>,TensorError>
+  {
+    self.xcorrelate(self,max_lag,normalize)
+  }
+
+  /// Cross-correlation of the signals `self` and `other` at lags `0..=max_lag`, where the value at lag
+  /// `k` is the sum of `self[n+k]*other[n]` over every `n` for which both are defined.
+  ///
+  /// The signals are not centered first. If `normalize` is set, every value is divided by the value at
+  /// lag zero, unless that is zero, in which case the values are left as they are.
+  pub(super) fn xcorrelate(&self, other: &Tensor<T,1>, max_lag: Idx, normalize: bool) -> Result<Tensor<T,1>,TensorError>
+  {
+    if max_lag>=self.dim[0] { return Err(TensorError::LagTooLarge{max_lag:max_lag,len:self.dim[0]}); }
+
+    let mut corr: Tensor<T,1>=Tensor::<T,1>::from_fn([max_lag+1],|[lag]| {
+      self.data[lag..].iter().zip(other.data.iter()).fold(T::default(),|sum,(&x,&y)| sum+x*y)
+    });
+    if normalize && corr[0]!=T::default()
+    {
+      let zero_lag: T=corr[0];
+      for elem in corr.data.iter_mut()
+      {
+        *elem=*elem/zero_lag;
+      }
+    }
+    Ok(corr)
+  }
+
+  /// Indices of the peaks of the signal, in increasing order, filtered by `min_height`,
+  /// `min_distance` and `min_prominence` in that order.
+  ///
+  /// A peak is a sample, or a run of equal samples, that is greater than the samples on either side of
+  /// it, so the first and last samples are never peaks. A run is reported by its centre, rounding down.
+  /// Of peaks closer together than `min_distance`, only the tallest is kept, the leftmost on ties. The
+  /// prominence of a peak is its height above the higher of the lowest points on either side of it
+  /// before the signal rises above the peak or ends.
+  pub(super) fn find_peaks(&self, min_height: Option<T>, min_distance: Idx, min_prominence: Option<T>) -> Vec<Idx>
+  {
+    let x: &[T]=&self.data;
+    let mut peaks: Vec<Idx>=Vec::new();
+    let mut itr: Idx=1;
+    while itr+1<x.len()
+    {
+      if x[itr-1]<x[itr]
+      {
+        let mut end: Idx=itr;
+        while end+1<x.len() && x[end+1]==x[itr] { end+=1; }
+        if end+1<x.len() && x[end+1]<x[itr] { peaks.push((itr+end)/2); }
+        itr=end;
+      }
+      itr+=1;
+    }
+
+    if let Some(min_height)=min_height { peaks.retain(|&peak| x[peak]>=min_height); }
+
+    if min_distance>1
+    {
+      let mut by_height: Vec<Idx>=(0..peaks.len()).collect();
+      by_height.sort_by(|&lhs,&rhs| x[peaks[rhs]].partial_cmp(&x[peaks[lhs]]).unwrap_or(Ordering::Equal).then(lhs.cmp(&rhs)));
+      let mut keep: Vec<bool>=vec![true;peaks.len()];
+      for ind in by_height
+      {
+        if !keep[ind] { continue; }
+        for (other,keep) in keep.iter_mut().enumerate()
+        {
+          if other!=ind && peaks[other].abs_diff(peaks[ind])<min_distance { *keep=false; }
+        }
+      }
+      let mut keep=keep.into_iter();
+      peaks.retain(|_| keep.next().unwrap());
+    }
+
+    if let Some(min_prominence)=min_prominence { peaks.retain(|&peak| prominence(x,peak)>=min_prominence); }
+    peaks
+  }
+
+  /// Signal resampled to `new_len` samples by linear interpolation, as `resample_axis` along its only
+  /// axis.
+  pub(super) fn resample_linear(&self, new_len: Idx) -> Result<Tensor<T,1>,TensorError>
+  {
+    self.resample_axis(0,new_len)
+  }
+}
+
+/// Prominence of the peak of `x` at `peak`.
+fn prominence<T>(x: &[T], peak: Idx) -> T
+where T: Float
+{
+  let height: T=x[peak];
+  let lowest=|range: &mut dyn Iterator<Item=&T>| {
+    range.take_while(|&&elem| elem<=height).fold(height,|min,&elem| if elem<min { elem } else { min })
+  };
+  let (left,right): (T,T)=(lowest(&mut x[..peak].iter().rev()),lowest(&mut x[peak+1..].iter()));
+  height-if left>right { left } else { right }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// Exponential moving average along every lane of `axis`, with `y[0]=x[0]` and
+  /// `y[t]=alpha*x[t]+(1-alpha)*y[t-1]`, failing unless `alpha` lies in `(0,1]`.
+  pub(super) fn ema(&self, alpha: T, axis: Idx) -> Result<Tensor<T,N>,TensorError>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+    if alpha.is_nan() || alpha<=T::default() || alpha>T::from_f64(1.0) { return Err(TensorError::InvalidSmoothing{alpha:alpha.to_f64()}); }
+
+    let decay: T=T::from_f64(1.0)-alpha;
+    Ok(self.filter_lanes(axis,|x,prev| match prev
+    {
+      None => x,
+      Some((_,y)) => alpha*x+decay*y,
+    }))
+  }
+
+  /// First order IIR filter along every lane of `axis`, with `y[t]=b0*x[t]+b1*x[t-1]-a1*y[t-1]` and the
+  /// signal and output taken to be zero before the start of the lane.
+  pub(super) fn filter_iir1(&self, b0: T, b1: T, a1: T, axis: Idx) -> Tensor<T,N>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    self.filter_lanes(axis,|x,prev| match prev
+    {
+      None => b0*x,
+      Some((x_prev,y_prev)) => b0*x+b1*x_prev-a1*y_prev,
+    })
+  }
+
+  /// Tensor resampled to `new_len` samples along `axis` by linear interpolation along every lane, with
+  /// the first and last samples of the result at those of the lane and the rest evenly spaced between.
+  ///
+  /// A single sample is the first of the lane. Fails if `new_len` is zero or the lanes are empty.
+  pub(super) fn resample_axis(&self, axis: Idx, new_len: Idx) -> Result<Tensor<T,N>,TensorError>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+    if new_len==0 || self.dim[axis]==0 { return Err(TensorError::EmptyAxis{axis:axis}); }
+
+    // The position of sample `k` is `k*(len-1)/(new_len-1)`, split exactly into a whole and fractional part.
+    let (len,steps): (Idx,Idx)=(self.dim[axis],(new_len-1).max(1));
+    let mut dim: [Idx;N]=self.dim;
+    dim[axis]=new_len;
+    Ok(Tensor::<T,N>::from_fn(dim,|mut ind| {
+      let (whole,part): (Idx,Idx)=((ind[axis]*(len-1))/steps,(ind[axis]*(len-1))%steps);
+      ind[axis]=whole;
+      let lower: T=self[ind];
+      if part==0 { return lower; }
+
+      let frac: T=T::from_f64(part as f64/steps as f64);
+      ind[axis]+=1;
+      lower+frac*(self[ind]-lower)
+    }))
+  }
+
+  /// Applies the recursive filter `f` along every lane of `axis`, which maps every input to its output
+  /// given the input and output before it, if any.
+  fn filter_lanes<F>(&self, axis: Idx, f: F) -> Tensor<T,N>
+  where F: Fn(T,Option<(T,T)>) -> T
+  {
+    let mut res: Tensor<T,N>=self.clone();
+    for mut lane in res.lanes_mut(axis)
+    {
+      let mut prev: Option<(T,T)>=None;
+      for itr in 0..lane.len()
+      {
+        let x: T=lane[itr];
+        lane[itr]=f(x,prev);
+        prev=Some((x,lane[itr]));
+      }
+    }
+    res
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod signal_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  #[rstest(period,case(20),case(12),case(8))]
+  fn signal_test_autocorrelate_period(period: Idx)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([10*period],|[itr]| (2.0*core::f64::consts::PI*itr as f64/period as f64).sin());
+    let corr: Tensor<f64,1>=t.autocorrelate(2*period,true).unwrap();
+
+    assert!(corr.dim==[2*period+1]);
+    assert!(corr[0]==1.0);
+    let peak: Idx=(1..2*period).find(|&lag| corr[lag-1]<corr[lag] && corr[lag]>corr[lag+1]).unwrap();
+    assert!(peak==period);
+    assert!(corr[period/2]< -0.9*corr[period]);
+  }
+
+  #[rstest(max_lag,normalize,expected,
+    case(3,false,&[-2.0,-2.0,3.0,4.0]),
+    case(2,false,&[-2.0,-2.0,3.0]),
+    case(1,true,&[1.0,1.0]),
+    case(3,true,&[1.0,1.0,-1.5,-2.0]),
+  )]
+  fn signal_test_xcorrelate(max_lag: Idx, normalize: bool, expected: &[f64])
+  {
+    let t1: Tensor<f64,1>=Tensor::<f64,1>::from_fn([4],|[itr]| [1.0,2.0,3.0,4.0][itr]);
+    let t2: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| [1.0,0.0,-1.0][itr]);
+
+    assert!(t1.xcorrelate(&t2,max_lag,normalize).unwrap().iter().eq(expected.iter()));
+  }
+
+  #[rstest(normalize,case(false),case(true))]
+  fn signal_test_autocorrelate_zero(normalize: bool)
+  {
+    let zero: Tensor<f64,1>=Tensor::<f64,1>::new([3]);
+    assert!(zero.autocorrelate(2,normalize).unwrap().iter().all(|&elem| elem==0.0));
+  }
+
+  #[rstest(len,max_lag,case(5,5),case(5,9),case(1,1))]
+  fn signal_test_xcorrelate_lag(len: Idx, max_lag: Idx)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::new([len]);
+    assert!(t.autocorrelate(max_lag,false).err()==Some(TensorError::LagTooLarge{max_lag:max_lag,len:len}));
+  }
+
+  /// Peaks of a signal with peaks of heights 3, 5, 2 and 4.5 at 2, 6, 10 and 12, a plateau peak at 15 to
+  /// 17, and a shoulder at the end that is not a peak.
+  ///
+  /// The peaks at 10 and 12 are closer than 3 apart, so only the taller survives a distance of 3. The
+  /// peak at 12 stands 4.5-1.0 above the lowest point before the taller peak at 6, and the plateau
+  /// 2.5-1.0 above the lowest point to its right, which is higher than that to its left.
+  #[rstest(min_height,min_distance,min_prominence,expected,
+    case(None,0,None,vec![2,6,10,12,16]),
+    case(Some(2.5),0,None,vec![2,6,12,16]),
+    case(None,3,None,vec![2,6,12,16]),
+    case(None,5,None,vec![6,12]),
+    case(None,0,Some(2.0),vec![2,6,12]),
+    case(None,0,Some(3.0),vec![6,12]),
+    case(Some(4.0),0,Some(1.0),vec![6,12]),
+  )]
+  fn signal_test_find_peaks(min_height: Option<f64>, min_distance: Idx, min_prominence: Option<f64>, expected: Vec<Idx>)
+  {
+    let x: [f64;20]=[0.0,1.0,3.0,1.0,0.5,2.0,5.0,2.0,1.0,1.5,2.0,1.5,4.5,1.0,0.0,2.5,2.5,2.5,1.0,1.0];
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([20],|[itr]| x[itr]);
+
+    assert!(t.find_peaks(min_height,min_distance,min_prominence)==expected);
+  }
+
+  #[rstest(peak,expected,case(2,2.5),case(6,5.0),case(10,0.5),case(12,3.5),case(16,1.5))]
+  fn signal_test_prominence(peak: Idx, expected: f64)
+  {
+    let x: [f64;20]=[0.0,1.0,3.0,1.0,0.5,2.0,5.0,2.0,1.0,1.5,2.0,1.5,4.5,1.0,0.0,2.5,2.5,2.5,1.0,1.0];
+    assert!(prominence(&x,peak)==expected);
+  }
+
+  #[rstest(x,min_distance,expected,
+    case(&[1.0,2.0,2.0,1.0],0,vec![1]),
+    case(&[2.0,1.0,2.0],0,vec![]),
+    case(&[],2,vec![]),
+    case(&[1.0],0,vec![]),
+  )]
+  fn signal_test_find_peaks_edges(x: &[f64], min_distance: Idx, expected: Vec<Idx>)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([x.len()],|[itr]| x[itr]);
+    assert!(t.find_peaks(None,min_distance,None)==expected);
+  }
+
+  #[rstest(len,case(13),case(9),case(5),case(3),case(2),case(1))]
+  fn signal_test_resample_linear(len: Idx)
+  {
+    let ramp: Tensor<f64,1>=Tensor::<f64,1>::from_fn([5],|[itr]| 2.0*itr as f64-1.0);
+    let res: Tensor<f64,1>=ramp.resample_linear(len).unwrap();
+
+    // The ramp runs from -1 to 7, and the first and last samples are exact.
+    assert!(res.dim==[len] && res[0]==-1.0);
+    assert!(len==1 || res[len-1]==7.0);
+    assert!((1..len).all(|itr| (res[itr]-(8.0*itr as f64/(len-1) as f64-1.0)).abs()<1e-12));
+    assert!(len!=5 || res==ramp);
+  }
+
+  #[rstest(len,case(1),case(3),case(8))]
+  fn signal_test_resample_linear_single(len: Idx)
+  {
+    let single: Tensor<f64,1>=Tensor::<f64,1>::from_fn([1],|_| 4.0);
+    assert!(single.resample_linear(len).unwrap().iter().all(|&elem| elem==4.0));
+  }
+
+  #[rstest(len,new_len,case(5,0),case(0,3),case(0,0))]
+  fn signal_test_resample_linear_empty(len: Idx, new_len: Idx)
+  {
+    assert!(Tensor::<f64,1>::new([len]).resample_linear(new_len).err()==Some(TensorError::EmptyAxis{axis:0}));
+  }
+
+  #[test]
+  #[cfg(feature="std")]
+  fn signal_test_resample_smooth()
+  {
+    let signal=|t: f64| (2.0*t).sin()+0.5*t;
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([201],|[itr]| signal(itr as f64/40.0));
+
+    let down: Tensor<f64,1>=t.resample_linear(51).unwrap();
+    assert!((0..51).all(|itr| (down[itr]-signal(itr as f64/10.0)).abs()<1e-12));
+    let back: Tensor<f64,1>=down.resample_linear(201).unwrap();
+    assert!(back.iter().zip(t.iter()).all(|(x,y)| (x-y).abs()<5e-3));
+  }
+
+  /// Elements of lane `lane` along `axis` of a matrix.
+  fn signal_test_lane(t: &Tensor<f64,2>, axis: Idx, lane: Idx) -> Vec<f64>
+  {
+    (0..t.dim[axis]).map(|itr| if axis==0 { t[[itr,lane]] } else { t[[lane,itr]] }).collect()
+  }
+
+  /// Direct evaluation of `y[t]=b0*x[t]+b1*x[t-1]-a1*y[t-1]` on a single signal.
+  fn signal_test_iir1_reference(x: &[f64], b0: f64, b1: f64, a1: f64) -> Vec<f64>
+  {
+    let mut y: Vec<f64>=Vec::new();
+    for itr in 0..x.len()
+    {
+      let (x_prev,y_prev): (f64,f64)=if itr==0 { (0.0,0.0) } else { (x[itr-1],y[itr-1]) };
+      y.push(b0*x[itr]+b1*x_prev-a1*y_prev);
+    }
+    y
+  }
+
+  #[rstest(layout,axis,new_len,
+    case(Layout::RowMajor,0,11),
+    case(Layout::ColMajor,0,11),
+    case(Layout::RowMajor,1,2),
+    case(Layout::ColMajor,1,2),
+    case(Layout::ColMajor,1,7),
+    case(Layout::RowMajor,0,1),
+  )]
+  fn signal_test_resample_axis(layout: Layout, axis: Idx, new_len: Idx)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,3],|[itr,jtr]| [3.0,-1.0,4.0,1.0,-5.0,9.0][(itr+2*jtr)%6]*(jtr+1) as f64);
+    let res: Tensor<f64,2>=t.to_layout(layout).resample_axis(axis,new_len).unwrap();
+
+    let mut dim: Dim<2>=t.dim;
+    dim[axis]=new_len;
+    assert!(res.dim==dim);
+    for lane in 0..dim[1-axis]
+    {
+      let x: Vec<f64>=signal_test_lane(&t,axis,lane);
+      let expected: Tensor<f64,1>=Tensor::<f64,1>::from_fn([x.len()],|[itr]| x[itr]).resample_linear(new_len).unwrap();
+      assert!(signal_test_lane(&res,axis,lane).iter().eq(expected.iter()));
+    }
+    assert!(t.resample_axis(axis,0).err()==Some(TensorError::EmptyAxis{axis:axis}));
+  }
+
+  #[rstest(layout,axis,b0,b1,a1,
+    case(Layout::RowMajor,0,0.5,0.25,-0.4),
+    case(Layout::ColMajor,0,0.5,0.25,-0.4),
+    case(Layout::RowMajor,1,1.0,-1.0,0.0),
+    case(Layout::ColMajor,1,1.0,-1.0,0.0),
+    case(Layout::ColMajor,1,0.2,0.0,0.8),
+  )]
+  fn signal_test_filter_iir1(layout: Layout, axis: Idx, b0: f64, b1: f64, a1: f64)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,3],|[itr,jtr]| [3.0,-1.0,4.0,1.0,-5.0,9.0][(itr+2*jtr)%6]*(jtr+1) as f64);
+    let res: Tensor<f64,2>=t.to_layout(layout).filter_iir1(b0,b1,a1,axis);
+
+    assert!(res.dim==t.dim);
+    for lane in 0..t.dim[1-axis]
+    {
+      let y: Vec<f64>=signal_test_iir1_reference(&signal_test_lane(&t,axis,lane),b0,b1,a1);
+      assert!(signal_test_lane(&res,axis,lane).iter().zip(y.iter()).all(|(x,y)| (x-y).abs()<1e-12));
+    }
+  }
+
+  #[rstest(layout,axis,alpha,
+    case(Layout::RowMajor,0,0.3),
+    case(Layout::ColMajor,0,0.3),
+    case(Layout::RowMajor,1,0.5),
+    case(Layout::ColMajor,1,0.5),
+    case(Layout::ColMajor,0,1.0),
+  )]
+  fn signal_test_ema(layout: Layout, axis: Idx, alpha: f64)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,3],|[itr,jtr]| [3.0,-1.0,4.0,1.0,-5.0,9.0][(itr+2*jtr)%6]*(jtr+1) as f64);
+    let avg: Tensor<f64,2>=t.to_layout(layout).ema(alpha,axis).unwrap();
+
+    // The average is an IIR filter that starts from the first sample rather than from zero.
+    for lane in 0..t.dim[1-axis]
+    {
+      let x: Vec<f64>=signal_test_lane(&t,axis,lane);
+      let y: Vec<f64>=signal_test_lane(&avg,axis,lane);
+      assert!(y[0]==x[0]);
+      assert!((1..x.len()).all(|itr| (y[itr]-(alpha*x[itr]+(1.0-alpha)*y[itr-1])).abs()<1e-12));
+    }
+    assert!(alpha!=1.0 || avg==t);
+  }
+
+  #[rstest(alpha,case(0.0),case(-0.5),case(1.5),case(f64::NAN))]
+  fn signal_test_ema_smoothing(alpha: f64)
+  {
+    assert!(matches!(Tensor::<f64,2>::new([6,3]).ema(alpha,0),Err(TensorError::InvalidSmoothing{..})));
+  }
+
+  #[rstest(axis,case(2),case(3))]
+  #[should_panic(expected="The axis must be less than the number of dimensions of the tensor.")]
+  fn signal_test_filter_iir1_axis(axis: Idx)
+  {
+    Tensor::<f64,2>::new([6,3]).filter_iir1(1.0,0.0,0.0,axis);
+  }
+}
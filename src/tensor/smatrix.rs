@@ -0,0 +1,255 @@
+use alloc::vec;
+
+use core::convert::TryFrom;
+use core::ops::Add;
+use core::ops::Index;
+use core::ops::IndexMut;
+
+use super::Float;
+use super::Idx;
+use super::Layout;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+
+/// Matrix of `R` rows and `C` columns whose dimensions are part of its type, for small problems of
+/// fixed size such as rotations and homogeneous transforms.
+///
+/// The elements are held inline in row-major order, so nothing is allocated, and operations on
+/// matrices of mismatched dimensions do not compile rather than panic: `matmul` only accepts a
+/// right-hand side with as many rows as `self` has columns. `From` and `TryFrom` convert to and from a
+/// `Tensor<T,2>`.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) struct SMatrix<T,const R: Idx,const C: Idx>
+{
+  data: [[T;C];R],
+}
+
+impl<T,const R: Idx,const C: Idx> SMatrix<T,R,C>
+where T: Scalar + Copy
+{
+  /// Matrix of zeros.
+  pub(super) fn new() -> SMatrix<T,R,C>
+  {
+    SMatrix{data:[[T::default();C];R]}
+  }
+
+  /// Matrix with the rows `rows`.
+  pub(super) fn from_rows(rows: [[T;C];R]) -> SMatrix<T,R,C>
+  {
+    SMatrix{data:rows}
+  }
+
+  /// Matrix whose element at `[itr,jtr]` is `f([itr,jtr])`.
+  pub(super) fn from_fn<F>(mut f: F) -> SMatrix<T,R,C>
+  where F: FnMut([Idx;2]) -> T
+  {
+    let mut res: SMatrix<T,R,C>=SMatrix::<T,R,C>::new();
+    for itr in 0..R
+    {
+      for jtr in 0..C
+      {
+        res.data[itr][jtr]=f([itr,jtr]);
+      }
+    }
+    res
+  }
+
+  /// Transpose of the matrix.
+  pub(super) fn transpose(&self) -> SMatrix<T,C,R>
+  {
+    SMatrix::<T,C,R>::from_fn(|[itr,jtr]| self.data[jtr][itr])
+  }
+
+  /// Matrix product of `self` and `rhs`.
+  pub(super) fn matmul<const K: Idx>(&self, rhs: &SMatrix<T,C,K>) -> SMatrix<T,R,K>
+  {
+    SMatrix::<T,R,K>::from_fn(|[itr,jtr]| (0..C).fold(T::default(),|sum,ktr| sum+self.data[itr][ktr]*rhs.data[ktr][jtr]))
+  }
+}
+
+impl<T,const R: Idx> SMatrix<T,R,R>
+where T: Float
+{
+  /// Identity matrix.
+  pub(super) fn identity() -> SMatrix<T,R,R>
+  {
+    SMatrix::<T,R,R>::from_fn(|[itr,jtr]| if itr==jtr { T::from_f64(1.0) } else { T::default() })
+  }
+
+  /// Inverse of the matrix by Gauss-Jordan elimination with partial pivoting, failing if a pivot is
+  /// zero.
+  pub(super) fn inverse(&self) -> Result<SMatrix<T,R,R>,TensorError>
+  {
+    let mut a: [[T;R];R]=self.data;
+    let mut inv: [[T;R];R]=SMatrix::<T,R,R>::identity().data;
+    for ktr in 0..R
+    {
+      let pivot: Idx=(ktr..R).fold(ktr,|max,itr| if a[itr][ktr].abs()>a[max][ktr].abs() { itr } else { max });
+      if a[pivot][ktr]==T::default() { return Err(TensorError::Singular); }
+      a.swap(ktr,pivot);
+      inv.swap(ktr,pivot);
+
+      let scale: T=T::from_f64(1.0)/a[ktr][ktr];
+      a[ktr].iter_mut().for_each(|elem| *elem*=scale);
+      inv[ktr].iter_mut().for_each(|elem| *elem*=scale);
+      for itr in (0..R).filter(|&itr| itr!=ktr)
+      {
+        let factor: T=a[itr][ktr];
+        for jtr in 0..R
+        {
+          a[itr][jtr]=a[itr][jtr]-factor*a[ktr][jtr];
+          inv[itr][jtr]=inv[itr][jtr]-factor*inv[ktr][jtr];
+        }
+      }
+    }
+    Ok(SMatrix{data:inv})
+  }
+}
+
+impl<T,const R: Idx,const C: Idx> Index<[Idx;2]> for SMatrix<T,R,C>
+{
+  type Output=T;
+  fn index(&self, ind: [Idx;2]) -> &Self::Output
+  {
+    &self.data[ind[0]][ind[1]]
+  }
+}
+
+impl<T,const R: Idx,const C: Idx> IndexMut<[Idx;2]> for SMatrix<T,R,C>
+{
+  fn index_mut(&mut self, ind: [Idx;2]) -> &mut Self::Output
+  {
+    &mut self.data[ind[0]][ind[1]]
+  }
+}
+
+impl<T,const R: Idx,const C: Idx> Add for SMatrix<T,R,C>
+where T: Scalar + Copy
+{
+  type Output=Self;
+  fn add(self, rhs: Self) -> Self::Output
+  {
+    SMatrix::<T,R,C>::from_fn(|[itr,jtr]| self.data[itr][jtr]+rhs.data[itr][jtr])
+  }
+}
+
+impl<T,const R: Idx,const C: Idx> Add for &SMatrix<T,R,C>
+where T: Scalar + Copy
+{
+  type Output=SMatrix<T,R,C>;
+  fn add(self, rhs: Self) -> Self::Output
+  {
+    SMatrix::<T,R,C>::from_fn(|[itr,jtr]| self.data[itr][jtr]+rhs.data[itr][jtr])
+  }
+}
+
+impl<T,const R: Idx,const C: Idx> From<SMatrix<T,R,C>> for Tensor<T,2>
+where T: Scalar + Copy
+{
+  fn from(m: SMatrix<T,R,C>) -> Tensor<T,2>
+  {
+    Tensor::<T,2>::from_fn([R,C],|[itr,jtr]| m.data[itr][jtr])
+  }
+}
+
+impl<T,const R: Idx,const C: Idx> TryFrom<&Tensor<T,2>> for SMatrix<T,R,C>
+where T: Scalar + Copy
+{
+  type Error=TensorError;
+  /// Copies the elements of a matrix of `R` rows and `C` columns, in either layout, failing for a
+  /// matrix of any other dimensions.
+  fn try_from(t: &Tensor<T,2>) -> Result<SMatrix<T,R,C>,TensorError>
+  {
+    if t.dim!=[R,C] { return Err(TensorError::DimMismatch{lhs:vec![R,C],rhs:t.dim.to_vec()}); }
+
+    let t: Tensor<T,2>=t.to_layout(Layout::RowMajor);
+    Ok(SMatrix::<T,R,C>::from_fn(|[itr,jtr]| t.data[itr*C+jtr]))
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod smatrix_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn smatrix_test_close<const R: Idx, const C: Idx>(lhs: &SMatrix<f64,R,C>, rhs: &SMatrix<f64,R,C>, tol: f64) -> bool
+  {
+    (0..R).all(|itr| (0..C).all(|jtr| (lhs[[itr,jtr]]-rhs[[itr,jtr]]).abs()<=tol))
+  }
+
+  /// Diagonally dominant matrix with a row swap, so inverting it needs a pivot.
+  fn smatrix_test_invertible<const R: Idx>() -> SMatrix<f64,R,R>
+  {
+    SMatrix::<f64,R,R>::from_fn(|[itr,jtr]| {
+      let row: Idx=if R>1 && itr<2 { 1-itr } else { itr };
+      if row==jtr { 4.0+row as f64 } else { 1.0/(1+row+2*jtr) as f64 }
+    })
+  }
+
+  #[test]
+  fn smatrix_test_matmul()
+  {
+    let a: SMatrix<i32,2,3>=SMatrix::<i32,2,3>::from_rows([[1,2,3],[4,5,6]]);
+    let b: SMatrix<i32,3,2>=SMatrix::<i32,3,2>::from_rows([[7,8],[9,10],[11,12]]);
+
+    // The product of a 2x3 and a 3x2 matrix is 2x2, which the type of the result states.
+    let c: SMatrix<i32,2,2>=a.matmul(&b);
+    assert!(c==SMatrix::<i32,2,2>::from_rows([[58,64],[139,154]]));
+    assert!(b.matmul(&a)[[2,2]]==105 && a.transpose()==SMatrix::<i32,3,2>::from_rows([[1,4],[2,5],[3,6]]));
+    assert!(Tensor::<i32,2>::from(c)==Tensor::<i32,2>::from(a).matmul(&Tensor::<i32,2>::from(b)));
+    let r: &SMatrix<i32,2,3>=&a;
+    assert!(a+a==a.matmul(&SMatrix::<i32,3,3>::from_fn(|[itr,jtr]| 2*(itr==jtr) as i32)) && r+r==a+a);
+  }
+
+  /// Each case checks an inverse against the identity, from the left or the right, or that inverting
+  /// twice returns the matrix.
+  #[rstest(check,
+    case(|| { let a: SMatrix<f64,2,2>=smatrix_test_invertible(); smatrix_test_close(&a.matmul(&a.inverse().unwrap()),&SMatrix::identity(),1e-14) }),
+    case(|| { let a: SMatrix<f64,3,3>=smatrix_test_invertible(); smatrix_test_close(&a.inverse().unwrap().matmul(&a),&SMatrix::identity(),1e-14) }),
+    case(|| { let a: SMatrix<f64,4,4>=smatrix_test_invertible(); smatrix_test_close(&a.matmul(&a.inverse().unwrap()),&SMatrix::identity(),1e-14) }),
+    case(|| { let a: SMatrix<f64,4,4>=smatrix_test_invertible(); smatrix_test_close(&a.inverse().unwrap().inverse().unwrap(),&a,1e-13) }),
+  )]
+  fn smatrix_test_inverse(check: fn() -> bool)
+  {
+    assert!(check());
+  }
+
+  #[test]
+  fn smatrix_test_inverse_exact()
+  {
+    let singular: SMatrix<f64,3,3>=SMatrix::<f64,3,3>::from_fn(|[itr,jtr]| (itr*jtr) as f64);
+    assert!(singular.inverse().err()==Some(TensorError::Singular));
+    assert!(SMatrix::<f64,2,2>::from_rows([[0.0,2.0],[0.5,0.0]]).inverse().unwrap()==SMatrix::from_rows([[0.0,2.0],[0.5,0.0]]));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn smatrix_test_tensor(layout: Layout)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64-2.5);
+    let m: SMatrix<f64,3,4>=SMatrix::<f64,3,4>::try_from(&t.to_layout(layout)).unwrap();
+    assert!(m[[1,3]]==4.5 && Tensor::<f64,2>::from(m)==t);
+    assert!(Tensor::<f64,2>::from(m.transpose())==t.transpose());
+  }
+
+  #[test]
+  fn smatrix_test_tensor_zero()
+  {
+    let mut zero: SMatrix<f64,3,4>=SMatrix::new();
+    zero[[2,1]]=1.0;
+    assert!(Tensor::<f64,2>::from(zero).sum()==1.0);
+  }
+
+  #[test]
+  fn smatrix_test_tensor_mismatch()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64-2.5);
+    assert!(SMatrix::<f64,4,3>::try_from(&t).err()==Some(TensorError::DimMismatch{lhs:vec![4,3],rhs:vec![3,4]}));
+  }
+}
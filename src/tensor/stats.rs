@@ -0,0 +1,211 @@
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
+use core::fmt;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+
+/// Summary statistics of the elements of a tensor, as returned by `Tensor::describe`.
+///
+/// NaN elements are counted in `nan_count` and otherwise ignored. Every other field is NaN if there
+/// are no elements left to compute it from.
+#[derive(Debug,Clone,PartialEq)]
+pub(super) struct TensorSummary<T>
+{
+  /// Number of elements that are not NaN.
+  pub(super) count: Idx,
+  pub(super) nan_count: Idx,
+  pub(super) min: T,
+  pub(super) max: T,
+  pub(super) mean: T,
+  /// Sample standard deviation, dividing by one less than `count`.
+  pub(super) std: T,
+  pub(super) p25: T,
+  pub(super) p50: T,
+  pub(super) p75: T,
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Float
+{
+  /// The `q`th quantile of the elements, for `q` between 0 and 1, interpolating linearly between the
+  /// two nearest elements.
+  ///
+  /// Returns NaN if any element is NaN or the tensor is empty.
+  pub(super) fn quantile(&self, q: f64) -> T
+  {
+    if self.data.iter().any(|elem| elem.is_nan()) { return T::nan(); }
+
+    let mut sorted: Vec<T>=self.data.to_vec();
+    sort(&mut sorted);
+    quantile_sorted(&sorted,q)
+  }
+
+  /// Count, NaN count, minimum, maximum, mean, standard deviation and quartiles of the elements.
+  ///
+  /// Only a single sorted copy of the elements is made, from which the order statistics are read.
+  pub(super) fn describe(&self) -> TensorSummary<T>
+  {
+    let mut sorted: Vec<T>=self.data.iter().copied().filter(|elem| !elem.is_nan()).collect();
+    sort(&mut sorted);
+
+    let count: Idx=sorted.len();
+    let mean: T=match count
+    {
+      0 => T::nan(),
+      _ => sorted.iter().fold(T::default(),|sum,&elem| sum+elem)/T::from_f64(count as f64),
+    };
+    let std: T=match count
+    {
+      0 | 1 => T::nan(),
+      _ =>
+      {
+        let ss: T=sorted.iter().fold(T::default(),|sum,&elem| sum+(elem-mean)*(elem-mean));
+        (ss/T::from_f64((count-1) as f64)).sqrt()
+      },
+    };
+
+    TensorSummary
+    {
+      count:count,
+      nan_count:self.data.len()-count,
+      min:sorted.first().copied().unwrap_or_else(T::nan),
+      max:sorted.last().copied().unwrap_or_else(T::nan),
+      mean:mean,
+      std:std,
+      p25:quantile_sorted(&sorted,0.25),
+      p50:quantile_sorted(&sorted,0.5),
+      p75:quantile_sorted(&sorted,0.75),
+    }
+  }
+}
+
+/// Sorts elements that are known not to be NaN in ascending order.
+fn sort<T>(elems: &mut [T])
+where T: Float
+{
+  elems.sort_unstable_by(|lhs,rhs| lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal));
+}
+
+/// The `q`th quantile of ascending `sorted`, interpolating linearly between the two nearest elements.
+///
+/// An element hit exactly, or between two equal ones, is returned as it is, so that an infinite one
+/// does not turn into NaN by being interpolated with a weight of zero.
+fn quantile_sorted<T>(sorted: &[T], q: f64) -> T
+where T: Float
+{
+  if !(0.0..=1.0).contains(&q) { panic!("The quantile must be between 0 and 1."); }
+  if sorted.is_empty() { return T::nan(); }
+
+  let pos: f64=q*(sorted.len()-1) as f64;
+  let lo: Idx=pos as Idx;
+  let hi: Idx=(lo+1).min(sorted.len()-1);
+  let frac: T=T::from_f64(pos-lo as f64);
+  if frac==T::default() || sorted[lo]==sorted[hi] { return sorted[lo]; }
+  sorted[lo]+(sorted[hi]-sorted[lo])*frac
+}
+
+impl<T> fmt::Display for TensorSummary<T>
+where T: Float + fmt::Display
+{
+  /// Writes one labelled statistic per line, applying a requested precision to every statistic but
+  /// the counts.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    writeln!(f,"count  {}",self.count)?;
+    writeln!(f,"nan    {}",self.nan_count)?;
+    let stats: [(&str,T);7]=[("mean",self.mean),("std",self.std),("min",self.min),("25%",self.p25),
+      ("50%",self.p50),("75%",self.p75),("max",self.max)];
+    for (itr,(label,stat)) in stats.iter().enumerate()
+    {
+      if itr>0 { writeln!(f)?; }
+      match f.precision()
+      {
+        Some(precision) => write!(f,"{:<7}{:.*}",label,precision,stat)?,
+        None => write!(f,"{:<7}{}",label,stat)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod stats_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::format;
+
+  #[rstest(elems,count,nan_count,moments,quartiles,
+    case(&[4.0,1.0,f64::NAN,3.0,2.0,5.0],5,1,[1.0,5.0,3.0,2.5f64.sqrt()],[2.0,3.0,4.0]),
+    case(&[f64::NAN,f64::NAN],0,2,[f64::NAN;4],[f64::NAN;3]),
+    case(&[2.0],1,0,[2.0,2.0,2.0,f64::NAN],[2.0;3]),
+    case(&[f64::INFINITY,f64::INFINITY],2,0,[f64::INFINITY,f64::INFINITY,f64::INFINITY,f64::NAN],[f64::INFINITY;3]),
+  )]
+  fn stats_test_describe(elems: &[f64], count: usize, nan_count: usize, moments: [f64;4], quartiles: [f64;3])
+  {
+    let same=|x: f64,y: f64| x==y || (x.is_nan() && y.is_nan());
+    let summary: TensorSummary<f64>=Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr]).describe();
+    let [min,max,mean,std]: [f64;4]=moments;
+
+    assert!(summary.count==count);
+    assert!(summary.nan_count==nan_count);
+    assert!(same(summary.min,min) && same(summary.max,max) && same(summary.mean,mean));
+    assert!(same(summary.std,std) || (summary.std-std).abs()<1e-15);
+    assert!(same(summary.p25,quartiles[0]) && same(summary.p50,quartiles[1]) && same(summary.p75,quartiles[2]));
+  }
+
+  #[rstest(precision,expected,
+    case(2,"count  5\nnan    1\nmean   3.00\nstd    1.58\nmin    1.00\n25%    2.00\n50%    3.00\n75%    4.00\nmax    5.00"),
+    case(0,"count  5\nnan    1\nmean   3\nstd    2\nmin    1\n25%    2\n50%    3\n75%    4\nmax    5"),
+  )]
+  fn stats_test_describe_display(precision: usize, expected: &str)
+  {
+    let elems: [f64;6]=[4.0,1.0,f64::NAN,3.0,2.0,5.0];
+    let summary: TensorSummary<f64>=Tensor::<f64,2>::from_fn([2,3],|[itr,jtr]| elems[3*itr+jtr]).describe();
+
+    assert!(format!("{:.*}",precision,summary)==expected);
+  }
+
+  #[rstest(elems,q,expected,
+    case(&[4.0,3.0,2.0,1.0],0.0,1.0),
+    case(&[4.0,3.0,2.0,1.0],0.25,1.75),
+    case(&[4.0,3.0,2.0,1.0],0.5,2.5),
+    case(&[4.0,3.0,2.0,1.0],1.0,4.0),
+    case(&[f64::NEG_INFINITY,1.0,2.0,f64::INFINITY],0.0,f64::NEG_INFINITY),
+    case(&[f64::NEG_INFINITY,1.0,2.0,f64::INFINITY],0.5,1.5),
+    case(&[f64::NEG_INFINITY,1.0,2.0,f64::INFINITY],0.9,f64::INFINITY),
+    case(&[f64::NEG_INFINITY,1.0,2.0,f64::INFINITY],1.0,f64::INFINITY),
+    case(&[f64::INFINITY,f64::INFINITY],0.5,f64::INFINITY),
+  )]
+  fn stats_test_quantile(elems: &[f64], q: f64, expected: f64)
+  {
+    assert!(Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr]).quantile(q)==expected);
+  }
+
+  #[rstest(elems,q,
+    case(&[1.0,f64::NAN,2.0],0.5),
+    case(&[f64::NAN],0.0),
+    case(&[f64::NAN,f64::NAN],1.0),
+    case(&[],0.5),
+  )]
+  fn stats_test_quantile_nan(elems: &[f64], q: f64)
+  {
+    assert!(Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr]).quantile(q).is_nan());
+  }
+
+  #[rstest(q,case(1.5),case(-0.5),case(f64::NAN))]
+  #[should_panic(expected="The quantile must be between 0 and 1.")]
+  fn stats_test_quantile_range(q: f64)
+  {
+    Tensor::<f64,1>::new([3]).quantile(q);
+  }
+}
@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+
+/// Values taken by the points outside a grid when a stencil reaches over its edge.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub(super) enum Boundary<T>
+{
+  /// Every point outside the grid has the fixed value.
+  Dirichlet(T),
+  /// The grid repeats along both axes, so a point past one edge is the point as far in from the other.
+  Periodic,
+  /// The grid is mirrored about its edge points, so central differences across the edge vanish.
+  Neumann,
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// Applies `stencil` centred on every point of the grid, with the points outside the grid given by
+  /// `boundary`.
+  ///
+  /// The stencil is of odd length along both axes, and its element at offset `[di,dj]` from its
+  /// centre weighs the grid point at offset `[di,dj]` from the point it is applied to. Unlike a
+  /// convolution the stencil is not flipped.
+  pub(super) fn apply_stencil(&self, stencil: &Tensor<T,2>, boundary: Boundary<T>) -> Tensor<T,2>
+  {
+    let mut out: Tensor<T,2>=Tensor::<T,2>::new(self.dim);
+    self.apply_stencil_into(stencil,boundary,&mut out);
+    out
+  }
+
+  /// Variant of `apply_stencil` that writes the result into `out`, which must be of the dimensions of
+  /// `self`, so repeated applications can reuse it.
+  pub(super) fn apply_stencil_into(&self, stencil: &Tensor<T,2>, boundary: Boundary<T>, out: &mut Tensor<T,2>)
+  {
+    if stencil.dim.iter().any(|&len| len%2==0) { panic!("A stencil must be of odd length along both axes to apply it.") }
+    if out.dim!=self.dim { panic!("The output must be of the dimensions of the grid to apply a stencil.") }
+
+    let radius: [isize;2]=[(stencil.dim[0]/2) as isize,(stencil.dim[1]/2) as isize];
+    let taps: Vec<(isize,isize,T)>=(0..stencil.dim[0])
+      .flat_map(|ktr| (0..stencil.dim[1]).map(move |ltr| (ktr,ltr)))
+      .filter(|&(ktr,ltr)| stencil[[ktr,ltr]]!=T::default())
+      .map(|(ktr,ltr)| (ktr as isize-radius[0],ltr as isize-radius[1],stencil[[ktr,ltr]].clone()))
+      .collect();
+
+    for (itr,jtr) in (0..self.dim[0]).flat_map(|itr| (0..self.dim[1]).map(move |jtr| (itr,jtr)))
+    {
+      out[[itr,jtr]]=taps.iter().fold(T::default(),|sum,(di,dj,weight)| {
+        let row: Option<Idx>=boundary_index(itr as isize+di,self.dim[0],&boundary);
+        let col: Option<Idx>=boundary_index(jtr as isize+dj,self.dim[1],&boundary);
+        let value: T=match (row,col,&boundary)
+        {
+          (Some(row),Some(col),_) => self[[row,col]].clone(),
+          (_,_,Boundary::Dirichlet(value)) => value.clone(),
+          _ => unreachable!(),
+        };
+        sum+weight.clone()*value
+      });
+    }
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Five point finite difference Laplacian of the grid with spacing `h` along both axes.
+  pub(super) fn laplacian_5pt(&self, h: T, boundary: Boundary<T>) -> Tensor<T,2>
+  {
+    let (side,centre): (T,T)=(T::from_f64(1.0)/(h*h),T::from_f64(-4.0)/(h*h));
+    let stencil: Tensor<T,2>=Tensor::<T,2>::from_fn([3,3],|[itr,jtr]| match (itr,jtr)
+    {
+      (1,1) => centre,
+      (1,_) | (_,1) => side,
+      _ => T::default(),
+    });
+    self.apply_stencil(&stencil,boundary)
+  }
+}
+
+/// Index along an axis of length `len` of the point at `ind`, which may lie outside the grid, or
+/// `None` if the point takes a Dirichlet value.
+fn boundary_index<T>(ind: isize, len: Idx, boundary: &Boundary<T>) -> Option<Idx>
+{
+  let len: isize=len as isize;
+  if 0<=ind && ind<len { return Some(ind as Idx); }
+
+  match boundary
+  {
+    Boundary::Dirichlet(_) => None,
+    Boundary::Periodic => Some(ind.rem_euclid(len) as Idx),
+    Boundary::Neumann =>
+    {
+      // Mirroring about both edge points repeats the grid with period 2*(len-1).
+      let period: isize=2*(len-1);
+      if period==0 { return Some(0); }
+      let ind: isize=ind.rem_euclid(period);
+      Some((if ind<len { ind } else { period-ind }) as Idx)
+    },
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod stencil_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Layout;
+
+  fn stencil_test_shift(di: Idx, dj: Idx) -> Tensor<i32,2>
+  {
+    Tensor::<i32,2>::from_fn([3,3],|[itr,jtr]| (itr==di && jtr==dj) as i32)
+  }
+
+  /// The Laplacian of x^2+y^2 is 4 everywhere, which the five point stencil recovers exactly away
+  /// from the boundary, whichever condition holds there.
+  #[rstest(boundary,case(Boundary::Dirichlet(0.0)),case(Boundary::Periodic),case(Boundary::Neumann))]
+  fn stencil_test_laplacian_quadratic(boundary: Boundary<f64>)
+  {
+    let h: f64=0.25;
+    let u: Tensor<f64,2>=Tensor::<f64,2>::from_fn([6,7],|[itr,jtr]| {
+      let (x,y): (f64,f64)=(itr as f64*h,jtr as f64*h);
+      x*x+y*y
+    });
+
+    let lap: Tensor<f64,2>=u.laplacian_5pt(h,boundary);
+    for (itr,jtr) in (1..5).flat_map(|itr| (1..6).map(move |jtr| (itr,jtr)))
+    {
+      assert!((lap[[itr,jtr]]-4.0).abs()<1e-12);
+    }
+  }
+
+  /// Reading the right and upper neighbours wraps around both axes.
+  #[rstest(di,dj,layout,expected,
+    case(1,2,Layout::RowMajor,[1,2,3,0,5,6,7,4,9,10,11,8]),
+    case(1,2,Layout::ColMajor,[1,2,3,0,5,6,7,4,9,10,11,8]),
+    case(0,1,Layout::RowMajor,[8,9,10,11,0,1,2,3,4,5,6,7]),
+    case(0,1,Layout::ColMajor,[8,9,10,11,0,1,2,3,4,5,6,7]),
+  )]
+  fn stencil_test_periodic(di: Idx, dj: Idx, layout: Layout, expected: [i32;12])
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as i32).to_layout(layout);
+    let res: Tensor<i32,2>=t.apply_stencil(&stencil_test_shift(di,dj),Boundary::Periodic);
+    assert!(res==Tensor::<i32,2>::from_fn([3,4],|[itr,jtr]| expected[4*itr+jtr]));
+  }
+
+  /// Off the left edge, the Dirichlet case reads the fixed value, and mirroring about the edge point
+  /// reads the point one in from the edge.
+  #[rstest(boundary,expected,
+    case(Boundary::Dirichlet(-1),[-1,1,2,-1,4,5]),
+    case(Boundary::Neumann,[2,1,2,5,4,5]),
+  )]
+  fn stencil_test_boundaries(boundary: Boundary<i32>, expected: [i32;6])
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr+1) as i32);
+    let left: Tensor<i32,2>=t.apply_stencil(&stencil_test_shift(1,0),boundary);
+    assert!(left.iter().copied().eq(expected.iter().copied()));
+  }
+
+  #[test]
+  fn stencil_test_wide()
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr+1) as i32);
+    let mut out: Tensor<i32,2>=Tensor::<i32,2>::new([2,3]);
+    let wide: Tensor<i32,2>=Tensor::<i32,2>::from_fn([1,5],|_| 1);
+    t.apply_stencil_into(&wide,Boundary::Neumann,&mut out);
+    assert!(out.iter().copied().eq([11,10,9,26,25,24].iter().copied()));
+  }
+
+  #[test]
+  #[should_panic(expected="A stencil must be of odd length along both axes to apply it.")]
+  fn stencil_test_even()
+  {
+    Tensor::<f64,2>::new([3,3]).apply_stencil(&Tensor::<f64,2>::new([2,3]),Boundary::Periodic);
+  }
+
+  #[test]
+  #[should_panic(expected="The output must be of the dimensions of the grid to apply a stencil.")]
+  fn stencil_test_out_dim()
+  {
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new([3,2]);
+    Tensor::<f64,2>::new([3,3]).apply_stencil_into(&Tensor::<f64,2>::new([3,3]),Boundary::Periodic,&mut out);
+  }
+}
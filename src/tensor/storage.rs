@@ -0,0 +1,440 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+/// Number of elements up to which a storage holds them inline rather than in a buffer on the heap.
+pub(super) const INLINE_LEN: usize=4;
+
+/// Element buffer of a tensor, which is either the range of `len` elements starting at `offset` of a
+/// reference counted buffer, or up to `INLINE_LEN` elements held in the storage itself.
+///
+/// Cloning a buffer only bumps the reference count, and `split_at` divides the range without copying.
+/// Mutable access first copies the elements in range if the buffer is shared with another tensor, so
+/// tensors keep behaving as independent values. The buffer stays a vector behind the reference count
+/// so it can be handed in and out without copying.
+///
+/// Elements held inline are copied on cloning instead, which is cheaper for so few elements than the
+/// allocation a buffer needs, so that small tensors are created, cloned and combined without
+/// allocating at all.
+pub(super) struct Storage<T>
+{
+  repr: Repr<T>,
+}
+
+enum Repr<T>
+{
+  Shared{buf: Arc<Vec<T>>, offset: usize, len: usize},
+  Inline(Inline<T>),
+}
+
+impl<T> Storage<T>
+{
+  /// Whether `self` and `other` share the same buffer, which elements held inline never do with
+  /// another storage.
+  pub(super) fn ptr_eq(&self, other: &Storage<T>) -> bool
+  {
+    match (&self.repr,&other.repr)
+    {
+      (Repr::Shared{buf,..},Repr::Shared{buf:other,..}) => Arc::ptr_eq(buf,other),
+      _ => core::ptr::eq(self,other),
+    }
+  }
+
+  /// Splits the range into the first `mid` elements and the rest, both still sharing the buffer, or
+  /// both inline if the elements are.
+  pub(super) fn split_at(self, mid: usize) -> (Storage<T>,Storage<T>)
+  {
+    assert!(mid<=self.len());
+
+    match self.repr
+    {
+      Repr::Shared{buf,offset,len} =>
+      {
+        let tail: Storage<T>=Storage{repr:Repr::Shared{buf:Arc::clone(&buf),offset:offset+mid,len:len-mid}};
+        (Storage{repr:Repr::Shared{buf:buf,offset:offset,len:mid}},tail)
+      },
+      Repr::Inline(mut inline) =>
+      {
+        let mut head: Inline<T>=Inline::new();
+        let mut tail: Inline<T>=Inline::new();
+        inline.drain(|itr,elem| if itr<mid { head.push(elem) } else { tail.push(elem) });
+        (Storage{repr:Repr::Inline(head)},Storage{repr:Repr::Inline(tail)})
+      },
+    }
+  }
+
+  /// Returns the elements in range as a boxed slice, which is the original allocation if the buffer
+  /// is not shared, the range covers all of it and it holds no spare capacity, and a copy otherwise.
+  pub(super) fn into_boxed_slice(self) -> Box<[T]>
+  where T: Clone
+  {
+    match self.repr
+    {
+      Repr::Shared{buf,offset,len} if offset==0 && len==buf.len() =>
+      {
+        match Arc::try_unwrap(buf)
+        {
+          Ok(buf) => buf.into_boxed_slice(),
+          Err(buf) => buf.to_vec().into_boxed_slice(),
+        }
+      },
+      Repr::Shared{buf,offset,len} => buf[offset..offset+len].to_vec().into_boxed_slice(),
+      Repr::Inline(mut inline) =>
+      {
+        let mut data: Vec<T>=Vec::with_capacity(inline.len);
+        inline.drain(|_,elem| data.push(elem));
+        data.into_boxed_slice()
+      },
+    }
+  }
+}
+
+impl<T> From<Box<[T]>> for Storage<T>
+{
+  fn from(data: Box<[T]>) -> Storage<T>
+  {
+    data.into_vec().into()
+  }
+}
+
+impl<T> From<Vec<T>> for Storage<T>
+{
+  /// Storage taking over the allocation of `data`, unless it holds few enough elements to be held
+  /// inline, in which case they are moved out of it.
+  fn from(data: Vec<T>) -> Storage<T>
+  {
+    let len: usize=data.len();
+    if len<=INLINE_LEN
+    {
+      let mut inline: Inline<T>=Inline::new();
+      data.into_iter().for_each(|elem| inline.push(elem));
+      return Storage{repr:Repr::Inline(inline)};
+    }
+    Storage{repr:Repr::Shared{buf:Arc::new(data),offset:0,len:len}}
+  }
+}
+
+impl<T> Clone for Storage<T>
+where T: Clone
+{
+  fn clone(&self) -> Storage<T>
+  {
+    match &self.repr
+    {
+      Repr::Shared{buf,offset,len} => Storage{repr:Repr::Shared{buf:Arc::clone(buf),offset:*offset,len:*len}},
+      Repr::Inline(inline) =>
+      {
+        let mut copy: Inline<T>=Inline::new();
+        inline.as_slice().iter().for_each(|elem| copy.push(elem.clone()));
+        Storage{repr:Repr::Inline(copy)}
+      },
+    }
+  }
+}
+
+impl<T> Deref for Storage<T>
+{
+  type Target=[T];
+  fn deref(&self) -> &[T]
+  {
+    match &self.repr
+    {
+      Repr::Shared{buf,offset,len} => &buf[*offset..*offset+*len],
+      Repr::Inline(inline) => inline.as_slice(),
+    }
+  }
+}
+
+impl<T> DerefMut for Storage<T>
+where T: Clone
+{
+  fn deref_mut(&mut self) -> &mut [T]
+  {
+    match &mut self.repr
+    {
+      Repr::Shared{buf,offset,len} =>
+      {
+        if Arc::get_mut(buf).is_none()
+        {
+          let copy: Vec<T>=buf[*offset..*offset+*len].to_vec();
+          *buf=Arc::new(copy);
+          *offset=0;
+        }
+        &mut Arc::get_mut(buf).unwrap()[*offset..*offset+*len]
+      },
+      Repr::Inline(inline) => inline.as_mut_slice(),
+    }
+  }
+}
+
+/// Up to `INLINE_LEN` elements, of which the first `len` are initialised.
+struct Inline<T>
+{
+  elems: [MaybeUninit<T>;INLINE_LEN],
+  len: usize,
+}
+
+impl<T> Inline<T>
+{
+  fn new() -> Inline<T>
+  {
+    // An array of `MaybeUninit` needs no initialisation.
+    Inline{elems:unsafe { MaybeUninit::<[MaybeUninit<T>;INLINE_LEN]>::uninit().assume_init() },len:0}
+  }
+
+  /// Appends `elem`, which must fit.
+  fn push(&mut self, elem: T)
+  {
+    self.elems[self.len]=MaybeUninit::new(elem);
+    self.len+=1;
+  }
+
+  /// Moves every element out in order, passing its index and the element to `f`, which leaves no
+  /// elements behind.
+  fn drain<F>(&mut self, mut f: F)
+  where F: FnMut(usize,T)
+  {
+    let len: usize=self.len;
+    self.len=0;
+    // The first `len` elements are initialised, and each is read out once and no longer counted.
+    (0..len).for_each(|itr| f(itr,unsafe { self.elems[itr].as_ptr().read() }));
+  }
+
+  fn as_slice(&self) -> &[T]
+  {
+    // The first `len` elements are initialised, and `MaybeUninit<T>` has the same layout as `T`.
+    unsafe { core::slice::from_raw_parts(self.elems.as_ptr() as *const T,self.len) }
+  }
+
+  fn as_mut_slice(&mut self) -> &mut [T]
+  {
+    unsafe { core::slice::from_raw_parts_mut(self.elems.as_mut_ptr() as *mut T,self.len) }
+  }
+}
+
+impl<T> Drop for Inline<T>
+{
+  fn drop(&mut self)
+  {
+    unsafe { core::ptr::drop_in_place(self.as_mut_slice()) }
+  }
+}
+
+/// Element buffer that has been allocated but whose elements have not been written yet, unless there
+/// are few enough of them to be held inline.
+pub(super) struct UninitStorage<T>
+{
+  buf: Option<Box<[MaybeUninit<T>]>>,
+  len: usize,
+}
+
+impl<T> UninitStorage<T>
+{
+  pub(super) fn new(len: usize) -> UninitStorage<T>
+  {
+    let buf: Option<Box<[MaybeUninit<T>]>>=if len<=INLINE_LEN { None } else { Some(Box::new_uninit_slice(len)) };
+    UninitStorage{buf:buf,len:len}
+  }
+
+  /// Writes the elements yielded by `elems` into the buffer in order and returns the initialised
+  /// buffer.
+  ///
+  /// Panics if `elems` does not yield exactly as many elements as the buffer holds. If it yields too
+  /// few, the elements already written are leaked rather than dropped, unless they are held inline.
+  pub(super) fn init<I>(self, elems: I) -> Storage<T>
+  where I: IntoIterator<Item=T>
+  {
+    let mut elems=elems.into_iter();
+    let mut next=|| elems.next().unwrap_or_else(|| panic!("The number of elements must equal the size of the tensor to initialise it."));
+    let storage: Storage<T>=match self.buf
+    {
+      None =>
+      {
+        let mut inline: Inline<T>=Inline::new();
+        (0..self.len).for_each(|_| inline.push(next()));
+        Storage{repr:Repr::Inline(inline)}
+      },
+      Some(mut buf) =>
+      {
+        buf.iter_mut().for_each(|slot| *slot=MaybeUninit::new(next()));
+        // Every slot has been written above, and `MaybeUninit<T>` has the same layout as `T`.
+        let buf: Box<[T]>=unsafe { Box::from_raw(Box::into_raw(buf) as *mut [T]) };
+        buf.into()
+      },
+    };
+    if elems.next().is_some() { panic!("The number of elements must equal the size of the tensor to initialise it.") }
+    storage
+  }
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod storage_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  fn storage_test_elems(len: usize) -> Vec<f64>
+  {
+    (0..len).map(|itr| itr as f64+1.0).collect()
+  }
+
+  /// Buffers are shared on cloning, and elements held inline are copied.
+  #[rstest(len,case(0),case(1),case(INLINE_LEN),case(INLINE_LEN+1),case(8))]
+  fn storage_test_clone(len: usize)
+  {
+    let s1: Storage<f64>=Storage::from(storage_test_elems(len).into_boxed_slice());
+    let s2: Storage<f64>=s1.clone();
+
+    assert!(*s1==*s2 && s1.ptr_eq(&s1));
+    assert!(s1.ptr_eq(&s2)==(len>INLINE_LEN));
+    assert!((s1.as_ptr()==s2.as_ptr())==(len>INLINE_LEN));
+  }
+
+  #[rstest(len,ind,case(5,0),case(5,4),case(8,3),case(INLINE_LEN,1),case(1,0))]
+  fn storage_test_deref_mut(len: usize, ind: usize)
+  {
+    let elems: Vec<f64>=storage_test_elems(len);
+    let mut s1: Storage<f64>=Storage::from(elems.clone().into_boxed_slice());
+    let ptr: *const f64=s1.as_ptr();
+
+    // Writing to a buffer that is not shared writes in place.
+    s1[ind]=0.5f64;
+    assert!(s1.as_ptr()==ptr);
+
+    let s2: Storage<f64>=s1.clone();
+    s1[ind]=0.25f64;
+    assert!(!s1.ptr_eq(&s2));
+    assert!(s1.iter().enumerate().all(|(itr,&elem)| elem==if itr==ind { 0.25f64 } else { elems[itr] }));
+    assert!(s2.iter().enumerate().all(|(itr,&elem)| elem==if itr==ind { 0.5f64 } else { elems[itr] }));
+    assert!(len<=INLINE_LEN || s2.as_ptr()==ptr);
+  }
+
+  /// Both halves of a buffer still share it, until either is written to.
+  #[rstest(len,mid,case(5,2),case(5,0),case(5,5),case(8,3),case(INLINE_LEN,1),case(2,2),case(0,0))]
+  fn storage_test_split_at(len: usize, mid: usize)
+  {
+    let elems: Vec<f64>=storage_test_elems(len);
+    let s: Storage<f64>=Storage::from(elems.clone().into_boxed_slice());
+    let ptr: *const f64=s.as_ptr();
+
+    let (mut s1,s2): (Storage<f64>,Storage<f64>)=s.split_at(mid);
+    assert!(*s1==elems[..mid] && *s2==elems[mid..]);
+    assert!(s1.ptr_eq(&s2)==(len>INLINE_LEN));
+    if len>INLINE_LEN { assert!(s1.as_ptr()==ptr && s2.as_ptr()==ptr.wrapping_add(mid)); }
+
+    if mid>0
+    {
+      s1[mid-1]=0.5f64;
+      assert!(!s1.ptr_eq(&s2));
+      assert!(s1[..mid-1]==elems[..mid-1] && s1[mid-1]==0.5f64 && *s2==elems[mid..]);
+    }
+  }
+
+  /// Splitting at the end leaves an empty tail, whose drop lets the head be written in place.
+  #[rstest(len,case(5),case(8),case(INLINE_LEN))]
+  fn storage_test_split_at_end(len: usize)
+  {
+    let s: Storage<f64>=Storage::from(storage_test_elems(len).into_boxed_slice());
+    let ptr: *const f64=s.as_ptr();
+
+    let (mut s1,s2): (Storage<f64>,Storage<f64>)=s.split_at(len);
+    assert!(*s1==storage_test_elems(len)[..] && s2.is_empty());
+    drop(s2);
+    s1[0]=7f64;
+    assert!(len<=INLINE_LEN || s1.as_ptr()==ptr);
+  }
+
+  /// The allocation is handed back if the buffer is not shared and the range covers all of it, and
+  /// copied otherwise.
+  #[rstest(len,shared,case(5,false),case(5,true),case(8,false),case(INLINE_LEN,false),case(INLINE_LEN,true),case(0,false))]
+  fn storage_test_into_boxed_slice(len: usize, shared: bool)
+  {
+    let s1: Storage<f64>=Storage::from(storage_test_elems(len).into_boxed_slice());
+    let s2: Option<Storage<f64>>=if shared { Some(s1.clone()) } else { None };
+    let ptr: *const f64=s1.as_ptr();
+
+    let buf: Box<[f64]>=s1.into_boxed_slice();
+    assert!(*buf==storage_test_elems(len)[..]);
+    assert!((buf.as_ptr()==ptr)==(len>INLINE_LEN && !shared));
+    assert!(s2.iter().all(|s2| **s2==*buf));
+  }
+
+  #[rstest(len,mid,case(5,1),case(5,4),case(8,0),case(INLINE_LEN,3))]
+  fn storage_test_into_boxed_slice_split(len: usize, mid: usize)
+  {
+    let (s1,s2): (Storage<f64>,Storage<f64>)=Storage::from(storage_test_elems(len).into_boxed_slice()).split_at(mid);
+    assert!(*s1.into_boxed_slice()==storage_test_elems(len)[..mid]);
+    assert!(*s2.into_boxed_slice()==storage_test_elems(len)[mid..]);
+  }
+
+  #[rstest(len,case(0),case(3),case(INLINE_LEN),case(INLINE_LEN+1),case(9))]
+  fn storage_test_init(len: usize)
+  {
+    let s: Storage<f64>=UninitStorage::new(len).init((0..len).map(|itr| itr as f64+1.0));
+    assert!(*s==storage_test_elems(len)[..]);
+  }
+
+  #[rstest(len,case(0),case(2),case(INLINE_LEN),case(6))]
+  fn storage_test_init_drop(len: usize)
+  {
+    use std::rc::Rc;
+
+    let counter: Rc<()>=Rc::new(());
+    let s: Storage<Rc<()>>=UninitStorage::new(len).init(vec![counter.clone();len]);
+    assert!(Rc::strong_count(&counter)==len+1);
+    drop(s);
+    assert!(Rc::strong_count(&counter)==1);
+  }
+
+  /// Elements are held inline if they fit, whether initialised in place or handed over in a buffer,
+  /// which they are moved out of.
+  #[rstest(len,case(0),case(1),case(INLINE_LEN),case(INLINE_LEN+1),case(8))]
+  fn storage_test_inline(len: usize)
+  {
+    let inline=|s: &Storage<f64>| matches!(s.repr,Repr::Inline(_));
+
+    let s: Storage<f64>=UninitStorage::new(len).init(storage_test_elems(len));
+    assert!(inline(&s)==(len<=INLINE_LEN) && *s==storage_test_elems(len)[..]);
+    let s: Storage<f64>=Storage::from(storage_test_elems(len));
+    assert!(inline(&s)==(len<=INLINE_LEN) && *s==storage_test_elems(len)[..]);
+
+    let (s1,s2): (Storage<f64>,Storage<f64>)=s.split_at(len/2);
+    assert!(inline(&s1)==(len<=INLINE_LEN) && inline(&s2)==(len<=INLINE_LEN));
+  }
+
+  /// Elements held inline are cloned with the storage, and dropped once each.
+  #[rstest(len,mid,case(2,1),case(INLINE_LEN,3),case(3,0),case(1,1))]
+  fn storage_test_inline_drop(len: usize, mid: usize)
+  {
+    use std::rc::Rc;
+
+    let counter: Rc<()>=Rc::new(());
+    let s: Storage<Rc<()>>=UninitStorage::new(len).init(vec![counter.clone();len]);
+    let (s1,s2): (Storage<Rc<()>>,Storage<Rc<()>>)=s.clone().split_at(mid);
+    assert!(Rc::strong_count(&counter)==2*len+1);
+    drop(s1);
+    let s2: Box<[Rc<()>]>=s2.into_boxed_slice();
+    assert!(s2.len()==len-mid && Rc::strong_count(&counter)==2*len-mid+1);
+    drop(s2);
+    assert!(Rc::strong_count(&counter)==len+1);
+    drop(s);
+    assert!(Rc::strong_count(&counter)==1);
+  }
+
+  #[rstest(len,elems,case(4,2),case(1,2),case(0,1),case(INLINE_LEN+1,INLINE_LEN),case(6,9))]
+  #[should_panic(expected="The number of elements must equal the size of the tensor to initialise it.")]
+  fn storage_test_init_len(len: usize, elems: usize)
+  {
+    UninitStorage::<f64>::new(len).init(storage_test_elems(elems));
+  }
+}
@@ -0,0 +1,291 @@
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Scalar;
+use super::Tensor;
+
+impl<T> Tensor<T,2>
+{
+  /// Whether the matrix has as many rows as columns.
+  pub(super) fn is_square(&self) -> bool
+  {
+    self.dim[0]==self.dim[1]
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Clone
+{
+  /// Elements of the diagonals at `offsets`, where an offset is how far a diagonal lies above the main
+  /// diagonal, or below it if negative, each as long as the diagonal is. A diagonal that lies outside
+  /// the matrix is empty.
+  ///
+  /// The offsets `[-1,0,1]` give the off-diagonals and diagonal that `solve_tridiagonal` takes.
+  pub(super) fn extract_diagonals(&self, offsets: &[isize]) -> Vec<Tensor<T,1>>
+  {
+    offsets.iter().map(|&offset| {
+      let (row,col): (Idx,Idx)=if offset<0 { (offset.unsigned_abs(),0) } else { (0,offset as Idx) };
+      let len: Idx=self.dim[0].saturating_sub(row).min(self.dim[1].saturating_sub(col));
+      Tensor::<T,1>::from_fn([len],|[itr]| self[[row+itr,col+itr]].clone())
+    }).collect()
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// Matrix with the elements more than `lower` below or `upper` above the main diagonal set to zero.
+  pub(super) fn band(&self, lower: Idx, upper: Idx) -> Tensor<T,2>
+  {
+    Tensor::<T,2>::from_fn(self.dim,|[itr,jtr]| if itr<=jtr+lower && jtr<=itr+upper { self[[itr,jtr]].clone() } else { T::default() })
+  }
+
+  /// Lower and upper bandwidth of the matrix, how far the furthest non-zero elements lie below and
+  /// above the main diagonal, which are both zero for a diagonal or zero matrix.
+  pub(super) fn bandwidth(&self) -> (Idx,Idx)
+  {
+    (0..self.dim[0])
+      .flat_map(|itr| (0..self.dim[1]).map(move |jtr| [itr,jtr]))
+      .filter(|&ind| self[ind]!=T::default())
+      .fold((0,0),|(lower,upper),[itr,jtr]| (lower.max(itr.saturating_sub(jtr)),upper.max(jtr.saturating_sub(itr))))
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Float
+{
+  /// Whether the matrix is square and every element differs from its mirror image in the diagonal by
+  /// at most `tol`.
+  pub(super) fn is_symmetric(&self, tol: T) -> bool
+  {
+    self.is_square() && (0..self.dim[0]).all(|itr| (0..itr).all(|jtr| (self[[itr,jtr]]-self[[jtr,itr]]).abs()<=tol))
+  }
+
+  /// Whether the matrix is square and the absolute value of every diagonal element is at least the sum
+  /// of the absolute values of the other elements of its row.
+  pub(super) fn is_diagonally_dominant(&self) -> bool
+  {
+    self.is_square() && (0..self.dim[0]).all(|itr| {
+      let off: T=(0..self.dim[1]).filter(|&jtr| jtr!=itr).fold(T::default(),|sum,jtr| sum+self[[itr,jtr]].abs());
+      self[[itr,itr]].abs()>=off
+    })
+  }
+
+  /// Whether every element below the diagonal, or above it unless `upper` is set, is at most `tol` in
+  /// absolute value. The matrix need not be square.
+  pub(super) fn is_triangular(&self, upper: bool, tol: T) -> bool
+  {
+    (0..self.dim[0]).all(|itr| (0..self.dim[1]).all(|jtr| {
+      let outside: bool=if upper { itr>jtr } else { itr<jtr };
+      !outside || self[[itr,jtr]].abs()<=tol
+    }))
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod structure_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use alloc::vec;
+
+  use super::super::Dim;
+  use super::super::Layout;
+
+  /// Step that is exactly representable, so differences of it compare exactly with a tolerance.
+  const STEP: f64=1.0/1048576.0;
+
+  fn structure_test_matrix(rows: &[[f64;3];3]) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn([3,3],|[itr,jtr]| rows[itr][jtr])
+  }
+
+  /// Matrix with lower bandwidth `lower` and upper bandwidth `upper`, the elements in the band being
+  /// `10*i+j+1`.
+  fn structure_test_banded(dim: Dim<2>, lower: Idx, upper: Idx) -> Tensor<f64,2>
+  {
+    Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| if itr<=jtr+lower && jtr<=itr+upper { (10*itr+jtr+1) as f64 } else { 0.0 })
+  }
+
+  #[rstest(dim,case([3,3]),case([0,0]),case([1,1]),case([3,2]),case([2,3]),case([1,4]),case([0,2]))]
+  fn structure_test_is_square(dim: Dim<2>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::new(dim);
+    assert!(t.is_square()==(dim[0]==dim[1]));
+    if !t.is_square() { assert!(!t.is_symmetric(1.0) && !t.is_diagonally_dominant()) }
+  }
+
+  #[rstest(dim,lower,upper,layout,
+    case([5,6],1,2,Layout::RowMajor),
+    case([5,6],1,2,Layout::ColMajor),
+    case([4,4],0,0,Layout::RowMajor),
+    case([4,4],3,0,Layout::ColMajor),
+    case([3,5],0,4,Layout::RowMajor),
+    case([6,2],2,1,Layout::ColMajor),
+  )]
+  fn structure_test_bandwidth(dim: Dim<2>, lower: Idx, upper: Idx, layout: Layout)
+  {
+    assert!(structure_test_banded(dim,lower,upper).to_layout(layout).bandwidth()==(lower,upper));
+  }
+
+  #[rstest(dim,ind,expected,
+    case([3,4],[2,0],(2,0)),
+    case([3,4],[0,3],(0,3)),
+    case([3,4],[1,1],(0,0)),
+    case([4,3],[3,2],(1,0)),
+  )]
+  fn structure_test_bandwidth_single(dim: Dim<2>, ind: Dim<2>, expected: (Idx,Idx))
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_fn(dim,|pos| (pos==ind) as i32);
+    assert!(t.bandwidth()==expected);
+  }
+
+  #[rstest(dim,case([4,3]),case([0,0]),case([1,1]),case([0,3]))]
+  fn structure_test_bandwidth_zero(dim: Dim<2>)
+  {
+    assert!(Tensor::<f64,2>::new(dim).bandwidth()==(0,0));
+  }
+
+  /// Band of the matrix of 5 rows and 6 columns with lower bandwidth 1 and upper bandwidth 2.
+  #[rstest(lower,upper,
+    case(1,2),
+    case(4,5),
+    case(1,1),
+    case(0,0),
+    case(2,0),
+    case(0,3),
+  )]
+  fn structure_test_band(lower: Idx, upper: Idx)
+  {
+    let t: Tensor<f64,2>=structure_test_banded([5,6],1,2);
+    let band: Tensor<f64,2>=t.band(lower,upper);
+
+    assert!(band.bandwidth()==(lower.min(1),upper.min(2)));
+    assert!(band==structure_test_banded([5,6],lower.min(1),upper.min(2)));
+    assert!(t.to_layout(Layout::ColMajor).band(lower,upper)==band);
+  }
+
+  /// Diagonals of the matrix of 5 rows and 6 columns with lower bandwidth 1 and upper bandwidth 2.
+  #[rstest(offset,expected,
+    case(-1,&[11.0,22.0,33.0,44.0]),
+    case(0,&[1.0,12.0,23.0,34.0,45.0]),
+    case(1,&[2.0,13.0,24.0,35.0,46.0]),
+    case(2,&[3.0,14.0,25.0,36.0]),
+    case(3,&[0.0,0.0,0.0]),
+    case(5,&[0.0]),
+    case(-4,&[0.0]),
+    case(-5,&[]),
+    case(7,&[]),
+  )]
+  fn structure_test_extract_diagonals(offset: isize, expected: &[f64])
+  {
+    let t: Tensor<f64,2>=structure_test_banded([5,6],1,2);
+    for layout in [Layout::RowMajor,Layout::ColMajor].iter()
+    {
+      let diags: Vec<Tensor<f64,1>>=t.to_layout(*layout).extract_diagonals(&[offset]);
+      assert!(diags.len()==1 && diags[0].dim==[expected.len()]);
+      assert!(diags[0].iter().copied().eq(expected.iter().copied()));
+    }
+  }
+
+  #[rstest(offsets,case(&[-1,0,2,5,-5,7]),case(&[0,0]),case(&[]))]
+  fn structure_test_extract_diagonals_many(offsets: &[isize])
+  {
+    let t: Tensor<f64,2>=structure_test_banded([5,6],1,2);
+    let diags: Vec<Tensor<f64,1>>=t.extract_diagonals(offsets);
+
+    assert!(diags.len()==offsets.len());
+    assert!(diags.iter().zip(offsets.iter()).all(|(diag,&offset)| *diag==t.extract_diagonals(&[offset])[0]));
+  }
+
+  /// The diagonals of a square banded matrix are the input of the tridiagonal solver.
+  #[rstest(n,case(2),case(3),case(5),case(8))]
+  fn structure_test_extract_tridiagonal(n: Idx)
+  {
+    let a: Tensor<f64,2>=Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]| if itr==jtr { 4.0 } else { 1.0/(itr+jtr) as f64 }).band(1,1);
+    let b: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|[itr]| itr as f64-2.0);
+    let diags: Vec<Tensor<f64,1>>=a.extract_diagonals(&[-1,0,1]);
+
+    let x: Tensor<f64,1>=Tensor::<f64,1>::solve_tridiagonal(&diags[0],&diags[1],&diags[2],&b).unwrap();
+    assert!(x.iter().zip(a.solve(&b).unwrap().iter()).all(|(x,y)| (x-y).abs()<1e-14));
+    assert!(diags==vec![a.extract_diagonals(&[-1])[0].clone(),a.diagonal(),a.transpose().extract_diagonals(&[-1])[0].clone()]);
+  }
+
+  /// A symmetric matrix with the element at `ind` replaced by `value`.
+  #[rstest(ind,value,tol,layout,expected,
+    case([0,1],0.5+STEP,STEP,Layout::RowMajor,true),
+    case([0,1],0.5+STEP,STEP,Layout::ColMajor,true),
+    case([0,1],0.5+STEP,STEP/2.0,Layout::RowMajor,false),
+    case([1,0],0.5-STEP,STEP/2.0,Layout::ColMajor,false),
+    case([0,1],0.5,0.0,Layout::RowMajor,true),
+    case([1,1],-7.0,0.0,Layout::ColMajor,true),
+    case([1,2],1.0,0.5,Layout::RowMajor,false),
+    case([2,0],f64::NAN,1.0,Layout::RowMajor,false),
+    case([1,2],f64::NAN,1.0,Layout::ColMajor,false),
+  )]
+  fn structure_test_is_symmetric(ind: Dim<2>, value: f64, tol: f64, layout: Layout, expected: bool)
+  {
+    let mut t: Tensor<f64,2>=structure_test_matrix(&[[2.0,0.5,-1.0],[0.5,3.0,0.25],[-1.0,0.25,1.0]]);
+    t[ind]=value;
+    assert!(t.to_layout(layout).is_symmetric(tol)==expected);
+  }
+
+  /// A matrix whose last row is dominant only just, with its diagonal equal to the sum of the rest of
+  /// the row, with `delta` added to the element at `ind`.
+  #[rstest(ind,delta,layout,expected,
+    case([0,0],0.0,Layout::RowMajor,true),
+    case([0,0],0.0,Layout::ColMajor,true),
+    case([2,2],-STEP,Layout::RowMajor,false),
+    case([2,2],-STEP,Layout::ColMajor,false),
+    case([2,1],-STEP,Layout::RowMajor,false),
+    case([2,1],0.5,Layout::RowMajor,true),
+    case([0,0],-1.0,Layout::ColMajor,true),
+    case([0,0],-1.0-STEP,Layout::RowMajor,false),
+    case([1,1],6.0,Layout::ColMajor,true),
+  )]
+  fn structure_test_is_diagonally_dominant(ind: Dim<2>, delta: f64, layout: Layout, expected: bool)
+  {
+    let mut t: Tensor<f64,2>=structure_test_matrix(&[[4.0,-1.0,2.0],[1.0,-3.0,1.5],[0.25,-0.5,0.75]]);
+    t[ind]+=delta;
+    assert!(t.to_layout(layout).is_diagonally_dominant()==expected);
+  }
+
+  #[rstest(upper,tol,transpose,expected,
+    case(true,STEP,false,true),
+    case(true,STEP/2.0,false,false),
+    case(false,1.0,false,false),
+    case(false,STEP,true,true),
+    case(false,STEP/2.0,true,false),
+    case(true,1.0,true,false),
+  )]
+  fn structure_test_is_triangular(upper: bool, tol: f64, transpose: bool, expected: bool)
+  {
+    let t: Tensor<f64,2>=structure_test_matrix(&[[1.0,2.0,3.0],[-STEP,4.0,5.0],[0.0,STEP,6.0]]);
+    let t: Tensor<f64,2>=if transpose { t.transpose() } else { t };
+    assert!(t.is_triangular(upper,tol)==expected);
+  }
+
+  /// Matrices that need not be square, with ones on and above the diagonal.
+  #[rstest(dim,upper,expected,
+    case([2,4],true,true),
+    case([2,4],false,false),
+    case([4,2],true,true),
+    case([4,2],false,false),
+    case([3,1],true,true),
+    case([3,1],false,true),
+    case([1,3],false,false),
+    case([0,3],false,true),
+  )]
+  fn structure_test_is_triangular_wide(dim: Dim<2>, upper: bool, expected: bool)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| if itr<=jtr { 1.0 } else { 0.0 });
+    assert!(t.is_triangular(upper,0.0)==expected && t.to_layout(Layout::ColMajor).is_triangular(upper,0.0)==expected);
+  }
+}
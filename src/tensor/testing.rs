@@ -0,0 +1,130 @@
+use alloc::format;
+use alloc::string::String;
+
+use core::fmt::Debug;
+
+use super::Dim;
+use super::Float;
+use super::Idx;
+use super::Indices;
+use super::Tensor;
+
+/// Asserts that two tensors are of the same dimensions and equal element for element, whatever their
+/// layouts, naming the first index at which they differ in row-major order, both elements and both
+/// dimensions if they are not.
+macro_rules! assert_tensor_eq {
+  ($actual:expr,$expected:expr) => {
+    if let Some(msg)=$crate::tensor::testing::tensor_eq_failure(&$actual,&$expected) { panic!("{}",msg) }
+  };
+}
+
+/// Asserts that two float tensors are of the same dimensions and that every element differs from its
+/// counterpart by at most `tol`, reporting the first that does not as `assert_tensor_eq` does. A NaN is
+/// close to nothing.
+macro_rules! assert_tensor_allclose {
+  ($actual:expr,$expected:expr,$tol:expr) => {
+    if let Some(msg)=$crate::tensor::testing::tensor_allclose_failure(&$actual,&$expected,$tol) { panic!("{}",msg) }
+  };
+}
+
+pub(super) use assert_tensor_allclose;
+pub(super) use assert_tensor_eq;
+
+/// Message describing how `actual` differs from `expected`, if it does.
+pub(super) fn tensor_eq_failure<T,const N: Idx>(actual: &Tensor<T,N>, expected: &Tensor<T,N>) -> Option<String>
+where T: PartialEq + Debug
+{
+  first_failure(actual,expected,|lhs,rhs| lhs==rhs)
+}
+
+/// Message describing the first element of `actual` that differs from that of `expected` by more than
+/// `tol`, if any does.
+pub(super) fn tensor_allclose_failure<T,const N: Idx>(actual: &Tensor<T,N>, expected: &Tensor<T,N>, tol: T) -> Option<String>
+where T: Float + Debug
+{
+  first_failure(actual,expected,|&lhs,&rhs| (lhs-rhs).abs()<=tol)
+}
+
+fn first_failure<T,F,const N: Idx>(actual: &Tensor<T,N>, expected: &Tensor<T,N>, mut equal: F) -> Option<String>
+where T: Debug, F: FnMut(&T,&T) -> bool
+{
+  if actual.dim!=expected.dim
+  {
+    return Some(format!("The tensors are of dimensions {:?} and {:?} rather than of the same dimensions.",actual.dim,expected.dim));
+  }
+  let ind: Dim<N>=Indices::new(actual.dim).find(|&ind| !equal(&actual[ind],&expected[ind]))?;
+  Some(format!("The tensors of dimensions {:?} and {:?} differ at index {:?}, where the actual element is {:?} and the expected element is {:?}.",
+    actual.dim,expected.dim,ind,actual[ind],expected[ind]))
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod testing_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  use super::super::Layout;
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn testing_test_assert_tensor_eq(layout: Layout)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_rows(&[&[1,2,3],&[4,5,6]]).unwrap();
+    assert_tensor_eq!(t,t.to_layout(layout));
+  }
+
+  #[test]
+  fn testing_test_assert_tensor_eq_empty()
+  {
+    assert_tensor_eq!(Tensor::<f64,2>::new([0,3]),Tensor::<f64,2>::new([0,3]));
+  }
+
+  /// Only the first differing index is reported.
+  #[rstest(other,expected,
+    case(|t: &Tensor<i32,2>| { let mut other: Tensor<i32,2>=t.clone(); other[[1,0]]=40; other[[1,2]]=60; other },
+      "The tensors of dimensions [2, 3] and [2, 3] differ at index [1, 0], where the actual element is 4 and the expected element is 40."),
+    case(|t: &Tensor<i32,2>| t.transpose(),"The tensors are of dimensions [2, 3] and [3, 2] rather than of the same dimensions."),
+  )]
+  fn testing_test_tensor_eq_failure(other: fn(&Tensor<i32,2>) -> Tensor<i32,2>, expected: &str)
+  {
+    let t: Tensor<i32,2>=Tensor::<i32,2>::from_rows(&[&[1,2,3],&[4,5,6]]).unwrap();
+    assert!(tensor_eq_failure(&t,&other(&t)).unwrap()==expected);
+  }
+
+  #[test]
+  #[should_panic(expected="differ at index [0, 1], where the actual element is 2 and the expected element is 7.")]
+  fn testing_test_assert_tensor_eq_fails()
+  {
+    assert_tensor_eq!(Tensor::<i32,2>::from_rows(&[&[1,2],&[3,4]]).unwrap(),Tensor::<i32,2>::from_rows(&[&[1,7],&[3,8]]).unwrap());
+  }
+
+  #[test]
+  fn testing_test_assert_tensor_allclose()
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64*0.5);
+    assert_tensor_allclose!(t,t.map(|elem| elem+0.25),0.25);
+    assert!(tensor_allclose_failure(&t,&t.map(|elem| elem+0.5),0.25).unwrap()
+      =="The tensors of dimensions [3] and [3] differ at index [0], where the actual element is 0.0 and the expected element is 0.5.");
+  }
+
+  /// NaN is close to nothing, not even itself, whatever the tolerance.
+  #[rstest(lhs_nan,tol,case(true,1.0),case(false,f64::INFINITY))]
+  fn testing_test_assert_tensor_allclose_nan(lhs_nan: bool, tol: f64)
+  {
+    let t: Tensor<f64,1>=Tensor::<f64,1>::from_fn([3],|[itr]| itr as f64*0.5);
+    let mut nan: Tensor<f64,1>=t.clone();
+    nan[2]=f64::NAN;
+    assert!(tensor_allclose_failure(if lhs_nan { &nan } else { &t },&nan,tol).is_some());
+  }
+
+  #[test]
+  #[should_panic(expected="The tensors are of dimensions [2] and [3] rather than of the same dimensions.")]
+  fn testing_test_assert_tensor_allclose_dims()
+  {
+    assert_tensor_allclose!(Tensor::<f64,1>::new([2]),Tensor::<f64,1>::new([3]),1.0);
+  }
+}
@@ -0,0 +1,128 @@
+use alloc::vec::Vec;
+
+use super::Float;
+use super::Idx;
+use super::Tensor;
+use super::TensorError;
+
+impl<T> Tensor<T,1>
+where T: Float
+{
+  /// Solution `x` of the tridiagonal system whose matrix has the subdiagonal `lower`, the diagonal
+  /// `diag` and the superdiagonal `upper`, with right-hand side `rhs`.
+  ///
+  /// The system is solved with the Thomas algorithm, Gaussian elimination without pivoting that takes
+  /// linear time. It is stable for diagonally dominant or symmetric positive definite matrices. The
+  /// diagonal and right-hand side must be of the same length `n`, and the off-diagonals of length
+  /// `n-1`. A pivot that is zero after elimination is reported as a singular matrix.
+  pub(super) fn solve_tridiagonal(lower: &Tensor<T,1>, diag: &Tensor<T,1>, upper: &Tensor<T,1>, rhs: &Tensor<T,1>) -> Result<Tensor<T,1>,TensorError>
+  {
+    let n: Idx=diag.dim[0];
+    if rhs.dim[0]!=n { return Err(TensorError::LengthMismatch{lhs:n,rhs:rhs.dim[0]}); }
+    for off_diag in [lower,upper].iter()
+    {
+      if off_diag.dim[0]!=n.saturating_sub(1) { return Err(TensorError::LengthMismatch{lhs:n.saturating_sub(1),rhs:off_diag.dim[0]}); }
+    }
+    if n==0 { return Ok(Tensor::<T,1>::new([0])); }
+
+    // Eliminate the subdiagonal, keeping the superdiagonal and right-hand side of the resulting unit
+    // upper bidiagonal system.
+    let zero: T=T::default();
+    let mut sup: Vec<T>=Vec::with_capacity(n);
+    let mut res: Vec<T>=Vec::with_capacity(n);
+    for itr in 0..n
+    {
+      let (pivot,elem): (T,T)=match itr
+      {
+        0 => (diag[0],rhs[0]),
+        _ => (diag[itr]-lower[itr-1]*sup[itr-1],rhs[itr]-lower[itr-1]*res[itr-1]),
+      };
+      if pivot==zero { return Err(TensorError::Singular); }
+
+      sup.push(if itr+1<n { upper[itr]/pivot } else { zero });
+      res.push(elem/pivot);
+    }
+
+    for itr in (0..n-1).rev()
+    {
+      res[itr]=res[itr]-sup[itr]*res[itr+1];
+    }
+    Tensor::<T,1>::from_vec([n],res)
+  }
+}
+
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod tridiagonal_tests
+{
+  use super::*;
+  use rstest::rstest;
+
+  fn tridiagonal_test_vector(elems: &[f64]) -> Tensor<f64,1>
+  {
+    Tensor::<f64,1>::from_fn([elems.len()],|[itr]| elems[itr])
+  }
+
+  /// The second difference is exact for the solution x*(1-x)/2 of -u''=1 with u(0)=u(1)=0.
+  #[rstest(n,case(1),case(2),case(9),case(33))]
+  fn tridiagonal_test_poisson(n: Idx)
+  {
+    let h: f64=1.0/((n+1) as f64);
+    let lower: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n-1],|_| -1.0);
+    let diag: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|_| 2.0);
+    let rhs: Tensor<f64,1>=Tensor::<f64,1>::from_fn([n],|_| h*h);
+
+    let x: Tensor<f64,1>=Tensor::<f64,1>::solve_tridiagonal(&lower,&diag,&lower,&rhs).unwrap();
+    for itr in 0..n
+    {
+      let pos: f64=((itr+1) as f64)*h;
+      assert!((x[itr]-pos*(1.0-pos)/2.0).abs()<1e-14);
+    }
+  }
+
+  #[rstest(lower,diag,upper,rhs,
+    case(&[1.0,-2.0,0.5,3.0][..],&[4.0,-5.0,6.0,3.0,7.0][..],&[-1.0,2.0,1.5,-2.5][..],&[1.0,2.0,-3.0,4.0,0.5][..]),
+    case(&[2.0][..],&[3.0,-1.0][..],&[0.5][..],&[1.0,-4.0][..]),
+  )]
+  #[cfg(feature="std")]
+  fn tridiagonal_test_dense(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64])
+  {
+    let n: Idx=diag.len();
+    let dense: Tensor<f64,2>=Tensor::<f64,2>::from_fn([n,n],|[itr,jtr]|
+    {
+      if itr==jtr { diag[itr] } else if itr==jtr+1 { lower[jtr] } else if jtr==itr+1 { upper[itr] } else { 0.0 }
+    });
+    let (lower,diag,upper,rhs): (Tensor<f64,1>,Tensor<f64,1>,Tensor<f64,1>,Tensor<f64,1>)=
+      (tridiagonal_test_vector(lower),tridiagonal_test_vector(diag),tridiagonal_test_vector(upper),tridiagonal_test_vector(rhs));
+
+    let x: Tensor<f64,1>=Tensor::<f64,1>::solve_tridiagonal(&lower,&diag,&upper,&rhs).unwrap();
+    let expected: Tensor<f64,1>=dense.lstsq(&rhs).unwrap();
+    assert!(x.iter().zip(expected.iter()).all(|(x,y)| (x-y).abs()<1e-12));
+  }
+
+  /// In the first singular case, the second pivot is 1-1*1 after eliminating the first row.
+  #[rstest(lower,diag,upper,rhs,error,
+    case(&[0.0;2][..],&[0.0;3][..],&[0.0;2][..],&[0.0;2][..],TensorError::LengthMismatch{lhs:3,rhs:2}),
+    case(&[0.0;3][..],&[0.0;3][..],&[0.0;2][..],&[0.0;3][..],TensorError::LengthMismatch{lhs:2,rhs:3}),
+    case(&[0.0;2][..],&[0.0;3][..],&[0.0;3][..],&[0.0;3][..],TensorError::LengthMismatch{lhs:2,rhs:3}),
+    case(&[1.0;2][..],&[1.0;3][..],&[1.0;2][..],&[0.0;3][..],TensorError::Singular),
+    case(&[0.0;2][..],&[0.0;3][..],&[0.0;2][..],&[0.0;3][..],TensorError::Singular),
+  )]
+  fn tridiagonal_test_errors(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64], error: TensorError)
+  {
+    let res: Result<Tensor<f64,1>,TensorError>=Tensor::<f64,1>::solve_tridiagonal(&tridiagonal_test_vector(lower),
+      &tridiagonal_test_vector(diag),&tridiagonal_test_vector(upper),&tridiagonal_test_vector(rhs));
+    assert!(res.err()==Some(error));
+  }
+
+  #[test]
+  fn tridiagonal_test_empty()
+  {
+    let empty: Tensor<f64,1>=Tensor::<f64,1>::new([0]);
+    assert!(Tensor::<f64,1>::solve_tridiagonal(&empty,&empty,&empty,&empty).unwrap().is_empty());
+  }
+}
@@ -0,0 +1,41 @@
+// Entry points for the compile-fail tests under `tests/ui`, which cannot reach the private API of the
+// crate otherwise. Each wraps a method whose arguments are checked at compile time, so that a test
+// calling it with arguments that break the checks fails to build for the reason the method gives.
+
+use super::Dim;
+use super::Idx;
+use super::Tensor;
+use super::smatrix::SMatrix;
+
+/// Dimensions of the sum along the const axis `AXIS` of a tensor of zeros of dimensions `dim`, as
+/// `sum_axis_const` gives it.
+pub fn sum_axis_const<const AXIS: Idx,const N: Idx,const M: Idx>(dim: Dim<N>) -> Dim<M>
+{
+  Tensor::<f64,N>::new(dim).sum_axis_const::<AXIS,M>().dim
+}
+
+/// Matrix of `R` rows and `C` columns of `f64` elements, as an `SMatrix` is.
+pub struct Matrix<const R: Idx,const C: Idx>(SMatrix<f64,R,C>);
+
+impl<const R: Idx,const C: Idx> Matrix<R,C>
+{
+  /// Matrix whose element at `[itr,jtr]` is `f([itr,jtr])`.
+  pub fn from_fn<F>(f: F) -> Matrix<R,C>
+  where F: FnMut([Idx;2]) -> f64
+  {
+    Matrix(SMatrix::<f64,R,C>::from_fn(f))
+  }
+
+  /// Matrix product of `self` and `rhs`, as `SMatrix::matmul` gives it, which only builds for a
+  /// right-hand side with as many rows as `self` has columns.
+  pub fn matmul<const K: Idx>(&self, rhs: &Matrix<C,K>) -> Matrix<R,K>
+  {
+    Matrix(self.0.matmul(&rhs.0))
+  }
+
+  /// Element at `[itr,jtr]`.
+  pub fn get(&self, ind: [Idx;2]) -> f64
+  {
+    self.0[ind]
+  }
+}
@@ -0,0 +1,805 @@
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ops::Mul;
+use core::ops::MulAssign;
+use core::ops::RemAssign;
+
+use super::Dim;
+use super::Dimension;
+use super::Idx;
+use super::Indices;
+use super::Layout;
+use super::Operand;
+use super::Scalar;
+use super::Tensor;
+use super::TensorError;
+use super::layout::layout_indices;
+use super::layout::strides;
+use super::region::check_block;
+
+/// Read-only tensor over borrowed elements, for example a buffer owned by foreign code, a
+/// memory-mapped file or another tensor.
+///
+/// The element at a multi-index is found through a stride per axis from the element at `offset`, so
+/// a view can also present its elements transposed, flipped, or repeated along an axis with a stride
+/// of zero. Nothing is copied or allocated to construct or read from a view; `to_owned` copies the
+/// elements into a `Tensor` when needed.
+pub(super) struct TensorView<'a,T: Scalar, const N: Idx>
+{
+  data: &'a [T],
+  dim: Dim<N>,
+  strides: [isize;N],
+  offset: Idx,
+}
+
+impl<'a,T,const N: Idx> TensorView<'a,T,N>
+where T: Scalar
+{
+  /// Creates a view with dimensions `dim` over the row-major elements `data`, which must hold exactly
+  /// as many elements as the dimensions describe.
+  pub(super) fn from_slice(dim: Dim<N>, data: &'a [T]) -> Result<TensorView<'a,T,N>,TensorError>
+  {
+    if dim.size()!=data.len() { return Err(TensorError::SizeMismatch{size:dim.size(),len:data.len()}); }
+
+    Ok(TensorView{data:data,dim:dim,strides:strides(dim,Layout::RowMajor),offset:0})
+  }
+
+  /// Iterator over the elements in row-major order.
+  pub(super) fn iter(&self) -> ViewIter<'a,T,N>
+  {
+    ViewIter{view:*self,indices:Indices::new(self.dim)}
+  }
+
+  /// Sum of all elements.
+  pub(super) fn sum(&self) -> T
+  {
+    match self.contiguous()
+    {
+      Some(data) => T::sum_slice(data),
+      None => self.iter().fold(T::default(),|sum,elem| sum+elem.clone()),
+    }
+  }
+
+  /// Copies the viewed elements into a new tensor.
+  pub(super) fn to_owned(self) -> Tensor<T,N>
+  {
+    match self.contiguous()
+    {
+      Some(data) => Tensor::<T,N>::new_uninit(self.dim).init(data.iter().cloned()),
+      None => Tensor::<T,N>::new_uninit(self.dim).init(self.iter().cloned()),
+    }
+  }
+
+  /// The viewed elements as a slice, if they are adjacent in row-major order.
+  fn contiguous(&self) -> Option<&'a [T]>
+  {
+    let size: Idx=self.dim.size();
+    if size==0 { return Some(&[]); }
+    let row_major: bool=(0..N).all(|axis| self.dim[axis]==1 || self.strides[axis]==strides(self.dim,Layout::RowMajor)[axis]);
+    if row_major { Some(&self.data[self.offset..self.offset+size]) } else { None }
+  }
+
+  /// Position in `data` of the element at `ind`, which must be in bounds.
+  fn position(&self, ind: Dim<N>) -> Idx
+  {
+    ind.iter().zip(self.strides.iter()).fold(self.offset as isize,|pos,(&itr,&stride)| pos+itr as isize*stride) as Idx
+  }
+
+  /// Positions in `data` of the first and last of the viewed elements in memory, unless the view is
+  /// empty.
+  fn span(&self) -> Option<(Idx,Idx)>
+  {
+    if self.dim.size()==0 { return None; }
+    let (mut first,mut last): (isize,isize)=(self.offset as isize,self.offset as isize);
+    for (&len,&stride) in self.dim.iter().zip(self.strides.iter())
+    {
+      let reach: isize=(len-1) as isize*stride;
+      if reach<0 { first+=reach; } else { last+=reach; }
+    }
+    Some((first as Idx,last as Idx))
+  }
+}
+
+impl<'a,T,const N: Idx> Clone for TensorView<'a,T,N>
+where T: Scalar
+{
+  fn clone(&self) -> TensorView<'a,T,N>
+  {
+    *self
+  }
+}
+
+impl<'a,T,const N: Idx> Copy for TensorView<'a,T,N>
+where T: Scalar
+{}
+
+/// Iterator over the elements of a `TensorView` in row-major order.
+pub(super) struct ViewIter<'a,T: Scalar, const N: Idx>
+{
+  view: TensorView<'a,T,N>,
+  indices: Indices<N>,
+}
+
+impl<'a,T,const N: Idx> Iterator for ViewIter<'a,T,N>
+where T: Scalar
+{
+  type Item=&'a T;
+  fn next(&mut self) -> Option<&'a T>
+  {
+    let ind: Dim<N>=self.indices.next()?;
+    Some(&self.view.data[self.view.position(ind)])
+  }
+
+  fn size_hint(&self) -> (Idx,Option<Idx>)
+  {
+    self.indices.size_hint()
+  }
+}
+
+impl<'a,T,const N: Idx> DoubleEndedIterator for ViewIter<'a,T,N>
+where T: Scalar
+{
+  fn next_back(&mut self) -> Option<&'a T>
+  {
+    let ind: Dim<N>=self.indices.next_back()?;
+    Some(&self.view.data[self.view.position(ind)])
+  }
+}
+
+impl<'a,T,const N: Idx> ExactSizeIterator for ViewIter<'a,T,N>
+where T: Scalar
+{}
+
+/// Mutable view of a block of a tensor, returned by `Tensor::slice_view_mut`, through which the
+/// elements of the block are changed in place without copying them out and back.
+///
+/// The rows of the block are generally not adjacent in the tensor, so operations on the whole view
+/// work through one contiguous run along the innermost axis in memory at a time.
+pub(super) struct TensorViewMut<'a,T: Scalar, const N: Idx>
+{
+  data: &'a mut [T],
+  dim: Dim<N>,
+  strides: [isize;N],
+  offset: Idx,
+}
+
+impl<'a,T,const N: Idx> TensorViewMut<'a,T,N>
+where T: Scalar
+{
+  /// Dimensions of the viewed block.
+  pub(super) fn dim(&self) -> Dim<N>
+  {
+    self.dim
+  }
+
+  /// Adds `rhs` to every element of the view in place.
+  pub(super) fn add_assign_scalar(&mut self, rhs: T)
+  {
+    *self+=rhs;
+  }
+
+  /// Multiplies every element of the view by `rhs` in place.
+  pub(super) fn mul_assign_scalar(&mut self, rhs: T)
+  {
+    *self*=rhs;
+  }
+
+  /// Copies the viewed elements into a new tensor.
+  pub(super) fn to_owned(&self) -> Tensor<T,N>
+  {
+    Tensor::<T,N>::new_uninit(self.dim).init(Indices::new(self.dim).map(|ind| self.data[self.position(ind)].clone()))
+  }
+
+  /// Applies `f` to every element of the view, in the order the elements are stored.
+  fn for_each_mut<F>(&mut self, mut f: F)
+  where F: FnMut(&mut T)
+  {
+    if self.dim.size()==0 { return; }
+
+    match (0..N).find(|&axis| self.strides[axis]==1)
+    {
+      // The elements along the innermost axis are adjacent, so each run along it is a slice.
+      Some(inner) =>
+      {
+        let len: Idx=self.dim[inner];
+        let mut outer: Dim<N>=self.dim;
+        outer[inner]=1;
+        for ind in Indices::new(outer)
+        {
+          let start: Idx=self.position(ind);
+          self.data[start..start+len].iter_mut().for_each(&mut f);
+        }
+      },
+      None => for ind in Indices::new(self.dim)
+      {
+        let pos: Idx=self.position(ind);
+        f(&mut self.data[pos]);
+      },
+    }
+  }
+
+  /// Position in `data` of the element at `ind`, which must be in bounds.
+  fn position(&self, ind: Dim<N>) -> Idx
+  {
+    ind.iter().zip(self.strides.iter()).fold(self.offset as isize,|pos,(&itr,&stride)| pos+itr as isize*stride) as Idx
+  }
+}
+
+impl<T,const N: Idx> Tensor<T,N>
+where T: Scalar
+{
+  /// View of all elements of `self`, in either layout.
+  pub(super) fn view(&self) -> TensorView<'_,T,N>
+  {
+    TensorView{data:&self.data,dim:self.dim,strides:strides(self.dim,self.layout),offset:0}
+  }
+
+  /// View of `self` repeated along every axis of length one to the length of that axis in `dim`,
+  /// without copying. The repeated axes have a stride of zero.
+  pub(super) fn broadcast_view(&self, dim: Dim<N>) -> TensorView<'_,T,N>
+  {
+    let mut view: TensorView<T,N>=self.view();
+    for ((stride,&len),&target) in view.strides.iter_mut().zip(self.dim.iter()).zip(dim.iter())
+    {
+      if len==target { continue; }
+      if len!=1 { panic!("Every axis of a tensor must be of length one or of the target length to broadcast it.") }
+      *stride=0;
+    }
+    view.dim=dim;
+    view
+  }
+
+  /// View of `self` with the order of the elements along `axis` reversed, without copying.
+  pub(super) fn flip_view(&self, axis: Idx) -> TensorView<'_,T,N>
+  {
+    if axis>=N { panic!("The axis must be less than the number of dimensions of the tensor.") }
+
+    let mut view: TensorView<T,N>=self.view();
+    if !self.is_empty()
+    {
+      view.offset=(view.offset as isize+(self.dim[axis]-1) as isize*view.strides[axis]) as Idx;
+    }
+    view.strides[axis]*=-1;
+    view
+  }
+
+  /// Mutable view of the block of dimensions `size` whose first element is the element of `self` at
+  /// `start`, failing if the block does not fit in `self`.
+  pub(super) fn slice_view_mut(&mut self, start: Dim<N>, size: Dim<N>) -> Result<TensorViewMut<'_,T,N>,TensorError>
+  {
+    check_block(&self.dim,&start,&size)?;
+
+    let strides: [isize;N]=strides(self.dim,self.layout);
+    let offset: Idx=start.iter().zip(strides.iter()).map(|(&itr,&stride)| itr*stride as Idx).sum();
+    Ok(TensorViewMut{data:&mut self.data,dim:size,strides:strides,offset:offset})
+  }
+
+  /// Whether any element that `view` presents lies in the memory of the elements of `self`, as when
+  /// it views `self`, a clone of it or a tensor split from the same buffer.
+  ///
+  /// The in-place operators that take a view need not check this: a view that overlaps `self` borrows
+  /// a buffer that `self` shares, so `self` copies its elements before it writes to them.
+  pub(super) fn overlaps(&self, view: &TensorView<T,N>) -> bool
+  {
+    match (view.span(),self.is_empty())
+    {
+      (Some((first,last)),false) =>
+      {
+        let size: Idx=core::mem::size_of::<T>();
+        let start: Idx=self.data.as_ptr() as Idx;
+        let (view_start,view_end): (Idx,Idx)=(view.data.as_ptr() as Idx+first*size,view.data.as_ptr() as Idx+last*size);
+        view_start<start+self.len()*size && start<=view_end
+      },
+      _ => false,
+    }
+  }
+
+  /// Writes `self+rhs` to `out`, reading `rhs` through its strides rather than copying it first.
+  pub(super) fn add_into(&self, rhs: &TensorView<T,N>, out: &mut Tensor<T,N>)
+  {
+    for ((dim1,dim2),dim3) in self.dim.iter().zip(rhs.dim.iter()).zip(out.dim.iter())
+    {
+      if dim1!=dim2 || dim1!=dim3 { panic!("All dimensions of two tensors and the output must be of the same size to add them.")}
+    }
+
+    let layout: Layout=out.layout;
+    for (ind,elem) in layout_indices(self.dim,layout).zip(out.data.iter_mut())
+    {
+      *elem=self[ind].clone()+rhs.data[rhs.position(ind)].clone();
+    }
+  }
+
+  /// Applies `f` to every element of `self` and the element of `rhs` at the same multi-index, passing
+  /// the elements of `rhs` as a slice in the order of those of `self` to `kernel` instead if they are
+  /// adjacent in that order. `verb` completes the panic message when the dimensions differ.
+  ///
+  /// If `rhs` views elements of the buffer of `self`, the buffer is shared, so mutable access to the
+  /// elements of `self` copies them first, and no element of `rhs` is read after it has been written
+  /// to.
+  fn zip_view_mut<F,K>(&mut self, rhs: &TensorView<T,N>, verb: &str, mut f: F, kernel: K)
+  where F: FnMut(&mut T,&T), K: FnOnce(&mut [T],&[T])
+  {
+    for (dim1,dim2) in self.dim.iter().zip(rhs.dim.iter())
+    {
+      if dim1!=dim2 { panic!("All dimensions of two tensors must be of the same size to {}.",verb) }
+    }
+
+    match rhs.contiguous()
+    {
+      Some(data) if self.layout==Layout::RowMajor => kernel(&mut self.data,data),
+      _ =>
+      {
+        let layout: Layout=self.layout;
+        for (ind,elem) in layout_indices(self.dim,layout).zip(self.data.iter_mut())
+        {
+          f(elem,&rhs.data[rhs.position(ind)]);
+        }
+      },
+    }
+  }
+}
+
+impl<T> Tensor<T,2>
+where T: Scalar
+{
+  /// View of the transpose of `self`, without copying.
+  pub(super) fn t_view(&self) -> TensorView<'_,T,2>
+  {
+    let mut view: TensorView<T,2>=self.view();
+    view.dim.swap(0,1);
+    view.strides.swap(0,1);
+    view
+  }
+}
+
+impl<'a,T,const N: Idx> Index<Dim<N>> for TensorView<'a,T,N>
+where T: Scalar
+{
+  type Output=T;
+  fn index(&self, ind: Dim<N>) -> &Self::Output
+  {
+    self.dim.checked_index(ind);
+    &self.data[self.position(ind)]
+  }
+}
+
+impl<'a,T> Index<Idx> for TensorView<'a,T,1>
+where T: Scalar
+{
+  type Output=T;
+  fn index(&self, ind: Idx) -> &Self::Output
+  {
+    &self[[ind]]
+  }
+}
+
+impl<'a,T,const N: Idx> fmt::Display for TensorView<'a,T,N>
+where T: Scalar + fmt::Display
+{
+  /// Writes the viewed elements in the same format as a `Tensor`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+  {
+    let elems: Vec<&T>=self.iter().collect();
+    super::display::fmt_display(f,&elems,&self.dim)
+  }
+}
+
+impl<'a,T,const N: Idx> AddAssign<&TensorView<'a,T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  /// Adds the elements of `rhs` in place, gathering them through its strides unless they are adjacent
+  /// in row-major order and `self` is row-major.
+  fn add_assign(&mut self, rhs: &TensorView<'a,T,N>)
+  {
+    self.zip_view_mut(rhs,"add them",|this,other| *this+=other.clone(),|lhs,rhs| T::add_assign_slice(lhs,rhs));
+  }
+}
+
+impl<'a,T,const N: Idx> MulAssign<&TensorView<'a,T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  /// Multiplies by the elements of `rhs` in place, gathering them through its strides unless they are
+  /// adjacent in row-major order and `self` is row-major.
+  fn mul_assign(&mut self, rhs: &TensorView<'a,T,N>)
+  {
+    self.zip_view_mut(rhs,"multiply them",|this,other| *this*=other.clone(),|lhs,rhs| T::mul_assign_slice(lhs,rhs));
+  }
+}
+
+impl<'a,T,const N: Idx> RemAssign<&TensorView<'a,T,N>> for Tensor<T,N>
+where T: Scalar + RemAssign
+{
+  fn rem_assign(&mut self, rhs: &TensorView<'a,T,N>)
+  {
+    let rem=|this: &mut T, other: &T| *this%=other.clone();
+    self.zip_view_mut(rhs,"take the remainder of them",rem,|lhs,rhs| lhs.iter_mut().zip(rhs.iter()).for_each(|(this,other)| rem(this,other)));
+  }
+}
+
+impl<'a,T,const N: Idx> Add<&TensorView<'a,T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn add(mut self, rhs: &TensorView<'a,T,N>) -> Self::Output
+  {
+    self+=rhs;
+    self
+  }
+}
+
+impl<'a,T,const N: Idx> Add<&TensorView<'a,T,N>> for &Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn add(self, rhs: &TensorView<'a,T,N>) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t+=rhs;
+    t
+  }
+}
+
+impl<'a,T,const N: Idx> Mul<&TensorView<'a,T,N>> for Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn mul(mut self, rhs: &TensorView<'a,T,N>) -> Self::Output
+  {
+    self*=rhs;
+    self
+  }
+}
+
+impl<'a,T,const N: Idx> Mul<&TensorView<'a,T,N>> for &Tensor<T,N>
+where T: Scalar
+{
+  type Output=Tensor<T,N>;
+  fn mul(self, rhs: &TensorView<'a,T,N>) -> Self::Output
+  {
+    let mut t: Tensor<T,N>=self.clone();
+    t*=rhs;
+    t
+  }
+}
+
+impl<'a,T,const N: Idx> Index<Dim<N>> for TensorViewMut<'a,T,N>
+where T: Scalar
+{
+  type Output=T;
+  fn index(&self, ind: Dim<N>) -> &Self::Output
+  {
+    self.dim.checked_index(ind);
+    &self.data[self.position(ind)]
+  }
+}
+
+impl<'a,T,const N: Idx> IndexMut<Dim<N>> for TensorViewMut<'a,T,N>
+where T: Scalar
+{
+  fn index_mut(&mut self, ind: Dim<N>) -> &mut Self::Output
+  {
+    self.dim.checked_index(ind);
+    let pos: Idx=self.position(ind);
+    &mut self.data[pos]
+  }
+}
+
+impl<'a,T,U,const N: Idx> AddAssign<U> for TensorViewMut<'a,T,N>
+where T: Scalar + AddAssign<U>, U: Operand
+{
+  fn add_assign(&mut self, rhs: U)
+  {
+    self.for_each_mut(|this| *this+=rhs.clone());
+  }
+}
+
+impl<'a,T,U,const N: Idx> MulAssign<U> for TensorViewMut<'a,T,N>
+where T: Scalar + MulAssign<U>, U: Operand
+{
+  fn mul_assign(&mut self, rhs: U)
+  {
+    self.for_each_mut(|this| *this*=rhs.clone());
+  }
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod view_tests
+{
+  use super::*;
+  use rstest::rstest;
+  use crate::counting_alloc::count_allocations;
+
+  #[test]
+  fn view_test_from_slice()
+  {
+    let data: Vec<f64>=(0..24).map(|itr| itr as f64).collect();
+
+    let ((view,sum,elems),allocations)=count_allocations(|| {
+      let view: TensorView<f64,3>=TensorView::<f64,3>::from_slice([2,3,4],&data).unwrap();
+      let sum: f64=view.sum();
+      let elems: usize=view.iter().filter(|&&elem| elem>=12.0).count();
+      (view,sum,elems)
+    });
+
+    assert!(allocations==0);
+    assert!(view.data.as_ptr()==data.as_ptr());
+    assert!(sum==276.0);
+    assert!(elems==12);
+    for itr in 0..2
+    {
+      for jtr in 0..3
+      {
+        for ktr in 0..4
+        {
+          assert!(view[[itr,jtr,ktr]]==(12*itr+4*jtr+ktr) as f64);
+        }
+      }
+    }
+  }
+
+  #[rstest(dim,size,case([3,4],12),case([11,1],11),case([1,11],11),case([4,3],12))]
+  fn view_test_from_slice_size(dim: Dim<2>, size: Idx)
+  {
+    let data: Vec<f64>=vec![0f64;10];
+
+    let view: Result<TensorView<f64,2>,TensorError>=TensorView::<f64,2>::from_slice(dim,&data);
+    assert!(view.err()==Some(TensorError::SizeMismatch{size:size,len:10}));
+  }
+
+  #[rstest(len,case(0),case(1),case(4))]
+  fn view_test_to_owned(len: Idx)
+  {
+    let data: Vec<f64>=(0..len).map(|itr| itr as f64+1.5).collect();
+    let view: TensorView<f64,1>=TensorView::<f64,1>::from_slice([len],&data).unwrap();
+    let t: Tensor<f64,1>=view.to_owned();
+
+    assert!(t.dim==[len]);
+    assert!(len==0 || t.data.as_ptr()!=data.as_ptr());
+    for itr in 0..len
+    {
+      assert!(t[itr]==view[itr]);
+    }
+  }
+
+  #[rstest(dim,data,expected,
+    case([2,2],&[1.0,-2.0,30.0,0.5],"[[ 1, -2  ],\n [30,  0.5]]"),
+    case([1,3],&[1.0,-2.0,30.0],"[[1, -2, 30]]"),
+    case([3,1],&[1.0,-2.0,0.5],"[[ 1  ],\n [-2  ],\n [ 0.5]]"),
+  )]
+  fn view_test_display(dim: Dim<2>, data: &[f64], expected: &str)
+  {
+    let view: TensorView<f64,2>=TensorView::<f64,2>::from_slice(dim,data).unwrap();
+
+    assert!(alloc::format!("{}",view)==expected);
+    assert!(alloc::format!("{}",view)==alloc::format!("{}",view.to_owned()));
+  }
+
+  #[rstest(dim,case([3,4]),case([1,5]),case([4,1]),case([0,3]))]
+  fn view_test_t_view(dim: Dim<2>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64-5.5);
+    let other: Tensor<f64,2>=Tensor::<f64,2>::from_fn([dim[1],dim[0]],|[itr,jtr]| (itr*jtr) as f64);
+
+    let (view,allocations)=count_allocations(|| t.t_view());
+    assert!(allocations==0);
+    assert!(view.dim==[dim[1],dim[0]]);
+    assert!(Indices::new(view.dim).all(|[itr,jtr]| view[[itr,jtr]]==t[[jtr,itr]]));
+    assert!(view.to_owned().iter().eq(t.transpose().iter()));
+
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new(view.dim);
+    other.add_into(&view,&mut out);
+    assert!(out.iter().eq((&other+&t.transpose()).iter()));
+    assert!(alloc::format!("{}",view)==alloc::format!("{}",t.transpose()));
+  }
+
+  #[rstest(from,dim,
+    case([1,4],[3,4]),
+    case([3,1],[3,4]),
+    case([1,1],[2,5]),
+    case([3,4],[3,4]),
+    case([1,4],[0,4]),
+  )]
+  fn view_test_broadcast_view(from: Dim<2>, dim: Dim<2>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64-5.5);
+    let small: Tensor<f64,2>=Tensor::<f64,2>::from_fn(from,|[itr,jtr]| 10.0*jtr as f64-itr as f64);
+
+    let view: TensorView<f64,2>=small.broadcast_view(dim);
+    assert!(view.dim==dim && view.data.len()==small.data.len());
+    assert!(view.to_owned().iter().eq(small.broadcast_to(dim).iter()));
+    assert!(view.sum()==small.broadcast_to(dim).sum());
+
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new_col_major(dim);
+    t.add_into(&view,&mut out);
+    let expected: Tensor<f64,2>=&t+&small.broadcast_to(dim);
+    for ind in Indices::new(dim)
+    {
+      assert!(out[ind]==expected[ind]);
+    }
+  }
+
+  #[rstest(from,dim,case([3,4],[3,8]),case([3,4],[6,4]),case([2,1],[4,4]),case([3,4],[1,4]))]
+  #[should_panic(expected="Every axis of a tensor must be of length one or of the target length to broadcast it.")]
+  fn view_test_broadcast_view_dims(from: Dim<2>, dim: Dim<2>)
+  {
+    Tensor::<f64,2>::new(from).broadcast_view(dim);
+  }
+
+  #[rstest(layout,axis,dim,
+    case(Layout::RowMajor,0,[3,4]),
+    case(Layout::RowMajor,1,[3,4]),
+    case(Layout::ColMajor,0,[3,4]),
+    case(Layout::ColMajor,1,[3,4]),
+    case(Layout::RowMajor,1,[0,3]),
+    case(Layout::ColMajor,0,[0,3]),
+  )]
+  fn view_test_flip_view(layout: Layout, axis: Idx, dim: Dim<2>)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn(dim,|[itr,jtr]| (dim[1]*itr+jtr) as f64-5.5);
+    let laid_out: Tensor<f64,2>=t.to_layout(layout);
+
+    let view: TensorView<f64,2>=laid_out.flip_view(axis);
+    assert!(view.dim==dim);
+    assert!(view.to_owned().iter().eq(t.flip(axis).iter()));
+    assert!(laid_out.view().to_owned().iter().eq(t.iter()));
+
+    let mut out: Tensor<f64,2>=Tensor::<f64,2>::new(dim);
+    t.add_into(&view,&mut out);
+    assert!(out.iter().eq((&t+&t.flip(axis)).iter()));
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn view_test_slice_view_mut(layout: Layout)
+  {
+    let matrix=|| Tensor::<f64,2>::from_fn([4,5],|[itr,jtr]| (5*itr+jtr) as f64);
+    let t: Tensor<f64,2>=matrix();
+    let inside=|itr: Idx, jtr: Idx| (1..3).contains(&itr) && (1..4).contains(&jtr);
+
+    // The tensor does not share its elements, so the view changes them where they are.
+    let mut res: Tensor<f64,2>=matrix().into_layout(layout);
+    let ptr: *const f64=res.data.as_ptr();
+    let ((),allocations)=count_allocations(|| {
+      let mut view: TensorViewMut<f64,2>=res.slice_view_mut([1,1],[2,3]).unwrap();
+      view+=1.0;
+      view.mul_assign_scalar(2.0);
+      view.add_assign_scalar(0.5);
+      view[[1,2]]=100.0;
+    });
+
+    assert!(allocations==0 && res.data.as_ptr()==ptr && res.layout()==layout);
+    for ind in Indices::new([4,5])
+    {
+      let expected: f64=match ind
+      {
+        [2,3] => 100.0,
+        [itr,jtr] if inside(itr,jtr) => 2.0*(t[ind]+1.0)+0.5,
+        _ => t[ind],
+      };
+      assert!(res[ind]==expected);
+    }
+  }
+
+  #[test]
+  fn view_test_slice_view_mut_block()
+  {
+    let mut t: Tensor<i32,3>=Tensor::<i32,3>::from_fn([2,3,4],|[itr,jtr,ktr]| (12*itr+4*jtr+ktr) as i32);
+    let mut view: TensorViewMut<i32,3>=t.slice_view_mut([1,0,2],[1,3,2]).unwrap();
+    assert!(view.dim()==[1,3,2] && view[[0,2,1]]==23);
+    assert!(view.to_owned()==Tensor::<i32,3>::from_fn([1,3,2],|[_,jtr,ktr]| (14+4*jtr+ktr) as i32));
+    view*=-1;
+    assert!(t.iter().filter(|&&elem| elem<0).count()==6 && t[[1,1,3]]==-19 && t[[1,1,1]]==17);
+
+    // A shared tensor is copied before the view changes it.
+    let copy: Tensor<i32,3>=t.clone();
+    t.slice_view_mut([0,0,0],[2,3,4]).unwrap().add_assign_scalar(1);
+    assert!(t.iter().zip(copy.iter()).all(|(elem,old)| *elem==old+1));
+
+    assert!(t.slice_view_mut([1,2,0],[1,2,1]).err()==Some(TensorError::OutOfBounds{axis:1}));
+    t.slice_view_mut([2,3,4],[0,0,0]).unwrap().add_assign_scalar(5);
+    assert!(t.iter().zip(copy.iter()).all(|(elem,old)| *elem==old+1));
+  }
+
+  #[rstest(ind,case([2,0]),case([2,1]))]
+  #[should_panic(expected="The index 2 is out of bounds for axis 0 of length 2.")]
+  fn view_test_slice_view_mut_index(ind: Dim<2>)
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::new([3,4]);
+    t.slice_view_mut([1,1],[2,2]).unwrap()[ind]=0.0;
+  }
+
+  #[rstest(layout,case(Layout::RowMajor),case(Layout::ColMajor))]
+  fn view_test_add_assign_view(layout: Layout)
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64-5.5);
+    let other: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,3],|[itr,jtr]| (itr*jtr) as f64+0.5);
+    let expected: Tensor<f64,2>=&other+&t.transpose();
+
+    let mut res: Tensor<f64,2>=other.to_layout(layout);
+    res+=&t.t_view();
+    assert!(res==expected && res.layout()==layout);
+    assert!(&other.to_layout(layout)+&t.t_view()==expected && other.to_layout(layout)+&t.t_view()==expected);
+
+    // A contiguous view is added as a slice.
+    let mut res: Tensor<f64,2>=Tensor::<f64,2>::from_fn([4,3],|[itr,jtr]| (itr*jtr) as f64+0.5);
+    let ((),allocations)=count_allocations(|| res+=&other.view());
+    assert!(allocations==0 && res==&other+&other);
+
+    let row: Tensor<f64,2>=Tensor::<f64,2>::from_fn([1,4],|[_,jtr]| 10.0*jtr as f64);
+    let mut res: Tensor<f64,2>=t.to_layout(layout);
+    res+=&row.broadcast_view([3,4]);
+    assert!(res==&t+&row.broadcast_to([3,4]));
+  }
+
+  #[test]
+  fn view_test_mul_assign_view()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64-5.5);
+    let col: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,1],|[itr,_]| itr as f64-1.0);
+    let expected: Tensor<f64,2>=&t*&col.broadcast_to([3,4]);
+
+    let mut res: Tensor<f64,2>=t.clone();
+    res*=&col.broadcast_view([3,4]);
+    assert!(res==expected && t.clone()*&col.broadcast_view([3,4])==expected && &t*&col.broadcast_view([3,4])==expected);
+    assert!(&t*&t.flip_view(1)==&t*&t.flip(1) && &t*&t.view()==&t*&t);
+
+    let mut ints: Tensor<i32,2>=Tensor::<i32,2>::from_fn([2,3],|[itr,jtr]| (3*itr+jtr) as i32+7);
+    let divisors: Tensor<i32,2>=Tensor::<i32,2>::from_fn([3,2],|[itr,jtr]| (itr+jtr) as i32+2);
+    ints%=&divisors.t_view();
+    assert!(ints.iter().copied().eq([1,2,1,1,3,2].iter().copied()));
+  }
+
+  #[rstest(lhs,rhs,case([3,4],[3,4]),case([2,3],[2,3]),case([3,4],[1,4]))]
+  #[should_panic(expected="All dimensions of two tensors must be of the same size to add them.")]
+  fn view_test_add_assign_view_dims(lhs: Dim<2>, rhs: Dim<2>)
+  {
+    let mut t: Tensor<f64,2>=Tensor::<f64,2>::new(lhs);
+    let other: Tensor<f64,2>=Tensor::<f64,2>::new(rhs);
+    t+=&other.t_view();
+  }
+
+  #[test]
+  fn view_test_overlaps()
+  {
+    let t: Tensor<f64,2>=Tensor::<f64,2>::from_fn([3,4],|[itr,jtr]| (4*itr+jtr) as f64-5.5);
+    let copy: Tensor<f64,2>=t.clone();
+    let separate: Tensor<f64,2>=t.map(|&elem| elem);
+    assert!(t.overlaps(&t.view()) && t.overlaps(&copy.t_view()) && t.overlaps(&copy.flip_view(0)));
+    assert!(!t.overlaps(&separate.view()) && !t.overlaps(&TensorView::<f64,2>::from_slice([0,4],&[]).unwrap()));
+
+    // Halves split from the same buffer share the allocation but not their elements.
+    let (head,tail): (Tensor<f64,2>,Tensor<f64,2>)=t.clone().split_first_axis(1);
+    assert!(!head.overlaps(&tail.view()) && !tail.overlaps(&head.view()) && t.overlaps(&tail.view()));
+    let data: Vec<f64>=(0..12).map(|itr| itr as f64).collect();
+    assert!(!t.overlaps(&TensorView::<f64,2>::from_slice([3,4],&data).unwrap()));
+  }
+
+  #[test]
+  fn view_test_add_assign_overlapping()
+  {
+    // A view shifted one element along the buffer of the tensor it is added to.
+    let full: Tensor<f64,1>=Tensor::<f64,1>::from_fn([8],|[itr]| (itr*itr) as f64);
+    let (mut t,_): (Tensor<f64,1>,Tensor<f64,1>)=full.clone().split_first_axis(7);
+    let shifted: TensorView<f64,1>=TensorView::<f64,1>::from_slice([7],&full.data[1..]).unwrap();
+    assert!(t.overlaps(&shifted));
+
+    // Writing to the shared buffer copies the elements of `t` once, into a new buffer behind a new
+    // reference count, and the view is not copied at all.
+    let ((),allocations): ((),Idx)=crate::counting_alloc::count_allocations(|| t+=&shifted);
+    assert!(allocations==2 && !t.overlaps(&shifted));
+    assert!(t.iter().copied().eq((0..7).map(|itr| (itr*itr+(itr+1)*(itr+1)) as f64)));
+    assert!(full.iter().copied().eq((0..8).map(|itr| (itr*itr) as f64)));
+
+    let mut t: Tensor<f64,1>=full.clone();
+    t*=&full.flip_view(0);
+    assert!(t.iter().copied().eq((0..8).map(|itr| (itr*itr*(7-itr)*(7-itr)) as f64)));
+  }
+}
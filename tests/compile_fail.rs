@@ -0,0 +1,18 @@
+// The const-generic checks are evaluated when a call is built rather than checked, which trybuild
+// only does if there are cases that must pass, so every set of cases that must fail to build comes
+// with one that must build.
+//
+// The expected output is that of a build with the standard library, as the panics of the checks point
+// into `std` rather than `core`.
+#![cfg(feature="std")]
+
+#[test]
+fn compile_fail()
+{
+  let t: trybuild::TestCases=trybuild::TestCases::new();
+  t.pass("tests/ui/const_axis.rs");
+  t.compile_fail("tests/ui/const_axis_range.rs");
+  t.compile_fail("tests/ui/const_axis_rank.rs");
+  t.pass("tests/ui/smatrix.rs");
+  t.compile_fail("tests/ui/smatrix_matmul.rs");
+}
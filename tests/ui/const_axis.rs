@@ -0,0 +1,7 @@
+// A const-axis reduction along an axis of the tensor to a result of one rank less builds.
+
+fn main()
+{
+  assert!(lemma::ui::sum_axis_const::<0,3,2>([2,3,4])==[3,4]);
+  assert!(lemma::ui::sum_axis_const::<2,3,2>([2,3,4])==[2,3]);
+}
@@ -0,0 +1,6 @@
+// The axis of a const-axis reduction must be an axis of the tensor.
+
+fn main()
+{
+  let _: [usize;2]=lemma::ui::sum_axis_const::<5,3,2>([2,3,4]);
+}
@@ -0,0 +1,6 @@
+// The result of a const-axis reduction must be of one rank less than the tensor.
+
+fn main()
+{
+  let _: [usize;1]=lemma::ui::sum_axis_const::<1,3,1>([2,3,4]);
+}
@@ -0,0 +1,9 @@
+// Matrices whose inner dimensions agree are multiplied into one of the outer dimensions.
+
+fn main()
+{
+  let a: lemma::ui::Matrix<2,3>=lemma::ui::Matrix::from_fn(|[itr,jtr]| (3*itr+jtr) as f64);
+  let b: lemma::ui::Matrix<3,1>=lemma::ui::Matrix::from_fn(|[itr,_]| itr as f64);
+  let c: lemma::ui::Matrix<2,1>=a.matmul(&b);
+  assert!(c.get([0,0])==5.0 && c.get([1,0])==14.0);
+}
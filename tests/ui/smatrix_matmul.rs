@@ -0,0 +1,8 @@
+// A matrix is only multiplied with one that has as many rows as it has columns.
+
+fn main()
+{
+  let a: lemma::ui::Matrix<2,3>=lemma::ui::Matrix::from_fn(|[itr,jtr]| (3*itr+jtr) as f64);
+  let b: lemma::ui::Matrix<2,3>=lemma::ui::Matrix::from_fn(|[itr,jtr]| (3*itr+jtr) as f64);
+  let _=a.matmul(&b);
+}